@@ -0,0 +1,50 @@
+//compares parse_json_in, with one arena reset between parses, with parse_json_string and
+//parse_json_str_borrowed, run with `cargo bench --bench arena --features arena`
+use std::time::{Duration, Instant};
+
+use json_parser::{parse_json_in, parse_json_str_borrowed, parse_json_string, JsonArena};
+
+fn fixture() -> String {
+    let records: Vec<String> = (0..20_000)
+        .map(|i| {
+            format!(
+                r#"{{ "id" : "user-{}", "first" : "ann", "last" : "lee", "age" : {}, "tags" : [ "a", "bb", "ccc", "d\n" ], "address" : {{ "city" : "oslo", "zip" : [ 1, 2, 3 ] }} }}"#,
+                i, i % 90
+            )
+        })
+        .collect();
+
+    format!("[ {} ]", records.join(", "))
+}
+
+fn time(name: &str, input: &str, iterations: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iteration: Duration = start.elapsed() / iterations;
+    let throughput = input.len() as f64 / per_iteration.as_secs_f64() / 1e6;
+    println!(
+        "{:<40} {:>12?} {:>8.1} MB/s",
+        name, per_iteration, throughput
+    );
+}
+
+fn main() {
+    let input = fixture();
+    println!("fixture: {} bytes", input.len());
+
+    time("parse_json_string", &input, 10, || {
+        parse_json_string(&input).unwrap();
+    });
+    time("parse_json_str_borrowed", &input, 10, || {
+        parse_json_str_borrowed(&input).unwrap();
+    });
+
+    let mut arena = JsonArena::new();
+    time("parse_json_in, then reset", &input, 10, || {
+        parse_json_in(&input, &arena).unwrap();
+        arena.reset();
+    });
+    println!("arena: {} bytes", arena.allocated_bytes());
+}
@@ -0,0 +1,41 @@
+//compares parse_json_str_borrowed with parse_json_string on many short strings, run with `cargo bench --bench borrowed`
+use std::time::{Duration, Instant};
+
+use json_parser::{parse_json_str_borrowed, parse_json_string};
+
+fn fixture() -> String {
+    let records: Vec<String> = (0..20_000)
+        .map(|i| {
+            format!(
+                r#"{{ "id" : "user-{}", "first" : "ann", "last" : "lee", "city" : "oslo", "tags" : [ "a", "bb", "ccc", "d\n" ] }}"#,
+                i
+            )
+        })
+        .collect();
+
+    format!("[ {} ]", records.join(", "))
+}
+
+fn time(name: &str, iterations: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iteration: Duration = start.elapsed() / iterations;
+    println!("{:<40} {:>12?}", name, per_iteration);
+}
+
+fn main() {
+    let input = fixture();
+    println!("fixture: {} bytes", input.len());
+
+    time("parse_json_string", 10, || {
+        parse_json_string(&input).unwrap();
+    });
+    time("parse_json_str_borrowed", 10, || {
+        parse_json_str_borrowed(&input).unwrap();
+    });
+    time("parse_json_str_borrowed, then to_owned", 10, || {
+        parse_json_str_borrowed(&input).unwrap().to_owned();
+    });
+}
@@ -0,0 +1,45 @@
+//compares lookups in a large Object and IndexedObject, run with `cargo bench --bench indexed`
+use std::time::Instant;
+
+use json_parser::{parse_json_string, IndexedObject, JsonObject, Object};
+
+fn fixture() -> Object {
+    let entries: Vec<String> = (0..50_000)
+        .map(|i| format!(r#""id{}" : {{ "n" : {} }}"#, i, i))
+        .collect();
+
+    parse_json_string(&format!("{{ {} }}", entries.join(", ")))
+        .unwrap()
+        .into_object()
+        .unwrap()
+}
+
+//looks up every given key once
+fn time<'a>(name: &str, keys: &[String], get: impl Fn(&str) -> Option<&'a JsonObject>) {
+    let start = Instant::now();
+    for key in keys {
+        assert!(get(key).is_some());
+    }
+    let elapsed = start.elapsed();
+
+    println!("{:<40} {:>12?}", name, elapsed);
+}
+
+fn main() {
+    let object = fixture();
+
+    let start = Instant::now();
+    let indexed = IndexedObject::from(object.clone());
+    println!("{:<40} {:>12?}", "building the index", start.elapsed());
+
+    //spread over the whole object, so the scan goes halfway on average
+    let keys: Vec<String> = (0..50_000)
+        .step_by(10)
+        .map(|i| format!("id{}", i))
+        .collect();
+
+    time("Object::get, 5k lookups", &keys, |key| object.get(key));
+    time("IndexedObject::get, 5k lookups", &keys, |key| {
+        indexed.get(key)
+    });
+}
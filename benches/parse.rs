@@ -0,0 +1,85 @@
+//parse_json_string and parse_json_from_iter on documents of about 1 MB, one mixed, one mostly
+//numbers and one mostly strings, run with `cargo bench --bench parse`
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use json_parser::{parse_json_from_iter, parse_json_string};
+
+const SIZE: usize = 1 << 20;
+
+//records until the document is about `SIZE` bytes long
+fn document(record: impl Fn(usize) -> String) -> String {
+    let mut records = Vec::new();
+    let mut len = 0;
+
+    while len < SIZE {
+        let next = record(records.len());
+        len += next.len() + 2;
+        records.push(next);
+    }
+
+    format!("[{}]", records.join(", "))
+}
+
+fn mixed() -> String {
+    document(|i| {
+        format!(
+            r#"{{ "id": {}, "name": "user-{}", "active": {}, "score": {}.{}, "tags": ["a", "bb"], "manager": null, "address": {{ "city": "oslo", "zip": "0{}" }} }}"#,
+            i,
+            i,
+            i % 3 == 0,
+            i % 100,
+            i % 7,
+            i % 1000
+        )
+    })
+}
+
+fn numbers() -> String {
+    document(|i| {
+        format!(
+            "[{}, -{}.{}, {}e-3, 0.{}, {}]",
+            i * 7919,
+            i % 1000,
+            i % 97,
+            i,
+            i * 31,
+            i as f64 * 1.25
+        )
+    })
+}
+
+fn strings() -> String {
+    document(|i| {
+        format!(
+            r#""lorem ipsum dolor sit amet {} consectetur adipiscing elit", "café \"{}\" \n tab\t", "{}""#,
+            i,
+            i,
+            "x".repeat(i % 64)
+        )
+    })
+}
+
+fn parse(c: &mut Criterion) {
+    for (name, input) in [
+        ("mixed", mixed()),
+        ("numbers", numbers()),
+        ("strings", strings()),
+    ] {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.sample_size(20);
+
+        group.bench_function("parse_json_string", |b| {
+            b.iter(|| parse_json_string(black_box(&input)).unwrap())
+        });
+        //any iterator is read one char at a time, even over a `&str`
+        group.bench_function("parse_json_from_iter", |b| {
+            b.iter(|| parse_json_from_iter(black_box(&input).chars()).unwrap())
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
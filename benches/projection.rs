@@ -0,0 +1,58 @@
+//compares parse_pointer_only and extract_pointers with a full parse, run with `cargo bench --bench projection`
+use std::time::{Duration, Instant};
+
+use json_parser::{extract_pointers, parse_json_string, parse_pointer_only};
+
+fn fixture() -> String {
+    let records: Vec<String> = (0..20_000)
+        .map(|i| {
+            format!(
+                r#"{{ "id" : {}, "name" : "record {}", "tags" : [ "a", "b\n" ], "score" : {}.5 }}"#,
+                i, i, i
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{ "early" : {{ "count" : 20000 }}, "results" : {{ "items" : [ {} ] }}, "late" : true }}"#,
+        records.join(", ")
+    )
+}
+
+fn time(name: &str, iterations: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iteration: Duration = start.elapsed() / iterations;
+    println!("{:<40} {:>12?}", name, per_iteration);
+}
+
+fn main() {
+    let input = fixture();
+    println!("fixture: {} bytes", input.len());
+
+    time("full parse", 10, || {
+        parse_json_string(&input).unwrap();
+    });
+
+    for pointer in &["/early/count", "/results/items/19999", "/late", "/missing"] {
+        time(&format!("parse_pointer_only {}", pointer), 10, || {
+            parse_pointer_only(&input, pointer).unwrap();
+        });
+    }
+
+    time("extract_pointers, five targets", 10, || {
+        extract_pointers(
+            &input,
+            &[
+                "/early/count",
+                "/results/items/0/id",
+                "/results/items/10000/tags",
+                "/results/items/19999/name",
+                "/late",
+            ],
+        )
+        .unwrap();
+    });
+}
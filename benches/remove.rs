@@ -0,0 +1,48 @@
+//compares Object::shift_remove and swap_remove, run with `cargo bench --bench remove`
+use std::time::Instant;
+
+use json_parser::{parse_json_string, JsonObject, Object};
+
+fn fixture() -> Object {
+    let entries: Vec<String> = (0..10_000)
+        .map(|i| format!(r#""key{}" : {}"#, i, i))
+        .collect();
+
+    parse_json_string(&format!("{{ {} }}", entries.join(", ")))
+        .unwrap()
+        .into_object()
+        .unwrap()
+}
+
+//strips the given half of the keys from a fresh 10k entry object
+fn time(
+    name: &str,
+    keys: &[String],
+    mut remove: impl FnMut(&mut Object, &str) -> Option<JsonObject>,
+) {
+    let mut object = fixture();
+
+    let start = Instant::now();
+    for key in keys {
+        remove(&mut object, key).unwrap();
+    }
+    let elapsed = start.elapsed();
+
+    assert_eq!(object.entries().len(), 5_000);
+    println!("{:<40} {:>12?}", name, elapsed);
+}
+
+fn main() {
+    //found right away, so the cost is in shifting the rest
+    let front: Vec<String> = (0..5_000).map(|i| format!("key{}", i)).collect();
+    //every other key, the lookup scan weighs in as well
+    let even: Vec<String> = (0..10_000)
+        .step_by(2)
+        .map(|i| format!("key{}", i))
+        .collect();
+
+    time("shift_remove, first half", &front, Object::shift_remove);
+    time("swap_remove, first half", &front, Object::swap_remove);
+    time("shift_remove, every other key", &even, Object::shift_remove);
+    time("swap_remove, every other key", &even, Object::swap_remove);
+}
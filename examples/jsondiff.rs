@@ -0,0 +1,67 @@
+//! Compares two JSON files structurally and prints what changed from the first to the second, one
+//! change per line, or with `--patch` an RFC 6902 JSON Patch turning the first into the second.
+//! Member order and number representation are not changes. Exits with 0 when the files are the
+//! same, 1 when they differ and 2 when one could not be read or parsed.
+//!
+//!     cargo run --example jsondiff -- staging.json production.json
+//!     cargo run --example jsondiff -- --patch staging.json production.json
+
+use std::process::ExitCode;
+
+use json_parser::{diff, diff_patch, parse_json_string, DiffPatchOptions, JsonObject};
+
+//reports why the file could not be used, a parse error as jsonlint does
+fn load(path: &str) -> Option<JsonObject> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            return None;
+        }
+    };
+
+    match parse_json_string(&text) {
+        Ok(json) => Some(json),
+        Err(error) => {
+            eprintln!("{}", error.diagnostic(path, &text));
+            None
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let patch = args.iter().any(|arg| arg == "--patch");
+    args.retain(|arg| arg != "--patch");
+
+    let (from, to) = match args.as_slice() {
+        [from, to] => (load(from), load(to)),
+        _ => {
+            eprintln!("usage: jsondiff [--patch] <from.json> <to.json>");
+            return ExitCode::from(2);
+        }
+    };
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) => (from, to),
+        _ => return ExitCode::from(2),
+    };
+
+    let changes = diff(&from, &to);
+    if changes.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    if patch {
+        let options = DiffPatchOptions {
+            detect_moves: true,
+            detect_copies: true,
+        };
+        println!("{:#}", diff_patch(&from, &to, &options));
+    } else {
+        for change in &changes {
+            println!("{}", change);
+        }
+    }
+
+    ExitCode::FAILURE
+}
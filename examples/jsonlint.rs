@@ -0,0 +1,60 @@
+//! Validates JSON files and prints a `file:line:column` diagnostic with a caret for each invalid
+//! one. Reads stdin when no paths are given, exits with 1 if any input was invalid. With
+//! `--all-errors` every error of a file is reported instead of only the first.
+//!
+//!     cargo run --example jsonlint -- config.json data/*.json
+//!     cargo run --example jsonlint -- --all-errors config.json
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use json_parser::{parse_json_string, validate_json};
+
+fn report(name: &str, text: &str, all_errors: bool) -> bool {
+    let errors = if all_errors {
+        validate_json(text)
+    } else {
+        parse_json_string(text).err().into_iter().collect()
+    };
+
+    for error in &errors {
+        eprintln!("{}", error.diagnostic(name, text));
+    }
+
+    errors.is_empty()
+}
+
+fn main() -> ExitCode {
+    let mut paths: Vec<String> = std::env::args().skip(1).collect();
+    let all_errors = paths.iter().any(|arg| arg == "--all-errors");
+    paths.retain(|arg| arg != "--all-errors");
+
+    let mut valid = true;
+
+    if paths.is_empty() {
+        let mut text = String::new();
+        match std::io::stdin().read_to_string(&mut text) {
+            Ok(_) => valid &= report("<stdin>", &text, all_errors),
+            Err(err) => {
+                eprintln!("<stdin>: {}", err);
+                valid = false;
+            }
+        }
+    }
+
+    for path in &paths {
+        match std::fs::read_to_string(path) {
+            Ok(text) => valid &= report(path, &text, all_errors),
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                valid = false;
+            }
+        }
+    }
+
+    if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
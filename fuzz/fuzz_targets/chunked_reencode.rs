@@ -0,0 +1,53 @@
+//streaming through a reader that hands out arbitrary chunk sizes must match a one-shot parse
+#![no_main]
+
+use std::io::Read;
+
+use arbitrary::Arbitrary;
+use json_parser::{parse_json_string, reencode, SerializeOptions};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    text: String,
+    chunk_sizes: Vec<u8>,
+}
+
+struct Chunked<'a> {
+    data: &'a [u8],
+    sizes: std::iter::Cycle<std::vec::IntoIter<usize>>,
+}
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let size = self.sizes.next().unwrap().min(buf.len()).min(self.data.len());
+        buf[..size].copy_from_slice(&self.data[..size]);
+        self.data = &self.data[size..];
+        Ok(size)
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let mut sizes: Vec<usize> = input
+        .chunk_sizes
+        .iter()
+        .map(|&size| usize::from(size).max(1))
+        .collect();
+    if sizes.is_empty() {
+        sizes.push(1);
+    }
+
+    let reader = Chunked {
+        data: input.text.as_bytes(),
+        sizes: sizes.into_iter().cycle(),
+    };
+
+    let mut streamed = vec![];
+    let streamed = reencode(reader, &mut streamed, &SerializeOptions::compact())
+        .map(|()| String::from_utf8(streamed).unwrap());
+
+    match parse_json_string(&input.text) {
+        Ok(value) => assert_eq!(streamed.unwrap(), value.to_json_string()),
+        Err(_) => assert!(streamed.is_err()),
+    }
+});
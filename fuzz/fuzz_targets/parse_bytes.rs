@@ -0,0 +1,21 @@
+//arbitrary bytes must never panic the byte parser, and valid UTF-8 must parse as the str would
+#![no_main]
+
+use json_parser::{parse_json_bytes, parse_json_string, JsonErrorKind};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let parsed = parse_json_bytes(data);
+    let without_bom = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+
+    match std::str::from_utf8(without_bom) {
+        Ok(input) => match parse_json_string(input) {
+            Ok(value) => assert_eq!(parsed.unwrap(), value),
+            Err(e) => assert_eq!(parsed.unwrap_err().kind, e.kind),
+        },
+        Err(_) => assert!(matches!(
+            parsed.unwrap_err().kind,
+            JsonErrorKind::InvalidUtf8 { .. }
+        )),
+    }
+});
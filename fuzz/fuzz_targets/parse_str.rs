@@ -0,0 +1,13 @@
+//arbitrary bytes must never panic or hang the parser, only produce errors
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(value) = json_parser::parse_json_string(input) {
+            //whatever parses serializes to something that parses again
+            json_parser::parse_json_string(&value.to_json_string()).unwrap();
+        }
+    }
+});
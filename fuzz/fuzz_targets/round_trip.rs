@@ -0,0 +1,50 @@
+//generated trees must survive serialization, pretty or compact
+#![no_main]
+
+use arbitrary::Arbitrary;
+use json_parser::{parse_json_string, JsonObject, Object, SerializeOptions};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+enum Value {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn into_json(self) -> JsonObject {
+        match self {
+            Value::Null => JsonObject::Null,
+            Value::Boolean(b) => JsonObject::Boolean(b),
+            //non-finite numbers are written as null
+            Value::Number(n) if n.is_finite() => JsonObject::Number(n),
+            Value::Number(_) => JsonObject::Null,
+            Value::String(s) => JsonObject::String(s),
+            Value::Array(values) => {
+                JsonObject::Array(values.into_iter().map(Value::into_json).collect())
+            }
+            Value::Object(entries) => {
+                let mut object = Object::new();
+                for (key, value) in entries {
+                    object.entries_mut().push((key.into(), value.into_json()));
+                }
+                JsonObject::Object(object)
+            }
+        }
+    }
+}
+
+fuzz_target!(|value: Value| {
+    let value = value.into_json();
+
+    for options in &[SerializeOptions::compact(), SerializeOptions::pretty()] {
+        let text = value.serialize(options).unwrap();
+        let reparsed = parse_json_string(&text).unwrap();
+        //numbers are written in their shortest form that reads back to the same f64
+        assert_eq!(reparsed, value, "{}", text);
+    }
+});
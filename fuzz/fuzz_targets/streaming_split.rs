@@ -0,0 +1,56 @@
+//feeding a document to the streaming parser in pieces must match a one-shot parse
+#![no_main]
+
+use arbitrary::Arbitrary;
+use json_parser::{parse_json_string, JsonObject, Progress, StreamingParser};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    text: String,
+    splits: Vec<u16>,
+}
+
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r')
+}
+
+//`None` when the text is not a single document
+fn stream(text: &str, splits: &[u16]) -> Option<JsonObject> {
+    //split points are moved back to char boundaries and sorted
+    let mut splits: Vec<usize> = splits
+        .iter()
+        .map(|&split| {
+            let mut split = usize::from(split) % (text.len() + 1);
+            while !text.is_char_boundary(split) {
+                split -= 1;
+            }
+            split
+        })
+        .collect();
+    splits.sort_unstable();
+    splits.push(text.len());
+
+    let mut parser = StreamingParser::new();
+    let mut start = 0;
+    for end in splits {
+        match parser.feed(&text[start..end]).ok()? {
+            Progress::NeedMore => start = end,
+            Progress::Complete { value, consumed } => {
+                //only whitespace may follow the document
+                let rest = text[start + consumed..].trim_matches(is_whitespace);
+                return Some(value).filter(|_| rest.is_empty());
+            }
+        }
+    }
+    parser.finish().ok()
+}
+
+fuzz_target!(|input: Input| {
+    let streamed = stream(&input.text, &input.splits);
+
+    match parse_json_string(&input.text) {
+        Ok(value) => assert_eq!(streamed, Some(value)),
+        Err(_) => assert_eq!(streamed, None),
+    }
+});
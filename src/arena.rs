@@ -0,0 +1,446 @@
+use std::ops::Index;
+
+use bumpalo::Bump;
+
+use crate::borrowed::{parse_document, Parser};
+use crate::{
+    check_depth, entry_value, unclosed, unexpected, Container, Expected, JsonError, JsonErrorKind,
+    JsonObject, JsonValueRef, Object, ParseOptions,
+};
+
+/// The memory of the values parsed by [`parse_json_in`]. Values are never dropped one by one,
+/// everything is reclaimed at once by [`JsonArena::reset`] or when the arena is dropped, so an
+/// arena created per request and reset between requests makes parsing nearly allocation free.
+///
+/// ```
+/// use json_parser::{parse_json_in, JsonArena};
+///
+/// let mut arena = JsonArena::new();
+/// for request in [r#"{ "user" : "ann" }"#, r#"{ "user" : "bob" }"#] {
+///     let json = parse_json_in(request, &arena).unwrap();
+///     assert!(json["user"].as_str().is_some());
+///
+///     arena.reset();
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct JsonArena {
+    bump: Bump,
+}
+
+impl JsonArena {
+    #[inline]
+    pub fn new() -> Self {
+        JsonArena::default()
+    }
+
+    /// An arena with room for `bytes` before it has to grow.
+    #[inline]
+    pub fn with_capacity(bytes: usize) -> Self {
+        JsonArena {
+            bump: Bump::with_capacity(bytes),
+        }
+    }
+
+    /// Frees every value parsed into the arena, keeping its largest block of memory for the next
+    /// ones.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+
+    /// The memory held by the arena, in use or not.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
+
+/// A parsed value living in a [`JsonArena`], see [`parse_json_in`]. Strings and keys are copied
+/// into the arena, so the value does not borrow the input. Arrays and objects are slices and can
+/// be iterated as such.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonValue<'a> {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    Str(&'a str),
+    Array(&'a [JsonValue<'a>]),
+    /// Entries in document order, duplicate keys included.
+    Object(&'a [(&'a str, JsonValue<'a>)]),
+}
+
+impl<'a> JsonValue<'a> {
+    /// The value of the first entry with the key, `None` when missing or not an object.
+    pub fn get(&self, key: &str) -> Option<&'a JsonValue<'a>> {
+        self.as_object()?
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self {
+            JsonValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_array(&self) -> Option<&'a [JsonValue<'a>]> {
+        match self {
+            JsonValue::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_object(&self) -> Option<&'a [(&'a str, JsonValue<'a>)]> {
+        match self {
+            JsonValue::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Copies the value out of the arena into an owned [`JsonObject`].
+    pub fn to_owned(&self) -> JsonObject {
+        match *self {
+            JsonValue::Null => JsonObject::Null,
+            JsonValue::Boolean(b) => JsonObject::Boolean(b),
+            JsonValue::Number(n) => JsonObject::Number(n),
+            JsonValue::Str(s) => JsonObject::String(s.to_owned()),
+            JsonValue::Array(array) => {
+                JsonObject::Array(array.iter().map(JsonValue::to_owned).collect())
+            }
+            JsonValue::Object(entries) => {
+                let mut object = Object::new();
+                object.entries_mut().extend(
+                    entries
+                        .iter()
+                        .map(|(key, value)| ((*key).into(), value.to_owned())),
+                );
+                JsonObject::Object(object)
+            }
+        }
+    }
+}
+
+static NULL: JsonValue<'static> = JsonValue::Null;
+
+/// Looks up an object member like [`JsonObject`] does, anything missing gives a null.
+impl<'a> Index<&str> for JsonValue<'a> {
+    type Output = JsonValue<'a>;
+
+    fn index(&self, key: &str) -> &JsonValue<'a> {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+/// Looks up an array element, anything out of bounds or not an array gives a null.
+impl<'a> Index<usize> for JsonValue<'a> {
+    type Output = JsonValue<'a>;
+
+    fn index(&self, index: usize) -> &JsonValue<'a> {
+        self.as_array()
+            .and_then(|array| array.get(index))
+            .unwrap_or(&NULL)
+    }
+}
+
+/// Parses a whole document like [`crate::parse_json_string`] into the arena. The grammar and
+/// errors are the same as [`crate::parse_json_str_borrowed`]'s.
+pub fn parse_json_in<'a>(input: &str, arena: &'a JsonArena) -> Result<JsonValue<'a>, JsonError> {
+    let mut builder = Builder {
+        bump: &arena.bump,
+        values: vec![],
+        entries: vec![],
+    };
+
+    parse_document(input, |parser| builder.parse_value(parser))
+}
+
+enum Frame<'a> {
+    //where the elements start in `values`
+    Array(usize),
+    //where the entries start in `entries`, and the key of the value being parsed
+    Object(usize, &'a str),
+}
+
+impl Frame<'_> {
+    fn container(&self) -> Container {
+        match self {
+            Frame::Array(_) => Container::Array,
+            Frame::Object(..) => Container::Object,
+        }
+    }
+}
+
+//the members of the open containers, moved into the arena as one slice when a container closes
+struct Builder<'a> {
+    bump: &'a Bump,
+    values: Vec<JsonValue<'a>>,
+    entries: Vec<(&'a str, JsonValue<'a>)>,
+}
+
+impl<'a> Builder<'a> {
+    fn parse_key(&self, parser: &mut Parser<'_>) -> Result<&'a str, JsonErrorKind> {
+        Ok(self.bump.alloc_str(&parser.parse_key()?))
+    }
+
+    fn parse_value(&mut self, parser: &mut Parser<'_>) -> Result<JsonValue<'a>, JsonErrorKind> {
+        let mut stack = vec![];
+
+        self.parse_nested(parser, &mut stack)
+            .map_err(|kind| match stack.last() {
+                Some(frame) => unclosed(kind, frame.container(), stack.len() - 1),
+                None => kind,
+            })
+    }
+
+    //the same loop as `Parser::parse_nested`
+    fn parse_nested(
+        &mut self,
+        parser: &mut Parser<'_>,
+        stack: &mut Vec<Frame<'a>>,
+    ) -> Result<JsonValue<'a>, JsonErrorKind> {
+        let options = ParseOptions::default();
+        let mut pending: Option<char> = None;
+
+        loop {
+            let first = match pending.take() {
+                Some(ch) => ch,
+                None => parser.next_non_whitespace()?,
+            };
+            if let Some(Frame::Object(..)) = stack.last() {
+                entry_value(first)?;
+            }
+
+            let mut value = match first {
+                '[' => {
+                    check_depth(stack.len(), &options)?;
+
+                    //not on the stack until it has a member
+                    let open = |kind| unclosed(kind, Container::Array, stack.len());
+                    match parser.next_non_whitespace().map_err(open)? {
+                        ']' => JsonValue::Array(&[]),
+                        first => {
+                            stack.push(Frame::Array(self.values.len()));
+                            pending = Some(first);
+                            continue;
+                        }
+                    }
+                }
+                '{' => {
+                    check_depth(stack.len(), &options)?;
+
+                    let open = |kind| unclosed(kind, Container::Object, stack.len());
+                    match parser.next_non_whitespace().map_err(open)? {
+                        '}' => JsonValue::Object(&[]),
+                        '"' => {
+                            let key = self.parse_key(parser).map_err(open)?;
+                            stack.push(Frame::Object(self.entries.len(), key));
+                            continue;
+                        }
+                        ch => return Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+                    }
+                }
+                ch => match parser.parse_scalar(ch)? {
+                    JsonValueRef::Null => JsonValue::Null,
+                    JsonValueRef::Boolean(b) => JsonValue::Boolean(b),
+                    JsonValueRef::Number(n) => JsonValue::Number(n),
+                    JsonValueRef::Str(s) => JsonValue::Str(self.bump.alloc_str(&s)),
+                    JsonValueRef::Array(_) | JsonValueRef::Object(_) => unreachable!(),
+                },
+            };
+
+            //hand the value to its container, closing as many containers as end here
+            loop {
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return Ok(value),
+                };
+
+                let ch = parser.next_non_whitespace()?;
+
+                match frame {
+                    Frame::Array(_) => {
+                        self.values.push(value);
+
+                        match ch {
+                            ',' => break,
+                            ']' => {}
+                            ch => return Err(unexpected(ch, Expected::CommaOrArrayEnd)),
+                        }
+                    }
+                    Frame::Object(_, key) => {
+                        self.entries.push((key, value));
+
+                        match ch {
+                            ',' => match parser.next_non_whitespace()? {
+                                '"' => {
+                                    *key = self.parse_key(parser)?;
+                                    break;
+                                }
+                                ch => return Err(unexpected(ch, Expected::ObjectKey)),
+                            },
+                            '}' => {}
+                            ch => return Err(unexpected(ch, Expected::CommaOrObjectEnd)),
+                        }
+                    }
+                }
+
+                value = match stack.pop() {
+                    Some(Frame::Array(start)) => {
+                        let array = self.bump.alloc_slice_copy(&self.values[start..]);
+                        self.values.truncate(start);
+                        JsonValue::Array(array)
+                    }
+                    Some(Frame::Object(start, _)) => {
+                        let entries = self.bump.alloc_slice_copy(&self.entries[start..]);
+                        self.entries.truncate(start);
+                        JsonValue::Object(entries)
+                    }
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, parse_json_str_borrowed, parse_json_string};
+
+    #[test]
+    fn tree_api() -> Result<(), JsonError> {
+        let arena = JsonArena::new();
+        let input = r#"{ "users" : [ { "name" : "ann", "admin" : true }, { "name" : "bøb" } ],
+                         "count" : 2, "next" : null, "count" : 3 }"#;
+        let json = parse_json_in(input, &arena)?;
+
+        assert_eq!(json["users"][1]["name"].as_str(), Some("bøb"));
+        assert_eq!(json["users"][0]["admin"].as_bool(), Some(true));
+        assert_eq!(json.get("count"), Some(&JsonValue::Number(2.)));
+        assert!(json["next"].is_null());
+        assert!(json["users"][2].is_null());
+        assert!(json["missing"]["deeper"][0].is_null());
+        assert_eq!(json.get("next"), Some(&JsonValue::Null));
+        assert_eq!(json.get("missing"), None);
+
+        let names: Vec<&str> = json["users"]
+            .as_array()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|user| user["name"].as_str())
+            .collect();
+        assert_eq!(names, ["ann", "bøb"]);
+
+        let keys: Vec<&str> = json
+            .as_object()
+            .unwrap_or_default()
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        assert_eq!(keys, ["users", "count", "next", "count"]);
+
+        assert_eq!(json.to_owned(), parse_json_string(input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn values_do_not_borrow_the_input() -> Result<(), JsonError> {
+        let arena = JsonArena::new();
+
+        let json = {
+            let input = String::from(r#"[ "a", { "b" : [] } ]"#);
+            parse_json_in(&input, &arena)?
+        };
+
+        assert_eq!(json.to_owned(), json!(["a", { "b": [] }]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reused_across_resets() -> Result<(), JsonError> {
+        let mut arena = JsonArena::with_capacity(4096);
+        let mut capacity = None;
+
+        for i in 0..1000 {
+            let input = format!(
+                r#"{{ "id" : {}, "name" : "user-{}", "tags" : [ "a", "b\n", {{ "x" : [ {} ] }} ] }}"#,
+                i, i, i
+            );
+            {
+                let json = parse_json_in(&input, &arena)?;
+                assert_eq!(json["id"].as_f64(), Some(i as f64));
+                assert_eq!(json.to_owned(), parse_json_string(&input)?);
+            }
+            arena.reset();
+
+            //the memory is reused rather than growing with every document
+            match capacity {
+                None => capacity = Some(arena.allocated_bytes()),
+                Some(capacity) => assert_eq!(arena.allocated_bytes(), capacity),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_as_the_borrowed_parser() {
+        let arena = JsonArena::new();
+
+        for input in [
+            "null",
+            " [ true , false,null ] ",
+            "[ 1, -2.5, 3e2, 0.5 ]",
+            r#"{ "a" : { "b" : [ {}, [], "c" ] }, "a" : 1 }"#,
+            r#"[ [ [ 1 ], { "a" : [ 2, { "b" : 3 } ] } ], [ 4 ] ]"#,
+            "\u{a0}[ \"\u{e9}\" ]\u{2003}",
+            "",
+            "[ 1, ]",
+            "{ \"a\" : 1, }",
+            "{ 1 }",
+            "[ \"a\\q\" ]",
+            "[ 1.",
+            "{ \"a\" : [ 1, 2 }",
+            "[ 1 ]\n\n  \u{e9}",
+        ] {
+            let in_arena = parse_json_in(input, &arena).map(|value| value.to_owned());
+            let borrowed = parse_json_str_borrowed(input).map(|value| value.to_owned());
+            assert_eq!(in_arena, borrowed, "{:?}", input);
+        }
+
+        let deep = "[".repeat(ParseOptions::DEFAULT_MAX_DEPTH + 1);
+        assert_eq!(
+            parse_json_in(&deep, &arena).map_err(|e| e.kind),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+    }
+}
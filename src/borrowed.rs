@@ -0,0 +1,464 @@
+use std::borrow::Cow;
+use std::iter::once;
+use std::str::Chars;
+
+use crate::cursor::Cursor;
+use crate::{
+    check_depth, entry_value, extra_chars, parse_false_impl, parse_null_impl, parse_number_impl,
+    parse_string_impl, parse_true_impl, unclosed, unexpected, Container, Expected, JsonError,
+    JsonErrorKind, JsonObject, Object, ParseOptions, Position,
+};
+
+/// A parsed value borrowing its strings and keys from the input, see
+/// [`parse_json_str_borrowed`]. Only strings containing escapes are allocated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValueRef<'a> {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    Str(Cow<'a, str>),
+    Array(Vec<JsonValueRef<'a>>),
+    /// Entries in document order, duplicate keys included.
+    Object(Vec<(Cow<'a, str>, JsonValueRef<'a>)>),
+}
+
+impl<'a> JsonValueRef<'a> {
+    /// The value of the first entry with the key, `None` when missing or not an object.
+    pub fn get(&self, key: &str) -> Option<&JsonValueRef<'a>> {
+        match self {
+            JsonValueRef::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_ref() == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValueRef::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Copies the value into an owned [`JsonObject`].
+    pub fn to_owned(&self) -> JsonObject {
+        match self {
+            JsonValueRef::Null => JsonObject::Null,
+            JsonValueRef::Boolean(b) => JsonObject::Boolean(*b),
+            JsonValueRef::Number(n) => JsonObject::Number(*n),
+            JsonValueRef::Str(s) => JsonObject::String(s.as_ref().to_owned()),
+            JsonValueRef::Array(array) => {
+                JsonObject::Array(array.iter().map(JsonValueRef::to_owned).collect())
+            }
+            JsonValueRef::Object(entries) => {
+                let mut object = Object::new();
+                object.entries_mut().extend(
+                    entries
+                        .iter()
+                        .map(|(key, value)| (key.as_ref().into(), value.to_owned())),
+                );
+                JsonObject::Object(object)
+            }
+        }
+    }
+}
+
+impl From<JsonValueRef<'_>> for JsonObject {
+    fn from(value: JsonValueRef<'_>) -> Self {
+        match value {
+            JsonValueRef::Null => JsonObject::Null,
+            JsonValueRef::Boolean(b) => JsonObject::Boolean(b),
+            JsonValueRef::Number(n) => JsonObject::Number(n),
+            JsonValueRef::Str(s) => JsonObject::String(s.into_owned()),
+            JsonValueRef::Array(array) => {
+                JsonObject::Array(array.into_iter().map(JsonObject::from).collect())
+            }
+            JsonValueRef::Object(entries) => {
+                let mut object = Object::new();
+                object.entries_mut().extend(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (key.into_owned().into(), JsonObject::from(value))),
+                );
+                JsonObject::Object(object)
+            }
+        }
+    }
+}
+
+/// Parses a whole document like [`crate::parse_json_string`], but strings and keys without
+/// escapes are slices of `input` instead of copies. The input is read by byte offsets, the
+/// grammar and errors are the same as the other entry points'.
+#[inline]
+pub fn parse_json_str_borrowed(input: &str) -> Result<JsonValueRef<'_>, JsonError> {
+    parse_document(input, Parser::parse_value)
+}
+
+//runs `parse` on the value, then checks that nothing but whitespace follows it
+pub(crate) fn parse_document<'a, T>(
+    input: &'a str,
+    parse: impl FnOnce(&mut Parser<'a>) -> Result<T, JsonErrorKind>,
+) -> Result<T, JsonError> {
+    let mut parser = Parser { input, pos: 0 };
+
+    let value = parse(&mut parser).map_err(|kind| parser.error(kind))?;
+
+    match parser.skip_whitespace() {
+        Some(ch) => Err(JsonError {
+            position: parser.position(),
+            kind: extra_chars(once(ch).chain(parser.rest())),
+        }),
+        None => Ok(value),
+    }
+}
+
+enum Partial<'a> {
+    Array(Vec<JsonValueRef<'a>>),
+    //the key of the value being parsed
+    Object(Vec<(Cow<'a, str>, JsonValueRef<'a>)>, Cow<'a, str>),
+}
+
+impl Partial<'_> {
+    fn container(&self) -> Container {
+        match self {
+            Partial::Array(_) => Container::Array,
+            Partial::Object(..) => Container::Object,
+        }
+    }
+}
+
+pub(crate) struct Parser<'a> {
+    input: &'a str,
+    //everything before has been read
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next_char(&mut self) -> Option<char> {
+        let byte = *self.input.as_bytes().get(self.pos)?;
+
+        let ch = if byte.is_ascii() {
+            byte as char
+        } else {
+            self.input[self.pos..].chars().next()?
+        };
+        self.pos += ch.len_utf8();
+
+        Some(ch)
+    }
+
+    //`None` at the end of the input
+    fn skip_whitespace(&mut self) -> Option<char> {
+        loop {
+            match self.next_char()? {
+                ch if ch.is_whitespace() => {}
+                ch => return Some(ch),
+            }
+        }
+    }
+
+    pub(crate) fn next_non_whitespace(&mut self) -> Result<char, JsonErrorKind> {
+        self.skip_whitespace()
+            .ok_or(JsonErrorKind::EarlyEndOfStream)
+    }
+
+    fn rest(&mut self) -> Chars<'a> {
+        let rest = self.input[self.pos..].chars();
+        self.pos = self.input.len();
+        rest
+    }
+
+    //hands the rest of the input to one of the char based parsers
+    fn with_chars<T>(
+        &mut self,
+        f: impl FnOnce(&mut Chars<'a>) -> Result<T, JsonErrorKind>,
+    ) -> Result<T, JsonErrorKind> {
+        let mut chars = self.input[self.pos..].chars();
+        let result = f(&mut chars);
+        self.pos = self.input.len() - chars.as_str().len();
+        result
+    }
+
+    //expects the starting '"' to already be eaten
+    fn parse_string(&mut self) -> Result<Cow<'a, str>, JsonErrorKind> {
+        let start = self.pos;
+
+        for (i, &byte) in self.input.as_bytes()[start..].iter().enumerate() {
+            match byte {
+                b'"' => {
+                    self.pos = start + i + 1;
+                    return Ok(Cow::Borrowed(&self.input[start..start + i]));
+                }
+                //escapes and errors take the slow path from the start
+                b'\\' | 0x00..=0x1F => break,
+                _ => {}
+            }
+        }
+
+        self.with_chars(parse_string_impl).map(Cow::Owned)
+    }
+
+    pub(crate) fn parse_key(&mut self) -> Result<Cow<'a, str>, JsonErrorKind> {
+        let key = self.parse_string()?;
+
+        match self.next_non_whitespace()? {
+            ':' => Ok(key),
+            _ => Err(JsonErrorKind::MissingColon),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValueRef<'a>, JsonErrorKind> {
+        let mut stack = vec![];
+
+        self.parse_nested(&mut stack)
+            .map_err(|kind| match stack.last() {
+                Some(partial) => unclosed(kind, partial.container(), stack.len() - 1),
+                None => kind,
+            })
+    }
+
+    //the same loop as `parse_value_impl`, containers are kept on `stack`
+    fn parse_nested(
+        &mut self,
+        stack: &mut Vec<Partial<'a>>,
+    ) -> Result<JsonValueRef<'a>, JsonErrorKind> {
+        let options = ParseOptions::default();
+        let mut pending: Option<char> = None;
+
+        loop {
+            let first = match pending.take() {
+                Some(ch) => ch,
+                None => self.next_non_whitespace()?,
+            };
+            if let Some(Partial::Object(..)) = stack.last() {
+                entry_value(first)?;
+            }
+
+            let mut value = match first {
+                '[' => {
+                    check_depth(stack.len(), &options)?;
+
+                    //not on the stack until it has a member
+                    let open = |kind| unclosed(kind, Container::Array, stack.len());
+                    match self.next_non_whitespace().map_err(open)? {
+                        ']' => JsonValueRef::Array(vec![]),
+                        first => {
+                            stack.push(Partial::Array(vec![]));
+                            pending = Some(first);
+                            continue;
+                        }
+                    }
+                }
+                '{' => {
+                    check_depth(stack.len(), &options)?;
+
+                    let open = |kind| unclosed(kind, Container::Object, stack.len());
+                    match self.next_non_whitespace().map_err(open)? {
+                        '}' => JsonValueRef::Object(vec![]),
+                        '"' => {
+                            let key = self.parse_key().map_err(open)?;
+                            stack.push(Partial::Object(vec![], key));
+                            continue;
+                        }
+                        ch => return Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+                    }
+                }
+                ch => self.parse_scalar(ch)?,
+            };
+
+            //hand the value to its container, closing as many containers as end here
+            loop {
+                let partial = match stack.last_mut() {
+                    Some(partial) => partial,
+                    None => return Ok(value),
+                };
+
+                let ch = self.next_non_whitespace()?;
+
+                match partial {
+                    Partial::Array(array) => {
+                        array.push(value);
+
+                        match ch {
+                            ',' => break,
+                            ']' => {}
+                            ch => return Err(unexpected(ch, Expected::CommaOrArrayEnd)),
+                        }
+                    }
+                    Partial::Object(entries, key) => {
+                        entries.push((std::mem::take(key), value));
+
+                        match ch {
+                            ',' => match self.next_non_whitespace()? {
+                                '"' => {
+                                    *key = self.parse_key()?;
+                                    break;
+                                }
+                                ch => return Err(unexpected(ch, Expected::ObjectKey)),
+                            },
+                            '}' => {}
+                            ch => return Err(unexpected(ch, Expected::CommaOrObjectEnd)),
+                        }
+                    }
+                }
+
+                value = match stack.pop() {
+                    Some(Partial::Array(array)) => JsonValueRef::Array(array),
+                    Some(Partial::Object(entries, _)) => JsonValueRef::Object(entries),
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    //a value other than a container, starting with the already read `first`
+    pub(crate) fn parse_scalar(&mut self, first: char) -> Result<JsonValueRef<'a>, JsonErrorKind> {
+        let value = match first {
+            'n' => self
+                .with_chars(parse_null_impl)
+                .map(|_| JsonValueRef::Null)?,
+            't' => self
+                .with_chars(parse_true_impl)
+                .map(|_| JsonValueRef::Boolean(true))?,
+            'f' => self
+                .with_chars(parse_false_impl)
+                .map(|_| JsonValueRef::Boolean(false))?,
+            '"' => JsonValueRef::Str(self.parse_string()?),
+            ch => {
+                let (n, excess) = self.with_chars(|chars| {
+                    let mut cursor = Cursor::new(chars);
+                    Ok((parse_number_impl(&mut cursor, ch)?, cursor.into_peeked()))
+                })?;
+                //read again by the container
+                if let Some(excess) = excess {
+                    self.pos -= excess.len_utf8();
+                }
+                JsonValueRef::Number(n)
+            }
+        };
+
+        Ok(value)
+    }
+
+    //of the last char read, counted the same way as `Located` does
+    fn position(&self) -> Position {
+        let read = &self.input[..self.pos];
+        let before_last = match read.char_indices().next_back() {
+            Some((i, _)) => &read[..i],
+            None => return Position { line: 1, column: 0 },
+        };
+        let line_start = before_last.rfind('\n').map_or(0, |i| i + 1);
+
+        Position {
+            line: 1 + before_last.matches('\n').count(),
+            column: before_last[line_start..].chars().count() + 1,
+        }
+    }
+
+    fn error(&self, kind: JsonErrorKind) -> JsonError {
+        JsonError {
+            kind,
+            position: self.position(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json_string;
+
+    #[test]
+    fn borrowed_unless_escaped() -> Result<(), JsonError> {
+        let input =
+            r#"{ "plain" : "text", "esc\"aped" : [ "a\nb", "é😀", "", "\u00e9" ], "n" : -1.5e1 }"#;
+        let value = parse_json_str_borrowed(input)?;
+
+        let entries = match &value {
+            JsonValueRef::Object(entries) => entries,
+            other => panic!("{:?}", other),
+        };
+        assert!(matches!(entries[0].0, Cow::Borrowed("plain")));
+        assert!(matches!(
+            value.get("plain"),
+            Some(JsonValueRef::Str(Cow::Borrowed("text")))
+        ));
+        assert!(matches!(&entries[1].0, Cow::Owned(key) if key == "esc\"aped"));
+
+        let array = match value.get("esc\"aped") {
+            Some(JsonValueRef::Array(array)) => array,
+            other => panic!("{:?}", other),
+        };
+        assert!(matches!(&array[0], JsonValueRef::Str(Cow::Owned(s)) if s == "a\nb"));
+        assert!(matches!(array[1], JsonValueRef::Str(Cow::Borrowed("é😀"))));
+        assert!(matches!(array[2], JsonValueRef::Str(Cow::Borrowed(""))));
+        assert_eq!(array[3].as_str(), Some("é"));
+        assert_eq!(value.get("n"), Some(&JsonValueRef::Number(-15.)));
+
+        assert_eq!(value.to_owned(), parse_json_string(input)?);
+        assert_eq!(JsonObject::from(value), parse_json_string(input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn slices_outlive_the_value() -> Result<(), JsonError> {
+        let input = String::from(r#"[ { "name" : "bob" } ]"#);
+
+        let name: &str = {
+            let value = parse_json_str_borrowed(&input)?;
+            match &value {
+                JsonValueRef::Array(array) => match array[0].get("name") {
+                    Some(JsonValueRef::Str(Cow::Borrowed(name))) => name,
+                    other => panic!("{:?}", other),
+                },
+                other => panic!("{:?}", other),
+            }
+        };
+
+        assert_eq!(name, "bob");
+        assert_eq!(name.as_ptr(), input[14..].as_ptr());
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_as_the_char_parser() {
+        for input in [
+            "null",
+            " [ true , false,null ] ",
+            "[ 1, -2.5, 3e2, 0.5 ]",
+            r#"{ "a" : { "b" : [ {}, [], "c" ] }, "a" : 1 }"#,
+            "\u{a0}[ \"\u{e9}\" ]\u{2003}",
+            "",
+            "x",
+            "[ 1, ]",
+            "[ 1 2 ]",
+            "{ \"a\" 1 }",
+            "{ \"a\" : 1, }",
+            "{ 1 }",
+            "[ nul ]",
+            "[ \"a\\q\" ]",
+            "[ \"a\tb\" ]",
+            "[ \"\\ud83d\" ]",
+            "[ \"open",
+            "[ 1.",
+            "[ -x ]",
+            "[\n  1,\n  \u{e9} ]",
+            "{ \"a\" : [ 1, 2 }",
+            "[ 1 ] x y",
+            "[ 1 ]\n\n  \u{e9}",
+            "1 2",
+        ] {
+            let borrowed = parse_json_str_borrowed(input).map(|value| value.to_owned());
+            assert_eq!(borrowed, parse_json_string(input), "{:?}", input);
+        }
+
+        let deep = "[".repeat(ParseOptions::DEFAULT_MAX_DEPTH + 1);
+        assert_eq!(
+            parse_json_str_borrowed(&deep).map_err(|e| e.kind),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+    }
+}
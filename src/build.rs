@@ -0,0 +1,247 @@
+use crate::{Array, JsonObject, Key, Object};
+
+impl Object {
+    /// Builds an object from key-value pairs. A key given more than once keeps its first position
+    /// and its last value, like [`Object::insert`], unlike `collect` which keeps every entry.
+    pub fn from_pairs<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Object
+    where
+        K: Into<Key>,
+        V: Into<JsonObject>,
+    {
+        let mut object = Object::new();
+        for (key, value) in pairs {
+            object.insert(key, value.into());
+        }
+
+        object
+    }
+
+    #[inline]
+    pub fn builder() -> ObjectBuilder {
+        ObjectBuilder::new()
+    }
+}
+
+/// Chainable construction of an [`Object`], e.g. for API responses where optional fields are
+/// left out rather than written as `null`. Keys follow the duplicate policy of
+/// [`Object::from_pairs`].
+///
+/// ```
+/// use json_parser::{json, JsonObject, ObjectBuilder};
+///
+/// let nickname: Option<&str> = None;
+/// let user = ObjectBuilder::new()
+///     .key("name", "bob")
+///     .key_opt("nickname", nickname)
+///     .key_if(true, "admin", true)
+///     .build();
+///
+/// assert_eq!(JsonObject::from(user), json!({ "name": "bob", "admin": true }));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectBuilder {
+    object: Object,
+}
+
+impl ObjectBuilder {
+    #[inline]
+    pub const fn new() -> Self {
+        ObjectBuilder {
+            object: Object::new(),
+        }
+    }
+
+    pub fn key(mut self, key: impl Into<Key>, value: impl Into<JsonObject>) -> Self {
+        self.object.insert(key, value.into());
+        self
+    }
+
+    //the value is built either way, see `key_opt` to build it only when needed
+    pub fn key_if(
+        self,
+        condition: bool,
+        key: impl Into<Key>,
+        value: impl Into<JsonObject>,
+    ) -> Self {
+        if condition {
+            self.key(key, value)
+        } else {
+            self
+        }
+    }
+
+    /// Adds the entry when there is a value, `None` leaves the key out entirely.
+    pub fn key_opt<V: Into<JsonObject>>(self, key: impl Into<Key>, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.key(key, value),
+            None => self,
+        }
+    }
+
+    #[inline]
+    pub fn build(self) -> Object {
+        self.object
+    }
+}
+
+impl From<ObjectBuilder> for JsonObject {
+    #[inline]
+    fn from(builder: ObjectBuilder) -> Self {
+        JsonObject::Object(builder.build())
+    }
+}
+
+/// The [`ObjectBuilder`] of arrays.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrayBuilder {
+    array: Array,
+}
+
+impl ArrayBuilder {
+    #[inline]
+    pub const fn new() -> Self {
+        ArrayBuilder { array: Vec::new() }
+    }
+
+    pub fn push(mut self, value: impl Into<JsonObject>) -> Self {
+        self.array.push(value.into());
+        self
+    }
+
+    pub fn push_if(self, condition: bool, value: impl Into<JsonObject>) -> Self {
+        if condition {
+            self.push(value)
+        } else {
+            self
+        }
+    }
+
+    //`None` adds nothing rather than a null
+    pub fn push_opt<V: Into<JsonObject>>(self, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.push(value),
+            None => self,
+        }
+    }
+
+    pub fn extend<V: Into<JsonObject>>(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        self.array.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    #[inline]
+    pub fn build(self) -> Array {
+        self.array
+    }
+}
+
+impl From<ArrayBuilder> for JsonObject {
+    #[inline]
+    fn from(builder: ArrayBuilder) -> Self {
+        JsonObject::Array(builder.build())
+    }
+}
+
+/// An array of anything that converts into a [`JsonObject`], e.g. `array_of(vec![1, 2, 3])`.
+pub fn array_of<V: Into<JsonObject>>(values: impl IntoIterator<Item = V>) -> JsonObject {
+    JsonObject::Array(values.into_iter().map(Into::into).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    struct User {
+        name: &'static str,
+        email: Option<&'static str>,
+        age: Option<u32>,
+        tags: Vec<&'static str>,
+        admin: bool,
+    }
+
+    fn response(user: &User) -> JsonObject {
+        ObjectBuilder::new()
+            .key("name", user.name)
+            .key_opt("email", user.email)
+            .key_opt("age", user.age)
+            .key_if(!user.tags.is_empty(), "tags", array_of(user.tags.clone()))
+            .key_if(user.admin, "admin", true)
+            .key(
+                "links",
+                ArrayBuilder::new()
+                    .push("/self")
+                    .push_opt(user.email.map(|email| format!("mailto:{}", email)))
+                    .push_if(user.admin, "/admin"),
+            )
+            .into()
+    }
+
+    #[test]
+    fn optional_fields_are_left_out() -> Result<(), JsonError> {
+        let full = User {
+            name: "ann",
+            email: Some("ann@example.com"),
+            age: Some(41),
+            tags: vec!["a", "b"],
+            admin: true,
+        };
+        assert_eq!(
+            response(&full),
+            parse_json_string(
+                r#"{
+                    "name": "ann", "email": "ann@example.com", "age": 41, "tags": ["a", "b"],
+                    "admin": true, "links": ["/self", "mailto:ann@example.com", "/admin"]
+                }"#
+            )?
+        );
+
+        let sparse = User {
+            name: "bob",
+            email: None,
+            age: None,
+            tags: vec![],
+            admin: false,
+        };
+        assert_eq!(
+            response(&sparse),
+            parse_json_string(r#"{ "name": "bob", "links": ["/self"] }"#)?
+        );
+        assert_eq!(
+            response(&sparse).to_string(),
+            r#"{"name":"bob","links":["/self"]}"#
+        );
+
+        //an explicit null is still written
+        let with_null = ObjectBuilder::new()
+            .key_opt("a", Some(JsonObject::Null))
+            .key_opt::<JsonObject>("b", None)
+            .build();
+        assert_eq!(JsonObject::from(with_null), json!({ "a": null }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys() -> Result<(), JsonError> {
+        let object = Object::from_pairs(vec![("a", 1), ("b", 2), ("a", 3)]);
+        assert_eq!(object.len(), 2);
+        assert_eq!(
+            JsonObject::from(object),
+            parse_json_string(r#"{ "a": 3, "b": 2 }"#)?
+        );
+
+        let built = Object::builder()
+            .key("x", "first")
+            .key(String::from("x"), "last")
+            .build();
+        assert_eq!(built.get("x"), Some(&JsonObject::from("last")));
+        assert_eq!(built.len(), 1);
+
+        assert_eq!(
+            Object::from_pairs(Vec::<(&str, bool)>::new()),
+            Object::new()
+        );
+
+        Ok(())
+    }
+}
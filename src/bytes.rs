@@ -0,0 +1,149 @@
+use std::str::{Chars, Utf8Chunks};
+
+use crate::cursor::Source;
+use crate::position::Located;
+use crate::{parse_document_impl, JsonError, JsonErrorKind, JsonObject, ParseOptions, ParseReport};
+
+//decodes while the parser reads, ending the input at the first invalid sequence whose offset is
+//kept aside so the caller can report it instead of the parse error it causes
+struct Utf8Chars<'a> {
+    chunks: Utf8Chunks<'a>,
+    valid: Chars<'a>,
+    offset: usize,
+    invalid: Option<usize>,
+}
+
+impl Iterator for Utf8Chars<'_> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(ch) = self.valid.next() {
+                return Some(ch);
+            }
+
+            if self.invalid.is_some() {
+                return None;
+            }
+
+            let chunk = self.chunks.next()?;
+            self.valid = chunk.valid().chars();
+            if !chunk.invalid().is_empty() {
+                self.invalid = Some(self.offset + chunk.valid().len());
+            }
+            self.offset += chunk.valid().len() + chunk.invalid().len();
+        }
+    }
+}
+
+//within the valid part of the current chunk, the next one is decoded by `next`
+impl Source for Utf8Chars<'_> {
+    #[inline]
+    fn read_unescaped(&mut self, out: &mut String, max_len: usize) {
+        self.valid.read_unescaped(out, max_len)
+    }
+}
+
+/// Parses UTF-8 bytes without validating or copying them up front, an optional byte order mark
+/// is skipped. Malformed or truncated UTF-8 fails with [`JsonErrorKind::InvalidUtf8`], which
+/// takes precedence over the parse error it caused.
+pub fn parse_json_bytes(input: &[u8]) -> Result<JsonObject, JsonError> {
+    const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+    let (input, skipped) = match input.strip_prefix(BOM) {
+        Some(rest) => (rest, BOM.len()),
+        None => (input, 0),
+    };
+
+    let mut located = Located::new(Utf8Chars {
+        chunks: input.utf8_chunks(),
+        valid: "".chars(),
+        offset: skipped,
+        invalid: None,
+    });
+    let result = parse_document_impl(
+        &mut located,
+        &ParseOptions::default(),
+        &mut ParseReport::default(),
+    );
+
+    match located.get_mut().invalid {
+        Some(offset) => Err(located.error(JsonErrorKind::InvalidUtf8 { offset })),
+        None => result,
+    }
+}
+
+impl JsonObject {
+    /// See [`parse_json_bytes`].
+    #[inline]
+    pub fn from_bytes(input: &[u8]) -> Result<JsonObject, JsonError> {
+        parse_json_bytes(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_json_string, Position};
+
+    #[test]
+    fn valid_input() -> Result<(), JsonError> {
+        let source = "{ \"clé\" : [ \"😀 naïve\", 1, { \"日本\" : null } ] }";
+        assert_eq!(
+            parse_json_bytes(source.as_bytes())?,
+            parse_json_string(source)?
+        );
+
+        let with_bom = [b"\xEF\xBB\xBF".as_slice(), source.as_bytes()].concat();
+        assert_eq!(parse_json_bytes(&with_bom)?, parse_json_string(source)?);
+        assert_eq!(
+            JsonObject::from_bytes(&with_bom)?,
+            parse_json_string(source)?
+        );
+
+        //only one, and only at the start
+        assert!(parse_json_bytes(b"\xEF\xBB\xBF\xEF\xBB\xBF[]").is_err());
+        assert!(parse_json_bytes(b"[]\xEF\xBB\xBF").is_err());
+        assert!(parse_json_string("\u{feff}[]").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let kind = |input: &[u8]| parse_json_bytes(input).map_err(|e| e.kind);
+
+        //a bad continuation byte in a string and a key
+        assert_eq!(
+            kind(b"[ \"ab\xC3(\" ]"),
+            Err(JsonErrorKind::InvalidUtf8 { offset: 5 })
+        );
+        assert_eq!(
+            kind(b"{ \"k\xFF\" : 1 }"),
+            Err(JsonErrorKind::InvalidUtf8 { offset: 4 })
+        );
+        //truncated sequences at the end, even after a complete value
+        assert_eq!(
+            kind(b"[ \"\xF0\x9F\x98"),
+            Err(JsonErrorKind::InvalidUtf8 { offset: 3 })
+        );
+        assert_eq!(
+            kind(b"[] \xE2\x82"),
+            Err(JsonErrorKind::InvalidUtf8 { offset: 3 })
+        );
+        //offsets count the byte order mark
+        assert_eq!(
+            kind(b"\xEF\xBB\xBF[ \x80 ]"),
+            Err(JsonErrorKind::InvalidUtf8 { offset: 5 })
+        );
+
+        //positioned at the last char decoded
+        let error = parse_json_bytes(b"[\n  \"\xC3\xA9\xFF\" ]").unwrap_err();
+        assert_eq!(error.position, Position { line: 2, column: 4 });
+        assert_eq!(
+            error.to_string(),
+            "invalid UTF-8 at byte 7 at line 2, column 4"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{exact_integer, JsonObject, Object};
+
+impl JsonObject {
+    /// Equality up to representation. Object members may come in any order while arrays keep
+    /// theirs. Numbers are compared by value, so `-0` equals `0` and a raw `1.0` equals `1`.
+    /// Objects with duplicate keys are equal when they hold the same members the same number of
+    /// times.
+    pub fn canonical_eq(&self, other: &JsonObject) -> bool {
+        let mut pending = vec![(self, other)];
+
+        while let Some((left, right)) = pending.pop() {
+            match (left, right) {
+                (JsonObject::Object(left), JsonObject::Object(right)) => {
+                    if left.entries().len() != right.entries().len() {
+                        return false;
+                    }
+
+                    for ((left_key, left), (right_key, right)) in
+                        sorted_entries(left).into_iter().zip(sorted_entries(right))
+                    {
+                        if left_key != right_key {
+                            return false;
+                        }
+                        pending.push((left, right));
+                    }
+                }
+                (JsonObject::Array(left), JsonObject::Array(right)) => {
+                    if left.len() != right.len() {
+                        return false;
+                    }
+                    pending.extend(left.iter().zip(right));
+                }
+                (left, right) if left.is_number() && right.is_number() => {
+                    if !number_eq(left, right) {
+                        return false;
+                    }
+                }
+                (left, right) => {
+                    if left != right {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+//plain comparisons with scalars, for assertions like `assert_eq!(json["name"], "bob")`
+impl PartialEq<str> for JsonObject {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, JsonObject::String(s) if s == other)
+    }
+}
+
+impl PartialEq<&str> for JsonObject {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<f64> for JsonObject {
+    fn eq(&self, other: &f64) -> bool {
+        self.as_f64() == Some(*other)
+    }
+}
+
+impl PartialEq<bool> for JsonObject {
+    fn eq(&self, other: &bool) -> bool {
+        matches!(self, JsonObject::Boolean(b) if b == other)
+    }
+}
+
+//raw numbers too, integral literals are compared exactly past the precision of an f64
+pub(crate) fn number_eq(left: &JsonObject, right: &JsonObject) -> bool {
+    let exact = |n: &JsonObject| match n {
+        JsonObject::RawNumber(literal) => exact_integer(literal),
+        _ => None,
+    };
+
+    match (exact(left), exact(right)) {
+        (Some(left), Some(right)) => left == right,
+        _ => left.as_f64() == right.as_f64(),
+    }
+}
+
+//stable, so duplicate keys keep their relative order
+fn sorted_entries(object: &Object) -> Vec<(&str, &JsonObject)> {
+    let mut entries: Vec<_> = object
+        .entries()
+        .iter()
+        .map(|(key, value)| (key.as_str(), value))
+        .collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn representation_details() -> Result<(), JsonError> {
+        let pairs = [
+            (
+                r#"{ "a" : 1, "b" : [1, 2] }"#,
+                r#"{ "b" : [1, 2], "a" : 1.0 }"#,
+            ),
+            ("[ 0 ]", "[ -0.0 ]"),
+            ("[1]", "[1e0]"),
+            ("[100]", "[1E2]"),
+            (r#""é\n""#, r#""é\u000a""#),
+            (r#"{ "é" : null }"#, r#"{ "é" : null }"#),
+            (r#"{ "a" : 1, "a" : 2 }"#, r#"{ "a" : 1, "a" : 2 }"#),
+        ];
+
+        for (left, right) in &pairs {
+            let (left, right) = (parse_json_string(left)?, parse_json_string(right)?);
+            assert!(left.canonical_eq(&right), "{:?} {:?}", left, right);
+            assert!(right.canonical_eq(&left));
+        }
+
+        let different = [
+            ("[1, 2]", "[2, 1]"),
+            (r#"{ "a" : 1 }"#, r#"{ "a" : 1, "b" : 2 }"#),
+            (r#"{ "a" : 1, "a" : 2 }"#, r#"{ "a" : 2, "a" : 1 }"#),
+            (r#"{ "a" : null }"#, r#"{ "b" : null }"#),
+            ("[0]", "[false]"),
+            (r#""1""#, "1"),
+        ];
+
+        for (left, right) in &different {
+            let (left, right) = (parse_json_string(left)?, parse_json_string(right)?);
+            assert!(!left.canonical_eq(&right), "{:?} {:?}", left, right);
+            assert!(!right.canonical_eq(&left));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn raw_numbers() -> Result<(), JsonError> {
+        let raw = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..ParseOptions::default()
+        };
+        let parse = |input| parse_json_string_with_options(input, &raw);
+
+        let left = parse(r#"{ "a" : [1, -0, 2.5], "b" : 18446744073709551617 }"#)?;
+        let right = parse(r#"{ "b" : 18446744073709551617.0, "a" : [1.0, 0, 25e-1] }"#)?;
+        assert!(left.canonical_eq(&right));
+        assert!(left.canonical_eq(&parse_json_string(
+            r#"{ "a" : [1, 0, 2.5], "b" : 18446744073709551617 }"#
+        )?));
+
+        //integers differing past the precision of an f64
+        assert!(!parse("9007199254740993")?.canonical_eq(&parse("9007199254740992")?));
+        assert!(!parse("1")?.canonical_eq(&parse("\"1\"")?));
+
+        let numbers = parse("[5, 25e-1, 12345678901234567890123]")?;
+        assert_eq!(numbers[0], 5.);
+        assert_eq!(numbers[1], 2.5);
+        assert_eq!(numbers[2], 12345678901234567890123.);
+        assert_ne!(numbers[0], 5.5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_comparisons() -> Result<(), JsonError> {
+        let json =
+            parse_json_string(r#"{ "name" : "bob", "age" : 42, "admin" : true, "id" : "42" }"#)?;
+
+        assert_eq!(json["name"], "bob");
+        assert_eq!(json["age"], 42.);
+        assert_eq!(json["admin"], true);
+        assert!(json["name"] == *"bob");
+
+        //no conversions between types
+        assert_ne!(json["id"], 42.);
+        assert_ne!(json["age"], "42");
+        assert_ne!(json["missing"], false);
+        assert_ne!(json["name"], "Bob");
+
+        Ok(())
+    }
+
+    #[test]
+    fn clones_are_independent() -> Result<(), JsonError> {
+        let json = parse_json_string(r#"{ "user" : { "name" : "bob", "tags" : [ "a" ] } }"#)?;
+
+        let mut user = json["user"].clone();
+        user["name"] = JsonObject::from("ann");
+        user["tags"]
+            .as_array_mut()
+            .unwrap()
+            .push(JsonObject::from("b"));
+
+        assert_eq!(json["user"]["name"], "bob");
+        assert_eq!(
+            json["user"]["tags"],
+            JsonObject::from(vec![JsonObject::from("a")])
+        );
+        assert_eq!(user["name"], "ann");
+        assert_eq!(user["tags"][1], "b");
+
+        assert_eq!(JsonObject::default(), JsonObject::Null);
+        assert!(Object::default().is_empty());
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn canonical_round_trip(value in strategies::documents(strategies::strings(), 4)) {
+            let canonical = value.serialize(&SerializeOptions::canonical()).unwrap();
+            let parsed = parse_json_string(&canonical).unwrap();
+
+            prop_assert!(parsed.canonical_eq(&value), "{}", canonical);
+            prop_assert!(value.canonical_eq(&parsed));
+        }
+    }
+}
@@ -0,0 +1,688 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use crate::{parse_json_string, JsonError, JsonObject, Key, Object};
+
+#[derive(Debug, PartialEq)]
+pub enum GetParsedErrorKind<E> {
+    Missing,
+    /// Null, arrays and objects have no text to parse.
+    NotAScalar,
+    Parse(E),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GetParsedError<E> {
+    /// The key looked up, `None` when parsing a value directly.
+    pub key: Option<String>,
+    pub kind: GetParsedErrorKind<E>,
+}
+
+impl<E: fmt::Display> fmt::Display for GetParsedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(key) = &self.key {
+            write!(f, "key {:?}: ", key)?;
+        }
+
+        match &self.kind {
+            GetParsedErrorKind::Missing => write!(f, "missing"),
+            GetParsedErrorKind::NotAScalar => write!(f, "not a string, number or boolean"),
+            GetParsedErrorKind::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for GetParsedError<E> {}
+
+impl JsonObject {
+    /// Parses a value with [`FromStr`]. Strings are parsed as they are, numbers and booleans are
+    /// rendered first with [`JsonObject::to_display_string`] so `8080` and `"8080"` both give a
+    /// port. A [`JsonObject::RawNumber`] is parsed from its literal, without going through `f64`.
+    pub fn get_parsed<T: FromStr>(&self) -> Result<T, GetParsedError<T::Err>> {
+        let error = |kind| GetParsedError { key: None, kind };
+
+        let text = match self {
+            JsonObject::String(s) => {
+                return s.parse().map_err(|e| error(GetParsedErrorKind::Parse(e)))
+            }
+            JsonObject::Number(_) | JsonObject::RawNumber(_) | JsonObject::Boolean(_) => {
+                self.to_display_string()
+            }
+            _ => None,
+        };
+
+        text.ok_or_else(|| error(GetParsedErrorKind::NotAScalar))?
+            .parse()
+            .map_err(|e| error(GetParsedErrorKind::Parse(e)))
+    }
+}
+
+impl Object {
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Result<T, GetParsedError<T::Err>> {
+        let value = self.get(key).ok_or(GetParsedError {
+            key: Some(key.to_owned()),
+            kind: GetParsedErrorKind::Missing,
+        })?;
+
+        value.get_parsed().map_err(|err| GetParsedError {
+            key: Some(key.to_owned()),
+            ..err
+        })
+    }
+}
+
+/// Why a value could not be converted with `TryFrom` or [`Object::get_as`].
+#[derive(Debug, PartialEq)]
+pub enum ConversionError {
+    /// The value has another type, e.g. a string where a number was expected.
+    Mismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// A number that is not integral or does not fit the target type.
+    InvalidNumber {
+        expected: &'static str,
+        found: f64,
+    },
+    MissingKey(String),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::Mismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            ConversionError::InvalidNumber { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            ConversionError::MissingKey(key) => write!(f, "missing key {:?}", key),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl JsonObject {
+    pub(crate) fn mismatch(&self, expected: &'static str) -> ConversionError {
+        ConversionError::Mismatch {
+            expected,
+            found: self.json_type().name(),
+        }
+    }
+}
+
+impl Object {
+    /// Looks up `key` and converts its value, e.g. `object.get_as::<Vec<String>>("tags")`.
+    pub fn get_as<'a, T>(&'a self, key: &str) -> Result<T, ConversionError>
+    where
+        T: TryFrom<&'a JsonObject, Error = ConversionError>,
+    {
+        self.get(key)
+            .ok_or_else(|| ConversionError::MissingKey(key.to_owned()))
+            .and_then(T::try_from)
+    }
+}
+
+impl TryFrom<JsonObject> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: JsonObject) -> Result<Self, Self::Error> {
+        match value {
+            JsonObject::String(s) => Ok(s),
+            other => Err(other.mismatch("string")),
+        }
+    }
+}
+
+impl TryFrom<&JsonObject> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: &JsonObject) -> Result<Self, Self::Error> {
+        <&str>::try_from(value).map(str::to_owned)
+    }
+}
+
+impl<'a> TryFrom<&'a JsonObject> for &'a str {
+    type Error = ConversionError;
+
+    fn try_from(value: &'a JsonObject) -> Result<Self, Self::Error> {
+        value.as_str().ok_or_else(|| value.mismatch("string"))
+    }
+}
+
+impl TryFrom<&JsonObject> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(value: &JsonObject) -> Result<Self, Self::Error> {
+        value.as_f64().ok_or_else(|| value.mismatch("number"))
+    }
+}
+
+impl TryFrom<&JsonObject> for i64 {
+    type Error = ConversionError;
+
+    //only integral numbers in range, see `JsonObject::as_i64`
+    fn try_from(value: &JsonObject) -> Result<Self, Self::Error> {
+        let n = f64::try_from(value)?;
+
+        value.as_i64().ok_or(ConversionError::InvalidNumber {
+            expected: "i64",
+            found: n,
+        })
+    }
+}
+
+impl TryFrom<&JsonObject> for bool {
+    type Error = ConversionError;
+
+    fn try_from(value: &JsonObject) -> Result<Self, Self::Error> {
+        value.as_bool().ok_or_else(|| value.mismatch("boolean"))
+    }
+}
+
+//scalars are copied either way, the owned versions are for symmetry with `String`
+macro_rules! try_from_owned {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<JsonObject> for $ty {
+                type Error = ConversionError;
+
+                #[inline]
+                fn try_from(value: JsonObject) -> Result<Self, Self::Error> {
+                    <$ty>::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+try_from_owned!(f64, i64, bool);
+
+impl<T: TryFrom<JsonObject, Error = ConversionError>> TryFrom<JsonObject> for Vec<T> {
+    type Error = ConversionError;
+
+    fn try_from(value: JsonObject) -> Result<Self, Self::Error> {
+        match value {
+            JsonObject::Array(array) => array.into_iter().map(T::try_from).collect(),
+            other => Err(other.mismatch("array")),
+        }
+    }
+}
+
+impl<'a, T: TryFrom<&'a JsonObject, Error = ConversionError>> TryFrom<&'a JsonObject> for Vec<T> {
+    type Error = ConversionError;
+
+    fn try_from(value: &'a JsonObject) -> Result<Self, Self::Error> {
+        value
+            .as_array()
+            .ok_or_else(|| value.mismatch("array"))?
+            .iter()
+            .map(T::try_from)
+            .collect()
+    }
+}
+
+//later entries replace earlier ones with the same key, like they would in the map
+macro_rules! try_from_object {
+    ($($map:ident),*) => {
+        $(
+            impl<T: TryFrom<JsonObject, Error = ConversionError>> TryFrom<JsonObject>
+                for $map<String, T>
+            {
+                type Error = ConversionError;
+
+                fn try_from(value: JsonObject) -> Result<Self, Self::Error> {
+                    match value {
+                        JsonObject::Object(object) => object
+                            .into_iter()
+                            .map(|(key, value)| Ok((key.into_string(), T::try_from(value)?)))
+                            .collect(),
+                        other => Err(other.mismatch("object")),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+try_from_object!(HashMap, BTreeMap);
+
+impl JsonObject {
+    /// Converts an object into a map of `T`, failing on the first value that does not convert.
+    /// Of duplicate keys the last one wins. See `BTreeMap::try_from` for a sorted map.
+    #[inline]
+    pub fn try_into_map<T>(self) -> Result<HashMap<String, T>, ConversionError>
+    where
+        T: TryFrom<JsonObject, Error = ConversionError>,
+    {
+        HashMap::try_from(self)
+    }
+}
+
+impl Object {
+    /// The entries as a map, the last of duplicate keys wins.
+    #[inline]
+    pub fn into_hashmap(self) -> HashMap<String, JsonObject> {
+        self.into_iter()
+            .map(|(key, value)| (key.into_string(), value))
+            .collect()
+    }
+
+    /// The entries as a map sorted by key, the last of duplicate keys wins.
+    #[inline]
+    pub fn into_btreemap(self) -> BTreeMap<String, JsonObject> {
+        self.into_iter()
+            .map(|(key, value)| (key.into_string(), value))
+            .collect()
+    }
+}
+
+/// Entries in the map's iteration order, which is unspecified.
+impl<K: Into<Key>, T: Into<JsonObject>, S: BuildHasher> From<HashMap<K, T, S>> for Object {
+    fn from(map: HashMap<K, T, S>) -> Self {
+        map.into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect()
+    }
+}
+
+/// Entries sorted by key.
+impl<K: Into<Key>, T: Into<JsonObject>> From<BTreeMap<K, T>> for Object {
+    fn from(map: BTreeMap<K, T>) -> Self {
+        map.into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect()
+    }
+}
+
+impl<K: Into<Key>, T: Into<JsonObject>, S: BuildHasher> From<HashMap<K, T, S>> for JsonObject {
+    #[inline]
+    fn from(map: HashMap<K, T, S>) -> Self {
+        JsonObject::Object(map.into())
+    }
+}
+
+impl<K: Into<Key>, T: Into<JsonObject>> From<BTreeMap<K, T>> for JsonObject {
+    #[inline]
+    fn from(map: BTreeMap<K, T>) -> Self {
+        JsonObject::Object(map.into())
+    }
+}
+
+/// The same as [`parse_json_string`], so `text.parse::<JsonObject>()`
+/// also rejects anything but whitespace after the value.
+impl FromStr for JsonObject {
+    type Err = JsonError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_json_string(s)
+    }
+}
+
+impl Default for JsonObject {
+    #[inline]
+    fn default() -> Self {
+        JsonObject::Null
+    }
+}
+
+macro_rules! from_number {
+    ($($ty:ty),*) => {
+        $(
+            //integers beyond 2^53 lose precision like they do when parsed
+            impl From<$ty> for JsonObject {
+                #[inline]
+                fn from(n: $ty) -> Self {
+                    JsonObject::Number(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+from_number!(f64, f32, i64, i32, u64, u32, usize);
+
+impl From<&str> for JsonObject {
+    #[inline]
+    fn from(s: &str) -> Self {
+        JsonObject::String(s.to_owned())
+    }
+}
+
+impl From<String> for JsonObject {
+    #[inline]
+    fn from(s: String) -> Self {
+        JsonObject::String(s)
+    }
+}
+
+impl From<bool> for JsonObject {
+    #[inline]
+    fn from(b: bool) -> Self {
+        JsonObject::Boolean(b)
+    }
+}
+
+impl From<Vec<JsonObject>> for JsonObject {
+    #[inline]
+    fn from(array: Vec<JsonObject>) -> Self {
+        JsonObject::Array(array)
+    }
+}
+
+impl From<Object> for JsonObject {
+    #[inline]
+    fn from(object: Object) -> Self {
+        JsonObject::Object(object)
+    }
+}
+
+//entries are kept as they come, duplicate keys included
+impl<K: Into<Key>> FromIterator<(K, JsonObject)> for Object {
+    fn from_iter<I: IntoIterator<Item = (K, JsonObject)>>(iter: I) -> Self {
+        Object::from_impl(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+}
+
+//appended after the existing entries, duplicate keys included
+impl<K: Into<Key>> Extend<(K, JsonObject)> for Object {
+    fn extend<I: IntoIterator<Item = (K, JsonObject)>>(&mut self, iter: I) {
+        self.entries
+            .extend(iter.into_iter().map(|(key, value)| (key.into(), value)));
+    }
+}
+
+impl FromIterator<JsonObject> for JsonObject {
+    fn from_iter<I: IntoIterator<Item = JsonObject>>(iter: I) -> Self {
+        JsonObject::Array(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::net::IpAddr;
+
+    #[test]
+    fn building_documents() -> Result<(), JsonError> {
+        let tags: JsonObject = ["a", "b"].iter().copied().map(JsonObject::from).collect();
+
+        let user: Object = vec![
+            ("name", JsonObject::from("bob")),
+            ("age", 42.into()),
+            ("score", 4.5.into()),
+            ("active", true.into()),
+            ("tags", tags),
+            ("meta", JsonObject::default()),
+        ]
+        .into_iter()
+        .collect();
+
+        let json = JsonObject::from(vec![
+            JsonObject::from(user),
+            JsonObject::from(String::from("x")),
+            JsonObject::from(-1_i64),
+            JsonObject::from(u32::MAX),
+            Object::new().into(),
+        ]);
+
+        assert_eq!(
+            json,
+            parse_json_string(
+                r#"[
+                    { "name" : "bob", "age" : 42, "score" : 4.5, "active" : true, "tags" : [ "a", "b" ], "meta" : null },
+                    "x", -1, 4294967295, {}
+                ]"#,
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn std_maps() -> Result<(), Box<dyn std::error::Error>> {
+        use std::collections::{BTreeMap, HashMap};
+        use std::convert::TryFrom;
+
+        let prices: BTreeMap<String, f64> = [("pear", 1.5), ("apple", 0.25)]
+            .iter()
+            .map(|&(name, price)| (name.to_owned(), price))
+            .collect();
+        let json = JsonObject::from(prices.clone());
+        //sorted, whatever the insertion order
+        assert_eq!(json.to_json_string(), r#"{"apple":0.25,"pear":1.5}"#);
+        assert_eq!(BTreeMap::<String, f64>::try_from(json.clone())?, prices);
+        assert_eq!(json.try_into_map::<f64>()?, prices.into_iter().collect());
+
+        let nested: HashMap<&str, JsonObject> = vec![
+            ("tags", json!(["a", "b"])),
+            ("owner", json!({ "name": "ann", "ids": [1, 2] })),
+            ("none", JsonObject::Null),
+        ]
+        .into_iter()
+        .collect();
+        let json = JsonObject::from(nested.clone());
+        assert_eq!(json.as_object().unwrap().len(), 3);
+        assert_eq!(json.get_path("owner.ids.1"), Some(&json!(2)));
+
+        let back = json.clone().into_object().unwrap().into_hashmap();
+        assert_eq!(back.len(), 3);
+        for (key, value) in &nested {
+            assert_eq!(back.get(*key), Some(value));
+        }
+
+        //every value has to convert, which one fails first depends on the map order
+        assert!(matches!(
+            json.try_into_map::<Vec<String>>(),
+            Err(ConversionError::Mismatch {
+                expected: "array",
+                found: "object" | "null"
+            })
+        ));
+        assert_eq!(
+            json!([1]).try_into_map::<f64>(),
+            Err(ConversionError::Mismatch {
+                expected: "object",
+                found: "array"
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn merged_maps_keep_the_last_duplicate() -> Result<(), Box<dyn std::error::Error>> {
+        use std::collections::{BTreeMap, HashMap};
+
+        let defaults: BTreeMap<&str, JsonObject> =
+            vec![("host", json!("localhost")), ("port", json!(80))]
+                .into_iter()
+                .collect();
+        let overrides: HashMap<&str, i64> = vec![("port", 8080)].into_iter().collect();
+
+        let mut object = Object::from(defaults);
+        object.extend(Object::from(overrides));
+
+        //both entries are kept, lookups find the first
+        assert_eq!(object.len(), 3);
+        assert_eq!(object.get("port"), Some(&json!(80)));
+        assert_eq!(object.key_count("port"), 2);
+
+        let map = object.clone().into_btreemap();
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            [
+                ("host".to_owned(), json!("localhost")),
+                ("port".to_owned(), json!(8080))
+            ]
+        );
+        assert_eq!(object.into_hashmap()["port"], json!(8080));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_str() -> Result<(), JsonError> {
+        let json: JsonObject = r#" { "a" : [ 1, "two" ] } "#.parse()?;
+        assert_eq!(json, json!({ "a": [1, "two"] }));
+        assert_eq!("null".parse::<JsonObject>()?, JsonObject::Null);
+
+        assert_eq!(
+            "[ 1 ] [ 2 ]".parse::<JsonObject>().map_err(|e| e.kind),
+            Err(JsonErrorKind::ExtraChars {
+                preview: "[ 2 ]".to_owned(),
+                count: 5
+            })
+        );
+        assert_eq!(
+            "{ \"a\" 1 }".parse::<JsonObject>(),
+            parse_json_string("{ \"a\" 1 }")
+        );
+        assert!("".parse::<JsonObject>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsed_values() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(
+            r#"{ "port" : "8080", "admin_port" : 9090, "host" : "::1", "debug" : true, "tags" : [] }"#,
+        )?;
+        let config = json.as_object().unwrap();
+
+        assert_eq!(config.get_parsed::<u16>("port")?, 8080);
+        assert_eq!(config.get_parsed::<u16>("admin_port")?, 9090);
+        assert_eq!(
+            config.get_parsed::<IpAddr>("host")?,
+            "::1".parse::<IpAddr>()?
+        );
+        assert!(config.get_parsed::<bool>("debug")?);
+        assert_eq!(
+            config.get_parsed::<String>("tags"),
+            Err(GetParsedError {
+                key: Some("tags".to_owned()),
+                kind: GetParsedErrorKind::NotAScalar
+            })
+        );
+        assert_eq!(
+            config.get_parsed::<u16>("missing"),
+            Err(GetParsedError {
+                key: Some("missing".to_owned()),
+                kind: GetParsedErrorKind::Missing
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn typed_extraction() -> Result<(), Box<dyn std::error::Error>> {
+        use std::convert::{TryFrom, TryInto};
+
+        let json = parse_json_string(
+            r#"{ "name" : "bob", "tags" : [ "a", "b" ], "grid" : [ [ 1, 2 ], [ 3 ] ], "age" : 42, "admin" : false }"#,
+        )?;
+        let user = json.as_object().unwrap();
+
+        assert_eq!(user.get_as::<String>("name")?, "bob");
+        assert_eq!(user.get_as::<&str>("name")?, "bob");
+        assert_eq!(user.get_as::<Vec<String>>("tags")?, ["a", "b"]);
+        assert_eq!(
+            user.get_as::<Vec<Vec<f64>>>("grid")?,
+            [vec![1., 2.], vec![3.]]
+        );
+        assert_eq!(user.get_as::<i64>("age")?, 42);
+        assert!(!user.get_as::<bool>("admin")?);
+
+        //owned conversions move out of the tree
+        let tags: Vec<String> = json["tags"].clone().try_into()?;
+        assert_eq!(tags, ["a", "b"]);
+        assert_eq!(
+            Vec::<Vec<i64>>::try_from(json["grid"].clone())?,
+            [vec![1, 2], vec![3]]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn conversion_errors() -> Result<(), JsonError> {
+        use std::convert::TryFrom;
+
+        let json = parse_json_string(
+            r#"{ "name" : "bob", "tags" : [ "a", 1 ], "ratio" : 0.5, "big" : 1e300 }"#,
+        )?;
+        let user = json.as_object().unwrap();
+
+        let err = user.get_as::<f64>("name").unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError::Mismatch {
+                expected: "number",
+                found: "string"
+            }
+        );
+        assert_eq!(err.to_string(), "expected number, found string");
+
+        //the first element that fails
+        assert_eq!(
+            user.get_as::<Vec<String>>("tags").unwrap_err().to_string(),
+            "expected string, found number"
+        );
+        assert_eq!(
+            user.get_as::<String>("missing").unwrap_err().to_string(),
+            "missing key \"missing\""
+        );
+        assert_eq!(
+            user.get_as::<i64>("ratio"),
+            Err(ConversionError::InvalidNumber {
+                expected: "i64",
+                found: 0.5
+            })
+        );
+        assert!(user.get_as::<i64>("big").is_err());
+        assert_eq!(
+            Vec::<bool>::try_from(JsonObject::Null),
+            Err(ConversionError::Mismatch {
+                expected: "array",
+                found: "null"
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_failure_names_the_key() -> Result<(), JsonError> {
+        let json = parse_json_string(r#"{ "port" : "eighty", "big" : 70000 }"#)?;
+        let config = json.as_object().unwrap();
+
+        let err = config.get_parsed::<u16>("port").unwrap_err();
+        assert_eq!(err.key.as_deref(), Some("port"));
+        assert_eq!(
+            err.to_string(),
+            format!("key \"port\": {}", "eighty".parse::<u16>().unwrap_err())
+        );
+
+        let err = config.get_parsed::<u16>("big").unwrap_err();
+        assert!(err.to_string().starts_with("key \"big\": "));
+        assert!(matches!(err.kind, GetParsedErrorKind::Parse(_)));
+
+        assert!(matches!(
+            JsonObject::Null.get_parsed::<u16>(),
+            Err(GetParsedError { key: None, .. })
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use std::str::Chars;
+
+use crate::{unexpected, Expected, JsonErrorKind, ParseOptions};
+
+//the input of the parser. The parsing functions are generic over it so that the usual sources
+//are read without dynamic dispatch, `dyn Source` is left for the paths that are not hot
+pub(crate) trait Source: Iterator<Item = char> {
+    //appends the chars before the next '"', '\' or control char to `out` and consumes them, as
+    //long as `out` stays within `max_len` bytes. Sources that cannot look ahead append nothing
+    //and the string is read one char at a time
+    #[inline]
+    fn read_unescaped(&mut self, _out: &mut String, _max_len: usize) {}
+}
+
+impl<S: Source + ?Sized> Source for &mut S {
+    #[inline]
+    fn read_unescaped(&mut self, out: &mut String, max_len: usize) {
+        (**self).read_unescaped(out, max_len)
+    }
+}
+
+impl Source for Chars<'_> {
+    #[inline]
+    fn read_unescaped(&mut self, out: &mut String, max_len: usize) {
+        let rest = self.as_str();
+        let mut len = rest
+            .bytes()
+            .position(|b| b == b'"' || b == b'\\' || b < 0x20)
+            .unwrap_or(rest.len());
+
+        //the char that goes over the limit is left for the caller to fail on
+        if len > max_len.saturating_sub(out.len()) {
+            len = max_len.saturating_sub(out.len());
+            while !rest.is_char_boundary(len) {
+                len -= 1;
+            }
+        }
+
+        out.push_str(&rest[..len]);
+        *self = rest[len..].chars();
+    }
+}
+
+//any other iterator, read one char at a time
+pub(crate) struct Plain<I>(pub(crate) I);
+
+impl<I: Iterator<Item = char>> Iterator for Plain<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<I: Iterator<Item = char>> Source for Plain<I> {}
+
+//the input with one char of lookahead, so a number can end without consuming the char after it.
+//What is left peeked when parsing stops belongs to whoever reads on, see `into_peeked`
+pub(crate) struct Cursor<'a, S: Source + ?Sized> {
+    iter: &'a mut S,
+    peeked: Option<char>,
+    //the chars consumed since `record`, for keeping the text of a number
+    recorded: Option<String>,
+}
+
+impl<'a, S: Source + ?Sized> Cursor<'a, S> {
+    #[inline]
+    pub(crate) fn new(iter: &'a mut S) -> Self {
+        Cursor {
+            iter,
+            peeked: None,
+            recorded: None,
+        }
+    }
+
+    //continues with a char that was already read from `iter`
+    #[inline]
+    pub(crate) fn resume(iter: &'a mut S, peeked: Option<char>) -> Self {
+        Cursor {
+            iter,
+            peeked,
+            recorded: None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_peeked(self) -> Option<char> {
+        self.peeked
+    }
+
+    //starts recording with the already consumed `first`, peeked chars are not recorded until
+    //they are consumed
+    pub(crate) fn record(&mut self, first: char) {
+        self.recorded = Some(first.to_string());
+    }
+
+    pub(crate) fn take_recorded(&mut self) -> String {
+        self.recorded.take().unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn peek(&mut self) -> Option<char> {
+        if self.peeked.is_none() {
+            self.peeked = self.iter.next();
+        }
+        self.peeked
+    }
+
+    //`None` at the end of the input, comments count as whitespace when allowed
+    pub(crate) fn peek_non_whitespace(
+        &mut self,
+        options: &ParseOptions,
+    ) -> Result<Option<char>, JsonErrorKind> {
+        loop {
+            match self.peek() {
+                Some(ch) if ch.is_whitespace() => self.peeked = None,
+                Some('/') if options.allow_comments => {
+                    self.peeked = None;
+                    self.skip_comment()?;
+                }
+                ch => return Ok(ch),
+            }
+        }
+    }
+
+    pub(crate) fn next_non_whitespace(
+        &mut self,
+        options: &ParseOptions,
+    ) -> Result<char, JsonErrorKind> {
+        let ch = self.peek_non_whitespace(options)?;
+        self.peeked = None;
+        ch.ok_or(JsonErrorKind::EarlyEndOfStream)
+    }
+
+    //expects the starting '/' to already be eaten
+    fn skip_comment(&mut self) -> Result<(), JsonErrorKind> {
+        match self.next().ok_or(JsonErrorKind::EarlyEndOfStream)? {
+            //a line comment can end the input
+            '/' => {
+                self.find(|&ch| ch == '\n');
+            }
+            '*' => {
+                let mut star = false;
+                loop {
+                    match self.next().ok_or(JsonErrorKind::EarlyEndOfStream)? {
+                        '/' if star => break,
+                        ch => star = ch == '*',
+                    }
+                }
+            }
+            ch => return Err(unexpected(ch, Expected::CommentStart)),
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: Source + ?Sized> Iterator for Cursor<'_, S> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peeked.take().or_else(|| self.iter.next());
+
+        if let (Some(recorded), Some(ch)) = (&mut self.recorded, ch) {
+            recorded.push(ch);
+        }
+
+        ch
+    }
+}
+
+impl<S: Source + ?Sized> Source for Cursor<'_, S> {
+    #[inline]
+    fn read_unescaped(&mut self, out: &mut String, max_len: usize) {
+        if self.peeked.is_none() && self.recorded.is_none() {
+            self.iter.read_unescaped(out, max_len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use proptest::prelude::*;
+
+    fn value() -> impl Strategy<Value = JsonObject> {
+        let leaf = prop_oneof![
+            Just(JsonObject::Null),
+            any::<bool>().prop_map(JsonObject::Boolean),
+            any::<i64>().prop_map(JsonObject::from),
+            (-1e9..1e9_f64).prop_map(JsonObject::Number),
+            any::<String>().prop_map(JsonObject::String),
+        ];
+
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..8).prop_map(JsonObject::Array),
+                prop::collection::vec((any::<String>(), inner), 0..8)
+                    .prop_map(|entries| JsonObject::Object(entries.into_iter().collect())),
+            ]
+        })
+    }
+
+    //serialized, pretty or not, and maybe cut short or followed by junk
+    fn document() -> impl Strategy<Value = String> {
+        (
+            value(),
+            any::<bool>(),
+            any::<prop::sample::Index>(),
+            "[\\[\\]{}\",: \n\\\\a-z0-9é]{0,3}",
+        )
+            .prop_map(|(value, pretty, cut, junk)| {
+                let text = if pretty {
+                    format!("{:#}", value)
+                } else {
+                    value.to_string()
+                };
+
+                if junk.is_empty() {
+                    return text;
+                }
+                let mut cut = cut.index(text.len() + 1);
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                format!("{}{}", &text[..cut], junk)
+            })
+    }
+
+    proptest! {
+        //`&str` and bytes input read runs of string content at once, any other iterator reads
+        //one char at a time as all input used to be
+        #[test]
+        fn fast_paths_agree(input in document()) {
+            let expected = parse_json_from_iter(input.chars());
+
+            prop_assert_eq!(&parse_json_string(&input), &expected);
+            prop_assert_eq!(&parse_json_bytes(input.as_bytes()), &expected);
+            prop_assert_eq!(
+                parse_json_str_borrowed(&input).map(JsonObject::from),
+                expected
+            );
+        }
+
+        #[test]
+        fn fast_paths_agree_on_errors(input in "[\\[\\]{}\",: \t\n\\\\/a-z0-9.eE+\\-\u{1}é😀]{0,40}") {
+            let expected = parse_json_from_iter(input.chars());
+
+            prop_assert_eq!(&parse_json_string(&input), &expected);
+            prop_assert_eq!(&parse_json_bytes(input.as_bytes()), &expected);
+        }
+
+        #[test]
+        fn string_limits(content in "[a-zé😀\\\\\"]{0,24}", max in 0..16_usize) {
+            let input = format!("[\"{}\"]", content);
+            let options = ParseOptions {
+                max_string_len: Some(max),
+                ..ParseOptions::default()
+            };
+
+            prop_assert_eq!(
+                parse_json_string_with_options(&input, &options),
+                parse_json_from_iter_with_options(input.chars(), &options)
+            );
+        }
+
+        //the same value as `f64::from_str`, whatever the digits and the exponent
+        #[test]
+        fn numbers_match_std(
+            literal in "-?(0|[1-9][0-9]{0,24})(\\.[0-9]{1,24})?([eE][+-]?[0-9]{1,3})?"
+        ) {
+            let expected: f64 = literal.parse().unwrap();
+            let n = parse_json_string(&literal).unwrap().as_f64().unwrap();
+            prop_assert_eq!(n.to_bits(), expected.to_bits(), "{}", literal);
+        }
+
+        //rounded once, however many digits there are
+        #[test]
+        fn integers_are_exact(digits in "-?[1-9][0-9]{0,40}") {
+            let expected: f64 = digits.parse().unwrap();
+            prop_assert_eq!(parse_json_string(&digits), Ok(JsonObject::Number(expected)));
+        }
+    }
+}
@@ -0,0 +1,988 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::compare::number_eq;
+use crate::walk::{JsonPath, PathSegment};
+use crate::{json, Array, JsonObject, Object, PointerError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffKind {
+    /// A member or trailing array element only the second document has.
+    Added(JsonObject),
+    /// A member or trailing array element only the first document has.
+    Removed(JsonObject),
+    Changed {
+        from: JsonObject,
+        to: JsonObject,
+    },
+}
+
+/// A difference between two documents and where it is, see [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub path: JsonPath,
+    pub kind: DiffKind,
+}
+
+//`changed /port: 80 -> 8080`
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DiffKind::Added(value) => write!(f, "added {}: {}", self.path, value),
+            DiffKind::Removed(value) => write!(f, "removed {}: {}", self.path, value),
+            DiffKind::Changed { from, to } => {
+                write!(f, "changed {}: {} -> {}", self.path, from, to)
+            }
+        }
+    }
+}
+
+/// What changed from `from` to `to`, in document order.
+///
+/// Objects are matched by key whatever the order of their members, and numbers by value so `1`
+/// and `1.0` are the same. Arrays are compared position by position, elements past the end of
+/// the shorter one being added or removed: inserting at the front of an array changes every
+/// element after it, which is cheaper to compute and simpler to read than a minimal edit script.
+/// Removed elements are listed from the end, so that every index is valid when the entries are
+/// applied in order by [`JsonObject::apply_diff`].
+///
+/// Objects holding duplicate keys cannot be matched member by member and are reported as
+/// changed as a whole, unless they are equal up to member order.
+///
+/// ```
+/// use json_parser::{diff, json};
+///
+/// let before = json!({ "port": 80, "hosts": ["a", "b"], "debug": true });
+/// let after = json!({ "hosts": ["a", "c", "d"], "port": 8080 });
+///
+/// let changes: Vec<String> = diff(&before, &after).iter().map(ToString::to_string).collect();
+/// assert_eq!(
+///     changes,
+///     [
+///         "changed /port: 80 -> 8080",
+///         r#"changed /hosts/1: "b" -> "c""#,
+///         r#"added /hosts/2: "d""#,
+///         "removed /debug: true",
+///     ]
+/// );
+/// ```
+pub fn diff(from: &JsonObject, to: &JsonObject) -> Vec<DiffEntry> {
+    let mut differ = Differ {
+        path: vec![],
+        entries: vec![],
+    };
+    differ.value(from, to);
+
+    differ.entries
+}
+
+struct Differ {
+    path: Vec<PathSegment>,
+    entries: Vec<DiffEntry>,
+}
+
+impl Differ {
+    fn value(&mut self, from: &JsonObject, to: &JsonObject) {
+        match (from, to) {
+            (JsonObject::Object(from), JsonObject::Object(to))
+                if !has_duplicates(from) && !has_duplicates(to) =>
+            {
+                self.object(from, to)
+            }
+            (JsonObject::Array(from), JsonObject::Array(to)) => self.array(from, to),
+            (from, to) if from.is_number() && to.is_number() => {
+                if !number_eq(from, to) {
+                    self.changed(from, to);
+                }
+            }
+            (from, to) if from.canonical_eq(to) => {}
+            (from, to) => self.changed(from, to),
+        }
+    }
+
+    fn object(&mut self, from: &Object, to: &Object) {
+        for (key, value) in from {
+            self.path.push(PathSegment::Key(key.to_owned()));
+            match to.get(key) {
+                Some(to) => self.value(value, to),
+                None => self.push(DiffKind::Removed(value.clone())),
+            }
+            self.path.pop();
+        }
+
+        for (key, value) in to {
+            if !from.contains_key(key) {
+                self.path.push(PathSegment::Key(key.to_owned()));
+                self.push(DiffKind::Added(value.clone()));
+                self.path.pop();
+            }
+        }
+    }
+
+    fn array(&mut self, from: &Array, to: &Array) {
+        let common = from.len().min(to.len());
+
+        for (i, (from, to)) in from.iter().zip(to).enumerate() {
+            self.path.push(PathSegment::Index(i));
+            self.value(from, to);
+            self.path.pop();
+        }
+
+        for (i, value) in to.iter().enumerate().skip(common) {
+            self.path.push(PathSegment::Index(i));
+            self.push(DiffKind::Added(value.clone()));
+            self.path.pop();
+        }
+
+        for (i, value) in from.iter().enumerate().skip(common).rev() {
+            self.path.push(PathSegment::Index(i));
+            self.push(DiffKind::Removed(value.clone()));
+            self.path.pop();
+        }
+    }
+
+    fn changed(&mut self, from: &JsonObject, to: &JsonObject) {
+        self.push(DiffKind::Changed {
+            from: from.clone(),
+            to: to.clone(),
+        });
+    }
+
+    fn push(&mut self, kind: DiffKind) {
+        self.entries.push(DiffEntry {
+            path: JsonPath::from_segments(self.path.clone()),
+            kind,
+        });
+    }
+}
+
+fn has_duplicates(object: &Object) -> bool {
+    let entries = object.entries();
+
+    entries
+        .iter()
+        .enumerate()
+        .any(|(i, (key, _))| entries[..i].iter().any(|(other, _)| other == key))
+}
+
+impl JsonObject {
+    /// Applies the entries of a [`diff`] in order, so that applying `diff(&a, &b)` to `a` makes
+    /// it [`canonical_eq`](JsonObject::canonical_eq) to `b`. Added members go to the end of
+    /// their object and added elements are inserted at their index. The `from` values are not
+    /// checked against the document.
+    ///
+    /// Stops at the first entry whose path does not resolve, leaving the ones before it applied.
+    pub fn apply_diff(&mut self, diff: &[DiffEntry]) -> Result<(), PointerError> {
+        diff.iter().try_for_each(|entry| apply_entry(self, entry))
+    }
+}
+
+fn apply_entry(target: &mut JsonObject, entry: &DiffEntry) -> Result<(), PointerError> {
+    let (last, parents) = match entry.path.segments().split_last() {
+        Some(split) => split,
+        //the document itself, removing it leaves null as `remove_pointer` does
+        None => {
+            *target = match &entry.kind {
+                DiffKind::Added(value) | DiffKind::Changed { to: value, .. } => value.clone(),
+                DiffKind::Removed(_) => JsonObject::Null,
+            };
+            return Ok(());
+        }
+    };
+
+    let parent = parents.iter().try_fold(target, child_mut)?;
+
+    match (&entry.kind, parent, last) {
+        (DiffKind::Changed { to, .. }, parent, last) => *child_mut(parent, last)? = to.clone(),
+        (DiffKind::Added(value), JsonObject::Object(object), PathSegment::Key(key)) => {
+            object.insert(key.as_str(), value.clone());
+        }
+        (DiffKind::Added(value), JsonObject::Array(array), PathSegment::Index(i)) => {
+            if *i > array.len() {
+                return Err(PointerError::IndexOutOfRange(array.len()));
+            }
+            array.insert(*i, value.clone());
+        }
+        (DiffKind::Removed(_), JsonObject::Object(object), PathSegment::Key(key)) => {
+            object.remove(key).ok_or(PointerError::NotFound)?;
+        }
+        (DiffKind::Removed(_), JsonObject::Array(array), PathSegment::Index(i)) => {
+            if *i >= array.len() {
+                return Err(PointerError::IndexOutOfRange(array.len()));
+            }
+            array.remove(*i);
+        }
+        (_, JsonObject::Object(_) | JsonObject::Array(_), _) => return Err(PointerError::NotFound),
+        _ => return Err(PointerError::NotAContainer),
+    }
+
+    Ok(())
+}
+
+fn child_mut<'a>(
+    value: &'a mut JsonObject,
+    segment: &PathSegment,
+) -> Result<&'a mut JsonObject, PointerError> {
+    match (value, segment) {
+        (JsonObject::Object(object), PathSegment::Key(key)) => {
+            object.get_mut(key).ok_or(PointerError::NotFound)
+        }
+        (JsonObject::Array(array), PathSegment::Index(i)) => {
+            let len = array.len();
+            array.get_mut(*i).ok_or(PointerError::IndexOutOfRange(len))
+        }
+        (JsonObject::Object(_) | JsonObject::Array(_), _) => Err(PointerError::NotFound),
+        _ => Err(PointerError::NotAContainer),
+    }
+}
+
+/// Options of [`diff_patch`], both off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffPatchOptions {
+    /// A value removed at one path and added at another, or replacing a member there, becomes a
+    /// single `move`.
+    pub detect_moves: bool,
+    /// An added value equal to one left unchanged elsewhere in `from` becomes a `copy` of it,
+    /// when the pointer to it is shorter than the value.
+    pub detect_copies: bool,
+}
+
+/// An RFC 6902 JSON Patch turning `from` into `to`, which [`apply_patch`] applies to make `from`
+/// [`canonical_eq`](JsonObject::canonical_eq) to `to`. Every entry of the [`diff`] becomes an
+/// `add`, `remove` or `replace` in the same order, unless the options find it a `move` or a
+/// `copy`. Candidates for those are matched by a hash which equal values share up to
+/// representation, then compared with `canonical_eq`.
+///
+/// ```
+/// use json_parser::{diff_patch, json, DiffPatchOptions};
+///
+/// let before = json!({ "name": "edge", "limits": { "rate": 1, "burst": 10 } });
+/// let after = json!({ "name": "edge", "quota": { "rate": 1, "burst": 10 } });
+/// let options = DiffPatchOptions {
+///     detect_moves: true,
+///     ..DiffPatchOptions::default()
+/// };
+///
+/// assert_eq!(
+///     diff_patch(&before, &after, &options),
+///     json!([{ "op": "move", "from": "/limits", "path": "/quota" }])
+/// );
+/// ```
+///
+/// [`apply_patch`]: crate::apply_patch
+pub fn diff_patch(from: &JsonObject, to: &JsonObject, options: &DiffPatchOptions) -> JsonObject {
+    let entries = diff(from, to);
+
+    //the removed entry each entry takes its value from, and the unchanged value it copies
+    let mut moves = vec![None; entries.len()];
+    let mut moved = vec![false; entries.len()];
+    let mut copies = vec![None; entries.len()];
+
+    if options.detect_moves {
+        let mut removed: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            if let DiffKind::Removed(value) = &entry.kind {
+                removed.entry(canonical_hash(value)).or_default().push(i);
+            }
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            let value = match target_value(entry) {
+                Some(value) => value,
+                None => continue,
+            };
+            let candidates = match removed.get_mut(&canonical_hash(value)) {
+                Some(candidates) => candidates,
+                None => continue,
+            };
+            let found = candidates
+                .iter()
+                .position(|&source| match &entries[source].kind {
+                    DiffKind::Removed(removed) => removed.canonical_eq(value),
+                    _ => false,
+                });
+            if let Some(found) = found {
+                let source = candidates.remove(found);
+                moves[i] = Some(source);
+                moved[source] = true;
+            }
+        }
+    }
+
+    if options.detect_copies {
+        let targets: Vec<(usize, &JsonObject, u64)> = entries
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| moves[*i].is_none())
+            .filter_map(|(i, entry)| target_value(entry).map(|value| (i, value)))
+            .map(|(i, value)| (i, value, canonical_hash(value)))
+            .collect();
+
+        let mut sources = Sources {
+            path: vec![],
+            changed: entries.iter().map(|entry| entry.path.segments()).collect(),
+            touched: entries
+                .iter()
+                .flat_map(|entry| {
+                    let segments = entry.path.segments();
+                    (0..segments.len()).map(move |len| &segments[..len])
+                })
+                .collect(),
+            wanted: targets.iter().map(|(_, _, hash)| *hash).collect(),
+            found: HashMap::new(),
+        };
+        if !sources.wanted.is_empty() {
+            sources.walk(from);
+        }
+
+        for (i, value, hash) in targets {
+            let len = value.to_json_string().len();
+            copies[i] = sources.found.get(&hash).and_then(|found| {
+                found
+                    .iter()
+                    .map(|(path, source)| (path.to_pointer(), source))
+                    .find(|(pointer, source)| pointer.len() < len && source.canonical_eq(value))
+                    .map(|(pointer, _)| pointer)
+            });
+        }
+    }
+
+    //the original indices of the removed elements still at the end of each array, after the
+    //`len` elements it keeps
+    let mut tails: HashMap<&[PathSegment], (usize, Vec<usize>)> = HashMap::new();
+    for entry in &entries {
+        if let (DiffKind::Removed(_), Some((PathSegment::Index(i), parent))) =
+            (&entry.kind, entry.path.segments().split_last())
+        {
+            tails.entry(parent).or_default().1.push(*i);
+        }
+    }
+    for (len, tail) in tails.values_mut() {
+        tail.sort_unstable();
+        *len = tail[0];
+    }
+
+    //where a removed value is now, elements kept for a move shift the ones removed before them
+    let mut take = |path: &JsonPath| match path.segments().split_last() {
+        Some((PathSegment::Index(i), parent)) => {
+            let (len, tail) = tails.get_mut(parent).unwrap();
+            let at = tail.binary_search(i).unwrap();
+            tail.remove(at);
+
+            let mut segments = parent.to_vec();
+            segments.push(PathSegment::Index(*len + at));
+            JsonPath::from_segments(segments).to_pointer()
+        }
+        _ => path.to_pointer(),
+    };
+
+    let mut patch = vec![];
+    for (i, entry) in entries.iter().enumerate() {
+        let path = entry.path.to_pointer();
+        let operation = match (&entry.kind, moves[i], copies[i].take()) {
+            (DiffKind::Removed(_), ..) if moved[i] => continue,
+            (_, Some(source), _) => {
+                json!({ "op": "move", "from": take(&entries[source].path), "path": path })
+            }
+            (_, _, Some(source)) => json!({ "op": "copy", "from": source, "path": path }),
+            (DiffKind::Added(value), ..) => {
+                json!({ "op": "add", "path": path, "value": value.clone() })
+            }
+            (DiffKind::Removed(_), ..) => json!({ "op": "remove", "path": take(&entry.path) }),
+            (DiffKind::Changed { to, .. }, ..) => {
+                json!({ "op": "replace", "path": path, "value": to.clone() })
+            }
+        };
+        patch.push(operation);
+    }
+
+    JsonObject::Array(patch)
+}
+
+//what a move or copy can stand in for: an `add`, or a `replace` of a member, which `add` replaces
+//too unlike an array element
+fn target_value(entry: &DiffEntry) -> Option<&JsonObject> {
+    match &entry.kind {
+        DiffKind::Added(value) => Some(value),
+        DiffKind::Changed { to, .. } if entry.path.key().is_some() => Some(to),
+        _ => None,
+    }
+}
+
+//the same for values that are `canonical_eq`, so members are combined in any order and numbers
+//hashed by value
+fn canonical_hash(value: &JsonObject) -> u64 {
+    match value {
+        JsonObject::Array(array) => array_hash(array.iter().map(canonical_hash)),
+        JsonObject::Object(object) => object_hash(
+            object
+                .entries()
+                .iter()
+                .map(|(key, value)| (key.as_str(), canonical_hash(value))),
+        ),
+        scalar => scalar_hash(scalar),
+    }
+}
+
+fn hash_of(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn array_hash(elements: impl Iterator<Item = u64>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    '['.hash(&mut hasher);
+    elements.for_each(|element| element.hash(&mut hasher));
+    hasher.finish()
+}
+
+fn object_hash<'a>(members: impl Iterator<Item = (&'a str, u64)>) -> u64 {
+    members.fold(hash_of('{'), |sum, member| {
+        sum.wrapping_add(hash_of(member))
+    })
+}
+
+fn scalar_hash(value: &JsonObject) -> u64 {
+    match value {
+        //numbers equal to `number_eq` have the same `f64`, adding 0 makes -0 positive
+        number if number.is_number() => hash_of(number.as_f64().map(|n| (n + 0.).to_bits())),
+        JsonObject::String(s) => hash_of(('"', s)),
+        JsonObject::Boolean(b) => hash_of(b),
+        _ => hash_of(()),
+    }
+}
+
+//the values of `from` no diff entry is at or under, which are at the same path throughout the
+//patch and can be copied from
+struct Sources<'a> {
+    path: Vec<PathSegment>,
+    changed: HashSet<&'a [PathSegment]>,
+    //the paths of the values holding an entry
+    touched: HashSet<&'a [PathSegment]>,
+    wanted: HashSet<u64>,
+    found: HashMap<u64, Vec<(JsonPath, &'a JsonObject)>>,
+}
+
+impl<'a> Sources<'a> {
+    //the hash of `value`, bottom up so every value is hashed once
+    fn walk(&mut self, value: &'a JsonObject) -> u64 {
+        if self.changed.contains(self.path.as_slice()) {
+            return 0;
+        }
+
+        let hash = match value {
+            JsonObject::Array(array) => {
+                let mut hashes = Vec::with_capacity(array.len());
+                for (i, element) in array.iter().enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    hashes.push(self.walk(element));
+                    self.path.pop();
+                }
+                array_hash(hashes.into_iter())
+            }
+            //a pointer finds the first member of a duplicate key, so none under them is a source
+            JsonObject::Object(object) if has_duplicates(object) => canonical_hash(value),
+            JsonObject::Object(object) => {
+                let mut hashes = Vec::with_capacity(object.len());
+                for (key, member) in object {
+                    self.path.push(PathSegment::Key(key.to_owned()));
+                    hashes.push((key, self.walk(member)));
+                    self.path.pop();
+                }
+                object_hash(hashes.into_iter())
+            }
+            scalar => scalar_hash(scalar),
+        };
+
+        if self.wanted.contains(&hash) && !self.touched.contains(self.path.as_slice()) {
+            let path = JsonPath::from_segments(self.path.clone());
+            self.found.entry(hash).or_default().push((path, value));
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use proptest::prelude::*;
+
+    fn changes(from: &JsonObject, to: &JsonObject) -> Vec<(String, DiffKind)> {
+        diff(from, to)
+            .into_iter()
+            .map(|entry| (entry.path.to_pointer(), entry.kind))
+            .collect()
+    }
+
+    fn changed(pointer: &str, from: JsonObject, to: JsonObject) -> (String, DiffKind) {
+        (pointer.to_owned(), DiffKind::Changed { from, to })
+    }
+
+    fn assert_round_trip(from: &JsonObject, to: &JsonObject) {
+        let diff = diff(from, to);
+
+        let mut applied = from.clone();
+        applied.apply_diff(&diff).unwrap();
+        assert!(applied.canonical_eq(to), "{} to {}: {}", from, to, applied);
+        assert_eq!(super::diff(&applied, to), []);
+
+        for (detect_moves, detect_copies) in
+            [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let options = DiffPatchOptions {
+                detect_moves,
+                detect_copies,
+            };
+            let patch = diff_patch(from, to, &options);
+            let mut patched = from.clone();
+            apply_patch(&mut patched, &patch).unwrap();
+            assert!(
+                patched.canonical_eq(to),
+                "{} to {} with {}: {}",
+                from,
+                to,
+                patch,
+                patched
+            );
+        }
+    }
+
+    fn patch_with_moves(from: &JsonObject, to: &JsonObject) -> JsonObject {
+        let options = DiffPatchOptions {
+            detect_moves: true,
+            detect_copies: true,
+        };
+        diff_patch(from, to, &options)
+    }
+
+    #[test]
+    fn config_changes() -> Result<(), JsonError> {
+        let before = parse_json_string(
+            r#"{
+                "name" : "edge",
+                "listen" : { "port" : 80, "host" : "0.0.0.0" },
+                "limits" : { "rate" : 1.0, "burst" : 10 },
+                "debug" : true,
+                "tags" : null
+            }"#,
+        )?;
+        let after = parse_json_string(
+            r#"{
+                "limits" : { "burst" : 10, "rate" : 1 },
+                "listen" : { "host" : "0.0.0.0", "port" : 8080, "tls" : { "cert" : "a.pem" } },
+                "name" : "edge",
+                "tags" : [ "prod" ],
+                "a/b" : 1
+            }"#,
+        )?;
+
+        assert_eq!(
+            changes(&before, &after),
+            [
+                changed("/listen/port", json!(80), json!(8080)),
+                (
+                    "/listen/tls".to_owned(),
+                    DiffKind::Added(json!({ "cert": "a.pem" }))
+                ),
+                ("/debug".to_owned(), DiffKind::Removed(json!(true))),
+                changed("/tags", JsonObject::Null, json!(["prod"])),
+                ("/a~1b".to_owned(), DiffKind::Added(json!(1))),
+            ]
+        );
+        assert_round_trip(&before, &after);
+
+        //member order and number representation are not changes
+        let reordered = parse_json_string(
+            r#"{ "tags" : null, "debug" : true, "limits" : { "burst" : 1e1, "rate" : 1 },
+                 "listen" : { "host" : "0.0.0.0", "port" : 80.0 }, "name" : "edge" }"#,
+        )?;
+        assert_eq!(diff(&before, &reordered), []);
+        let options = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            diff(
+                &before,
+                &parse_json_string_with_options(&before.to_json_string(), &options)?
+            ),
+            []
+        );
+
+        //integral raw literals are compared exactly, past the precision of an f64
+        let raw = |text: &str| parse_json_string_with_options(text, &options);
+        let (from, to) = (
+            raw(r#"{ "id" : 9007199254740993 }"#)?,
+            raw(r#"{ "id" : 9007199254740992 }"#)?,
+        );
+        assert_eq!(
+            changes(&from, &to),
+            [changed("/id", from["id"].clone(), to["id"].clone())]
+        );
+        assert_round_trip(&from, &to);
+        let (from, to) = (
+            raw(r#"{ "a" : 12345678901234567890123, "b" : 0 }"#)?,
+            raw(r#"{ "b" : 12345678901234567890124 }"#)?,
+        );
+        assert_eq!(
+            patch_with_moves(&from, &to),
+            json!([
+                { "op": "remove", "path": "/a" },
+                { "op": "replace", "path": "/b", "value": to["b"].clone() }
+            ])
+        );
+        assert_round_trip(&from, &to);
+
+        assert_eq!(
+            changes(&json!(1), &json!("1")),
+            [changed("", json!(1), json!("1"))]
+        );
+        assert_eq!(
+            diff(&before, &after)[0].to_string(),
+            "changed /listen/port: 80 -> 8080"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn arrays_of_objects() -> Result<(), JsonError> {
+        let before = parse_json_string(
+            r#"{ "servers" : [
+                { "host" : "a", "ports" : [ 80, 443 ] },
+                { "host" : "b", "ports" : [ 80 ] },
+                { "host" : "c", "ports" : [] },
+                { "host" : "d" }
+            ] }"#,
+        )?;
+        let after = parse_json_string(
+            r#"{ "servers" : [
+                { "ports" : [ 80, 443 ], "host" : "a" },
+                { "host" : "c", "ports" : [ 8080, 8443 ] }
+            ] }"#,
+        )?;
+
+        //`b` was removed, but positionally it became `c`
+        assert_eq!(
+            changes(&before, &after),
+            [
+                changed("/servers/1/host", json!("b"), json!("c")),
+                changed("/servers/1/ports/0", json!(80), json!(8080)),
+                (
+                    "/servers/1/ports/1".to_owned(),
+                    DiffKind::Added(json!(8443))
+                ),
+                (
+                    "/servers/3".to_owned(),
+                    DiffKind::Removed(json!({ "host": "d" }))
+                ),
+                (
+                    "/servers/2".to_owned(),
+                    DiffKind::Removed(json!({ "host": "c", "ports": [] }))
+                ),
+            ]
+        );
+        assert_round_trip(&before, &after);
+        assert_round_trip(&after, &before);
+
+        let nested = json!([[{ "a": [1, { "b": 2 }] }], []]);
+        let grown = json!([[{ "a": [1, { "b": 3, "c": [] }, 4] }, {}], [[]], 5]);
+        assert_round_trip(&nested, &grown);
+        assert_round_trip(&grown, &nested);
+        assert_round_trip(&nested, &json!([]));
+        assert_round_trip(&json!({}), &nested);
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys() -> Result<(), JsonError> {
+        let before = parse_json_string(r#"{ "a" : { "x" : 1, "y" : 0, "x" : 2 }, "b" : 1 }"#)?;
+        let after = parse_json_string(r#"{ "a" : { "y" : 0, "x" : 1, "x" : 2 }, "b" : 2 }"#)?;
+        assert_eq!(
+            changes(&before, &after),
+            [changed("/b", json!(1), json!(2))]
+        );
+
+        let after = parse_json_string(r#"{ "a" : { "x" : 1, "y" : 0, "x" : 3 }, "b" : 1 }"#)?;
+        assert_eq!(
+            changes(&before, &after),
+            [changed("/a", before["a"].clone(), after["a"].clone())]
+        );
+        assert_round_trip(&before, &after);
+
+        Ok(())
+    }
+
+    #[test]
+    fn patches() {
+        let before = json!({
+            "name": "edge",
+            "listen": { "port": 80, "host": "0.0.0.0" },
+            "debug": true
+        });
+        let after = json!({
+            "name": "edge",
+            "listen": { "port": 8080, "host": "0.0.0.0" },
+            "tags": ["prod"]
+        });
+
+        let patch = json!([
+            { "op": "replace", "path": "/listen/port", "value": 8080 },
+            { "op": "remove", "path": "/debug" },
+            { "op": "add", "path": "/tags", "value": ["prod"] }
+        ]);
+        assert_eq!(
+            diff_patch(&before, &after, &DiffPatchOptions::default()),
+            patch
+        );
+        assert_eq!(patch_with_moves(&before, &after), patch);
+        assert_round_trip(&before, &after);
+
+        assert_eq!(
+            diff_patch(&before, &before, &DiffPatchOptions::default()),
+            json!([])
+        );
+        assert_eq!(
+            diff_patch(&json!(1), &json!([1]), &DiffPatchOptions::default()),
+            json!([{ "op": "replace", "path": "", "value": [1] }])
+        );
+    }
+
+    #[test]
+    fn moves() {
+        let before = json!({ "limits": { "rate": 1, "burst": [10, 20] }, "name": "edge" });
+
+        //a renamed key
+        let renamed = json!({ "name": "edge", "quota": { "burst": [10, 20.0], "rate": 1 } });
+        assert_eq!(
+            patch_with_moves(&before, &renamed),
+            json!([{ "op": "move", "from": "/limits", "path": "/quota" }])
+        );
+        assert_round_trip(&before, &renamed);
+
+        //replacing a member
+        let replacing = json!({ "name": { "rate": 1, "burst": [10, 20] } });
+        assert_eq!(
+            patch_with_moves(&before, &replacing),
+            json!([{ "op": "move", "from": "/limits", "path": "/name" }])
+        );
+        assert_round_trip(&before, &replacing);
+
+        //elements at the end of arrays, which shift the ones removed after them
+        let from = json!({ "a": [0, "x", "y", "z"], "b": [1], "c": ["w"] });
+        let to = json!({ "a": [0], "b": [1, "y", "w"], "c": ["x", "z"] });
+        assert_eq!(
+            patch_with_moves(&from, &to),
+            json!([
+                { "op": "remove", "path": "/a/1" },
+                { "op": "move", "from": "/a/1", "path": "/b/1" },
+                { "op": "add", "path": "/b/2", "value": "w" },
+                { "op": "replace", "path": "/c/0", "value": "x" },
+                { "op": "move", "from": "/a/1", "path": "/c/1" }
+            ])
+        );
+        assert_round_trip(&from, &to);
+        assert_round_trip(&to, &from);
+
+        //moved before the removals from its array
+        let from = json!({ "b": [1], "a": [0, "x", "y", "z"] });
+        let to = json!({ "b": [1, "y"], "a": [0] });
+        assert_eq!(
+            patch_with_moves(&from, &to),
+            json!([
+                { "op": "move", "from": "/a/2", "path": "/b/1" },
+                { "op": "remove", "path": "/a/2" },
+                { "op": "remove", "path": "/a/1" }
+            ])
+        );
+        assert_round_trip(&from, &to);
+    }
+
+    //arrays of a few distinct values, so that removed elements are often added elsewhere
+    fn arrays() -> impl Strategy<Value = JsonObject> {
+        let element =
+            prop::sample::select(vec![json!("x"), json!("y"), json!([1]), json!({ "k": 1 })]);
+        let array = prop::collection::vec(element, 0..5).prop_map(JsonObject::Array);
+
+        prop::collection::vec((prop::sample::select(vec!["a", "b", "c"]), array), 0..4).prop_map(
+            |members| {
+                let mut object = Object::new();
+                for (key, array) in members {
+                    object.insert(key, array);
+                }
+                JsonObject::Object(object)
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn moves_between_arrays(from in arrays(), to in arrays()) {
+            assert_round_trip(&from, &to);
+        }
+    }
+
+    #[test]
+    fn copies() {
+        let before = json!({ "defaults": { "retries": 3, "timeout": 30 }, "services": {} });
+        let after = json!({
+            "defaults": { "retries": 3, "timeout": 30 },
+            "services": { "api": { "timeout": 30, "retries": 3 } }
+        });
+
+        assert_eq!(
+            patch_with_moves(&before, &after),
+            json!([{ "op": "copy", "from": "/defaults", "path": "/services/api" }])
+        );
+        assert_round_trip(&before, &after);
+
+        //values which change are not copied from, and short ones are added as they are
+        let changed = json!({
+            "defaults": { "retries": 4, "timeout": 30 },
+            "services": { "api": { "retries": 3, "timeout": 30 }, "web": 30 }
+        });
+        assert_eq!(
+            patch_with_moves(&before, &changed),
+            json!([
+                { "op": "replace", "path": "/defaults/retries", "value": 4 },
+                { "op": "add", "path": "/services/api", "value": { "retries": 3, "timeout": 30 } },
+                { "op": "add", "path": "/services/web", "value": 30 }
+            ])
+        );
+        assert_round_trip(&before, &changed);
+
+        //members under a duplicate key cannot be pointed at
+        let before =
+            parse_json_string(r#"{ "d" : { "k" : [1, 2, 3], "k" : [4, 5, 6] }, "x" : {} }"#)
+                .unwrap();
+        let mut after = before.clone();
+        after["x"] = json!({ "y": [4, 5, 6], "z": before["d"].clone() });
+        assert_eq!(
+            patch_with_moves(&before, &after),
+            json!([
+                { "op": "add", "path": "/x/y", "value": [4, 5, 6] },
+                { "op": "copy", "from": "/d", "path": "/x/z" }
+            ])
+        );
+        assert_round_trip(&before, &after);
+    }
+
+    #[test]
+    fn paths_that_do_not_resolve() {
+        let entry = |pointer: &[PathSegment], kind| DiffEntry {
+            path: JsonPath::from_segments(pointer.to_vec()),
+            kind,
+        };
+        let key = |key: &str| PathSegment::Key(key.to_owned());
+
+        let mut json = json!({ "a": [1, 2], "b": true });
+        let original = json.clone();
+
+        for (path, kind, error) in [
+            (
+                vec![key("c"), key("d")],
+                DiffKind::Added(json!(1)),
+                PointerError::NotFound,
+            ),
+            (
+                vec![key("a"), PathSegment::Index(3)],
+                DiffKind::Added(json!(1)),
+                PointerError::IndexOutOfRange(2),
+            ),
+            (
+                vec![key("a"), PathSegment::Index(2)],
+                DiffKind::Removed(json!(1)),
+                PointerError::IndexOutOfRange(2),
+            ),
+            (
+                vec![key("b"), key("c")],
+                DiffKind::Removed(json!(1)),
+                PointerError::NotAContainer,
+            ),
+            (
+                vec![key("a"), key("0")],
+                DiffKind::Changed {
+                    from: json!(1),
+                    to: json!(2),
+                },
+                PointerError::NotFound,
+            ),
+        ] {
+            assert_eq!(json.apply_diff(&[entry(&path, kind)]), Err(error));
+            assert_eq!(json, original);
+        }
+    }
+
+    //few keys so that documents share many of them
+    fn keys() -> impl Strategy<Value = String> + Clone {
+        prop::sample::select(vec!["id", "name", "tags", "a/b", "~"]).prop_map(String::from)
+    }
+
+    fn documents(depth: u32) -> BoxedStrategy<JsonObject> {
+        strategies::documents(keys(), depth)
+    }
+
+    //a copy with a few values replaced, and elements and members added or dropped, so that most
+    //of the document is unchanged
+    fn mutated(value: &JsonObject) -> BoxedStrategy<JsonObject> {
+        let kept = match value {
+            JsonObject::Array(array) => {
+                let elements: Vec<_> = array.iter().map(mutated).collect();
+                (elements, 0..4, documents(2))
+                    .prop_map(|(mut elements, change, added)| {
+                        match change {
+                            0 => elements.push(added),
+                            1 => drop(elements.pop()),
+                            _ => {}
+                        }
+                        JsonObject::Array(elements)
+                    })
+                    .boxed()
+            }
+            JsonObject::Object(object) => {
+                let members: Vec<_> = object
+                    .iter()
+                    .map(|(key, value)| {
+                        (
+                            Just(key.to_owned()),
+                            prop::option::weighted(0.85, mutated(value)),
+                        )
+                    })
+                    .collect();
+                (members, prop::option::weighted(0.3, (keys(), documents(2))))
+                    .prop_map(|(members, added)| {
+                        let mut object = Object::new();
+                        for (key, value) in members {
+                            if let Some(value) = value {
+                                object.entries_mut().push((key.into(), value));
+                            }
+                        }
+                        if let Some((key, value)) = added {
+                            object.insert(key, value);
+                        }
+                        JsonObject::Object(object)
+                    })
+                    .boxed()
+            }
+            value => Just(value.clone()).boxed(),
+        };
+
+        prop_oneof![1 => documents(2), 7 => kept].boxed()
+    }
+
+    proptest! {
+        #[test]
+        fn generated_round_trips(
+            (from, to) in documents(4).prop_flat_map(|from| (Just(from.clone()), mutated(&from))),
+            unrelated in documents(4),
+        ) {
+            assert_round_trip(&from, &to);
+            assert_round_trip(&to, &from);
+            assert_round_trip(&from, &unrelated);
+        }
+    }
+}
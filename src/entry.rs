@@ -0,0 +1,227 @@
+use crate::{ConversionError, JsonObject, Object, ObjectImpl};
+
+/// The first entry of an [`Object`] with some key or where it would go, from [`Object::entry`].
+#[derive(Debug)]
+pub enum Entry<'a> {
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>),
+}
+
+#[derive(Debug)]
+pub struct OccupiedEntry<'a> {
+    entries: &'a mut ObjectImpl,
+    position: usize,
+}
+
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    entries: &'a mut ObjectImpl,
+    key: String,
+}
+
+impl Object {
+    /// The first entry with the key, for updating it in place or appending it in one expression:
+    ///
+    /// ```
+    /// use json_parser::{JsonObject, Object};
+    ///
+    /// let mut object = Object::new();
+    /// for tag in ["a", "b"] {
+    ///     object
+    ///         .entry("tags")
+    ///         .or_insert_with(JsonObject::empty_array)
+    ///         .push_value(tag)
+    ///         .unwrap();
+    /// }
+    /// assert_eq!(JsonObject::Object(object).to_json_string(), r#"{"tags":["a","b"]}"#);
+    /// ```
+    pub fn entry(&mut self, key: &str) -> Entry<'_> {
+        match self.entries.iter().position(|(k, _)| k.as_str() == key) {
+            Some(position) => Entry::Occupied(OccupiedEntry {
+                entries: &mut self.entries,
+                position,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                entries: &mut self.entries,
+                key: key.to_owned(),
+            }),
+        }
+    }
+}
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    #[inline]
+    pub fn or_insert(self, default: JsonObject) -> &'a mut JsonObject {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> JsonObject) -> &'a mut JsonObject {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    //null for a vacant entry
+    #[inline]
+    pub fn or_default(self) -> &'a mut JsonObject {
+        self.or_insert_with(JsonObject::default)
+    }
+
+    pub fn and_modify(mut self, f: impl FnOnce(&mut JsonObject)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    #[inline]
+    pub fn key(&self) -> &str {
+        &self.entries[self.position].0
+    }
+
+    #[inline]
+    pub fn get(&self) -> &JsonObject {
+        &self.entries[self.position].1
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut JsonObject {
+        &mut self.entries[self.position].1
+    }
+
+    #[inline]
+    pub fn into_mut(self) -> &'a mut JsonObject {
+        &mut self.entries[self.position].1
+    }
+
+    /// Replaces the value in place, returning the old one.
+    #[inline]
+    pub fn insert(&mut self, value: JsonObject) -> JsonObject {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry, keeping the order of the others like [`Object::shift_remove`].
+    #[inline]
+    pub fn remove(self) -> JsonObject {
+        self.entries.remove(self.position).1
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    #[inline]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Appends the entry after the others.
+    pub fn insert(self, value: JsonObject) -> &'a mut JsonObject {
+        self.entries.push((self.key.into(), value));
+        &mut self.entries.last_mut().unwrap().1
+    }
+}
+
+impl JsonObject {
+    /// Moves the value out without cloning it, leaving [`JsonObject::Null`] behind.
+    #[inline]
+    pub fn take(&mut self) -> JsonObject {
+        std::mem::take(self)
+    }
+
+    /// Appends to an array, returning the new element. Anything but an array is left alone and
+    /// reported as a [`ConversionError::Mismatch`].
+    pub fn push_value(
+        &mut self,
+        value: impl Into<JsonObject>,
+    ) -> Result<&mut JsonObject, ConversionError> {
+        match self {
+            JsonObject::Array(array) => {
+                array.push(value.into());
+                Ok(array.last_mut().unwrap())
+            }
+            other => Err(other.mismatch("array")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn or_insert() -> Result<(), JsonError> {
+        let mut object = parse_json_string(r#"{ "a" : 1, "b" : [ true ], "a" : 2 }"#)?
+            .into_object()
+            .unwrap();
+
+        //occupied, the first entry with the key
+        *object.entry("a").or_insert(JsonObject::Null) = JsonObject::Number(10.);
+        object
+            .entry("b")
+            .or_insert_with(JsonObject::empty_array)
+            .push_value(false)
+            .unwrap();
+        //vacant, appended
+        object
+            .entry("c")
+            .or_insert_with(JsonObject::empty_array)
+            .push_value("new")
+            .unwrap();
+        object
+            .entry("d")
+            .and_modify(|d| *d = JsonObject::Boolean(true))
+            .or_default();
+        object
+            .entry("c")
+            .and_modify(|c| c.as_array_mut().unwrap().clear());
+
+        assert_eq!(
+            JsonObject::Object(object.clone()).to_json_string(),
+            r#"{"a":10,"b":[true,false],"a":2,"c":[],"d":null}"#
+        );
+
+        match object.entry("b") {
+            Entry::Occupied(entry) => {
+                assert_eq!(entry.key(), "b");
+                assert_eq!(entry.remove(), json!([true, false]));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(object.entry("b").key(), "b");
+        assert!(matches!(object.entry("b"), Entry::Vacant(_)));
+
+        let mut number = JsonObject::Number(1.);
+        assert_eq!(
+            number.push_value(2.).unwrap_err(),
+            ConversionError::Mismatch {
+                expected: "array",
+                found: "number"
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn take_leaves_null() -> Result<(), JsonError> {
+        let mut json = parse_json_string(r#"{ "a" : { "b" : [ 1, 2 ] }, "c" : 3 }"#)?;
+
+        let subtree = json.as_object_mut().unwrap().get_mut("a").unwrap().take();
+        assert_eq!(subtree, json!({ "b": [1, 2] }));
+        assert_eq!(json, json!({ "a": null, "c": 3 }));
+
+        assert_eq!(json.take(), json!({ "a": null, "c": 3 }));
+        assert_eq!(json, JsonObject::Null);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,674 @@
+use crate::cursor::{Cursor, Plain, Source};
+use crate::position::Located;
+use crate::serialize::non_finite_literal;
+use crate::{
+    check_depth, check_number, checked_number, extra_chars, parse_false_impl,
+    parse_non_finite_impl, parse_null_impl, parse_number_impl, parse_string_value_impl,
+    parse_true_impl, skip_string, unclosed, unexpected, Container, Expected, JsonError,
+    JsonErrorKind, JsonObject, Limit, LimitBehavior, NumberPolicy, ParseOptions, ParseReport,
+    Position,
+};
+
+/// One step through a document, from [`JsonTokenizer`]. Every `Start` is matched by an `End`
+/// and every `Key` is followed by the events of its value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(String),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    //a value, or the end of the array when it can be empty
+    Value { could_be_empty: bool },
+    //a key, or the end of the object when it can be empty
+    Key { could_be_empty: bool },
+    AfterValue,
+}
+
+//the grammar, shared by the tree parser and `JsonTokenizer`. Only the stack of open containers
+//is kept so memory is bounded by nesting depth rather than document size, and the input is
+//handed in on each call so that callers can own it however suits them
+pub(crate) struct Events {
+    //open containers with the number of members read so far
+    stack: Vec<(Container, usize)>,
+    state: State,
+    //nesting depth of the top level value
+    depth: usize,
+    keep_number_text: bool,
+    number_text: Option<String>,
+    //values started so far, for `ParseOptions::max_total_nodes`
+    nodes: usize,
+    //the stack length of the container being truncated, nothing inside it is reported
+    skipping: Option<usize>,
+    report: ParseReport,
+}
+
+impl Events {
+    pub(crate) fn new(depth: usize) -> Self {
+        Events {
+            stack: Vec::new(),
+            state: State::Value {
+                could_be_empty: false,
+            },
+            depth,
+            keep_number_text: false,
+            number_text: None,
+            nodes: 0,
+            skipping: None,
+            report: ParseReport::default(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub(crate) fn into_report(self) -> ParseReport {
+        self.report
+    }
+
+    //the text of the last number, always kept when the number policy needs it
+    #[inline]
+    pub(crate) fn take_number_text(&mut self) -> Option<String> {
+        self.number_text.take()
+    }
+
+    //`None` once the top level value is complete, without reading any further
+    #[inline]
+    pub(crate) fn next_event(
+        &mut self,
+        cursor: &mut Cursor<impl Source + ?Sized>,
+        options: &ParseOptions,
+    ) -> Result<Option<JsonEvent>, JsonErrorKind> {
+        self.next_event_impl(cursor, options)
+            .map_err(|kind| match self.stack.last() {
+                Some((container, _)) => {
+                    unclosed(kind, *container, self.depth + self.stack.len() - 1)
+                }
+                None => kind,
+            })
+    }
+
+    #[inline]
+    fn next_event_impl(
+        &mut self,
+        cursor: &mut Cursor<impl Source + ?Sized>,
+        options: &ParseOptions,
+    ) -> Result<Option<JsonEvent>, JsonErrorKind> {
+        match self.state {
+            State::Value { could_be_empty } => {
+                //checked up front, an element can fail on a ']' too as in `[-]`
+                if could_be_empty {
+                    match cursor.peek_non_whitespace(options)? {
+                        Some(']') => {
+                            cursor.next();
+                            return Ok(Some(self.close()));
+                        }
+                        Some(_) => {}
+                        None => return Err(JsonErrorKind::EarlyEndOfStream),
+                    }
+                }
+
+                let ch = cursor.next_non_whitespace(options)?;
+                let truncating = self.count_member(Container::Array, options)?;
+                let event = self.parse_value(cursor, options, ch).map_err(|kind| {
+                    match self.stack.last() {
+                        //right after the ':' of an entry
+                        Some((Container::Object, _)) if matches!(ch, ',' | '}') => {
+                            JsonErrorKind::MissingValue
+                        }
+                        _ => kind,
+                    }
+                })?;
+
+                if truncating {
+                    self.skip_members(cursor, options).map(Some)
+                } else {
+                    Ok(Some(event))
+                }
+            }
+            State::Key { could_be_empty } => match cursor.next_non_whitespace(options)? {
+                '"' => {
+                    let truncating = self.count_member(Container::Object, options)?;
+                    let key = if self.skipping.is_some() {
+                        skip_string(cursor)?;
+                        String::new()
+                    } else {
+                        parse_string_value_impl(cursor, options)?
+                    };
+
+                    match cursor.next_non_whitespace(options)? {
+                        ':' => {}
+                        _ => return Err(JsonErrorKind::MissingColon),
+                    }
+
+                    self.state = State::Value {
+                        could_be_empty: false,
+                    };
+
+                    if truncating {
+                        self.skip_members(cursor, options).map(Some)
+                    } else {
+                        Ok(Some(JsonEvent::Key(key)))
+                    }
+                }
+                '}' if could_be_empty => Ok(Some(self.close())),
+                ch if could_be_empty => Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+                ch => Err(unexpected(ch, Expected::ObjectKey)),
+            },
+            State::AfterValue => {
+                let container = match self.stack.last() {
+                    Some((container, _)) => *container,
+                    None => return Ok(None),
+                };
+
+                //after a comma the container can only end if trailing commas are allowed
+                let could_be_empty = options.allow_trailing_commas;
+
+                match (container, cursor.next_non_whitespace(options)?) {
+                    (Container::Array, ',') => self.state = State::Value { could_be_empty },
+                    (Container::Object, ',') => self.state = State::Key { could_be_empty },
+                    (Container::Array, ']') | (Container::Object, '}') => {
+                        return Ok(Some(self.close()))
+                    }
+                    (Container::Array, ch) => {
+                        return Err(unexpected(ch, Expected::CommaOrArrayEnd))
+                    }
+                    (Container::Object, ch) => {
+                        return Err(unexpected(ch, Expected::CommaOrObjectEnd))
+                    }
+                }
+
+                self.next_event_impl(cursor, options)
+            }
+        }
+    }
+
+    fn close(&mut self) -> JsonEvent {
+        self.state = State::AfterValue;
+
+        if self.skipping == Some(self.stack.len()) {
+            self.skipping = None;
+        }
+
+        match self.stack.pop() {
+            Some((Container::Array, _)) => JsonEvent::EndArray,
+            _ => JsonEvent::EndObject,
+        }
+    }
+
+    fn open(&mut self, container: Container, options: &ParseOptions) -> Result<(), JsonErrorKind> {
+        check_depth(self.depth + self.stack.len(), options)?;
+        self.stack.push((container, 0));
+        Ok(())
+    }
+
+    //counts a member of the innermost container as it starts, when it is a `container` and the
+    //options limit those. True when the member is the first one to be truncated
+    #[inline]
+    fn count_member(
+        &mut self,
+        container: Container,
+        options: &ParseOptions,
+    ) -> Result<bool, JsonErrorKind> {
+        let max = match container {
+            Container::Array => options.max_array_elements,
+            Container::Object => options.max_object_entries,
+        };
+
+        if let (Some(max), Some((top, len))) = (max, self.stack.last_mut()) {
+            if *top == container {
+                *len += 1;
+                if *len > max {
+                    return self.past_limit(container, options);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    //a member over the limit, which may start truncating its container
+    #[cold]
+    fn past_limit(
+        &mut self,
+        container: Container,
+        options: &ParseOptions,
+    ) -> Result<bool, JsonErrorKind> {
+        let level = self.stack.len();
+
+        match self.skipping {
+            Some(skipping) if skipping == level => self.report.skipped_values += 1,
+            //limits do not apply inside skipped members
+            Some(_) => {}
+            None if options.on_limit == LimitBehavior::Truncate => {
+                self.skipping = Some(level);
+                self.report.truncated_containers += 1;
+                self.report.skipped_values += 1;
+                return Ok(true);
+            }
+            None if container == Container::Array => {
+                return Err(JsonErrorKind::LimitExceeded(Limit::ArrayElements))
+            }
+            None => return Err(JsonErrorKind::LimitExceeded(Limit::ObjectEntries)),
+        }
+
+        Ok(false)
+    }
+
+    //reads the rest of the container being truncated, whose end is the next event reported
+    #[cold]
+    fn skip_members(
+        &mut self,
+        cursor: &mut Cursor<impl Source + ?Sized>,
+        options: &ParseOptions,
+    ) -> Result<JsonEvent, JsonErrorKind> {
+        loop {
+            let event = self.next_event_impl(cursor, options)?;
+
+            if self.skipping.is_none() {
+                return Ok(event.expect("the container ends before the value"));
+            }
+        }
+    }
+
+    #[inline]
+    fn parse_value(
+        &mut self,
+        cursor: &mut Cursor<impl Source + ?Sized>,
+        options: &ParseOptions,
+        ch: char,
+    ) -> Result<JsonEvent, JsonErrorKind> {
+        self.state = State::AfterValue;
+
+        let skipping = self.skipping.is_some();
+        if !skipping {
+            self.nodes += 1;
+            if options.max_total_nodes.is_some_and(|max| self.nodes > max) {
+                return Err(JsonErrorKind::LimitExceeded(Limit::TotalNodes));
+            }
+        }
+
+        let event = match ch {
+            //_n_ull
+            'n' => literal(parse_null_impl(cursor)?),
+            //_t_rue
+            't' => literal(parse_true_impl(cursor)?),
+            //_f_alse
+            'f' => literal(parse_false_impl(cursor)?),
+            '[' => {
+                self.open(Container::Array, options)?;
+                self.state = State::Value {
+                    could_be_empty: true,
+                };
+                JsonEvent::StartArray
+            }
+            '{' => {
+                self.open(Container::Object, options)?;
+                self.state = State::Key {
+                    could_be_empty: true,
+                };
+                JsonEvent::StartObject
+            }
+            '"' if skipping => {
+                skip_string(cursor)?;
+                JsonEvent::String(String::new())
+            }
+            '"' => JsonEvent::String(parse_string_value_impl(cursor, options)?),
+            'N' | 'I' if options.allow_non_finite_numbers => {
+                self.parse_non_finite(cursor, options, ch, 1.)?
+            }
+            '-' if options.allow_non_finite_numbers && cursor.peek() == Some('I') => {
+                cursor.next();
+                self.parse_non_finite(cursor, options, 'I', -1.)?
+            }
+            //has to be a number, which skipped members do not check
+            ch if skipping => JsonEvent::Number(parse_number_impl(cursor, ch)?),
+            ch if !self.keep_number_text && options.number_policy == NumberPolicy::Lossy => {
+                JsonEvent::Number(check_number(parse_number_impl(cursor, ch)?, options)?)
+            }
+            ch => {
+                cursor.record(ch);
+                let parsed = parse_number_impl(cursor, ch);
+                let text = cursor.take_recorded();
+
+                let n = match options.number_policy {
+                    NumberPolicy::Lossy => check_number(parsed?, options)?,
+                    _ => parsed.and_then(|n| checked_number(n, &text, options))?,
+                };
+
+                self.number_text = Some(text);
+                JsonEvent::Number(n)
+            }
+        };
+
+        Ok(event)
+    }
+
+    #[cold]
+    fn parse_non_finite(
+        &mut self,
+        cursor: &mut Cursor<impl Source + ?Sized>,
+        options: &ParseOptions,
+        first: char,
+        sign: f64,
+    ) -> Result<JsonEvent, JsonErrorKind> {
+        let n = check_number(sign * parse_non_finite_impl(cursor, first)?, options)?;
+
+        if self.keep_number_text || options.number_policy != NumberPolicy::Lossy {
+            self.number_text = Some(non_finite_literal(n).to_owned());
+        }
+        Ok(JsonEvent::Number(n))
+    }
+}
+
+fn literal(value: JsonObject) -> JsonEvent {
+    match value {
+        JsonObject::Boolean(b) => JsonEvent::Boolean(b),
+        _ => JsonEvent::Null,
+    }
+}
+
+/// Reads a document one [`JsonEvent`] at a time, pulling chars only as far as the event being
+/// produced, so a large document can be scanned or searched without building it. The grammar
+/// and options are those of [`crate::parse_json_string_with_options`], except for
+/// `duplicate_keys` and `intern_keys` which only make sense for trees.
+///
+/// The document is complete once the top level value has ended and only whitespace follows.
+/// Errors, extra chars after the value included, are returned once and end the iteration.
+///
+/// ```
+/// use json_parser::{JsonEvent, JsonTokenizer};
+///
+/// let input = r#"{ "id" : 7, "tags" : [ "a", "b" ] }"#;
+/// let tags = JsonTokenizer::new(input.chars())
+///     .filter_map(|event| match event {
+///         Ok(JsonEvent::String(s)) => Some(s),
+///         _ => None,
+///     })
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(tags, ["a", "b"]);
+/// ```
+pub struct JsonTokenizer<I: Iterator<Item = char>> {
+    iter: Located<I>,
+    //read past the last number, for the next event
+    excess: Option<char>,
+    events: Events,
+    options: ParseOptions,
+    done: bool,
+}
+
+impl<I: Iterator<Item = char>> JsonTokenizer<I> {
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        Self::with_options(iter, &ParseOptions::default())
+    }
+
+    pub fn with_options(iter: I, options: &ParseOptions) -> Self {
+        JsonTokenizer {
+            iter: Located::new(iter),
+            excess: None,
+            events: Events::new(0),
+            options: options.clone(),
+            done: false,
+        }
+    }
+
+    /// The position of the last char read, the end of the last event or a char past it.
+    #[inline]
+    pub fn position(&self) -> Position {
+        self.iter.current_position()
+    }
+
+    pub(crate) fn keep_number_text(mut self, keep: bool) -> Self {
+        self.events.keep_number_text = keep;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn take_number_text(&mut self) -> Option<String> {
+        self.events.take_number_text()
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut I {
+        self.iter.get_mut()
+    }
+
+    fn next_event(&mut self) -> Result<Option<JsonEvent>, JsonError> {
+        let mut source = Plain(&mut self.iter);
+        let mut cursor = Cursor::resume(&mut source, self.excess.take());
+
+        let event = match self.events.next_event(&mut cursor, &self.options) {
+            //nothing but whitespace may follow the value
+            Ok(None) => match cursor.peek_non_whitespace(&self.options) {
+                Ok(Some(ch)) => {
+                    //the extra char is the last one read, so the position is right
+                    let position = self.iter.current_position();
+                    return Err(JsonError {
+                        kind: extra_chars(std::iter::once(ch).chain(&mut self.iter)),
+                        position,
+                    });
+                }
+                end => end.map(|_| None),
+            },
+            event => {
+                self.excess = cursor.into_peeked();
+                event
+            }
+        };
+
+        event.map_err(|kind| self.iter.error(kind))
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for JsonTokenizer<I> {
+    type Item = Result<JsonEvent, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let next = self.next_event().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
+
+impl<I: Iterator<Item = char>> std::iter::FusedIterator for JsonTokenizer<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn collect(json: &str) -> Result<Vec<JsonEvent>, JsonError> {
+        JsonTokenizer::new(json.chars()).collect()
+    }
+
+    fn key(key: &str) -> JsonEvent {
+        JsonEvent::Key(key.to_owned())
+    }
+
+    #[test]
+    fn event_sequence() -> Result<(), JsonError> {
+        assert_eq!(
+            collect(r#" { "a" : [ 1.50 , {}, [] ], "b" : "x", "c": true, "d": null } "#)?,
+            [
+                JsonEvent::StartObject,
+                key("a"),
+                JsonEvent::StartArray,
+                JsonEvent::Number(1.5),
+                JsonEvent::StartObject,
+                JsonEvent::EndObject,
+                JsonEvent::StartArray,
+                JsonEvent::EndArray,
+                JsonEvent::EndArray,
+                key("b"),
+                JsonEvent::String("x".to_owned()),
+                key("c"),
+                JsonEvent::Boolean(true),
+                key("d"),
+                JsonEvent::Null,
+                JsonEvent::EndObject,
+            ]
+        );
+
+        assert_eq!(collect(" 12 ")?, [JsonEvent::Number(12.)]);
+
+        Ok(())
+    }
+
+    //the smallest tree builder, with no options to honour
+    fn build(
+        events: impl Iterator<Item = Result<JsonEvent, JsonError>>,
+    ) -> Result<JsonObject, JsonError> {
+        let mut stack: Vec<(JsonObject, Option<String>)> = vec![];
+
+        for event in events {
+            let value = match event? {
+                JsonEvent::StartArray => {
+                    stack.push((JsonObject::empty_array(), None));
+                    continue;
+                }
+                JsonEvent::StartObject => {
+                    stack.push((JsonObject::Object(Object::new()), None));
+                    continue;
+                }
+                JsonEvent::Key(key) => {
+                    stack.last_mut().unwrap().1 = Some(key);
+                    continue;
+                }
+                JsonEvent::EndArray | JsonEvent::EndObject => stack.pop().unwrap().0,
+                JsonEvent::String(s) => JsonObject::String(s),
+                JsonEvent::Number(n) => JsonObject::Number(n),
+                JsonEvent::Boolean(b) => JsonObject::Boolean(b),
+                JsonEvent::Null => JsonObject::Null,
+            };
+
+            match stack.last_mut() {
+                Some((JsonObject::Array(array), _)) => array.push(value),
+                Some((JsonObject::Object(object), key)) => object
+                    .entries_mut()
+                    .push((key.take().unwrap().into(), value)),
+                Some(_) => unreachable!(),
+                None => return Ok(value),
+            }
+        }
+
+        unreachable!("the tokenizer ends with an error on incomplete documents")
+    }
+
+    #[test]
+    fn tree_from_events() -> Result<(), JsonError> {
+        let json =
+            r#"{ "a" : { "b" : [ 1, -2.5e3, "é\n", [ [ ] ], { } ] }, "a" : null, "c" : false }"#;
+
+        let built = build(JsonTokenizer::new(json.chars()))?;
+        assert_eq!(built, parse_json_string(json)?);
+        assert_eq!(
+            built.to_json_string(),
+            parse_json_string(json)?.to_json_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn stops_at_the_first_matching_key() -> Result<(), JsonError> {
+        let mut document =
+            String::from(r#"{ "header" : { "id" : 1 }, "id" : "wanted", "rows" : ["#);
+        for i in 0..10_000 {
+            document.push_str(&format!(r#"{{ "id" : {} }},"#, i));
+        }
+        document.push_str("0 ] }");
+
+        let mut read = 0;
+        let mut events = JsonTokenizer::new(document.chars().inspect(|_| read += 1));
+
+        //`id` at the top level, not the one nested in the header
+        let mut depth = 0;
+        let found = loop {
+            match events.next().unwrap()? {
+                JsonEvent::StartObject | JsonEvent::StartArray => depth += 1,
+                JsonEvent::EndObject | JsonEvent::EndArray => depth -= 1,
+                JsonEvent::Key(key) if depth == 1 && key == "id" => {
+                    break events.next().unwrap()?
+                }
+                _ => {}
+            }
+        };
+        drop(events);
+
+        assert_eq!(found, JsonEvent::String("wanted".to_owned()));
+        assert_eq!(read, r#"{ "header" : { "id" : 1 }, "id" : "wanted""#.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_end_the_iteration() {
+        for json in [
+            "[1 2]",
+            "[,]",
+            "{,}",
+            "{\"a\" 1}",
+            "[1,]",
+            "{\"a\":1,}",
+            "[",
+            "{\"a\":",
+            "1 2",
+            "[-]",
+            "",
+            "[1] x",
+        ] {
+            let mut events = JsonTokenizer::new(json.chars());
+            let error = events.by_ref().find_map(Result::err);
+
+            assert_eq!(error, parse_json_string(json).err(), "{}", json);
+            assert_eq!(events.next(), None);
+        }
+    }
+
+    #[test]
+    fn options_are_honoured() -> Result<(), JsonError> {
+        let options = ParseOptions {
+            allow_comments: true,
+            allow_trailing_commas: true,
+            max_depth: 2,
+            ..ParseOptions::default()
+        };
+
+        let events: Vec<_> =
+            JsonTokenizer::with_options("[ 1, /* two */ [ ], ] // end".chars(), &options)
+                .collect::<Result<_, _>>()?;
+        assert_eq!(
+            events,
+            [
+                JsonEvent::StartArray,
+                JsonEvent::Number(1.),
+                JsonEvent::StartArray,
+                JsonEvent::EndArray,
+                JsonEvent::EndArray
+            ]
+        );
+
+        let error = JsonTokenizer::with_options("[[[]]]".chars(), &options)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(error.kind, JsonErrorKind::RecursionLimitExceeded);
+
+        Ok(())
+    }
+}
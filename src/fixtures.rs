@@ -0,0 +1,203 @@
+//! Loading JSON test data with readable errors, behind the `fixtures` feature.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::position::{source_line, write_diagnostic};
+use crate::{parse_json_string, JsonError, JsonObject};
+
+#[derive(Debug)]
+pub enum FixtureErrorKind {
+    Io(io::Error),
+    Json {
+        error: JsonError,
+        /// 1-based, counted in chars.
+        line: usize,
+        column: usize,
+        /// The offending line of the file.
+        source_line: String,
+    },
+}
+
+#[derive(Debug)]
+pub struct FixtureError {
+    pub path: PathBuf,
+    pub kind: FixtureErrorKind,
+}
+
+//`path:line:column: error` followed by the line and a caret under the column
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            FixtureErrorKind::Io(err) => write!(f, "{}: {}", self.path.display(), err),
+            FixtureErrorKind::Json {
+                error,
+                line,
+                column,
+                source_line,
+            } => write_diagnostic(
+                f,
+                &self.path.display(),
+                *line,
+                *column,
+                &error.kind,
+                source_line,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            FixtureErrorKind::Io(err) => Some(err),
+            FixtureErrorKind::Json { error, .. } => Some(error),
+        }
+    }
+}
+
+//parses `text`, turning an error into its line, column and source line
+fn parse_located(text: &str) -> Result<JsonObject, (JsonError, usize, usize, String)> {
+    let error = match parse_json_string(text) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    let (line, column) = (error.position.line, error.position.column);
+    let source_line = source_line(text, line).to_owned();
+
+    Err((error, line, column, source_line))
+}
+
+fn read(path: &Path) -> Result<String, FixtureError> {
+    std::fs::read_to_string(path).map_err(|err| FixtureError {
+        path: path.to_owned(),
+        kind: FixtureErrorKind::Io(err),
+    })
+}
+
+pub fn load_json_fixture(path: impl AsRef<Path>) -> Result<JsonObject, FixtureError> {
+    let path = path.as_ref();
+    let text = read(path)?;
+
+    parse_located(&text).map_err(|(error, line, column, source_line)| FixtureError {
+        path: path.to_owned(),
+        kind: FixtureErrorKind::Json {
+            error,
+            line,
+            column,
+            source_line,
+        },
+    })
+}
+
+/// One document per line, blank lines are skipped. Error lines are counted in the whole file.
+pub fn load_ndjson_fixture(path: impl AsRef<Path>) -> Result<Vec<JsonObject>, FixtureError> {
+    let path = path.as_ref();
+    let text = read(path)?;
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            parse_located(line).map_err(|(error, _, column, source_line)| FixtureError {
+                path: path.to_owned(),
+                kind: FixtureErrorKind::Json {
+                    error,
+                    line: index + 1,
+                    column,
+                    source_line,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json_string;
+
+    //a file in the temp directory, removed again on drop
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("json-parser-{}-{}", std::process::id(), name));
+            std::fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn valid_fixtures() -> Result<(), Box<dyn std::error::Error>> {
+        let file = TempFile::new("valid.json", "{\n  \"a\" : [ 1, 2 ]\n}\n");
+        assert_eq!(
+            load_json_fixture(&file.0)?,
+            parse_json_string(r#"{ "a" : [ 1, 2 ] }"#)?
+        );
+
+        let file = TempFile::new("valid.ndjson", "{ \"n\" : 1 }\n\n[ true ]\r\n");
+        assert_eq!(
+            load_ndjson_fixture(&file.0)?,
+            vec![
+                parse_json_string(r#"{ "n" : 1 }"#)?,
+                parse_json_string("[ true ]")?
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_file() {
+        let path = std::env::temp_dir().join("json-parser-no-such-fixture.json");
+        let err = load_json_fixture(&path).unwrap_err();
+
+        assert!(matches!(err.kind, FixtureErrorKind::Io(_)));
+        assert!(err.to_string().starts_with(&path.display().to_string()));
+    }
+
+    #[test]
+    fn invalid_fixture_is_located() {
+        let file = TempFile::new("invalid.json", "{\n  \"a\" : [ 1, 2 },\n  \"b\" : 3\n}\n");
+        let err = load_json_fixture(&file.0).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{}:2:16: expected ',' or ']' but found '}}'\n  \"a\" : [ 1, 2 }},\n               ^",
+                file.0.display()
+            )
+        );
+
+        let file = TempFile::new("trailing.json", "[ 1 ]\n  x y\n");
+        let err = load_json_fixture(&file.0).unwrap_err();
+        assert!(matches!(
+            err.kind,
+            FixtureErrorKind::Json {
+                line: 2,
+                column: 3,
+                ..
+            }
+        ));
+
+        let file = TempFile::new("invalid.ndjson", "[ 1 ]\n\n{ \"a\" : tru }\n");
+        let err = load_ndjson_fixture(&file.0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{}:3:12: unexpected keyword\n{{ \"a\" : tru }}\n           ^",
+                file.0.display()
+            )
+        );
+    }
+}
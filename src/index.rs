@@ -0,0 +1,125 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{JsonObject, Object};
+
+/// Looks up an object member, anything missing or not an object gives a null so lookups can be
+/// chained: `&json["users"][0]["name"]`.
+impl Index<&str> for JsonObject {
+    type Output = JsonObject;
+
+    fn index(&self, key: &str) -> &JsonObject {
+        self.as_object()
+            .and_then(|object| object.get(key))
+            .unwrap_or(JsonObject::null_ref())
+    }
+}
+
+/// Looks up an array element, anything out of bounds or not an array gives a null.
+impl Index<usize> for JsonObject {
+    type Output = JsonObject;
+
+    fn index(&self, index: usize) -> &JsonObject {
+        self.as_array()
+            .and_then(|array| array.get(index))
+            .unwrap_or(JsonObject::null_ref())
+    }
+}
+
+/// Gets an object member for assignment, inserting a null when it is missing. A null is first
+/// turned into an empty object, so `json["a"]["b"] = value` builds the path.
+///
+/// # Panics
+///
+/// When the value is neither an object nor null.
+impl IndexMut<&str> for JsonObject {
+    fn index_mut(&mut self, key: &str) -> &mut JsonObject {
+        if let JsonObject::Null = self {
+            *self = JsonObject::Object(Object::new());
+        }
+
+        let object = match self {
+            JsonObject::Object(object) => object,
+            _ => panic!("cannot index into a non-object with {:?}", key),
+        };
+
+        if !object.contains_key(key) {
+            object.entries_mut().push((key.into(), JsonObject::Null));
+        }
+        object.get_mut(key).unwrap()
+    }
+}
+
+/// # Panics
+///
+/// When the value is not an array or the index is out of bounds.
+impl IndexMut<usize> for JsonObject {
+    fn index_mut(&mut self, index: usize) -> &mut JsonObject {
+        match self {
+            JsonObject::Array(array) => {
+                let len = array.len();
+                array.get_mut(index).unwrap_or_else(|| {
+                    panic!("index {} out of bounds for an array of {}", index, len)
+                })
+            }
+            _ => panic!("cannot index into a non-array with {}", index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn chained_lookups() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{ "users" : [ { "name" : "ann", "roles" : [ "admin" ] }, { "name" : "bob" } ] }"#,
+        )?;
+
+        assert_eq!(json["users"][0]["name"], JsonObject::from("ann"));
+        assert_eq!(json["users"][0]["roles"][0], JsonObject::from("admin"));
+        assert_eq!(json["users"][1]["name"], JsonObject::from("bob"));
+
+        //misses degrade to null instead of panicking
+        assert_eq!(json["users"][2]["name"], JsonObject::Null);
+        assert_eq!(json["users"][1]["roles"][0], JsonObject::Null);
+        assert_eq!(json["missing"]["deeper"][3], JsonObject::Null);
+        assert_eq!(json[0], JsonObject::Null);
+        assert_eq!(json["users"]["name"], JsonObject::Null);
+        assert!(std::ptr::eq(&json["missing"], JsonObject::null_ref()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mutation() -> Result<(), JsonError> {
+        let mut json = parse_json_string(r#"{ "users" : [ { "name" : "ann" } ] }"#)?;
+
+        json["users"][0]["name"] = JsonObject::from("anne");
+        json["users"][0]["age"] = JsonObject::from(30);
+        json["settings"]["theme"]["dark"] = JsonObject::from(true);
+
+        assert_eq!(
+            json,
+            parse_json_string(
+                r#"{ "users" : [ { "name" : "anne", "age" : 30 } ], "settings" : { "theme" : { "dark" : true } } }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn mutation_out_of_bounds() {
+        let mut json = JsonObject::Array(vec![]);
+        json[0] = JsonObject::Null;
+    }
+
+    #[test]
+    #[should_panic(expected = "non-object")]
+    fn mutation_through_a_scalar() {
+        let mut json = JsonObject::from(1);
+        json["a"] = JsonObject::Null;
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::{JsonObject, Key, Object, ObjectImpl};
+
+/// Object entries in insertion order with a hash index on the keys, so lookups stay O(1) on
+/// objects too large for the linear scan of [`Object::get`]. Duplicate keys are kept and, as with
+/// [`Object::get`], lookups find the first of them.
+#[derive(Debug, Clone, Default)]
+pub struct IndexedObject {
+    entries: ObjectImpl,
+    //position of the first entry with each key
+    index: HashMap<Key, usize>,
+}
+
+impl IndexedObject {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonObject> {
+        Some(&self.entries[*self.index.get(key)?].1)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonObject> {
+        let position = *self.index.get(key)?;
+        Some(&mut self.entries[position].1)
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Replaces the value of the first entry with this key and returns the old one, or appends an
+    /// entry, like [`Object::insert`].
+    pub fn insert(&mut self, key: impl Into<Key>, value: JsonObject) -> Option<JsonObject> {
+        let key = key.into();
+
+        match self.index.get(&key) {
+            Some(&position) => Some(std::mem::replace(&mut self.entries[position].1, value)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the first entry with the key, keeping the order of the others. Later entries
+    /// shift down so this is O(n) like [`Object::shift_remove`].
+    pub fn remove(&mut self, key: &str) -> Option<JsonObject> {
+        let position = self.index.remove(key)?;
+        let (key, value) = self.entries.remove(position);
+
+        for later in self.index.values_mut().filter(|later| **later > position) {
+            *later -= 1;
+        }
+
+        //a duplicate further on is now the first entry with the key
+        if let Some(next) = self.entries[position..].iter().position(|(k, _)| *k == key) {
+            self.index.insert(key, position + next);
+        }
+
+        Some(value)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    //no mutable access to the entries, renaming a key would leave the index stale
+    #[inline]
+    pub fn entries(&self) -> &ObjectImpl {
+        &self.entries
+    }
+}
+
+impl PartialEq for IndexedObject {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl From<Object> for IndexedObject {
+    fn from(object: Object) -> Self {
+        let mut index = HashMap::with_capacity(object.entries.len());
+        for (position, (key, _)) in object.entries.iter().enumerate() {
+            index.entry(key.clone()).or_insert(position);
+        }
+
+        IndexedObject {
+            entries: object.entries,
+            index,
+        }
+    }
+}
+
+impl From<IndexedObject> for Object {
+    #[inline]
+    fn from(indexed: IndexedObject) -> Self {
+        Object::from_impl(indexed.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn matches_linear_object(
+            operations in prop::collection::vec((0..4u8, 0..200u32, 0..220u32), 0..500),
+        ) {
+            //some duplicate keys to begin with, which `insert` never adds
+            let mut object = parse_json_string(r#"{ "k1" : 0, "k2" : 0, "k1" : 1 }"#)
+                .unwrap()
+                .into_object()
+                .unwrap();
+            let mut indexed = IndexedObject::from(object.clone());
+
+            for (i, (operation, key, probe)) in operations.into_iter().enumerate() {
+                let key = format!("k{}", key);
+
+                match operation {
+                    0 | 1 => prop_assert_eq!(
+                        indexed.insert(key.clone(), JsonObject::Number(i as f64)),
+                        object.insert(key, JsonObject::Number(i as f64))
+                    ),
+                    2 => prop_assert_eq!(indexed.remove(&key), object.shift_remove(&key)),
+                    _ => {
+                        if let (Some(a), Some(b)) = (indexed.get_mut(&key), object.get_mut(&key)) {
+                            *a = JsonObject::Boolean(true);
+                            *b = JsonObject::Boolean(true);
+                        }
+                    }
+                }
+
+                let probe = format!("k{}", probe);
+                prop_assert_eq!(indexed.get(&probe), object.get(&probe), "{}", probe);
+                prop_assert_eq!(indexed.contains_key(&probe), object.get(&probe).is_some());
+            }
+
+            prop_assert_eq!(indexed.entries(), object.entries());
+            prop_assert_eq!(indexed.len(), object.entries().len());
+        }
+    }
+
+    #[test]
+    fn insertion_order_is_kept() -> Result<(), JsonError> {
+        let object = parse_json_string(r#"{ "b" : 1, "a" : 2, "c" : 3, "a" : 4 }"#)?
+            .into_object()
+            .unwrap();
+
+        let mut indexed = IndexedObject::from(object);
+        assert_eq!(indexed.get("a"), Some(&JsonObject::Number(2.)));
+
+        indexed.insert("d".to_owned(), JsonObject::Null);
+        assert_eq!(indexed.remove("a"), Some(JsonObject::Number(2.)));
+        //the duplicate takes over
+        assert_eq!(indexed.get("a"), Some(&JsonObject::Number(4.)));
+        assert_eq!(indexed.remove("b"), Some(JsonObject::Number(1.)));
+        assert_eq!(indexed.get("a"), Some(&JsonObject::Number(4.)));
+        assert_eq!(indexed.get("d"), Some(&JsonObject::Null));
+
+        assert_eq!(
+            JsonObject::Object(Object::from(indexed)).to_json_string(),
+            r#"{"c":3,"a":4,"d":null}"#
+        );
+
+        let mut empty = IndexedObject::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.remove("a"), None);
+        assert_eq!(empty.get("a"), None);
+
+        Ok(())
+    }
+}
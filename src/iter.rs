@@ -0,0 +1,173 @@
+use std::iter::FusedIterator;
+
+use crate::{JsonObject, Key, Object};
+
+/// The entries of an [`Object`] in insertion order, from [`Object::iter`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    entries: std::slice::Iter<'a, (Key, JsonObject)>,
+}
+
+/// The entries of an [`Object`] in insertion order with their values mutable, from
+/// [`Object::iter_mut`].
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    entries: std::slice::IterMut<'a, (Key, JsonObject)>,
+}
+
+impl Object {
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            entries: self.entries.iter(),
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            entries: self.entries.iter_mut(),
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a str, &'a JsonObject);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries
+            .next()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries
+            .next_back()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a str, &'a mut JsonObject);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries
+            .next()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IterMut<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries
+            .next_back()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_> {}
+
+impl FusedIterator for IterMut<'_> {}
+
+impl IntoIterator for Object {
+    type Item = (Key, JsonObject);
+    type IntoIter = std::vec::IntoIter<(Key, JsonObject)>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Object {
+    type Item = (&'a str, &'a JsonObject);
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Object {
+    type Item = (&'a str, &'a mut JsonObject);
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn iterating_entries() -> Result<(), JsonError> {
+        let mut object = parse_json_string(r#"{ "a" : 1, "b" : [ true ], "c" : 3, "a" : 4 }"#)?
+            .into_object()
+            .unwrap();
+
+        let mut keys = String::new();
+        for (key, value) in &object {
+            keys.push_str(key);
+            assert_eq!(object.get_key_value(key).unwrap().0, key);
+            assert!(value.is_number() || value.is_array());
+        }
+        assert_eq!(keys, "abca");
+
+        let last_number = object.iter().rev().find(|(_, value)| value.is_number());
+        assert_eq!(last_number, Some(("a", &json!(4))));
+        assert_eq!(object.iter().len(), 4);
+        assert_eq!(
+            object.keys().rev().collect::<Vec<&str>>(),
+            ["a", "c", "b", "a"]
+        );
+        assert_eq!(object.values().filter(|value| value.is_number()).count(), 3);
+
+        for (key, value) in &mut object {
+            if key == "a" {
+                *value = JsonObject::Null;
+            }
+        }
+        for key in object.keys_mut().filter(|key| *key == "c") {
+            key.make_mut().push('!');
+        }
+        assert_eq!(
+            JsonObject::Object(object.clone()).to_json_string(),
+            r#"{"a":null,"b":[true],"c!":3,"a":null}"#
+        );
+
+        assert_eq!(object.get_key_value("b"), Some(("b", &json!([true]))));
+        assert_eq!(object.get_key_value("c"), None);
+
+        let owned: Vec<(Key, JsonObject)> = object.into_iter().skip(2).collect();
+        assert_eq!(
+            owned,
+            [("c!".into(), json!(3)), ("a".into(), JsonObject::Null)]
+        );
+
+        Ok(())
+    }
+}
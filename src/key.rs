@@ -0,0 +1,294 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An object key, either a `String` of its own or an `Arc<str>` shared by equal keys of a
+/// document parsed with [`ParseOptions::intern_keys`](crate::ParseOptions::intern_keys).
+///
+/// Derefs to `str` and compares, orders and hashes like one whichever it is, so lookups take a
+/// `&str` as before.
+#[derive(Clone)]
+pub struct Key(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Owned(String),
+    Shared(Arc<str>),
+}
+
+impl Key {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Owned(s) => s,
+            Repr::Shared(s) => s,
+        }
+    }
+
+    /// The allocation an interned key shares with the other keys equal to it.
+    #[inline]
+    pub fn as_shared(&self) -> Option<&Arc<str>> {
+        match &self.0 {
+            Repr::Owned(_) => None,
+            Repr::Shared(s) => Some(s),
+        }
+    }
+
+    /// The key as a `String` to change in place, a shared key is copied first so the others
+    /// sharing it are left alone.
+    pub fn make_mut(&mut self) -> &mut String {
+        if let Repr::Shared(s) = &self.0 {
+            self.0 = Repr::Owned(s.to_string());
+        }
+
+        match &mut self.0 {
+            Repr::Owned(s) => s,
+            Repr::Shared(_) => unreachable!(),
+        }
+    }
+
+    //the heap memory the key accounts for, a shared allocation split evenly between its keys
+    pub(crate) fn heap_size(&self) -> usize {
+        match &self.0 {
+            Repr::Owned(s) => s.capacity(),
+            Repr::Shared(s) => (s.len() + 2 * size_of::<usize>()) / Arc::strong_count(s),
+        }
+    }
+
+    //a shared key has no spare capacity
+    pub(crate) fn shrink_to_fit(&mut self) {
+        if let Repr::Owned(s) = &mut self.0 {
+            s.shrink_to_fit();
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        match self.0 {
+            Repr::Owned(s) => s,
+            Repr::Shared(s) => s.to_string(),
+        }
+    }
+}
+
+impl Default for Key {
+    #[inline]
+    fn default() -> Self {
+        Key(Repr::Owned(String::new()))
+    }
+}
+
+impl Deref for Key {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Key {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for Key {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for Key {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialEq<str> for Key {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Key {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Key {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Key> for str {
+    #[inline]
+    fn eq(&self, other: &Key) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Key> for &str {
+    #[inline]
+    fn eq(&self, other: &Key) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Key> for String {
+    #[inline]
+    fn eq(&self, other: &Key) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialOrd for Key {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for Key {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for Key {
+    #[inline]
+    fn from(s: String) -> Self {
+        Key(Repr::Owned(s))
+    }
+}
+
+impl From<&str> for Key {
+    #[inline]
+    fn from(s: &str) -> Self {
+        Key(Repr::Owned(s.to_owned()))
+    }
+}
+
+impl From<Arc<str>> for Key {
+    #[inline]
+    fn from(s: Arc<str>) -> Self {
+        Key(Repr::Shared(s))
+    }
+}
+
+impl From<Key> for String {
+    #[inline]
+    fn from(key: Key) -> Self {
+        key.into_string()
+    }
+}
+
+//the keys of one document, so that each distinct key is allocated once
+#[derive(Default)]
+pub(crate) struct Interner {
+    keys: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    pub(crate) fn intern(&mut self, key: String) -> Key {
+        match self.keys.get(key.as_str()) {
+            Some(shared) => Key::from(Arc::clone(shared)),
+            None => {
+                let shared: Arc<str> = key.into();
+                self.keys.insert(Arc::clone(&shared));
+                Key::from(shared)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn interned_keys_are_shared() -> Result<(), JsonError> {
+        let record = r#"{"timestamp":1,"level":"info","message":"m"}"#;
+        let document = format!("[{}]", vec![record; 10_000].join(","));
+
+        let options = ParseOptions {
+            intern_keys: true,
+            ..ParseOptions::default()
+        };
+        let interned = parse_json_string_with_options(&document, &options)?;
+        let plain = parse_json_string(&document)?;
+
+        let records = interned.as_array().unwrap();
+        let first = records[0].as_object().unwrap();
+        for record in records {
+            for ((key, _), (first_key, _)) in record
+                .as_object()
+                .unwrap()
+                .entries()
+                .iter()
+                .zip(first.entries())
+            {
+                let shared = key.as_shared().unwrap();
+                assert!(Arc::ptr_eq(shared, first_key.as_shared().unwrap()));
+                assert_eq!(Arc::strong_count(shared), 10_000);
+            }
+            assert_eq!(
+                record.as_object().unwrap().get("level"),
+                Some(&json!("info"))
+            );
+        }
+
+        assert_eq!(interned, plain);
+        assert!(interned.deep_memory_usage() < plain.deep_memory_usage());
+
+        //changing one key leaves the others sharing it alone
+        let mut interned = interned;
+        let record = &mut interned.as_array_mut().unwrap()[0];
+        let (key, _) = &mut record.as_object_mut().unwrap().entries_mut()[0];
+        key.make_mut().push_str("_ms");
+        assert_eq!(
+            interned.as_array().unwrap()[0].to_json_string(),
+            r#"{"timestamp_ms":1,"level":"info","message":"m"}"#
+        );
+        assert_eq!(
+            interned.as_array().unwrap()[1],
+            plain.as_array().unwrap()[1]
+        );
+
+        Ok(())
+    }
+}
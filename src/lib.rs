@@ -1,18 +1,154 @@
+#[cfg(feature = "arena")]
+mod arena;
+mod borrowed;
+mod build;
+mod bytes;
+mod compare;
+mod convert;
+mod cursor;
+mod diff;
+mod entry;
+mod events;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+mod index;
+mod indexed;
+mod iter;
+mod key;
+mod lossy;
+mod macros;
+mod merge;
+mod patch;
+mod path;
+mod pointer;
+mod position;
+mod prefix;
+mod project;
+mod push;
+mod read;
+mod recover;
+mod reencode;
+mod selector;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod serialize;
+mod shape;
+mod sorted;
+mod stats;
+#[cfg(test)]
+mod strategies;
+mod stream;
+mod traits;
+mod transform;
+mod walk;
+
+#[cfg(feature = "arena")]
+pub use arena::{parse_json_in, JsonArena, JsonValue};
+pub use borrowed::{parse_json_str_borrowed, JsonValueRef};
+pub use build::{array_of, ArrayBuilder, ObjectBuilder};
+pub use bytes::parse_json_bytes;
+pub use convert::{ConversionError, GetParsedError, GetParsedErrorKind};
+pub use diff::{diff, diff_patch, DiffEntry, DiffKind, DiffPatchOptions};
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use events::{JsonEvent, JsonTokenizer};
+pub use indexed::IndexedObject;
+pub use iter::{Iter, IterMut};
+pub use key::Key;
+pub use lossy::{parse_json_bytes_lossy, Utf8Warning};
+pub use merge::{merge, ArrayStrategy, MergeOptions};
+pub use patch::{apply_patch, merge_patch, PatchError, PatchErrorKind};
+pub use path::{PathError, SetPathOptions};
+pub use pointer::PointerError;
+pub use position::{Diagnostic, Position};
+pub use prefix::{parse_json_prefix, parse_json_prefix_from_iter};
+pub use project::{extract_pointers, parse_pointer_only};
+pub use push::{Progress, StreamingParser};
+pub use read::{parse_json_from_reader, parse_ndjson_from_reader, NdjsonReader, ReaderError};
+pub use recover::{parse_json_recovering, validate_json};
+pub use reencode::{reencode, ReencodeError};
+pub use selector::{Selector, SelectorError};
+pub use serialize::{
+    format_json, FormatError, IndentStyle, NewlineStyle, NonFinitePolicy, SerializeError,
+    SerializeOptions, SerializeOptionsBuilder,
+};
+pub use shape::{Required, Shape, ShapeError, ShapeErrorKind, UnknownKeys, ValidateOptions};
+pub use sorted::SortedObject;
+pub use stats::{key_histogram, parse_json_string_with_stats, JsonStats, KeyStats, ParseStats};
+pub use stream::{
+    parse_json_array_stream, parse_ndjson, parse_ndjson_from_iter, ArrayStream, NdjsonStream,
+};
+pub use traits::{FromJson, FromJsonError, ToJson};
+pub use transform::PruneOptions;
+pub use walk::{JsonPath, Leaves, PathSegment};
+
+use std::convert::TryFrom;
+
+use cursor::{Cursor, Plain, Source};
+use events::Events;
+use key::Interner;
+use position::Located;
+
 pub type Array = Vec<JsonObject>;
-pub type ObjectImpl = Vec<(String, JsonObject)>;
+pub type ObjectImpl = Vec<(Key, JsonObject)>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Object {
     entries: ObjectImpl,
 }
 
 impl Object {
+    #[inline]
+    pub const fn new() -> Self {
+        Object {
+            entries: Vec::new(),
+        }
+    }
+
     pub fn get(&self, index: &str) -> Option<&JsonObject> {
-        Some(&self.entries.iter().find(|(key, _)| key == index)?.1)
+        self.entries
+            .iter()
+            .find(|(key, _)| key.as_str() == index)
+            .map(|(_, value)| value)
+    }
+
+    /// The first entry with the key, its key borrowed from the object rather than `index`.
+    pub fn get_key_value(&self, index: &str) -> Option<(&str, &JsonObject)> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.as_str() == index)
+            .map(|(key, value)| (key.as_str(), value))
     }
 
     pub fn get_mut(&mut self, index: &str) -> Option<&mut JsonObject> {
-        Some(&mut self.entries.iter_mut().find(|(key, _)| key == index)?.1)
+        self.entries
+            .iter_mut()
+            .find(|(key, _)| key.as_str() == index)
+            .map(|(_, value)| value)
+    }
+
+    //every value stored under a duplicated key, in insertion order
+    pub fn get_all<'a>(&'a self, index: &'a str) -> impl Iterator<Item = &'a JsonObject> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(key, _)| key.as_str() == index)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_all_mut<'a>(
+        &'a mut self,
+        index: &'a str,
+    ) -> impl Iterator<Item = &'a mut JsonObject> + 'a {
+        self.entries
+            .iter_mut()
+            .filter(move |(key, _)| key.as_str() == index)
+            .map(|(_, value)| value)
+    }
+
+    pub fn key_count(&self, index: &str) -> usize {
+        self.entries
+            .iter()
+            .filter(|(key, _)| key.as_str() == index)
+            .count()
     }
 
     #[inline]
@@ -25,11 +161,92 @@ impl Object {
         &mut self.entries
     }
 
-    pub fn keys(&self) -> impl DoubleEndedIterator + '_ {
-        self.entries().iter().map(|(key, _)| key)
+    pub fn first(&self) -> Option<(&str, &JsonObject)> {
+        self.entries
+            .first()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    pub fn last(&self) -> Option<(&str, &JsonObject)> {
+        self.entries
+            .last()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    pub fn first_mut(&mut self) -> Option<(&str, &mut JsonObject)> {
+        self.entries
+            .first_mut()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    pub fn last_mut(&mut self) -> Option<(&str, &mut JsonObject)> {
+        self.entries
+            .last_mut()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Replaces the value of the first entry with the key in place, returning the old value, or
+    /// appends a new entry.
+    pub fn insert(&mut self, key: impl Into<Key>, value: JsonObject) -> Option<JsonObject> {
+        let key = key.into();
+
+        match self.get_mut(&key) {
+            Some(old) => Some(std::mem::replace(old, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    //entries, duplicate keys included
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes the first entry with the key, keeping the order of the others. This shifts every
+    /// later entry so it is O(n), see [`Object::swap_remove`] when order does not matter.
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<JsonObject> {
+        self.shift_remove(key)
+    }
+
+    /// Removes the first entry with the key, keeping the order of the others.
+    pub fn shift_remove(&mut self, key: &str) -> Option<JsonObject> {
+        let position = self.entries.iter().position(|(k, _)| k.as_str() == key)?;
+        Some(self.entries.remove(position).1)
+    }
+
+    /// Removes the first entry with the key and moves the last entry into its place. Finding the
+    /// key is still a linear scan since there is no index, but no entries are shifted.
+    pub fn swap_remove(&mut self, key: &str) -> Option<JsonObject> {
+        let position = self.entries.iter().position(|(k, _)| k.as_str() == key)?;
+        Some(self.entries.swap_remove(position).1)
+    }
+
+    //removes the last entry
+    #[inline]
+    pub fn pop(&mut self) -> Option<(Key, JsonObject)> {
+        self.entries.pop()
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.entries().iter().map(|(key, _)| key.as_str())
     }
 
-    pub fn keys_mut(&mut self) -> impl DoubleEndedIterator + '_ {
+    /// The keys themselves, to rename entries in place through [`Key::make_mut`].
+    pub fn keys_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Key> + '_ {
         self.entries_mut().iter_mut().map(|(key, _)| key)
     }
 
@@ -41,23 +258,74 @@ impl Object {
         self.entries_mut().iter_mut().map(|(_, value)| value)
     }
 
+    /// Stable sort of the entries, equal entries keep their relative order.
+    pub fn sort_by(
+        &mut self,
+        cmp: impl FnMut(&(Key, JsonObject), &(Key, JsonObject)) -> std::cmp::Ordering,
+    ) {
+        self.entries.sort_by(cmp);
+    }
+
+    /// Sorts the entries by key, duplicate keys keep their relative order.
+    pub fn sort_keys(&mut self) {
+        self.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    /// Stable sort by a key derived from each entry's key, `f` is called once per entry.
+    pub fn sort_by_cached_key<K: Ord>(&mut self, mut f: impl FnMut(&str) -> K) {
+        self.entries.sort_by_cached_key(|(key, _)| f(key));
+    }
+
     fn from_impl(entries: ObjectImpl) -> Self {
         Object { entries }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum JsonObject {
     Object(Object),
     Array(Array),
     String(String),
     Boolean(bool),
     Number(f64),
+    /// A number kept as its literal text, from [`NumberPolicy::PreserveString`].
+    RawNumber(String),
+    Null,
+}
+
+/// The type of a [`JsonObject`], see [`JsonObject::json_type`]. Displays as its lowercase name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonType {
+    Object,
+    Array,
+    String,
+    Boolean,
+    Number,
     Null,
 }
 
+impl JsonType {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            JsonType::Object => "object",
+            JsonType::Array => "array",
+            JsonType::String => "string",
+            JsonType::Boolean => "boolean",
+            JsonType::Number => "number",
+            JsonType::Null => "null",
+        }
+    }
+}
+
+impl std::fmt::Display for JsonType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 macro_rules! getter {
-    ($pat:path, $ident:ident, $name:ident) => {
+    ($(#[$attr:meta])* $pat:path, $ident:ident, $name:ident) => {
+        $(#[$attr])*
         #[inline]
         pub fn $name(&self) -> Option<&$ident> {
             match self {
@@ -69,7 +337,8 @@ macro_rules! getter {
 }
 
 macro_rules! getter_mut {
-    ($pat:path, $ident:ident, $name:ident) => {
+    ($(#[$attr:meta])* $pat:path, $ident:ident, $name:ident) => {
+        $(#[$attr])*
         #[inline]
         pub fn $name(&mut self) -> Option<&mut $ident> {
             match self {
@@ -81,7 +350,8 @@ macro_rules! getter_mut {
 }
 
 macro_rules! getter_into {
-    ($pat:path, $ident:ident, $name:ident) => {
+    ($(#[$attr:meta])* $pat:path, $ident:ident, $name:ident) => {
+        $(#[$attr])*
         #[inline]
         pub fn $name(self) -> Option<$ident> {
             match self {
@@ -92,552 +362,2859 @@ macro_rules! getter_into {
     };
 }
 
+static NULL: JsonObject = JsonObject::Null;
+
 impl JsonObject {
-    getter!(JsonObject::Object, Object, object);
-    getter!(JsonObject::Array, Array, array);
-    getter!(JsonObject::Boolean, bool, boolean);
-    getter!(JsonObject::Number, f64, number);
-    getter!(JsonObject::String, String, string);
-    getter_mut!(JsonObject::Object, Object, object_mut);
-    getter_mut!(JsonObject::Array, Array, array_mut);
-    getter_mut!(JsonObject::Boolean, bool, boolean_mut);
-    getter_mut!(JsonObject::Number, f64, number_mut);
-    getter_mut!(JsonObject::String, String, string_mut);
+    pub const NULL: JsonObject = JsonObject::Null;
+
+    //a null that outlives everything, for fallbacks returning a reference
+    #[inline]
+    pub fn null_ref() -> &'static JsonObject {
+        &NULL
+    }
+
+    #[inline]
+    pub const fn empty_object() -> JsonObject {
+        JsonObject::Object(Object::new())
+    }
+
+    #[inline]
+    pub const fn empty_array() -> JsonObject {
+        JsonObject::Array(Vec::new())
+    }
+
+    getter!(JsonObject::Object, Object, as_object);
+    getter!(JsonObject::Array, Array, as_array);
+    getter!(JsonObject::RawNumber, String, raw_number);
+    getter_mut!(JsonObject::Object, Object, as_object_mut);
+    getter_mut!(JsonObject::Array, Array, as_array_mut);
+    getter_mut!(JsonObject::Boolean, bool, as_bool_mut);
+    getter_mut!(JsonObject::Number, f64, as_f64_mut);
+    getter_mut!(JsonObject::String, String, as_string_mut);
     getter_into!(JsonObject::Object, Object, into_object);
     getter_into!(JsonObject::Array, Array, into_array);
     getter_into!(JsonObject::Boolean, bool, into_boolean);
     getter_into!(JsonObject::Number, f64, into_number);
     getter_into!(JsonObject::String, String, into_string);
 
+    getter!(
+        #[deprecated(note = "renamed to `as_object`")]
+        JsonObject::Object,
+        Object,
+        object
+    );
+    getter!(
+        #[deprecated(note = "renamed to `as_array`")]
+        JsonObject::Array,
+        Array,
+        array
+    );
+    getter!(
+        #[deprecated(note = "use `as_bool`")]
+        JsonObject::Boolean,
+        bool,
+        boolean
+    );
+    getter!(
+        #[deprecated(note = "use `as_f64`, which also reads raw numbers")]
+        JsonObject::Number,
+        f64,
+        number
+    );
+    getter!(
+        #[deprecated(note = "use `as_str`")]
+        JsonObject::String,
+        String,
+        string
+    );
+    getter_mut!(
+        #[deprecated(note = "renamed to `as_object_mut`")]
+        JsonObject::Object,
+        Object,
+        object_mut
+    );
+    getter_mut!(
+        #[deprecated(note = "renamed to `as_array_mut`")]
+        JsonObject::Array,
+        Array,
+        array_mut
+    );
+    getter_mut!(
+        #[deprecated(note = "renamed to `as_bool_mut`")]
+        JsonObject::Boolean,
+        bool,
+        boolean_mut
+    );
+    getter_mut!(
+        #[deprecated(note = "renamed to `as_f64_mut`")]
+        JsonObject::Number,
+        f64,
+        number_mut
+    );
+    getter_mut!(
+        #[deprecated(note = "renamed to `as_string_mut`")]
+        JsonObject::String,
+        String,
+        string_mut
+    );
+
     #[inline]
-    pub fn is_null(self) -> bool {
-        matches!(self, JsonObject::Null)
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonObject::String(s) => Some(s),
+            _ => None,
+        }
     }
-}
 
-#[derive(Debug, PartialEq)]
-pub enum JsonError {
-    UnexpectedChar(char),
-    UnexpectedKeyword,
-    UnknownEscapeCharacter(char),
-    ExtraChars(Vec<char>),
-    EarlyEndOfStream,
-    InvalidUnicode,
-    LeadingZero,
-}
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonObject::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
 
-impl std::fmt::Display for JsonError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+    /// The type of the value for dispatching on without matching every variant, a raw number
+    /// is a [`JsonType::Number`].
+    pub fn json_type(&self) -> JsonType {
+        match self {
+            JsonObject::Object(_) => JsonType::Object,
+            JsonObject::Array(_) => JsonType::Array,
+            JsonObject::String(_) => JsonType::String,
+            JsonObject::Boolean(_) => JsonType::Boolean,
+            JsonObject::Number(_) | JsonObject::RawNumber(_) => JsonType::Number,
+            JsonObject::Null => JsonType::Null,
+        }
     }
-}
 
-impl std::error::Error for JsonError {}
+    #[inline]
+    pub fn is_object(&self) -> bool {
+        matches!(self, JsonObject::Object(_))
+    }
 
-#[inline]
-pub fn parse_json_string(json_str: &str) -> Result<JsonObject, JsonError> {
-    return parse_json_from_iter(&mut json_str.chars());
-}
+    #[inline]
+    pub fn is_array(&self) -> bool {
+        matches!(self, JsonObject::Array(_))
+    }
 
-#[inline]
-pub fn parse_json_from_iter(
-    json_iter: &mut dyn Iterator<Item = char>,
-) -> Result<JsonObject, JsonError> {
-    use core::iter::once;
+    #[inline]
+    pub fn is_string(&self) -> bool {
+        matches!(self, JsonObject::String(_))
+    }
 
-    let (value, excess) = parse_json_impl(json_iter)?;
+    #[inline]
+    pub fn is_boolean(&self) -> bool {
+        matches!(self, JsonObject::Boolean(_))
+    }
 
-    let mut should_be_empty = excess
-        .into_iter()
-        .chain(json_iter)
-        .skip_while(|ch| ch.is_whitespace());
+    //raw numbers included
+    #[inline]
+    pub fn is_number(&self) -> bool {
+        matches!(self, JsonObject::Number(_) | JsonObject::RawNumber(_))
+    }
 
-    if let Some(ch) = should_be_empty.next() {
-        Err(JsonError::ExtraChars(
-            once(ch).chain(should_be_empty).collect(),
-        ))
-    } else {
-        Ok(value)
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonObject::Null)
     }
-}
 
-fn parse_json_impl(
-    json_iter: &mut dyn Iterator<Item = char>,
-) -> Result<(JsonObject, Option<char>), JsonError> {
-    let mut chars = json_iter.skip_while(|ch| ch.is_whitespace());
-
-    let result = match chars.next().ok_or(JsonError::EarlyEndOfStream)? {
-        //_n_ull
-        'n' => parse_null_impl(&mut chars),
-        //_t_rue
-        't' => parse_true_impl(&mut chars),
-        //_f_alse
-        'f' => parse_false_impl(&mut chars),
-        //array
-        '[' => parse_array_impl(&mut chars).map(JsonObject::Array),
-        //string
-        '"' => parse_string_impl(&mut chars).map(JsonObject::String),
-        //object
-        '{' => parse_object_impl(&mut chars).map(JsonObject::Object),
-        //has to be a number
-        ch => {
-            return parse_number_impl(json_iter, ch)
-                .map(|(n, excess)| (JsonObject::Number(n), excess));
+    //lossy: truncates toward zero and saturates at the i64 bounds, NaN has no integer value
+    pub fn as_i64_lossy(&self) -> Option<i64> {
+        if let JsonObject::RawNumber(literal) = self {
+            if let Some((n, _)) = literal_integer(literal) {
+                return Some(n.clamp(i64::MIN.into(), i64::MAX.into()) as i64);
+            }
         }
-    };
-
-    result.map(|obj| (obj, None))
-}
 
-fn parse_number_impl(
-    iter: &mut dyn Iterator<Item = char>,
-    starting_character: char,
-) -> Result<(f64, Option<char>), JsonError> {
-    let sign;
+        self.as_f64().filter(|n| !n.is_nan()).map(|n| n as i64)
+    }
 
-    let first_char = match starting_character {
-        '-' => {
-            sign = -1.;
-            iter.next().ok_or(JsonError::EarlyEndOfStream)?
-        }
-        other => {
-            sign = 1.;
-            other
+    /// Raw numbers are parsed, rounding to the nearest `f64`.
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonObject::Number(n) => Some(*n),
+            JsonObject::RawNumber(literal) => literal.parse().ok(),
+            _ => None,
         }
-    };
-
-    let mut number = match first_char {
-        digit @ '1'..='9' => digit.to_digit(10).unwrap() as f64,
-        //no leading 0 allowed other than for fraction
-        '0' => match iter.next().ok_or(JsonError::EarlyEndOfStream)? {
-            '.' => return parse_fraction_part_impl(iter, 0., sign),
-            'e' | 'E' => return parse_e_notation_impl(iter, 0.),
-            ch => return Ok((0., Some(ch))),
-        },
-        _ => return Err(JsonError::UnexpectedChar(first_char)),
-    };
+    }
 
-    loop {
-        match iter.next() {
-            Some(digit @ '0'..='9') => {
-                number *= 10.;
-                number += digit.to_digit(10).unwrap() as f64;
-            }
-            Some('.') => {
-                return parse_fraction_part_impl(iter, number, sign);
+    /// The number as an `i64` if it is integral and in range, without rounding or saturating.
+    /// Numbers are stored as `f64`, so integers past 2^53 have already lost precision while
+    /// parsing and come back as the nearest `f64`. Raw numbers are read exactly from their
+    /// literal.
+    pub fn as_i64(&self) -> Option<i64> {
+        //2^63 is exact as an f64, unlike i64::MAX
+        const BOUND: f64 = 9_223_372_036_854_775_808.;
+
+        match self {
+            JsonObject::Number(n) if n.fract() == 0. && (-BOUND..BOUND).contains(n) => {
+                Some(*n as i64)
             }
-            Some('e' | 'E') => {
-                return parse_e_notation_impl(iter, number * sign);
-            }
-            //jesus…
-            option => return Ok((number * sign, option)),
+            JsonObject::RawNumber(literal) => i64::try_from(exact_integer(literal)?).ok(),
+            _ => None,
         }
     }
-}
 
-//to be called when '.' is encountered while parsing number, should return a fraction (0.something)
-fn parse_fraction_part_impl(
-    iter: &mut dyn Iterator<Item = char>,
-    integer_part: f64,
-    sign: f64,
-) -> Result<(f64, Option<char>), JsonError> {
-    let mut number = 0.;
+    /// The number as a `u64` if it is integral and in range, see [`JsonObject::as_i64`].
+    pub fn as_u64(&self) -> Option<u64> {
+        const BOUND: f64 = 18_446_744_073_709_551_616.;
 
-    for n in 1.. {
-        match iter.next() {
-            Some(digit @ '0'..='9') => {
-                let digit = digit.to_digit(10).unwrap() as f64;
-                number += digit / 10_f64.powi(n);
-            }
-            Some('e' | 'E') => {
-                return parse_e_notation_impl(iter, (number + integer_part) * sign);
-            }
-            //jesus…
-            option => {
-                let result = (integer_part + number) * sign;
-                return Ok((result, option));
-            }
+        match self {
+            JsonObject::Number(n) if n.fract() == 0. && (0. ..BOUND).contains(n) => Some(*n as u64),
+            JsonObject::RawNumber(literal) => u64::try_from(exact_integer(literal)?).ok(),
+            _ => None,
         }
     }
 
-    unreachable!();
-}
+    pub fn as_f64_finite(&self) -> Option<f64> {
+        self.as_f64().filter(|n| n.is_finite())
+    }
 
-fn parse_e_notation_impl(
-    json_iter: &mut dyn Iterator<Item = char>,
-    number: f64,
-) -> Result<(f64, Option<char>), JsonError> {
-    let mut maybe_digit = None;
+    //`Ok(None)` for anything but a number other than NaN
+    pub fn as_number_clamped(&self, min: f64, max: f64) -> Result<Option<f64>, InvalidBounds> {
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(InvalidBounds { min, max });
+        }
 
-    let sign: i32;
+        Ok(self
+            .as_f64()
+            .filter(|n| !n.is_nan())
+            .map(|n| n.clamp(min, max)))
+    }
+}
 
-    match json_iter.next().ok_or(JsonError::EarlyEndOfStream)? {
-        '-' => {
-            sign = -1;
-        }
-        '+' => {
-            sign = 1;
-        }
-        digit @ '0'..='9' => {
-            sign = 1;
-            maybe_digit = Some(digit);
+//the integer part of a number literal, truncated toward zero, and whether a nonzero fraction was
+//dropped. `None` past the i128 range and for the non-finite literals
+fn literal_integer(literal: &str) -> Option<(i128, bool)> {
+    let (negative, unsigned) = match literal.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, literal),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(at) => (&unsigned[..at], unsigned[at + 1..].parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (int, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if int.is_empty()
+        || !int
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    //digits before the point are part of the integer, the point moved by the exponent
+    let point = exponent.saturating_add(int.len() as i64);
+    let mut n: i128 = 0;
+    let mut dropped = false;
+    let mut digits = 0;
+    for digit in int.bytes().chain(fraction.bytes()) {
+        let digit = i128::from(digit - b'0');
+        if digits < point {
+            n = n.checked_mul(10)?.checked_add(digit)?;
+        } else {
+            dropped |= digit != 0;
         }
-        ch => {
-            return Err(JsonError::UnexpectedChar(ch));
+        digits += 1;
+    }
+    if n != 0 {
+        for _ in digits..point {
+            n = n.checked_mul(10)?;
         }
     }
 
-    let mut iter = maybe_digit.into_iter().chain(json_iter);
-
-    let mut exponent: i32 = 0;
+    Some((if negative { -n } else { n }, dropped))
+}
 
-    loop {
-        match iter.next() {
-            Some(digit @ '0'..='9') => {
-                exponent *= 10;
-                exponent += digit.to_digit(10).unwrap() as i32;
-            }
-            //jesus…
-            option => {
-                let result = number * (10_f64).powi(exponent * sign);
-                return Ok((result, option));
-            }
-        }
+fn exact_integer(literal: &str) -> Option<i128> {
+    match literal_integer(literal)? {
+        (n, false) => Some(n),
+        (_, true) => None,
     }
 }
 
-//expects starting '"' to already be eaten
-fn parse_string_impl(json_iter: &mut dyn Iterator<Item = char>) -> Result<String, JsonError> {
-    let mut result = String::new();
+#[derive(Debug, PartialEq)]
+pub struct InvalidBounds {
+    pub min: f64,
+    pub max: f64,
+}
 
-    loop {
-        match json_iter.next().ok_or(JsonError::EarlyEndOfStream)? {
-            '"' => {
-                return Ok(result);
-            }
-            '\\' => result.push(parse_escape_character_impl(json_iter)?),
-            ch => {
-                result.push(ch);
-            }
-        }
+impl std::fmt::Display for InvalidBounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid bounds {} to {}, the minimum cannot be above the maximum or NaN",
+            self.min, self.max
+        )
     }
 }
 
-//expects '\' to already be eaten
-fn parse_escape_character_impl(
-    json_iter: &mut dyn Iterator<Item = char>,
-) -> Result<char, JsonError> {
-    let ch = json_iter.next().ok_or(JsonError::EarlyEndOfStream)?;
+impl std::error::Error for InvalidBounds {}
 
-    match ch {
-        '"' | '\\' | '/' => Ok(ch),
-        'n' => Ok('\n'),
-        'r' => Ok('\r'),
-        't' => Ok('\t'),
-        'f' => Ok('\u{0C}'),
-        'b' => Ok('\u{08}'),
-        'u' => parse_escaped_unicode(json_iter),
-        _ => Err(JsonError::UnknownEscapeCharacter(ch)),
-    }
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonErrorKind {
+    UnexpectedChar {
+        found: char,
+        expected: Expected,
+    },
+    UnexpectedKeyword,
+    UnknownEscapeCharacter(char),
+    /// A char below U+0020 inside a string, which has to be escaped.
+    UnescapedControlCharacter(char),
+    /// Something other than whitespace after the value, with up to the first 16 of the `count`
+    /// chars that are left.
+    ExtraChars {
+        preview: String,
+        count: usize,
+    },
+    /// The input ended outside of any string or container, e.g. it is empty or ends in `-`.
+    EarlyEndOfStream,
+    /// The input ended inside a string, before its closing `"`.
+    UnterminatedString,
+    /// The input ended inside an array. `open` counts the containers left open, the array
+    /// included.
+    UnclosedArray {
+        open: usize,
+    },
+    /// The input ended inside an object, see [`JsonErrorKind::UnclosedArray`].
+    UnclosedObject {
+        open: usize,
+    },
+    /// Something other than `:` after an object key.
+    MissingColon,
+    /// A `,` or `}` right after the `:` of an object entry.
+    MissingValue,
+    InvalidUnicode,
+    /// A digit after a leading `0`, as in `01` or `-00.5`.
+    LeadingZero,
+    Number(NumberError),
+    /// `NaN`, `Infinity` or `-Infinity`, which JSON does not allow unless
+    /// [`ParseOptions::allow_non_finite_numbers`] is set.
+    NonFiniteNumber,
+    /// More containers are nested than [`ParseOptions::max_depth`] allows.
+    RecursionLimitExceeded,
+    /// A key seen before in the same object, with [`DuplicateKeyPolicy::Error`].
+    DuplicateKey(String),
+    /// The document is larger than one of the limits of [`ParseOptions`] allows.
+    LimitExceeded(Limit),
+    /// Malformed UTF-8 starting at this byte offset, see [`parse_json_bytes`].
+    InvalidUtf8 {
+        offset: usize,
+    },
 }
 
-fn parse_escaped_unicode(json_iter: &mut dyn Iterator<Item = char>) -> Result<char, JsonError> {
-    let mut sum = 0_u16;
-
-    for ch in json_iter.take(4) {
-        let digit = ch.to_digit(0x10).ok_or(JsonError::InvalidUnicode)? as u16;
+/// What the parser was looking for when it hit an unexpected character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// The start of a value, including inside an array.
+    Value,
+    /// A key or the `}` closing an empty object.
+    ObjectKeyOrEnd,
+    /// A key after a `,`.
+    ObjectKey,
+    CommaOrArrayEnd,
+    CommaOrObjectEnd,
+    /// A digit inside a number, after a `-`, `.` or exponent.
+    Digit,
+    /// The `[` of an array read with [`parse_json_array_stream`].
+    ArrayStart,
+    /// The `/` or `*` after the first `/` of a comment, with [`ParseOptions::allow_comments`].
+    CommentStart,
+}
 
-        sum *= 0x10;
-        sum += digit;
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Expected::Value => "a value",
+            Expected::ObjectKeyOrEnd => "a key or '}'",
+            Expected::ObjectKey => "a key",
+            Expected::CommaOrArrayEnd => "',' or ']'",
+            Expected::CommaOrObjectEnd => "',' or '}'",
+            Expected::Digit => "a digit",
+            Expected::ArrayStart => "'['",
+            Expected::CommentStart => "'/' or '*'",
+        })
     }
+}
 
-    //utf16 surrogate pair
-    if sum >= 0xD800 && sum <= 0xDFFF {
-        if json_iter.take(2).ne("\\u".chars()) {
-            //should be followed by another utf16 surrogate
-            return Err(JsonError::InvalidUnicode);
-        }
-
-        let mut second_sum = 0_u16;
+#[inline]
+pub(crate) fn unexpected(found: char, expected: Expected) -> JsonErrorKind {
+    JsonErrorKind::UnexpectedChar { found, expected }
+}
 
-        for ch in json_iter.take(4) {
-            let digit = ch.to_digit(0x10).ok_or(JsonError::InvalidUnicode)? as u16;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Container {
+    Array,
+    Object,
+}
 
-            second_sum *= 0x10;
-            second_sum += digit;
+//running out of input in a container at `depth` leaves it open, unless the error already says
+//where the input ended
+pub(crate) fn unclosed(kind: JsonErrorKind, container: Container, depth: usize) -> JsonErrorKind {
+    match (kind, container) {
+        (JsonErrorKind::EarlyEndOfStream, Container::Array) => {
+            JsonErrorKind::UnclosedArray { open: depth + 1 }
         }
-
-        let pair = [sum as u16, second_sum];
-
-        let mut utf16 = char::decode_utf16(pair).map(|r| r.map_err(|_| JsonError::InvalidUnicode));
-
-        let decoded_char = utf16.next().ok_or(JsonError::InvalidUnicode)?;
-
-        if utf16.next().is_none() {
-            decoded_char
-        } else {
-            //should always be a pair thus returning only one char
-            unreachable!();
+        (JsonErrorKind::EarlyEndOfStream, Container::Object) => {
+            JsonErrorKind::UnclosedObject { open: depth + 1 }
         }
-    } else {
-        char::from_u32(sum as u32).ok_or(JsonError::InvalidUnicode)
+        (kind, _) => kind,
     }
 }
 
-fn parse_object_impl(mut json_iter: &mut dyn Iterator<Item = char>) -> Result<Object, JsonError> {
-    let mut could_be_empty = true;
-
-    let mut object = vec![];
-
-    loop {
-        let mut skipped = json_iter.skip_while(|ch| ch.is_whitespace());
-
-        match skipped.next().ok_or(JsonError::EarlyEndOfStream)? {
-            '"' => {}
-            ch => {
-                if could_be_empty && ch == '}' {
-                    return Ok(Object::from_impl(object));
-                } else {
-                    return Err(JsonError::UnexpectedChar(ch));
-                }
-            }
-        }
-
-        could_be_empty = false;
+//the first char of the value after the ':' of an object entry
+#[inline]
+pub(crate) fn entry_value(ch: char) -> Result<char, JsonErrorKind> {
+    match ch {
+        ',' | '}' => Err(JsonErrorKind::MissingValue),
+        ch => Ok(ch),
+    }
+}
 
-        let key = parse_string_impl(json_iter)?;
+//the rest is counted but only the start of it kept, it can be the bulk of the input
+pub(crate) fn extra_chars(rest: impl Iterator<Item = char>) -> JsonErrorKind {
+    const PREVIEW_LEN: usize = 16;
 
-        let mut skipped = json_iter.skip_while(|ch| ch.is_whitespace());
+    let mut preview = String::new();
+    let mut count = 0;
 
-        match skipped.next().ok_or(JsonError::EarlyEndOfStream)? {
-            ':' => {}
-            ch => return Err(JsonError::UnexpectedChar(ch)),
+    for ch in rest {
+        if count < PREVIEW_LEN {
+            preview.push(ch);
         }
+        count += 1;
+    }
 
-        let (value, maybe_excess) = parse_json_impl(json_iter)?;
+    JsonErrorKind::ExtraChars { preview, count }
+}
 
-        object.push((key, value));
+/// Which size limit of [`ParseOptions`] a document went over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// [`ParseOptions::max_total_nodes`]
+    TotalNodes,
+    /// [`ParseOptions::max_string_len`]
+    StringLength,
+    /// [`ParseOptions::max_array_elements`]
+    ArrayElements,
+    /// [`ParseOptions::max_object_entries`]
+    ObjectEntries,
+}
 
-        let mut skipped = maybe_excess
-            .into_iter()
-            .chain(&mut json_iter)
-            .skip_while(|ch| ch.is_whitespace());
+/// A well formed number rejected by [`ParseOptions`], holding the parsed value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberError {
+    NotAnInteger(f64),
+    OutOfRange(f64),
+    /// The literal does not survive a round trip through `f64`, with
+    /// [`NumberPolicy::ErrorOnPrecisionLoss`].
+    PrecisionLoss(String),
+}
 
-        match skipped.next().ok_or(JsonError::EarlyEndOfStream)? {
-            ',' => continue,
-            '}' => return Ok(Object::from_impl(object)),
-            ch => return Err(JsonError::UnexpectedChar(ch)),
+impl std::fmt::Display for JsonErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonErrorKind::UnexpectedChar { found, expected } => {
+                write!(f, "expected {} but found {:?}", expected, found)
+            }
+            JsonErrorKind::UnexpectedKeyword => f.write_str("unexpected keyword"),
+            JsonErrorKind::UnknownEscapeCharacter(ch) => {
+                write!(f, "unknown escape character {:?}", ch)
+            }
+            JsonErrorKind::UnescapedControlCharacter(ch) => {
+                write!(f, "unescaped control character {:?}", ch)
+            }
+            JsonErrorKind::ExtraChars { preview, .. } => write!(
+                f,
+                "unexpected character {:?} after the value",
+                preview.chars().next().unwrap_or_default()
+            ),
+            JsonErrorKind::EarlyEndOfStream => f.write_str("unexpected end of input"),
+            JsonErrorKind::UnterminatedString => {
+                f.write_str("the input ends inside a string, its closing '\"' is missing")
+            }
+            JsonErrorKind::UnclosedArray { open } => unclosed_message(f, "an array", ']', *open),
+            JsonErrorKind::UnclosedObject { open } => unclosed_message(f, "an object", '}', *open),
+            JsonErrorKind::MissingColon => f.write_str("expected ':' after the object key"),
+            JsonErrorKind::MissingValue => f.write_str("missing value after ':'"),
+            JsonErrorKind::InvalidUnicode => f.write_str("invalid unicode escape"),
+            JsonErrorKind::LeadingZero => f.write_str("leading zero in a number"),
+            JsonErrorKind::Number(NumberError::NotAnInteger(n)) => {
+                write!(f, "number {} is not an integer", n)
+            }
+            JsonErrorKind::Number(NumberError::OutOfRange(n)) => {
+                write!(f, "number {} is out of range", n)
+            }
+            JsonErrorKind::Number(NumberError::PrecisionLoss(literal)) => {
+                write!(f, "number {} cannot be represented exactly", literal)
+            }
+            JsonErrorKind::NonFiniteNumber => f.write_str("NaN and infinities are not valid JSON"),
+            JsonErrorKind::RecursionLimitExceeded => f.write_str("containers nested too deeply"),
+            JsonErrorKind::LimitExceeded(Limit::TotalNodes) => f.write_str("too many values"),
+            JsonErrorKind::LimitExceeded(Limit::StringLength) => f.write_str("string too long"),
+            JsonErrorKind::LimitExceeded(Limit::ArrayElements) => {
+                f.write_str("too many array elements")
+            }
+            JsonErrorKind::LimitExceeded(Limit::ObjectEntries) => {
+                f.write_str("too many object entries")
+            }
+            JsonErrorKind::DuplicateKey(key) => write!(f, "duplicate key {:?}", key),
+            JsonErrorKind::InvalidUtf8 { offset } => write!(f, "invalid UTF-8 at byte {}", offset),
         }
     }
 }
 
-fn parse_null_impl(json_iter: &mut dyn Iterator<Item = char>) -> Result<JsonObject, JsonError> {
-    //                    "_n_ull"
-    if json_iter.take(3).eq("ull".chars()) {
-        Ok(JsonObject::Null)
-    } else {
-        Err(JsonError::UnexpectedKeyword)
+fn unclosed_message(
+    f: &mut std::fmt::Formatter<'_>,
+    container: &str,
+    end: char,
+    open: usize,
+) -> std::fmt::Result {
+    write!(
+        f,
+        "the input ends inside {}, its closing '{}' is missing",
+        container, end
+    )?;
+    if open > 1 {
+        write!(f, " ({} containers are left open)", open)?;
     }
+    Ok(())
 }
 
-fn parse_true_impl(json_iter: &mut dyn Iterator<Item = char>) -> Result<JsonObject, JsonError> {
-    //                    "_t_rue"
-    if json_iter.take(3).eq("rue".chars()) {
-        Ok(JsonObject::Boolean(true))
-    } else {
-        Err(JsonError::UnexpectedKeyword)
-    }
+/// A parse error and where it was noticed, see [`Position`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    pub kind: JsonErrorKind,
+    pub position: Position,
 }
 
-fn parse_false_impl(json_iter: &mut dyn Iterator<Item = char>) -> Result<JsonObject, JsonError> {
-    //                    "_f_alse"
-    if json_iter.take(4).eq("alse".chars()) {
-        Ok(JsonObject::Boolean(false))
-    } else {
-        Err(JsonError::UnexpectedKeyword)
+//`expected ',' or '}' but found ':' at line 12, column 34`
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.kind, self.position)
     }
 }
 
-fn parse_array_impl(mut json_iter: &mut dyn Iterator<Item = char>) -> Result<Array, JsonError> {
-    let mut vec: Vec<JsonObject> = Vec::new();
+impl std::error::Error for JsonError {}
 
-    let mut could_be_empty = true;
+/// What to do with a key appearing more than once in an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    /// Every entry is kept in order, [`Object::get`] finds the first one and
+    /// [`Object::get_all`] all of them.
+    #[default]
+    KeepAll,
+    /// Later entries with the key are dropped.
+    FirstWins,
+    /// Later entries replace the value of the first one in place, like [`Object::insert`] does.
+    LastWins,
+    /// Fail with [`JsonErrorKind::DuplicateKey`].
+    Error,
+}
 
-    loop {
-        let result = parse_json_impl(json_iter);
+/// What to do with a container holding more members than [`ParseOptions::max_array_elements`]
+/// or [`ParseOptions::max_object_entries`] allow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitBehavior {
+    /// Fail with [`JsonErrorKind::LimitExceeded`] at the first member past the limit.
+    #[default]
+    Error,
+    /// Keep the first members and skip the others. Skipped members are still checked for
+    /// syntax and nesting depth, but nothing is built or allocated for them and no other limit
+    /// applies to them. [`parse_json_with_report`] tells whether it happened.
+    Truncate,
+}
+
+/// How numbers that `f64` cannot hold are parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberPolicy {
+    /// Round to the nearest `f64`, overflowing to infinity.
+    #[default]
+    Lossy,
+    /// Fail with [`NumberError::PrecisionLoss`] when the nearest `f64` prints as a different
+    /// number than the literal, and [`NumberError::OutOfRange`] when it overflows. `0.1` is fine
+    /// since it reads back as `0.1`, a 30 digit integer is not.
+    ErrorOnPrecisionLoss,
+    /// Keep every number as a [`JsonObject::RawNumber`] holding the literal, which serializes
+    /// back unchanged.
+    PreserveString,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Normalize every key and string value to Unicode NFC, so that keys differing only in
+    /// normalization form compare equal.
+    #[cfg(feature = "nfc")]
+    pub normalize_nfc: bool,
+    /// Reject numbers with a fractional part. Fractions and exponents are fine as long as the
+    /// value is integral, so `2.0` and `1e0` are accepted.
+    pub numbers_integer_only: bool,
+    /// Reject numbers whose absolute value is larger, e.g. `2f64.powi(53) - 1.` for JS-safe
+    /// integers.
+    pub max_number_magnitude: Option<f64>,
+    /// Containers nested deeper than this get no tracing span, `None` traces all of them. The
+    /// top level container is at depth 0.
+    #[cfg(feature = "tracing")]
+    pub trace_max_depth: Option<usize>,
+    /// How many containers may be nested, deeper documents fail with
+    /// [`JsonErrorKind::RecursionLimitExceeded`]. Defaults to [`ParseOptions::DEFAULT_MAX_DEPTH`].
+    ///
+    /// Parsing itself does not recurse, but dropping, comparing or formatting a value does, so
+    /// raising the limit a lot can still overflow the stack later on.
+    pub max_depth: usize,
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Accept `// line` and `/* block */` comments wherever whitespace is allowed.
+    pub allow_comments: bool,
+    /// Accept a comma after the last element of an array or entry of an object. Empty containers
+    /// still cannot hold a lone comma.
+    pub allow_trailing_commas: bool,
+    pub number_policy: NumberPolicy,
+    /// Accept the `NaN`, `Infinity` and `-Infinity` literals written by Python's `json` and some
+    /// javascript emitters, spelled exactly so, as [`JsonObject::Number`]s. They are still
+    /// subject to `numbers_integer_only` and `max_number_magnitude`.
+    pub allow_non_finite_numbers: bool,
+    /// Share one allocation between the equal keys of a document, see [`Key`]. Worth it when
+    /// the same few keys repeat across many objects, at the cost of a hash lookup per key.
+    pub intern_keys: bool,
+    /// Fail with [`Limit::TotalNodes`] as soon as the document holds more values than this,
+    /// counting containers and the document itself but not keys.
+    pub max_total_nodes: Option<usize>,
+    /// Fail with [`Limit::StringLength`] as soon as a string or key is longer than this many
+    /// UTF-8 bytes after unescaping, before the rest of it is read.
+    pub max_string_len: Option<usize>,
+    /// How many elements an array may hold, see `on_limit`.
+    pub max_array_elements: Option<usize>,
+    /// How many entries an object may hold, duplicate keys included, see `on_limit`.
+    pub max_object_entries: Option<usize>,
+    pub on_limit: LimitBehavior,
+}
+
+impl ParseOptions {
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            #[cfg(feature = "nfc")]
+            normalize_nfc: false,
+            numbers_integer_only: false,
+            max_number_magnitude: None,
+            #[cfg(feature = "tracing")]
+            trace_max_depth: None,
+            max_depth: ParseOptions::DEFAULT_MAX_DEPTH,
+            duplicate_keys: DuplicateKeyPolicy::default(),
+            allow_comments: false,
+            allow_trailing_commas: false,
+            number_policy: NumberPolicy::default(),
+            allow_non_finite_numbers: false,
+            intern_keys: false,
+            max_total_nodes: None,
+            max_string_len: None,
+            max_array_elements: None,
+            max_object_entries: None,
+            on_limit: LimitBehavior::default(),
+        }
+    }
+}
+
+#[inline]
+pub fn parse_json_string(json_str: &str) -> Result<JsonObject, JsonError> {
+    parse_json_string_with_options(json_str, &ParseOptions::default())
+}
+
+#[inline]
+pub fn parse_json_string_with_options(
+    json_str: &str,
+    options: &ParseOptions,
+) -> Result<JsonObject, JsonError> {
+    parse_from_iter_impl(json_str.chars(), options, &mut ParseReport::default())
+}
+
+/// Parses from any source of chars, e.g. `input.chars()` or a `&mut` to an iterator to keep
+/// using it afterwards. Reading stops at the first error.
+#[inline]
+pub fn parse_json_from_iter<I: Iterator<Item = char>>(
+    json_iter: I,
+) -> Result<JsonObject, JsonError> {
+    parse_json_from_iter_with_options(json_iter, &ParseOptions::default())
+}
+
+pub fn parse_json_from_iter_with_options<I: Iterator<Item = char>>(
+    json_iter: I,
+    options: &ParseOptions,
+) -> Result<JsonObject, JsonError> {
+    parse_from_iter_impl(Plain(json_iter), options, &mut ParseReport::default())
+}
+
+/// What [`LimitBehavior::Truncate`] dropped from a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Arrays and objects that lost members.
+    pub truncated_containers: usize,
+    /// Members dropped from them, nested values not counted.
+    pub skipped_values: usize,
+}
+
+impl ParseReport {
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated_containers != 0
+    }
+}
+
+/// [`parse_json_string_with_options`] also reporting whether containers were truncated.
+///
+/// ```
+/// use json_parser::{json, parse_json_with_report, LimitBehavior, ParseOptions};
+///
+/// let options = ParseOptions {
+///     max_array_elements: Some(2),
+///     on_limit: LimitBehavior::Truncate,
+///     ..ParseOptions::default()
+/// };
+/// let (value, report) = parse_json_with_report("[1, 2, 3, 4]", &options)?;
+///
+/// assert_eq!(value, json!([1, 2]));
+/// assert_eq!(report.skipped_values, 2);
+/// # Ok::<(), json_parser::JsonError>(())
+/// ```
+pub fn parse_json_with_report(
+    json_str: &str,
+    options: &ParseOptions,
+) -> Result<(JsonObject, ParseReport), JsonError> {
+    let mut report = ParseReport::default();
+    let value = parse_from_iter_impl(json_str.chars(), options, &mut report)?;
+
+    Ok((value, report))
+}
+
+fn parse_from_iter_impl<S: Source>(
+    json_iter: S,
+    options: &ParseOptions,
+    report: &mut ParseReport,
+) -> Result<JsonObject, JsonError> {
+    let mut located = Located::new(json_iter);
+
+    #[cfg(feature = "tracing")]
+    {
+        let _document = tracing::debug_span!("json_document").entered();
+
+        let result = parse_document_impl(&mut located, options, report);
+
+        match &result {
+            Ok(_) => {
+                let end = located.current_position();
+                tracing::debug!(line = end.line, column = end.column, "document parsed")
+            }
+            Err(error) => tracing::debug!(
+                error = %error.kind,
+                line = error.position.line,
+                column = error.position.column,
+                "parse error"
+            ),
+        }
+
+        result
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    parse_document_impl(&mut located, options, report)
+}
+
+//a whole document, nothing but whitespace may follow the value
+fn parse_document_impl<S: Source>(
+    located: &mut Located<S>,
+    options: &ParseOptions,
+    report: &mut ParseReport,
+) -> Result<JsonObject, JsonError> {
+    use core::iter::once;
+
+    let parsed = {
+        let mut cursor = Cursor::new(located);
+        parse_reported_impl(&mut cursor, options, 0, report)
+            .and_then(|value| Ok((value, cursor.peek_non_whitespace(options)?)))
+    };
+    let (value, extra) = parsed.map_err(|kind| located.error(kind))?;
+
+    //the extra char is the last one read, so the position is right
+    match extra {
+        Some(ch) => {
+            let position = located.current_position();
+            Err(JsonError {
+                kind: extra_chars(once(ch).chain(located)),
+                position,
+            })
+        }
+        None => Ok(value),
+    }
+}
+
+//a container being parsed, values are added to the innermost one as they complete
+enum Partial {
+    Array(Array),
+    //the key of the value being parsed
+    Object(ObjectImpl, String),
+}
+
+struct Frame {
+    partial: Partial,
+    #[cfg(feature = "tracing")]
+    _span: Option<tracing::span::EnteredSpan>,
+}
+
+impl Frame {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn new(partial: Partial, depth: usize, options: &ParseOptions) -> Self {
+        Frame {
+            #[cfg(feature = "tracing")]
+            _span: container_span(
+                match partial {
+                    Partial::Array(_) => "array",
+                    Partial::Object(..) => "object",
+                },
+                depth,
+                options,
+            ),
+            partial,
+        }
+    }
+}
+
+//`depth` is the nesting depth of the value, 0 at the top level. A char read past the value is
+//left peeked in `cursor`
+fn parse_json_impl(
+    cursor: &mut Cursor<impl Source + ?Sized>,
+    options: &ParseOptions,
+    depth: usize,
+) -> Result<JsonObject, JsonErrorKind> {
+    parse_reported_impl(cursor, options, depth, &mut ParseReport::default())
+}
+
+//`parse_json_impl` also filling in `report`
+fn parse_reported_impl(
+    cursor: &mut Cursor<impl Source + ?Sized>,
+    options: &ParseOptions,
+    depth: usize,
+    report: &mut ParseReport,
+) -> Result<JsonObject, JsonErrorKind> {
+    let mut events = Events::new(depth);
+    let mut stack = Vec::new();
+    let mut interner = options.intern_keys.then(Interner::default);
+
+    let result = build_tree(cursor, options, &mut events, &mut stack, &mut interner);
+
+    //innermost first, so that container spans are exited in order on errors too
+    while stack.pop().is_some() {}
+
+    *report = events.into_report();
+    result
+}
+
+//the value the events of `Events` describe. Containers are kept on `stack` rather than the call
+//stack, so nesting is only bounded by memory and `ParseOptions::max_depth`
+fn build_tree(
+    cursor: &mut Cursor<impl Source + ?Sized>,
+    options: &ParseOptions,
+    events: &mut Events,
+    stack: &mut Vec<Frame>,
+    interner: &mut Option<Interner>,
+) -> Result<JsonObject, JsonErrorKind> {
+    let depth = events.depth();
+
+    loop {
+        let event = match events.next_event(cursor, options)? {
+            Some(event) => event,
+            None => unreachable!("the value is returned as soon as it is complete"),
+        };
+
+        let value = match event {
+            JsonEvent::StartArray => {
+                stack.push(Frame::new(
+                    Partial::Array(vec![]),
+                    depth + stack.len(),
+                    options,
+                ));
+                continue;
+            }
+            JsonEvent::StartObject => {
+                stack.push(Frame::new(
+                    Partial::Object(vec![], String::new()),
+                    depth + stack.len(),
+                    options,
+                ));
+                continue;
+            }
+            JsonEvent::Key(key) => {
+                if let Some(Frame {
+                    partial: Partial::Object(entries, pending),
+                    ..
+                }) = stack.last_mut()
+                {
+                    if options.duplicate_keys == DuplicateKeyPolicy::Error
+                        && entries.iter().any(|(k, _)| *k == key)
+                    {
+                        return Err(JsonErrorKind::DuplicateKey(key));
+                    }
+                    *pending = key;
+                }
+                continue;
+            }
+            JsonEvent::EndArray | JsonEvent::EndObject => {
+                match stack.pop().map(|frame| frame.partial) {
+                    Some(Partial::Array(array)) => JsonObject::Array(array),
+                    Some(Partial::Object(entries, _)) => {
+                        JsonObject::Object(Object::from_impl(entries))
+                    }
+                    None => unreachable!(),
+                }
+            }
+            JsonEvent::String(s) => JsonObject::String(s),
+            JsonEvent::Number(_) if options.number_policy == NumberPolicy::PreserveString => {
+                JsonObject::RawNumber(events.take_number_text().unwrap_or_default())
+            }
+            JsonEvent::Number(n) => JsonObject::Number(n),
+            JsonEvent::Boolean(b) => JsonObject::Boolean(b),
+            JsonEvent::Null => JsonObject::Null,
+        };
+
+        //hand the value to its container
+        match stack.last_mut().map(|frame| &mut frame.partial) {
+            Some(Partial::Array(array)) => array.push(value),
+            Some(Partial::Object(entries, key)) => {
+                add_entry(entries, std::mem::take(key), value, options, interner)
+            }
+            None => return Ok(value),
+        }
+    }
+}
+
+fn add_entry(
+    entries: &mut ObjectImpl,
+    key: String,
+    value: JsonObject,
+    options: &ParseOptions,
+    interner: &mut Option<Interner>,
+) {
+    let existing = match options.duplicate_keys {
+        DuplicateKeyPolicy::KeepAll | DuplicateKeyPolicy::Error => None,
+        DuplicateKeyPolicy::FirstWins | DuplicateKeyPolicy::LastWins => {
+            entries.iter_mut().find(|(k, _)| *k == key)
+        }
+    };
+
+    match existing {
+        Some((_, old)) if options.duplicate_keys == DuplicateKeyPolicy::LastWins => *old = value,
+        Some(_) => {}
+        None => {
+            let key = match interner {
+                Some(interner) => interner.intern(key),
+                None => Key::from(key),
+            };
+            entries.push((key, value));
+        }
+    }
+}
+
+//a container at `depth` has `depth` others around it
+fn check_depth(depth: usize, options: &ParseOptions) -> Result<(), JsonErrorKind> {
+    if depth < options.max_depth {
+        Ok(())
+    } else {
+        Err(JsonErrorKind::RecursionLimitExceeded)
+    }
+}
+
+fn check_number(n: f64, options: &ParseOptions) -> Result<f64, JsonErrorKind> {
+    if options.numbers_integer_only && n.fract() != 0. {
+        Err(JsonErrorKind::Number(NumberError::NotAnInteger(n)))
+    } else if options
+        .max_number_magnitude
+        .is_some_and(|max| n.abs() > max)
+    {
+        Err(JsonErrorKind::Number(NumberError::OutOfRange(n)))
+    } else {
+        Ok(n)
+    }
+}
+
+//`n` as parsed from `literal` by `parse_number_impl`, under a number policy that needs the text
+fn checked_number(n: f64, literal: &str, options: &ParseOptions) -> Result<f64, JsonErrorKind> {
+    let n = check_number(n, options)?;
+
+    match options.number_policy {
+        NumberPolicy::Lossy | NumberPolicy::PreserveString => Ok(n),
+        NumberPolicy::ErrorOnPrecisionLoss if n.is_infinite() => {
+            Err(JsonErrorKind::Number(NumberError::OutOfRange(n)))
+        }
+        NumberPolicy::ErrorOnPrecisionLoss => {
+            if significant_digits(literal) == significant_digits(&format!("{:e}", n)) {
+                Ok(n)
+            } else {
+                Err(JsonErrorKind::Number(NumberError::PrecisionLoss(
+                    literal.to_owned(),
+                )))
+            }
+        }
+    }
+}
+
+//the digits without leading or trailing zeros and the exponent of the first one, so that equal
+//decimal values compare equal. Zero has no digits, whatever its sign
+fn significant_digits(number: &str) -> (bool, String, i64) {
+    let negative = number.starts_with('-');
+    let number = number.trim_start_matches('-');
+
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(e) => (&number[..e], &number[e + 1..]),
+        None => (number, "0"),
+    };
+    //such exponents never make it through `f64` anyway, the digits differ by then
+    let exponent = exponent.trim_start_matches('+').parse().unwrap_or(0);
+
+    let integer_len = mantissa.find('.').unwrap_or(mantissa.len()) as i64;
+    let digits: String = mantissa.chars().filter(|&ch| ch != '.').collect();
+
+    let leading = digits.len() - digits.trim_start_matches('0').len();
+    let digits = digits.trim_matches('0');
+
+    if digits.is_empty() {
+        return (false, String::new(), 0);
+    }
+
+    (
+        negative,
+        digits.to_owned(),
+        exponent + integer_len - leading as i64,
+    )
+}
+
+//the char after the number is only peeked
+fn parse_number_impl(
+    cursor: &mut Cursor<impl Source + ?Sized>,
+    starting_character: char,
+) -> Result<f64, JsonErrorKind> {
+    let mut literal = Literal::default();
+
+    let first_digit = match starting_character {
+        '-' => {
+            literal.negative = true;
+            cursor.next().ok_or(JsonErrorKind::EarlyEndOfStream)?
+        }
+        other => other,
+    };
+
+    match first_digit {
+        '0' => {
+            literal.push(first_digit);
+            //a leading 0 is the whole integer part, so `01` is not `0` followed by a stray `1`
+            if let Some('0'..='9') = cursor.peek() {
+                cursor.next();
+                return Err(JsonErrorKind::LeadingZero);
+            }
+        }
+        '1'..='9' => {
+            literal.push(first_digit);
+            read_digits(cursor, &mut literal);
+        }
+        'I' if starting_character == '-' => return Err(rejected_non_finite(cursor, 'I')),
+        //a lone '-' is not a value yet
+        ch if starting_character == '-' => return Err(unexpected(ch, Expected::Digit)),
+        ch @ ('N' | 'I') => return Err(rejected_non_finite(cursor, ch)),
+        ch => return Err(unexpected(ch, Expected::Value)),
+    }
+
+    if cursor.peek() == Some('.') {
+        cursor.next();
+        parse_fraction_part_impl(cursor, &mut literal)?;
+    }
+
+    if let Some('e' | 'E') = cursor.peek() {
+        cursor.next();
+        parse_e_notation_impl(cursor, &mut literal)?;
+    }
+
+    Ok(literal.value())
+}
+
+//10^0 to 10^22, the powers of ten an `f64` holds exactly
+const POWERS_OF_TEN: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+//a `u64` holds any 19 digits
+const MANTISSA_DIGITS: usize = 19;
+
+//the digits of a number as they are read, the '.' left out. Most numbers fit the `u64` and are
+//exact from there, the others are parsed again from their digits
+#[derive(Default)]
+struct Literal {
+    negative: bool,
+    mantissa: u64,
+    digits: usize,
+    fraction_digits: usize,
+    //every digit once there are more than `MANTISSA_DIGITS`
+    long: String,
+    //as written after the 'e'
+    exponent: i32,
+}
+
+impl Literal {
+    //`digit` is an ASCII digit
+    #[inline]
+    fn push(&mut self, digit: char) {
+        if self.digits < MANTISSA_DIGITS {
+            self.mantissa = self.mantissa * 10 + (digit as u64 - '0' as u64);
+        } else {
+            if self.long.is_empty() {
+                self.long = format!("{:01$}", self.mantissa, MANTISSA_DIGITS);
+            }
+            self.long.push(digit);
+        }
+        self.digits += 1;
+    }
+
+    //correctly rounded, however many digits and whatever the exponent
+    fn value(&self) -> f64 {
+        let exponent = self.exponent as i64 - self.fraction_digits as i64;
+
+        //exact operands and a single rounding, as `f64::from_str` would do it
+        let fast = match exponent {
+            _ if self.digits > MANTISSA_DIGITS || self.mantissa > 1 << 53 => None,
+            0..=22 => Some(self.mantissa as f64 * POWERS_OF_TEN[exponent as usize]),
+            -22..=-1 => Some(self.mantissa as f64 / POWERS_OF_TEN[-exponent as usize]),
+            _ => None,
+        };
+
+        let n = fast.unwrap_or_else(|| self.text(exponent).parse().unwrap());
+
+        if self.negative {
+            -n
+        } else {
+            n
+        }
+    }
+
+    //what `f64::from_str` reads as the same value. It caps exponents at 65536, so the point is
+    //placed among the digits when there are many of them
+    fn text(&self, exponent: i64) -> String {
+        if self.long.is_empty() {
+            return format!("{}e{}", self.mantissa, exponent);
+        }
+
+        let digits = self.long.len() as i64;
+        let point = digits + exponent;
+        if point > digits {
+            format!("{}e{}", self.long, exponent)
+        } else if point >= 0 {
+            let (integer, fraction) = self.long.split_at(point as usize);
+            format!("{}.{}", integer, fraction)
+        } else {
+            format!(".{}e{}", self.long, point)
+        }
+    }
+}
+
+#[inline]
+fn read_digits(cursor: &mut Cursor<impl Source + ?Sized>, literal: &mut Literal) {
+    while let Some(digit @ '0'..='9') = cursor.peek() {
+        cursor.next();
+        literal.push(digit);
+    }
+}
+
+//to be called when '.' is encountered while parsing number
+fn parse_fraction_part_impl(
+    cursor: &mut Cursor<impl Source + ?Sized>,
+    literal: &mut Literal,
+) -> Result<(), JsonErrorKind> {
+    //at least one digit has to follow the '.'
+    match cursor.peek() {
+        Some('0'..='9') => {}
+        Some(ch) => {
+            cursor.next();
+            return Err(unexpected(ch, Expected::Digit));
+        }
+        None => return Err(JsonErrorKind::EarlyEndOfStream),
+    }
+
+    let before = literal.digits;
+    read_digits(cursor, literal);
+    literal.fraction_digits = literal.digits - before;
+    Ok(())
+}
+
+fn parse_e_notation_impl(
+    cursor: &mut Cursor<impl Source + ?Sized>,
+    literal: &mut Literal,
+) -> Result<(), JsonErrorKind> {
+    let mut exponent: i32 = 0;
+
+    let negative = match cursor.next().ok_or(JsonErrorKind::EarlyEndOfStream)? {
+        sign @ ('-' | '+') => {
+            //a sign must be followed by at least one digit
+            match cursor.peek() {
+                Some('0'..='9') => {}
+                Some(ch) => {
+                    cursor.next();
+                    return Err(unexpected(ch, Expected::Digit));
+                }
+                None => return Err(JsonErrorKind::EarlyEndOfStream),
+            }
+            sign == '-'
+        }
+        digit @ '0'..='9' => {
+            exponent = digit as i32 - '0' as i32;
+            false
+        }
+        ch => return Err(unexpected(ch, Expected::Digit)),
+    };
+
+    while let Some(digit @ '0'..='9') = cursor.peek() {
+        cursor.next();
+        //saturating, the number is 0 or infinite long before the exponent overflows
+        exponent = exponent
+            .saturating_mul(10)
+            .saturating_add(digit as i32 - '0' as i32);
+    }
+
+    literal.exponent = if negative { -exponent } else { exponent };
+    Ok(())
+}
+
+//expects starting '"' to already be eaten
+#[inline]
+fn parse_string_impl(json_iter: &mut (impl Source + ?Sized)) -> Result<String, JsonErrorKind> {
+    parse_limited_string_impl(json_iter, usize::MAX)
+}
+
+//`parse_string_impl` failing as soon as the string is longer than `max_len` bytes
+fn parse_limited_string_impl(
+    json_iter: &mut (impl Source + ?Sized),
+    max_len: usize,
+) -> Result<String, JsonErrorKind> {
+    let mut result = String::new();
+
+    loop {
+        //runs without escapes in one go when the source allows it
+        json_iter.read_unescaped(&mut result, max_len);
+
+        match json_iter.next().ok_or(JsonErrorKind::UnterminatedString)? {
+            '"' => {
+                return Ok(result);
+            }
+            '\\' => result.push(parse_escape_character_impl(json_iter)?),
+            ch if ch < '\u{20}' => return Err(JsonErrorKind::UnescapedControlCharacter(ch)),
+            ch => {
+                result.push(ch);
+            }
+        }
+
+        if result.len() > max_len {
+            return Err(JsonErrorKind::LimitExceeded(Limit::StringLength));
+        }
+    }
+}
+
+//validates a string without building it, expects '"' to already be eaten
+fn skip_string(json_iter: &mut (impl Iterator<Item = char> + ?Sized)) -> Result<(), JsonErrorKind> {
+    loop {
+        match json_iter.next().ok_or(JsonErrorKind::UnterminatedString)? {
+            '"' => return Ok(()),
+            '\\' => {
+                parse_escape_character_impl(json_iter)?;
+            }
+            ch if ch < '\u{20}' => return Err(JsonErrorKind::UnescapedControlCharacter(ch)),
+            _ => {}
+        }
+    }
+}
+
+//parse_string_impl plus the transformations requested in the options, for keys and values
+fn parse_string_value_impl(
+    json_iter: &mut (impl Source + ?Sized),
+    options: &ParseOptions,
+) -> Result<String, JsonErrorKind> {
+    let string =
+        parse_limited_string_impl(json_iter, options.max_string_len.unwrap_or(usize::MAX))?;
+
+    #[cfg(feature = "nfc")]
+    if options.normalize_nfc {
+        return Ok(normalize_nfc(string));
+    }
+
+    Ok(string)
+}
+
+#[cfg(feature = "nfc")]
+pub(crate) fn normalize_nfc(string: String) -> String {
+    use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+    match is_nfc_quick(string.chars()) {
+        IsNormalized::Yes => string,
+        _ => string.nfc().collect(),
+    }
+}
+
+//expects '\' to already be eaten, the escapes of strings only
+fn parse_escape_character_impl(
+    json_iter: &mut (impl Iterator<Item = char> + ?Sized),
+) -> Result<char, JsonErrorKind> {
+    let ch = json_iter.next().ok_or(JsonErrorKind::UnterminatedString)?;
+
+    match ch {
+        '"' | '\\' | '/' => Ok(ch),
+        'n' => Ok('\n'),
+        'r' => Ok('\r'),
+        't' => Ok('\t'),
+        'f' => Ok('\u{0C}'),
+        'b' => Ok('\u{08}'),
+        'u' => parse_escaped_unicode(json_iter),
+        _ => Err(JsonErrorKind::UnknownEscapeCharacter(ch)),
+    }
+}
+
+//the four hex digits of a `\u` escape
+fn parse_hex4(json_iter: &mut (impl Iterator<Item = char> + ?Sized)) -> Result<u16, JsonErrorKind> {
+    let mut sum = 0_u16;
+
+    for _ in 0..4 {
+        let ch = json_iter.next().ok_or(JsonErrorKind::UnterminatedString)?;
+        let digit = ch.to_digit(0x10).ok_or(JsonErrorKind::InvalidUnicode)? as u16;
+
+        sum *= 0x10;
+        sum += digit;
+    }
+
+    Ok(sum)
+}
+
+fn parse_escaped_unicode(
+    json_iter: &mut (impl Iterator<Item = char> + ?Sized),
+) -> Result<char, JsonErrorKind> {
+    let sum = parse_hex4(json_iter)?;
+
+    //utf16 surrogate pair
+    if (0xD800..=0xDFFF).contains(&sum) {
+        //should be followed by another utf16 surrogate
+        for expected in ['\\', 'u'] {
+            match json_iter.next() {
+                Some(ch) if ch == expected => {}
+                Some(_) => return Err(JsonErrorKind::InvalidUnicode),
+                None => return Err(JsonErrorKind::UnterminatedString),
+            }
+        }
+
+        let pair = [sum, parse_hex4(json_iter)?];
+
+        //a lone or reversed surrogate fails to decode, a valid pair is always a single char
+        char::decode_utf16(pair)
+            .next()
+            .and_then(Result::ok)
+            .ok_or(JsonErrorKind::InvalidUnicode)
+    } else {
+        char::from_u32(sum as u32).ok_or(JsonErrorKind::InvalidUnicode)
+    }
+}
+
+//entered for as long as the container is being parsed
+#[cfg(feature = "tracing")]
+fn container_span(
+    kind: &'static str,
+    depth: usize,
+    options: &ParseOptions,
+) -> Option<tracing::span::EnteredSpan> {
+    if options.trace_max_depth.is_none_or(|max| depth <= max) {
+        Some(tracing::trace_span!("json_container", kind, depth).entered())
+    } else {
+        None
+    }
+}
+
+//`NaN` or `Infinity` after its already eaten `first` char, the sign is up to the caller
+fn parse_non_finite_impl(
+    json_iter: &mut (impl Iterator<Item = char> + ?Sized),
+    first: char,
+) -> Result<f64, JsonErrorKind> {
+    let (rest, n) = match first {
+        'N' => ("aN", f64::NAN),
+        _ => ("nfinity", f64::INFINITY),
+    };
+
+    if eat_keyword(json_iter, rest) {
+        Ok(n)
+    } else {
+        Err(JsonErrorKind::UnexpectedKeyword)
+    }
+}
+
+//without `allow_non_finite_numbers` the literals are still read whole, so that they are told
+//apart from misspellings
+#[cold]
+fn rejected_non_finite(cursor: &mut Cursor<impl Source + ?Sized>, first: char) -> JsonErrorKind {
+    match parse_non_finite_impl(cursor, first) {
+        Ok(_) => JsonErrorKind::NonFiniteNumber,
+        Err(err) => err,
+    }
+}
+
+//true when the next chars are `rest`, nothing past the first mismatch is read
+#[inline]
+fn eat_keyword(json_iter: &mut (impl Iterator<Item = char> + ?Sized), rest: &str) -> bool {
+    rest.chars()
+        .all(|expected| json_iter.next() == Some(expected))
+}
+
+fn parse_null_impl(
+    json_iter: &mut (impl Iterator<Item = char> + ?Sized),
+) -> Result<JsonObject, JsonErrorKind> {
+    //                    "_n_ull"
+    if eat_keyword(json_iter, "ull") {
+        Ok(JsonObject::Null)
+    } else {
+        Err(JsonErrorKind::UnexpectedKeyword)
+    }
+}
+
+fn parse_true_impl(
+    json_iter: &mut (impl Iterator<Item = char> + ?Sized),
+) -> Result<JsonObject, JsonErrorKind> {
+    //                    "_t_rue"
+    if eat_keyword(json_iter, "rue") {
+        Ok(JsonObject::Boolean(true))
+    } else {
+        Err(JsonErrorKind::UnexpectedKeyword)
+    }
+}
+
+fn parse_false_impl(
+    json_iter: &mut (impl Iterator<Item = char> + ?Sized),
+) -> Result<JsonObject, JsonErrorKind> {
+    //                    "_f_alse"
+    if eat_keyword(json_iter, "alse") {
+        Ok(JsonObject::Boolean(false))
+    } else {
+        Err(JsonErrorKind::UnexpectedKeyword)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn null_type() {
+        assert_eq!(parse_json_string("null").unwrap(), JsonObject::Null);
+    }
+
+    #[test]
+    fn basic_boolean() {
+        assert!(matches!(
+            parse_json_string("true").unwrap(),
+            JsonObject::Boolean(true)
+        ));
+
+        assert!(matches!(
+            parse_json_string("false").unwrap(),
+            JsonObject::Boolean(false)
+        ));
+    }
+    #[test]
+    fn array_one_element() {
+        let result = parse_json_string("[ true ]").unwrap();
+
+        match result {
+            JsonObject::Array(array) => {
+                assert!(matches!(array.as_slice(), [JsonObject::Boolean(true),]));
+            }
+            _ => panic!(),
+        }
+
+        let result = parse_json_string("[ 123 ]").unwrap();
+
+        match result {
+            JsonObject::Array(array) => match array[0] {
+                JsonObject::Number(n) => assert_eq!(n, 123.),
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn array_multiple_elements() {
+        let result = parse_json_string("[null, true, false]").unwrap();
+
+        match result {
+            JsonObject::Array(array) => {
+                assert!(matches!(
+                    array.as_slice(),
+                    [
+                        JsonObject::Null,
+                        JsonObject::Boolean(true),
+                        JsonObject::Boolean(false)
+                    ]
+                ));
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn empty_array() {
+        //empty array
+        let result = parse_json_string("    [ ]    ").unwrap();
+
+        match result {
+            JsonObject::Array(array) => {
+                assert!(matches!(array.as_slice(), []));
+            }
+            _ => panic!(),
+        }
+
+        let result = parse_json_string("[]").unwrap();
+
+        match result {
+            JsonObject::Array(array) => {
+                assert!(matches!(array.as_slice(), []));
+            }
+            _ => panic!(),
+        }
+    }
 
-        let excess;
+    #[test]
+    fn nested_array_type() {
+        parse_json_string("[true, [ null, 123.321 ] ]").unwrap();
+        parse_json_string("[true, [ null, 123] ]").unwrap();
+    }
 
-        if could_be_empty {
-            match result {
-                Ok((value, maybe_excess)) => {
-                    excess = maybe_excess;
+    #[test]
+    fn empty_object() {
+        parse_json_string("{}").unwrap();
+    }
 
-                    vec.push(value)
-                }
-                Err(JsonError::UnexpectedChar(']')) => {
-                    //empty array
-                    return Ok(vec);
+    #[test]
+    fn just_a_number() {
+        assert!(
+            matches!(parse_json_string("123.55").unwrap(), JsonObject::Number(ch) if {ch == 123.55})
+        );
+
+        parse_json_string("    3216546549879876214351.25416546546545646546546321   ").unwrap();
+
+        parse_json_string("   0   ").unwrap();
+
+        //parse_json_string(r#"{ "my_number" : 1233.32465 }"#).unwrap();
+
+        assert!(
+            matches!(parse_json_string("123 ").unwrap(), JsonObject::Number(ch) if {ch == 123.})
+        );
+    }
+
+    #[test]
+    fn whitespace_around_numbers() -> Result<(), JsonError> {
+        let numbers = ["0", "7", "-12", "0.5", "3.25", "1e2", "-4E-1", "0e0"];
+        let spaces = ["", " ", "\t", "\n", "\r\n ", " \t\n"];
+
+        for n in &numbers {
+            let value: f64 = n.parse().unwrap();
+
+            for ws in &spaces {
+                let documents = [
+                    //top level
+                    format!("{}{}{}", ws, n, ws),
+                    //first, middle and last array elements
+                    format!("[{0}{1}{0},{0}{1}{0},{0}{1}{0}]", ws, n),
+                    //object values before ',' and '}'
+                    format!(r#"{{"a":{0}{1}{0},"b":{0}{1}{0}}}"#, ws, n),
+                    //nested, closing several containers at once
+                    format!(r#"[{{"a":[{0}{1}{0}]{0}}}{0},{0}{1}{0}]"#, ws, n),
+                ];
+
+                for document in &documents {
+                    let json = parse_json_string(document)?;
+                    assert!(
+                        json.all_numbers().all(|x| x == value),
+                        "{:?} -> {}",
+                        document,
+                        json
+                    );
                 }
-                Err(err) => return Err(err),
             }
-
-            could_be_empty = false;
-        } else {
-            let (value, maybe_excess) = result?;
-            excess = maybe_excess;
-            vec.push(value);
         }
 
-        let chars = &mut excess
-            .into_iter()
-            .chain(&mut json_iter)
-            .skip_while(|ch| ch.is_whitespace());
+        //comments end numbers the same way when they are allowed
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_json_string_with_options("[1/**/, 2// two\n, 0/**/]/**/", &options)?,
+            json!([1, 2, 0])
+        );
+        assert_eq!(
+            parse_json_string_with_options(r#"{"a":0.5/**/}"#, &options)?,
+            json!({ "a": 0.5 })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn whitespace_between_every_token() -> Result<(), JsonError> {
+        let tokens = [
+            "{", r#""a""#, ":", "[", "1", ",", "-2.5e3", ",", "true", ",", "null", ",", "{",
+            r#""b""#, ":", r#""c d""#, "}", ",", "[", "]", "]", ",", r#""e""#, ":", "{", "}", ",",
+            r#""f""#, ":", "false", "}",
+        ];
+        let spaces = ["", " ", "\t", "\n", "\r\n", " \t\n "];
+        let expected = json!({
+            "a": [1, -2500, true, null, { "b": "c d" }, []],
+            "e": {},
+            "f": false
+        });
+
+        //`gaps[i]` goes before `tokens[i]`, the last one after the document
+        let document = |gaps: &[&str]| {
+            let mut document = String::new();
+            for (gap, token) in gaps.iter().zip(&tokens) {
+                document.push_str(gap);
+                document.push_str(token);
+            }
+            document.push_str(gaps[tokens.len()]);
+            document
+        };
+
+        //every whitespace in every single gap, then in every gap at once in rotating order
+        let mut permutations = vec![];
+        for i in 0..=tokens.len() {
+            for ws in &spaces {
+                let mut gaps = vec![""; tokens.len() + 1];
+                gaps[i] = ws;
+                permutations.push(gaps);
+            }
+        }
+        for shift in 0..spaces.len() {
+            permutations.push(
+                (0..=tokens.len())
+                    .map(|i| spaces[(i + shift) % spaces.len()])
+                    .collect(),
+            );
+        }
 
-        //this is such a hack
+        for gaps in &permutations {
+            let document = document(gaps);
 
-        match chars.next().ok_or(JsonError::EarlyEndOfStream)? {
-            ',' => continue,
-            ']' => return Ok(vec),
-            ch => return Err(JsonError::UnexpectedChar(ch)),
+            assert_eq!(parse_json_string(&document)?, expected, "{:?}", document);
+            assert_eq!(parse_json_from_iter(&mut document.chars())?, expected);
+            assert_eq!(parse_json_bytes(document.as_bytes())?, expected);
+            assert_eq!(parse_json_str_borrowed(&document)?.to_owned(), expected);
+            assert_eq!(parse_pointer_only(&document, "/a/4/b")?, Some(json!("c d")));
         }
+
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn getters() -> Result<(), Box<dyn std::error::Error>> {
+        let result = parse_json_string(" 123456789 ")?
+            .into_number()
+            .ok_or("not a number")?;
+
+        assert_eq!(123456789., result);
+
+        Ok(())
+    }
 
     #[test]
-    fn null_type() {
-        assert_eq!(parse_json_string("null").unwrap(), JsonObject::Null);
+    fn type_queries() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{ "o" : {}, "a" : [ 1 ], "s" : "text", "b" : true, "n" : 2.5, "z" : null }"#,
+        )?;
+        let object = json.as_object().unwrap();
+        let value = |key| object.get(key).unwrap();
+        let raw = JsonObject::RawNumber("1e400".to_owned());
+
+        let predicates = |v: &JsonObject| {
+            [
+                v.is_object(),
+                v.is_array(),
+                v.is_string(),
+                v.is_boolean(),
+                v.is_number(),
+                v.is_null(),
+            ]
+        };
+        let cases = [
+            (value("o"), JsonType::Object, 0),
+            (value("a"), JsonType::Array, 1),
+            (value("s"), JsonType::String, 2),
+            (value("b"), JsonType::Boolean, 3),
+            (value("n"), JsonType::Number, 4),
+            (&raw, JsonType::Number, 4),
+            (value("z"), JsonType::Null, 5),
+        ];
+
+        for (v, json_type, predicate) in cases {
+            assert_eq!(v.json_type(), json_type);
+
+            let mut expected = [false; 6];
+            expected[predicate] = true;
+            assert_eq!(predicates(v), expected, "{:?}", v);
+
+            assert_eq!(v.as_object().is_some(), json_type == JsonType::Object);
+            assert_eq!(v.as_array().is_some(), json_type == JsonType::Array);
+            assert_eq!(v.as_str().is_some(), json_type == JsonType::String);
+            assert_eq!(v.as_bool().is_some(), json_type == JsonType::Boolean);
+            assert_eq!(v.as_f64().is_some(), json_type == JsonType::Number);
+
+            let mut owned = v.clone();
+            assert_eq!(owned.as_object_mut().is_some(), v.is_object());
+            assert_eq!(owned.as_array_mut().is_some(), v.is_array());
+            assert_eq!(owned.as_string_mut().is_some(), v.is_string());
+            assert_eq!(owned.as_bool_mut().is_some(), v.is_boolean());
+            //only a parsed number can be changed in place
+            assert_eq!(
+                owned.as_f64_mut().is_some(),
+                matches!(v, JsonObject::Number(_))
+            );
+        }
+
+        //borrowed rather than consumed
+        assert!(value("z").is_null());
+        assert!(!value("o").is_null());
+        assert!(json.as_object().unwrap().values().any(JsonObject::is_null));
+
+        assert_eq!(value("s").as_str(), Some("text"));
+        assert!(value("s").as_str() == Some("text"));
+        assert_eq!(value("b").as_bool(), Some(true));
+        assert_eq!(value("n").as_f64(), Some(2.5));
+        assert_eq!(raw.as_f64(), Some(f64::INFINITY));
+        assert_eq!(value("a").as_array(), Some(&vec![json!(1)]));
+        assert_eq!(JsonType::Boolean.to_string(), "boolean");
+
+        let mut json = json;
+        *json
+            .as_object_mut()
+            .and_then(|object| object.get_mut("n"))
+            .and_then(JsonObject::as_f64_mut)
+            .unwrap() += 1.;
+        json.as_object_mut()
+            .and_then(|object| object.get_mut("s"))
+            .and_then(JsonObject::as_string_mut)
+            .unwrap()
+            .push('!');
+        assert_eq!(json["n"], json!(3.5));
+        assert_eq!(json["s"], json!("text!"));
+
+        Ok(())
     }
 
     #[test]
-    fn basic_boolean() {
-        assert!(matches!(
-            parse_json_string("true").unwrap(),
-            JsonObject::Boolean(true)
-        ));
+    #[allow(deprecated)]
+    fn deprecated_getters() {
+        let mut json = json!({ "a": [true, 1, "s"] });
+
+        assert!(json.object().is_some());
+        assert!(json.object_mut().is_some());
+        let array = json["a"].array().unwrap().clone();
+        assert_eq!(array[0].boolean(), Some(&true));
+        assert_eq!(array[1].number(), Some(&1.));
+        assert_eq!(array[2].string().map(String::as_str), Some("s"));
+        assert_eq!(array[0].string(), None);
+    }
+
+    #[cfg(feature = "tracing")]
+    mod tracing_hooks {
+        use super::*;
+        use std::fmt::Write;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        //renders spans and events as lines like `enter json_container kind=array depth=0`
+        #[derive(Default)]
+        struct Collector {
+            spans: Mutex<Vec<String>>,
+            lines: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct Fields(String);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    write!(self.0, " {:?}", value).unwrap();
+                } else {
+                    write!(self.0, " {}={:?}", field.name(), value).unwrap();
+                }
+            }
+        }
+
+        impl Subscriber for Collector {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+                let mut fields = Fields(attributes.metadata().name().to_owned());
+                attributes.record(&mut fields);
+
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(fields.0);
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields("event".to_owned());
+                event.record(&mut fields);
+                self.lines.lock().unwrap().push(fields.0);
+            }
+
+            fn enter(&self, span: &Id) {
+                let name = self.spans.lock().unwrap()[span.into_u64() as usize - 1].clone();
+                self.lines.lock().unwrap().push(format!("enter {}", name));
+            }
+
+            fn exit(&self, span: &Id) {
+                let name = self.spans.lock().unwrap()[span.into_u64() as usize - 1].clone();
+                self.lines.lock().unwrap().push(format!("exit {}", name));
+            }
+        }
+
+        fn traced(json: &str, options: &ParseOptions) -> Vec<String> {
+            let collector = Collector::default();
+            let lines = collector.lines.clone();
+
+            tracing::subscriber::with_default(collector, || {
+                let _ = parse_json_string_with_options(json, options);
+            });
+
+            let lines = lines.lock().unwrap().clone();
+            lines
+        }
+
+        #[test]
+        fn document_and_containers() {
+            assert_eq!(
+                traced(r#"{ "a" : [ 1, {} ] }"#, &ParseOptions::default()),
+                [
+                    "enter json_document",
+                    "enter json_container kind=\"object\" depth=0",
+                    "enter json_container kind=\"array\" depth=1",
+                    "enter json_container kind=\"object\" depth=2",
+                    "exit json_container kind=\"object\" depth=2",
+                    "exit json_container kind=\"array\" depth=1",
+                    "exit json_container kind=\"object\" depth=0",
+                    "event document parsed line=1 column=19",
+                    "exit json_document",
+                ]
+            );
+        }
+
+        #[test]
+        fn depth_cutoff_and_errors() {
+            let options = ParseOptions {
+                trace_max_depth: Some(0),
+                ..ParseOptions::default()
+            };
+
+            assert_eq!(
+                traced("[ [ [ [] ] ], [ 1, ] ]", &options),
+                [
+                    "enter json_document",
+                    "enter json_container kind=\"array\" depth=0",
+                    "exit json_container kind=\"array\" depth=0",
+                    "event parse error error=expected a value but found ']' line=1 column=20",
+                    "exit json_document",
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn number_modes() -> Result<(), JsonError> {
+        let options = ParseOptions {
+            numbers_integer_only: true,
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            parse_json_string_with_options("[ 1.5 ]", &options).map_err(|e| e.kind),
+            Err(JsonErrorKind::Number(NumberError::NotAnInteger(1.5)))
+        );
+        assert_eq!(
+            parse_json_string_with_options(r#"{ "a" : [ 1e0, 2.0, 3E2, 4 ] }"#, &options)?,
+            parse_json_string(r#"{ "a" : [ 1, 2, 300, 4 ] }"#)?
+        );
 
+        let options = ParseOptions {
+            max_number_magnitude: Some(2_f64.powi(53) - 1.),
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            parse_json_string_with_options(
+                "[ 1, [ -9007199254740991, [ 2, -9007199254740992 ] ] ]",
+                &options
+            )
+            .map_err(|e| e.kind),
+            Err(JsonErrorKind::Number(NumberError::OutOfRange(
+                -9007199254740992.
+            )))
+        );
         assert!(matches!(
-            parse_json_string("false").unwrap(),
-            JsonObject::Boolean(false)
+            parse_json_string_with_options(r#"{ "id" : 1e300 }"#, &options).map_err(|e| e.kind),
+            Err(JsonErrorKind::Number(NumberError::OutOfRange(_)))
         ));
+        assert!(parse_json_string_with_options("[ 0.5, 9007199254740991 ]", &options).is_ok());
+
+        Ok(())
     }
+
     #[test]
-    fn array_one_element() {
-        let result = parse_json_string("[ true ]").unwrap();
+    fn number_policies() -> Result<(), Box<dyn std::error::Error>> {
+        let with = |number_policy| ParseOptions {
+            number_policy,
+            ..ParseOptions::default()
+        };
+        let parse_ok = |input: &str, policy| parse_json_string_with_options(input, &with(policy));
+        let parse = |input: &str, policy| {
+            parse_json_string_with_options(input, &with(policy)).map_err(|e| e.kind)
+        };
+
+        let big = "123456789012345678901234567890";
+        let document = format!(r#"{{ "big" : {}, "huge" : [ 1e400 ] }}"#, big);
+
+        let lossy = parse_ok(&document, NumberPolicy::Lossy)?;
+        //rounded, and not even correctly yet
+        let rounded = lossy["big"].as_f64().unwrap();
+        assert!((rounded / 1.2345678901234568e29 - 1.).abs() < 1e-15);
+        assert_eq!(lossy["huge"][0].as_f64(), Some(f64::INFINITY));
+
+        assert_eq!(
+            parse(&format!("[ {} ]", big), NumberPolicy::ErrorOnPrecisionLoss),
+            Err(JsonErrorKind::Number(NumberError::PrecisionLoss(
+                big.to_owned()
+            )))
+        );
+        assert_eq!(
+            parse("[ 9007199254740993 ]", NumberPolicy::ErrorOnPrecisionLoss),
+            Err(JsonErrorKind::Number(NumberError::PrecisionLoss(
+                "9007199254740993".to_owned()
+            )))
+        );
+        assert_eq!(
+            parse("[ 1e400 ]", NumberPolicy::ErrorOnPrecisionLoss),
+            Err(JsonErrorKind::Number(NumberError::OutOfRange(
+                f64::INFINITY
+            )))
+        );
+        //underflowing to zero loses every digit
+        assert!(parse("1e-400", NumberPolicy::ErrorOnPrecisionLoss).is_err());
+
+        let preserved = parse_ok(&document, NumberPolicy::PreserveString)?;
+        assert_eq!(preserved["big"], JsonObject::RawNumber(big.to_owned()));
+        assert_eq!(
+            preserved["big"].get_parsed::<u128>()?,
+            123456789012345678901234567890
+        );
+        assert_eq!(
+            preserved["huge"][0].raw_number().map(|s| &s[..]),
+            Some("1e400")
+        );
+        assert_eq!(preserved.to_json_string(), document.replace(' ', ""));
 
-        match result {
-            JsonObject::Array(array) => {
-                assert!(matches!(array.as_slice(), [JsonObject::Boolean(true),]));
+        //ordinary numbers are the same whatever the policy, the literal aside
+        let ordinary = "[ 0, -1, 42, 0.1, 2.5, -3.25e-2, 1E3, 1e+2, 100.000, 9007199254740992 ]";
+        let expected = parse_json_string(ordinary)?;
+
+        assert_eq!(parse_ok(ordinary, NumberPolicy::Lossy)?, expected);
+        assert_eq!(
+            parse_ok(ordinary, NumberPolicy::ErrorOnPrecisionLoss)?,
+            expected
+        );
+
+        let preserved = parse_ok(ordinary, NumberPolicy::PreserveString)?;
+        assert_eq!(
+            preserved.to_json_string(),
+            "[0,-1,42,0.1,2.5,-3.25e-2,1E3,1e+2,100.000,9007199254740992]"
+        );
+        let raw = preserved
+            .descendants()
+            .filter_map(|value| value.raw_number());
+        for (raw, n) in raw.zip(expected.all_numbers()) {
+            assert_eq!(raw.parse::<f64>()?, n);
+        }
+
+        //invalid numbers are still errors, with the other number options still applied
+        assert_eq!(
+            parse("[ 01 ]", NumberPolicy::PreserveString),
+            parse("[ 01 ]", NumberPolicy::Lossy)
+        );
+        assert_eq!(
+            parse("[ 1. ]", NumberPolicy::ErrorOnPrecisionLoss),
+            parse("[ 1. ]", NumberPolicy::Lossy)
+        );
+        let options = ParseOptions {
+            numbers_integer_only: true,
+            ..with(NumberPolicy::PreserveString)
+        };
+        assert_eq!(
+            parse_json_string_with_options("[ 0.5 ]", &options).map_err(|e| e.kind),
+            Err(JsonErrorKind::Number(NumberError::NotAnInteger(0.5)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fraction_precision() -> Result<(), JsonError> {
+        for literal in [
+            "0.1",
+            "0.2",
+            "0.3",
+            "-0.5",
+            "1.1",
+            "2.675",
+            "123.456",
+            "0.000001",
+            "3.141592653589793",
+            "2.718281828459045",
+            "9007199254740.991",
+            "0.1e1",
+            "-0.0",
+        ] {
+            let n = parse_json_string(literal)?.as_f64().unwrap();
+            assert_eq!(n, literal.parse::<f64>().unwrap(), "{}", literal);
+            assert_eq!(n.is_sign_negative(), literal.starts_with('-'));
+        }
+        assert_eq!(
+            parse_json_string("3.141592653589793")?,
+            json!(std::f64::consts::PI)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn long_fractions() -> Result<(), JsonError> {
+        let exact = |literal: &str| {
+            let n = parse_json_string(literal).unwrap().as_f64().unwrap();
+            let expected: f64 = literal.parse().unwrap();
+            assert_eq!(n.to_bits(), expected.to_bits(), "{} != {}", n, expected);
+        };
+
+        let digits: String = "1234567890".repeat(40);
+        exact(&format!("0.{}", digits));
+        exact(&format!("-7.{}", digits));
+        exact(&format!("0.{}e-5", digits));
+        //significant digits after many zeros still count, even past 10^-308
+        exact(&format!("0.{}12345", "0".repeat(300)));
+        exact(&format!("1{}.5", "0".repeat(300)));
+        assert_eq!(
+            parse_json_string(&format!("1.{}1", "0".repeat(400)))?,
+            json!(1)
+        );
+        assert_eq!(
+            parse_json_string(&format!("0.{}1", "0".repeat(400)))?,
+            json!(0)
+        );
+
+        //megabytes of digits take linear time and are read to the end
+        let run = "9".repeat(10_000_000);
+        assert_eq!(
+            parse_json_string(&format!("[0.{}, 2]", run))?,
+            json!([1, 2])
+        );
+        assert_eq!(
+            parse_json_string(&format!("[0.{}1, 2]", "0".repeat(10_000_000)))?,
+            json!([0, 2])
+        );
+        assert_eq!(
+            parse_json_string(&format!("0.{}x", run)).map_err(|e| e.kind),
+            Err(JsonErrorKind::ExtraChars {
+                preview: "x".to_owned(),
+                count: 1
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_numbers() -> Result<(), JsonError> {
+        let lenient = ParseOptions {
+            allow_non_finite_numbers: true,
+            ..ParseOptions::default()
+        };
+        let parse_lenient = |input: &str| parse_json_string_with_options(input, &lenient);
+
+        assert!(parse_lenient("NaN")?.as_f64().is_some_and(f64::is_nan));
+        assert_eq!(parse_lenient("Infinity")?.as_f64(), Some(f64::INFINITY));
+        assert_eq!(
+            parse_lenient(" -Infinity ")?.as_f64(),
+            Some(f64::NEG_INFINITY)
+        );
+
+        let json = parse_lenient(r#"{"a": [NaN, Infinity,-Infinity, -1], "b": -Infinity}"#)?;
+        let numbers: Vec<f64> = json.all_numbers().collect();
+        assert!(numbers[0].is_nan());
+        assert_eq!(
+            numbers[1..],
+            [f64::INFINITY, f64::NEG_INFINITY, -1., f64::NEG_INFINITY]
+        );
+        //the chars after them are read as usual
+        assert_eq!(
+            parse_json_from_iter_with_options(&mut "[Infinity]".chars(), &lenient)?,
+            json!([f64::INFINITY])
+        );
+
+        //strict parsing says what is wrong with them, on every path built on the same grammar
+        for input in [
+            "NaN",
+            "Infinity",
+            "-Infinity",
+            "[ 1, NaN ]",
+            r#"{ "a" : -Infinity }"#,
+        ] {
+            let kind = Some(JsonErrorKind::NonFiniteNumber);
+            assert_eq!(parse_json_string(input).err().map(|e| e.kind), kind);
+            assert_eq!(
+                parse_json_bytes(input.as_bytes()).err().map(|e| e.kind),
+                kind
+            );
+            assert_eq!(parse_json_str_borrowed(input).err().map(|e| e.kind), kind);
+        }
+        assert_eq!(
+            parse_json_string("[ 1, NaN ]")
+                .map_err(|e| e.to_string())
+                .unwrap_err(),
+            "NaN and infinities are not valid JSON at line 1, column 8"
+        );
+
+        //only spelled the way Python writes them
+        for input in [
+            "nan", "Nan", "NAN", "inf", "Inf", "infinity", "-Inf", "Infinit",
+        ] {
+            let strict = parse_json_string(input).map_err(|e| e.kind);
+            assert!(
+                matches!(
+                    strict,
+                    Err(JsonErrorKind::UnexpectedKeyword
+                        | JsonErrorKind::EarlyEndOfStream
+                        | JsonErrorKind::UnexpectedChar { .. })
+                ),
+                "{}: {:?}",
+                input,
+                strict
+            );
+            assert_eq!(
+                parse_lenient(input).map_err(|e| e.kind),
+                strict,
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_lenient("-NaN").map_err(|e| e.kind),
+            Err(unexpected('N', Expected::Digit))
+        );
+
+        //the other number options still apply
+        let options = ParseOptions {
+            numbers_integer_only: true,
+            ..lenient.clone()
+        };
+        assert!(parse_json_string_with_options("[ Infinity ]", &options).is_err());
+        let options = ParseOptions {
+            max_number_magnitude: Some(1e9),
+            ..lenient.clone()
+        };
+        assert!(parse_json_string_with_options("[ -Infinity ]", &options).is_err());
+        let options = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..lenient
+        };
+        assert_eq!(
+            parse_json_string_with_options("[ NaN, -Infinity ]", &options)?,
+            JsonObject::Array(vec![
+                JsonObject::RawNumber("NaN".to_owned()),
+                JsonObject::RawNumber("-Infinity".to_owned())
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn depth_limit() -> Result<(), JsonError> {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let kind = |input: &str, options: &ParseOptions| {
+            parse_json_string_with_options(input, options).map_err(|e| e.kind)
+        };
+        let default = ParseOptions::default();
+
+        assert_eq!(
+            kind(&nested(1_000), &default),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+        assert_eq!(
+            kind(&nested(100_000), &default),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+
+        //exactly at the limit is fine, one more is not
+        parse_json_string(&nested(ParseOptions::DEFAULT_MAX_DEPTH))?;
+        assert_eq!(
+            parse_json_string(&nested(ParseOptions::DEFAULT_MAX_DEPTH + 1))
+                .unwrap_err()
+                .position
+                .column,
+            ParseOptions::DEFAULT_MAX_DEPTH + 1
+        );
+
+        let options = ParseOptions {
+            max_depth: 2,
+            ..ParseOptions::default()
+        };
+        parse_json_string_with_options(r#"{ "a" : [ 1 ], "b" : {} }"#, &options)?;
+        assert_eq!(
+            kind(r#"{ "a" : [ 1, { "b" : null } ] }"#, &options),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+        //scalars do not count
+        parse_json_string_with_options("[ [ 1, \"s\", null ] ]", &options)?;
+
+        let options = ParseOptions {
+            max_depth: 0,
+            ..ParseOptions::default()
+        };
+        parse_json_string_with_options("true", &options)?;
+        assert_eq!(
+            kind("[]", &options),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn size_limits() -> Result<(), JsonError> {
+        let nodes = |max| ParseOptions {
+            max_total_nodes: Some(max),
+            ..ParseOptions::default()
+        };
+        let json = r#"{ "a" : [ 1, "x" ], "b" : {} }"#;
+
+        //the object, the array and its two elements and the empty object
+        assert_eq!(parse_json_string(json)?.stats().total_nodes, 5);
+        parse_json_string_with_options(json, &nodes(5))?;
+        assert_eq!(
+            parse_json_string_with_options(json, &nodes(4)),
+            Err(JsonError {
+                kind: JsonErrorKind::LimitExceeded(Limit::TotalNodes),
+                position: Position {
+                    line: 1,
+                    column: 27
+                }
+            })
+        );
+        assert_eq!(
+            parse_json_string_with_options("null", &nodes(0)).map_err(|e| e.kind),
+            Err(JsonErrorKind::LimitExceeded(Limit::TotalNodes))
+        );
+
+        let length = |max| ParseOptions {
+            max_string_len: Some(max),
+            ..ParseOptions::default()
+        };
+        //bytes after unescaping, keys included
+        let json = r#"{ "key" : [ "\u00e9t\u00e9", "abcde" ] }"#;
+        parse_json_string_with_options(json, &length(5))?;
+        assert_eq!(
+            parse_json_string_with_options(json, &length(4)),
+            Err(JsonError {
+                kind: JsonErrorKind::LimitExceeded(Limit::StringLength),
+                position: Position {
+                    line: 1,
+                    column: 26
+                }
+            })
+        );
+        assert_eq!(
+            parse_json_string_with_options(json, &length(2)).map_err(|e| e.kind),
+            Err(JsonErrorKind::LimitExceeded(Limit::StringLength))
+        );
+
+        //the rest of the string is never read
+        let endless = std::iter::once('"').chain(std::iter::repeat('a'));
+        assert_eq!(
+            parse_json_from_iter_with_options(endless, &length(1_000)),
+            Err(JsonError {
+                kind: JsonErrorKind::LimitExceeded(Limit::StringLength),
+                position: Position {
+                    line: 1,
+                    column: 1_002
+                }
+            })
+        );
+
+        Ok(())
+    }
+
+    fn member_limits(on_limit: LimitBehavior) -> ParseOptions {
+        ParseOptions {
+            max_array_elements: Some(3),
+            max_object_entries: Some(2),
+            on_limit,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn member_limit_errors() -> Result<(), JsonError> {
+        let options = member_limits(LimitBehavior::Error);
+
+        parse_json_string_with_options(r#"[ 1, 2, 3 ]"#, &options)?;
+        assert_eq!(
+            parse_json_string_with_options("[ 1, 2, 3, 4 ]", &options),
+            Err(JsonError {
+                kind: JsonErrorKind::LimitExceeded(Limit::ArrayElements),
+                position: Position {
+                    line: 1,
+                    column: 12
+                }
+            })
+        );
+        //duplicates count
+        assert_eq!(
+            parse_json_string_with_options(r#"{ "a" : 1, "a" : 2, "a" : 3 }"#, &options),
+            Err(JsonError {
+                kind: JsonErrorKind::LimitExceeded(Limit::ObjectEntries),
+                position: Position {
+                    line: 1,
+                    column: 21
+                }
+            })
+        );
+        assert_eq!(
+            JsonErrorKind::LimitExceeded(Limit::ArrayElements).to_string(),
+            "too many array elements"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncating_members() -> Result<(), JsonError> {
+        let options = member_limits(LimitBehavior::Truncate);
+
+        let mut huge = String::from("[");
+        for i in 0..200_000 {
+            huge.push_str(&format!(r#"{{ "id" : {}, "name" : "item {}" }},"#, i, i));
+        }
+        huge.push_str("null]");
+
+        let (value, report) = parse_json_with_report(&huge, &options)?;
+        assert_eq!(
+            value,
+            json!([{ "id": 0, "name": "item 0" }, { "id": 1, "name": "item 1" }, { "id": 2, "name": "item 2" }])
+        );
+        //dropped elements never reached the array
+        assert!(value.as_array().unwrap().capacity() < 8);
+        assert_eq!(
+            report,
+            ParseReport {
+                truncated_containers: 1,
+                skipped_values: 199_998,
             }
-            _ => panic!(),
+        );
+        assert!(report.is_truncated());
+
+        //each container is held to its own limit, the skipped ones to none
+        let (value, report) = parse_json_with_report(
+            r#"{
+                "a" : [ [ 1, 2, 3, 4 ], { "x" : 1, "y" : 2, "z" : 3 }, [], [ 5, 6, 7, 8, 9 ] ],
+                "b" : { "c" : [ 1, 2, 3, 4 ], "d" : 1, "e" : [ 1, 2, 3, 4 ] },
+                "f" : "skipped"
+            }"#,
+            &options,
+        )?;
+        assert_eq!(
+            value,
+            json!({
+                "a": [[1, 2, 3], { "x": 1, "y": 2 }, []],
+                "b": { "c": [1, 2, 3], "d": 1 }
+            })
+        );
+        assert_eq!(
+            report,
+            ParseReport {
+                truncated_containers: 6,
+                skipped_values: 6,
+            }
+        );
+
+        let (_, report) = parse_json_with_report("[[1, 2], {}]", &options)?;
+        assert!(!report.is_truncated());
+
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_members_are_validated() {
+        let options = ParseOptions {
+            max_total_nodes: Some(5),
+            max_string_len: Some(1),
+            max_depth: 3,
+            ..member_limits(LimitBehavior::Truncate)
+        };
+        let parse = |json: &str| parse_json_string_with_options(json, &options).map_err(|e| e.kind);
+
+        //other limits do not apply to them, but syntax and depth do
+        assert_eq!(
+            parse(r#"[ 1, 2, 3, "long", [ 1, 2, 3, 4, 5 ], { "long" : 1 } ]"#),
+            Ok(json!([1, 2, 3]))
+        );
+        assert_eq!(
+            parse(r#"[ 1, 2, 3, 4, 5 ] x"#).unwrap_err(),
+            JsonErrorKind::ExtraChars {
+                preview: "x".to_owned(),
+                count: 1
+            }
+        );
+
+        for (json, expected) in [
+            ("[ 1, 2, 3, 4 5 ]", Expected::CommaOrArrayEnd),
+            ("[ 1, 2, 3, { 1 : 1 } ]", Expected::ObjectKeyOrEnd),
+            (
+                r#"{ "a" : 1, "b" : 2, "c" : 3 "d" : 4 }"#,
+                Expected::CommaOrObjectEnd,
+            ),
+            ("[ 1, 2, 3, ] ", Expected::Value),
+        ] {
+            assert!(
+                matches!(parse(json), Err(JsonErrorKind::UnexpectedChar { expected: e, .. }) if e == expected),
+                "{}",
+                json
+            );
         }
 
-        let result = parse_json_string("[ 123 ]").unwrap();
+        assert_eq!(
+            parse(r#"[ 1, 2, 3, { "a" 1 } ]"#),
+            Err(JsonErrorKind::MissingColon)
+        );
+        assert_eq!(
+            parse(r#"[ 1, 2, 3, "a\qb" ]"#),
+            Err(JsonErrorKind::UnknownEscapeCharacter('q'))
+        );
+        assert_eq!(
+            parse("[ 1, 2, 3, [ [ [ ] ] ] ]"),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+        assert_eq!(
+            parse("[ 1, 2, 3, tru ]"),
+            Err(JsonErrorKind::UnexpectedKeyword)
+        );
+        assert!(parse("[ 1, 2, 3, -x ]").is_err());
+        assert_eq!(
+            parse("[ 1, 2, 3, 4"),
+            Err(JsonErrorKind::UnclosedArray { open: 1 })
+        );
+    }
 
-        match result {
-            JsonObject::Array(array) => match array[0] {
-                JsonObject::Number(n @ _) => assert_eq!(n, 123.),
-                _ => panic!(),
-            },
-            _ => panic!(),
+    //nothing recurses while parsing, but dropping the result would
+    #[test]
+    fn deep_nesting() -> Result<(), JsonError> {
+        let options = ParseOptions {
+            max_depth: usize::MAX,
+            ..ParseOptions::default()
+        };
+        let depth = 50_000;
+
+        let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let mut json = parse_json_string_with_options(&input, &options)?;
+        let mut levels = 0;
+        while let JsonObject::Array(mut array) = json {
+            levels += 1;
+            json = array.pop().unwrap_or_default();
+        }
+        assert_eq!(levels, depth);
+
+        let input = format!("{} 1 {}", r#"{ "a" : ["#.repeat(depth), "] }".repeat(depth));
+        let mut json = parse_json_string_with_options(&input, &options)?;
+        let mut levels = 0;
+        loop {
+            json = match json {
+                JsonObject::Object(mut object) => object.remove("a").unwrap(),
+                JsonObject::Array(mut array) => array.pop().unwrap(),
+                other => {
+                    assert_eq!(other, JsonObject::Number(1.));
+                    break;
+                }
+            };
+            levels += 1;
         }
+        assert_eq!(levels, 2 * depth);
+
+        let input = "[{\"a\":".repeat(depth);
+        assert_eq!(
+            parse_json_string_with_options(&input, &options).map_err(|e| e.kind),
+            Err(JsonErrorKind::UnclosedObject { open: 2 * depth })
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn array_multiple_elements() {
-        let result = parse_json_string("[null, true, false]").unwrap();
+    fn duplicate_key_policies() -> Result<(), JsonError> {
+        let input = r#"{ "a" : 1, "b" : { "c" : true, "c" : false }, "a" : [ 2 ], "a" : 3 }"#;
+        let with = |duplicate_keys: DuplicateKeyPolicy| {
+            let options = ParseOptions {
+                duplicate_keys,
+                ..ParseOptions::default()
+            };
+            parse_json_string_with_options(input, &options)
+        };
+
+        //the default keeps everything
+        assert_eq!(
+            with(DuplicateKeyPolicy::KeepAll)?,
+            parse_json_string(input)?
+        );
+        assert_eq!(
+            with(DuplicateKeyPolicy::FirstWins)?,
+            parse_json_string(r#"{ "a" : 1, "b" : { "c" : true } }"#)?
+        );
+        assert_eq!(
+            with(DuplicateKeyPolicy::LastWins)?,
+            parse_json_string(r#"{ "a" : 3, "b" : { "c" : false } }"#)?
+        );
 
-        match result {
-            JsonObject::Array(array) => {
-                assert!(matches!(
-                    array.as_slice(),
-                    [
-                        JsonObject::Null,
-                        JsonObject::Boolean(true),
-                        JsonObject::Boolean(false)
-                    ]
-                ));
-            }
-            _ => panic!(),
-        }
+        //the nested duplicate comes first, noticed at its ':'
+        let error = with(DuplicateKeyPolicy::Error).unwrap_err();
+        assert_eq!(error.kind, JsonErrorKind::DuplicateKey("c".to_owned()));
+        assert_eq!(error.position.column, 36);
+
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeyPolicy::Error,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_json_string_with_options(
+                r#"[ { "a" : 1 }, { "a" : 2, "b" : { "a" : 3 } } ]"#,
+                &options
+            )?,
+            parse_json_string(r#"[ { "a" : 1 }, { "a" : 2, "b" : { "a" : 3 } } ]"#)?
+        );
+
+        //`LastWins` agrees with building the object through `insert`
+        let mut object = Object::new();
+        object.insert("a", JsonObject::from(1));
+        object.insert("b", JsonObject::from(2));
+        object.insert("a", JsonObject::from(3));
+        assert_eq!(
+            JsonObject::Object(object),
+            parse_json_string_with_options(
+                r#"{ "a" : 1, "b" : 2, "a" : 3 }"#,
+                &ParseOptions {
+                    duplicate_keys: DuplicateKeyPolicy::LastWins,
+                    ..ParseOptions::default()
+                }
+            )?
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn empty_array() {
-        //empty array
-        let result = parse_json_string("    [ ]    ").unwrap();
+    fn comments_and_trailing_commas() -> Result<(), JsonError> {
+        let lenient = ParseOptions {
+            allow_comments: true,
+            allow_trailing_commas: true,
+            ..ParseOptions::default()
+        };
+
+        let input = r#"// settings
+            {
+                "name" : "app", // the name
+                /* spread
+                   over lines, with a * and / inside */
+                "ports" : [ 80, 443, ],
+                "nested" : { "a" : [ [ 1, ], { "b" : null, }, ], },
+                "ratio" : 1.5/**/, "count" : 2// no space
+            } /* done */ // really
+        "#;
+        assert_eq!(
+            parse_json_string_with_options(input, &lenient)?,
+            parse_json_string(
+                r#"{ "name" : "app", "ports" : [ 80, 443 ], "nested" : { "a" : [ [ 1 ], { "b" : null } ] }, "ratio" : 1.5, "count" : 2 }"#
+            )?
+        );
 
-        match result {
-            JsonObject::Array(array) => {
-                assert!(matches!(array.as_slice(), []));
-            }
-            _ => panic!(),
-        }
+        let kind = |input: &str, options: &ParseOptions| {
+            parse_json_string_with_options(input, options).map_err(|e| e.kind)
+        };
+
+        //a lone comma is still no element
+        assert_eq!(
+            kind("[,]", &lenient),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ',',
+                expected: Expected::Value
+            })
+        );
+        assert_eq!(
+            kind("{,}", &lenient),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ',',
+                expected: Expected::ObjectKeyOrEnd
+            })
+        );
+        assert_eq!(
+            kind("[1,,]", &lenient),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ',',
+                expected: Expected::Value
+            })
+        );
+        assert_eq!(
+            kind("[1] /* open", &lenient),
+            Err(JsonErrorKind::EarlyEndOfStream)
+        );
+        assert_eq!(
+            kind("[1] / 2", &lenient),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ' ',
+                expected: Expected::CommentStart
+            })
+        );
 
-        let result = parse_json_string("[]").unwrap();
+        //each option only allows its own syntax
+        let comments = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(kind("[ 1 /* c */ ]", &comments), Ok(json!([1])));
+        assert_eq!(
+            kind("[ 1, ]", &comments),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ']',
+                expected: Expected::Value
+            })
+        );
 
-        match result {
-            JsonObject::Array(array) => {
-                assert!(matches!(array.as_slice(), []));
-            }
-            _ => panic!(),
+        //strict parsing is unaffected
+        for input in [
+            "[ 1 // c\n ]",
+            "[ /* c */ 1 ]",
+            "{ \"a\" : 1, }",
+            "[ 1, ]",
+            "[ [ ], ]",
+        ] {
+            assert!(parse_json_string(input).is_err(), "{}", input);
+            assert!(kind(input, &lenient).is_ok(), "{}", input);
         }
-    }
 
-    #[test]
-    fn nested_array_type() {
-        parse_json_string("[true, [ null, 123.321 ] ]").unwrap();
-        parse_json_string("[true, [ null, 123] ]").unwrap();
+        Ok(())
     }
 
     #[test]
-    fn empty_object() {
-        parse_json_string("{}").unwrap();
+    fn e_notation() -> Result<(), Box<dyn std::error::Error>> {
+        let result = parse_json_string(" 1.6E-35 ")?
+            .into_number()
+            .ok_or("not a number")?;
+
+        let float = 1.6E-35;
+
+        let diff = (float - result).abs();
+
+        assert!(diff < 0.01);
+
+        Ok(())
     }
 
     #[test]
-    fn just_a_number() {
-        assert!(
-            matches!(parse_json_string("123.55").unwrap(), JsonObject::Number(ch @ _) if {ch == 123.55})
+    fn empty_fractions() -> Result<(), JsonError> {
+        for (input, ch) in [
+            ("[1.]", ']'),
+            ("[3., 4]", ','),
+            ("[1.,2]", ','),
+            (r#"{"x": 2.}"#, '}'),
+            ("[ -0. ]", ' '),
+            ("[ 1.e5 ]", 'e'),
+        ] {
+            assert_eq!(
+                parse_json_string(input).map_err(|e| e.kind),
+                Err(JsonErrorKind::UnexpectedChar {
+                    found: ch,
+                    expected: Expected::Digit
+                }),
+                "{}",
+                input
+            );
+        }
+        for input in ["1.", "-0."] {
+            assert_eq!(
+                parse_json_string(input).map_err(|e| e.kind),
+                Err(JsonErrorKind::EarlyEndOfStream),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_json_string("[ 0.").map_err(|e| e.kind),
+            Err(JsonErrorKind::UnclosedArray { open: 1 })
         );
 
-        parse_json_string("    3216546549879876214351.25416546546545646546546321   ").unwrap();
+        assert_eq!(parse_json_string("1.0")?, JsonObject::Number(1.));
+        assert_eq!(
+            parse_json_string(r#"{"x": 2.5}"#)?,
+            parse_json_string(r#"{"x": 25e-1}"#)?
+        );
 
-        parse_json_string("   0   ").unwrap();
+        Ok(())
+    }
 
-        //parse_json_string(r#"{ "my_number" : 1233.32465 }"#).unwrap();
+    #[test]
+    fn signs_and_leading_zeros() -> Result<(), JsonError> {
+        for input in ["-0", "-0.0", "[-0]", "-0e3", "-0E-2", "-0.000"] {
+            let n = parse_json_string(input)?.all_numbers().next().unwrap();
+            assert!(n == 0. && n.is_sign_negative(), "{}", input);
+        }
+        for input in ["0", "0.0", "0e3", "[0]"] {
+            let n = parse_json_string(input)?.all_numbers().next().unwrap();
+            assert!(n == 0. && n.is_sign_positive(), "{}", input);
+        }
+        assert_eq!(parse_json_string("-0.5")?, JsonObject::Number(-0.5));
+        assert_eq!(parse_json_string("-10")?, JsonObject::Number(-10.));
+        assert_eq!(parse_json_string("-0")?.to_json_string(), "-0");
 
-        assert!(
-            matches!(parse_json_string("123 ").unwrap(), JsonObject::Number(ch @ _) if {ch == 123.})
+        assert_eq!(
+            parse_json_string("-").map_err(|e| e.kind),
+            Err(JsonErrorKind::EarlyEndOfStream)
         );
+        for (input, found) in [("-.5", '.'), ("-x", 'x'), ("[-]", ']'), ("- 1", ' ')] {
+            assert_eq!(
+                parse_json_string(input).map_err(|e| e.kind),
+                Err(JsonErrorKind::UnexpectedChar {
+                    found,
+                    expected: Expected::Digit
+                }),
+                "{}",
+                input
+            );
+        }
+
+        for input in ["01", "-01", "00", "-00.5", "[01]", "{\"a\":09}", "0123"] {
+            assert_eq!(
+                parse_json_string(input).map_err(|e| e.kind),
+                Err(JsonErrorKind::LeadingZero),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_json_string("-01").unwrap_err().position,
+            Position { line: 1, column: 3 }
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn getters() -> Result<(), Box<dyn std::error::Error>> {
-        let result = parse_json_string(" 123456789 ")?
-            .into_number()
-            .ok_or("not a number")?;
+    fn exponent_limits() -> Result<(), JsonError> {
+        let number = |literal: &str| parse_json_string(literal).unwrap().as_f64().unwrap();
+
+        for literal in [
+            "8.5e-1",
+            "680e-9",
+            "3.7529e-11",
+            "1.7976931348623157e308",
+            "-1.7976931348623157e308",
+            "0.1e309",
+            "17976931348623157e292",
+            "2.2250738585072014e-308",
+            "2.225073858507201e-308",
+            "5e-324",
+            "4.9406564584124654e-324",
+            "1e-320",
+            "123e-310",
+            "1e22",
+            "1e23",
+            "9007199254740993",
+            "9007199254740993e-22",
+            "1e-400",
+            "-1e-400",
+            "0e99999999999",
+            "1e400",
+        ] {
+            let expected: f64 = literal.parse().unwrap();
+            assert_eq!(number(literal).to_bits(), expected.to_bits(), "{}", literal);
+        }
 
-        assert_eq!(123456789., result);
+        assert_eq!(number("1.7976931348623157e308"), f64::MAX);
+        assert_eq!(number("2.2250738585072014e-308"), f64::MIN_POSITIVE);
+        //the smallest subnormal, and what rounds to it or to zero
+        assert_eq!(number("5e-324"), f64::from_bits(1));
+        assert_eq!(number("3e-324"), f64::from_bits(1));
+        assert_eq!(number("2e-324"), 0.);
+        assert_eq!(number("1.8e308"), f64::INFINITY);
+        assert_eq!(number("-1e-400").to_bits(), (-0_f64).to_bits());
 
         Ok(())
     }
 
     #[test]
-    fn e_notation() -> Result<(), Box<dyn std::error::Error>> {
-        let result = parse_json_string(" 1.6E-35 ")?
-            .into_number()
-            .ok_or("not a number")?;
+    fn exponent_forms() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{ "distance" : 1.5e8, "list" : [ 1e10, 2.5E-3, 6.02e+23, -4E2, 1e0 ], "small" : 7e-1 }"#,
+        )?;
+        let numbers: Vec<f64> = json.all_numbers().collect();
+        let expected = [1.5e8, 1e10, 2.5e-3, 6.02e23, -4e2, 1., 0.7];
 
-        let float = 1.6E-35;
+        assert_eq!(numbers, expected);
 
-        let diff = (float - result).abs();
+        //the char after the exponent still ends containers
+        assert_eq!(
+            parse_json_string("[1e2,2E1]")?,
+            parse_json_string("[100, 20]")?
+        );
 
-        assert!(diff < 0.01);
+        for input in &["[ 1e ]", "[ 1e+ ]", "[ 1E- ]", "[ 1e+x ]", "[ 1.5e, 2 ]"] {
+            assert!(
+                matches!(
+                    parse_json_string(input).map_err(|e| e.kind),
+                    Err(JsonErrorKind::UnexpectedChar {
+                        expected: Expected::Digit,
+                        ..
+                    })
+                ),
+                "{}",
+                input
+            );
+        }
+        for input in &["1e", "1e+"] {
+            assert_eq!(
+                parse_json_string(input).map_err(|e| e.kind),
+                Err(JsonErrorKind::EarlyEndOfStream),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_json_string("[ 2.5E-").map_err(|e| e.kind),
+            Err(JsonErrorKind::UnclosedArray { open: 1 })
+        );
 
         Ok(())
     }
@@ -668,6 +3245,386 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn escape_matrix() {
+        let string = |input: &str| {
+            parse_json_string(input)
+                .map(|json| json.into_string().unwrap())
+                .map_err(|e| e.kind)
+        };
+
+        for (escape, ch) in [
+            ('"', '"'),
+            ('\\', '\\'),
+            ('/', '/'),
+            ('b', '\u{8}'),
+            ('f', '\u{c}'),
+            ('n', '\n'),
+            ('r', '\r'),
+            ('t', '\t'),
+        ] {
+            assert_eq!(
+                string(&format!(r#""a\{}b""#, escape)),
+                Ok(format!("a{}b", ch)),
+                "\\{}",
+                escape
+            );
+        }
+        assert_eq!(string(r#""page\fbreak""#), Ok("page\u{c}break".to_owned()));
+
+        //everything else is rejected rather than passed through
+        for escape in ['a', 'v', '0', 'x', 'U', '\'', ' ', 'é'] {
+            assert_eq!(
+                string(&format!(r#""\{}""#, escape)),
+                Err(JsonErrorKind::UnknownEscapeCharacter(escape))
+            );
+        }
+        assert_eq!(string(r#""\"#), Err(JsonErrorKind::UnterminatedString));
+    }
+
+    proptest! {
+        //strings made of the pieces of escapes must fail cleanly, never panic
+        #[test]
+        fn escape_soup(
+            pieces in prop::collection::vec(
+                prop::sample::select(vec![
+                    "\\", "u", "d83d", "de00", "dc00", "D800", "00", "e9", "g", "\"", "b", "f", "n", "x",
+                ]),
+                0..8,
+            ),
+            closed in any::<bool>(),
+        ) {
+            let mut input = String::from("\"");
+            input.extend(pieces);
+            if closed {
+                input.push('"');
+            }
+
+            let _ = parse_json_string(&input);
+            let _ = parse_json_string(&format!("[{}]", input));
+        }
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        let string = |input: &str| {
+            parse_json_string(input)
+                .map(|json| json.into_string().unwrap())
+                .map_err(|e| e.kind)
+        };
+
+        assert_eq!(string(r#""\u00e9""#), Ok("\u{e9}".to_owned()));
+        assert_eq!(string(r#""\ud83d\ude00""#), Ok("\u{1f600}".to_owned()));
+        assert_eq!(string(r#""a\u0000b""#), Ok("a\u{0}b".to_owned()));
+
+        //unpaired surrogates, followed by text or by a non-surrogate escape
+        assert_eq!(string(r#""\ud83d""#), Err(JsonErrorKind::InvalidUnicode));
+        assert_eq!(string(r#""\ud83dx""#), Err(JsonErrorKind::InvalidUnicode));
+        assert_eq!(string(r#""\ud83d\n""#), Err(JsonErrorKind::InvalidUnicode));
+        assert_eq!(string(r#""\ude00""#), Err(JsonErrorKind::InvalidUnicode));
+        assert_eq!(string(r#""\u00g9""#), Err(JsonErrorKind::InvalidUnicode));
+
+        //truncated, by the closing quote or by the end of input
+        assert_eq!(string(r#""\u12""#), Err(JsonErrorKind::InvalidUnicode));
+        for input in &[
+            r#""\u12"#,
+            r#""\u"#,
+            r#""\ud83d"#,
+            r#""\ud83d\"#,
+            r#""\ud83d\ude0"#,
+        ] {
+            assert_eq!(
+                string(input),
+                Err(JsonErrorKind::UnterminatedString),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn control_characters() -> Result<(), JsonError> {
+        for ch in ['\n', '\t', '\r', '\u{0}', '\u{1f}'] {
+            assert_eq!(
+                parse_json_string(&format!("[ \"a{}b\" ]", ch)).map_err(|e| e.kind),
+                Err(JsonErrorKind::UnescapedControlCharacter(ch))
+            );
+            assert_eq!(
+                parse_json_string(&format!("{{ \"a{}b\" : 1 }}", ch)).map_err(|e| e.kind),
+                Err(JsonErrorKind::UnescapedControlCharacter(ch))
+            );
+        }
+
+        //escaped they are fine, and so is whitespace between tokens
+        assert_eq!(
+            parse_json_string("[\n\t\"a\\nb\\t\\u0000\"\r\n]")?,
+            JsonObject::Array(vec![JsonObject::String("a\nb\t\u{0}".to_owned())])
+        );
+        //U+007F is not a control character for JSON
+        assert_eq!(
+            parse_json_string("\"\u{7f}\"")?,
+            JsonObject::String("\u{7f}".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn expected_tokens() {
+        let expected = |input: &str| match parse_json_string(input).map_err(|e| e.kind) {
+            Err(JsonErrorKind::UnexpectedChar { expected, .. }) => expected,
+            other => panic!("{}: {:?}", input, other),
+        };
+
+        assert_eq!(expected("x"), Expected::Value);
+        assert_eq!(expected("[ 1, ]"), Expected::Value);
+        assert_eq!(expected("{ 1 : 2 }"), Expected::ObjectKeyOrEnd);
+        assert_eq!(expected(r#"{ "a" : 1, }"#), Expected::ObjectKey);
+        assert_eq!(expected("[ 1 : 2 ]"), Expected::CommaOrArrayEnd);
+        assert_eq!(expected(r#"{ "a" : 1 ]"#), Expected::CommaOrObjectEnd);
+        assert_eq!(expected("[ -x ]"), Expected::Digit);
+        assert_eq!(expected("[ 1.x ]"), Expected::Digit);
+        assert_eq!(expected("[ 1e+x ]"), Expected::Digit);
+
+        let lenient = ParseOptions {
+            allow_comments: true,
+            allow_trailing_commas: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_json_string_with_options(r#"{ "a" : 1, 2 }"#, &lenient).map_err(|e| e.kind),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: '2',
+                expected: Expected::ObjectKeyOrEnd
+            })
+        );
+
+        assert_eq!(
+            parse_json_string(r#"{ "a" : 1 : 2 }"#)
+                .unwrap_err()
+                .to_string(),
+            "expected ',' or '}' but found ':' at line 1, column 11"
+        );
+        assert_eq!(
+            parse_json_string("[ 1, ]").unwrap_err().kind.to_string(),
+            "expected a value but found ']'"
+        );
+    }
+
+    #[test]
+    fn unclosed_and_missing() {
+        use JsonErrorKind::*;
+
+        for (input, kind) in [
+            (r#""abc"#, UnterminatedString),
+            ("[1, 2", UnclosedArray { open: 1 }),
+            (r#"{"a": 1"#, UnclosedObject { open: 1 }),
+            (r#"{"a"}"#, MissingColon),
+            (r#"{"a":}"#, MissingValue),
+            //escapes are part of the string, numbers and keys are not containers
+            (r#"["a\u00"#, UnterminatedString),
+            (r#"{"a"#, UnterminatedString),
+            ("[1, -", UnclosedArray { open: 1 }),
+            (r#"[{"a": [1, {"#, UnclosedObject { open: 4 }),
+            (r#"[{"a": [1], "b""#, UnclosedObject { open: 2 }),
+            (r#"{"a" 1}"#, MissingColon),
+            (r#"[{"a": , "b": 1}]"#, MissingValue),
+            //nothing is open yet
+            ("", EarlyEndOfStream),
+            ("-", EarlyEndOfStream),
+        ] {
+            let error = parse_json_string(input).unwrap_err();
+            assert_eq!(error.kind, kind, "{}", input);
+
+            assert_eq!(
+                parse_json_from_iter(input.chars()),
+                Err(error.clone()),
+                "{}",
+                input
+            );
+            assert_eq!(
+                parse_json_str_borrowed(input),
+                Err(error.clone()),
+                "{}",
+                input
+            );
+            assert_eq!(validate_json(input).first(), Some(&error), "{}", input);
+
+            let mut streaming = StreamingParser::new();
+            let fed = streaming.feed(input).map(|_| ());
+            assert_eq!(
+                fed.and_then(|()| streaming.finish()).map_err(|e| e.kind),
+                Err(kind),
+                "{}",
+                input
+            );
+        }
+
+        let message = |input: &str| parse_json_string(input).unwrap_err().to_string();
+        assert_eq!(
+            message(r#""abc"#),
+            "the input ends inside a string, its closing '\"' is missing at line 1, column 4"
+        );
+        assert_eq!(
+            message("[1, 2"),
+            "the input ends inside an array, its closing ']' is missing at line 1, column 5"
+        );
+        assert_eq!(
+            message(r#"[{"a": 1"#),
+            "the input ends inside an object, its closing '}' is missing (2 containers are left \
+             open) at line 1, column 8"
+        );
+        assert_eq!(
+            message(r#"{"a"}"#),
+            "expected ':' after the object key at line 1, column 5"
+        );
+        assert_eq!(
+            message(r#"{"a":}"#),
+            "missing value after ':' at line 1, column 6"
+        );
+    }
+
+    #[test]
+    fn error_positions() {
+        use JsonErrorKind::*;
+
+        let error = |input: &str| {
+            let error = parse_json_string(input).unwrap_err();
+            (error.kind, error.position.line, error.position.column)
+        };
+
+        assert_eq!(
+            error("{\n  \"a\" : [ 1, 2 },\n  \"b\" : 3\n}"),
+            (
+                UnexpectedChar {
+                    found: '}',
+                    expected: Expected::CommaOrArrayEnd
+                },
+                2,
+                16
+            )
+        );
+        //inside strings, after escapes of different lengths
+        assert_eq!(
+            error("[\n  \"ok\",\n  \"bad\\q\"\n]"),
+            (UnknownEscapeCharacter('q'), 3, 8)
+        );
+        assert_eq!(
+            error("{\n  \"\\u00e9\\n\" : \"a\tb\"\n}"),
+            (UnescapedControlCharacter('\t'), 2, 18)
+        );
+        assert_eq!(error("[\n\"\\ud83dx\"]"), (InvalidUnicode, 2, 8));
+        //the char ending a number is read once, even when handed back to the container
+        assert_eq!(
+            error("[ 1,\n  23x ]"),
+            (
+                UnexpectedChar {
+                    found: 'x',
+                    expected: Expected::CommaOrArrayEnd
+                },
+                2,
+                5
+            )
+        );
+        assert_eq!(
+            error("{ \"a\" : 1\n  \"b\" : 2 }"),
+            (
+                UnexpectedChar {
+                    found: '"',
+                    expected: Expected::CommaOrObjectEnd
+                },
+                2,
+                3
+            )
+        );
+        //extra chars start at the first one
+        assert_eq!(
+            error("[ 1 ]\n\n  x y"),
+            (
+                ExtraChars {
+                    preview: "x y".to_owned(),
+                    count: 3
+                },
+                3,
+                3
+            )
+        );
+        //only the start of a long tail is kept
+        assert_eq!(
+            error(&format!("[] {}", "[1] ".repeat(1000))).0,
+            ExtraChars {
+                preview: "[1] [1] [1] [1] ".to_owned(),
+                count: 4000
+            }
+        );
+        //the end of input is the last char read
+        assert_eq!(error("[ 1,\n"), (UnclosedArray { open: 1 }, 1, 5));
+        assert_eq!(error(""), (EarlyEndOfStream, 1, 0));
+        //columns count chars, a `\r` is just another one
+        assert_eq!(
+            error("[ \"\u{e9}\", \u{e9} ]"),
+            (
+                UnexpectedChar {
+                    found: '\u{e9}',
+                    expected: Expected::Value
+                },
+                1,
+                8
+            )
+        );
+        assert_eq!(
+            error("[\r\n  1,\r\n  x ]"),
+            (
+                UnexpectedChar {
+                    found: 'x',
+                    expected: Expected::Value
+                },
+                3,
+                3
+            )
+        );
+
+        assert_eq!(
+            parse_json_string("[ 1,\n  23x ]").unwrap_err().to_string(),
+            "expected ',' or ']' but found 'x' at line 2, column 5"
+        );
+    }
+
+    //inputs that used to panic, found while setting up the fuzz targets
+    #[test]
+    fn fuzz_regressions() {
+        for input in &[r#""\ud800\u""#, r#""\udc00\ud800""#, r#""\ud800\u0041""#] {
+            assert_eq!(
+                parse_json_string(input).map_err(|e| e.kind),
+                Err(JsonErrorKind::InvalidUnicode),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(
+            parse_json_string("[ 1e99999999999 ]"),
+            Ok(JsonObject::Array(vec![JsonObject::Number(f64::INFINITY)]))
+        );
+        assert_eq!(
+            parse_json_string("[ 1E-99999999999, 0.1e2147483648 ]"),
+            Ok(JsonObject::Array(vec![
+                JsonObject::Number(0.),
+                JsonObject::Number(f64::INFINITY)
+            ]))
+        );
+
+        //a lone minus sign used to be mistaken for an empty array
+        assert_eq!(
+            parse_json_string("[-]").map_err(|e| e.kind),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ']',
+                expected: Expected::Digit
+            })
+        );
+        assert_eq!(parse_json_string("[ ]"), Ok(JsonObject::Array(vec![])));
+    }
+
     #[test]
     fn escape_characters() -> Result<(), Box<dyn std::error::Error>> {
         let str = parse_json_string(r#" "\b\f\t\n\r\\\/\"" "#)?
@@ -692,27 +3649,362 @@ mod tests {
         }"#,
         )?;
 
-        json.object().unwrap().entries().iter().for_each(|v| println!("{:?}", v));
+        json.as_object()
+            .unwrap()
+            .entries()
+            .iter()
+            .for_each(|v| println!("{:?}", v));
 
-        json.object_mut()
+        json.as_object_mut()
             .unwrap()
             .get_mut("my_array")
             .unwrap()
-            .array_mut()
+            .as_array_mut()
             .unwrap()
-            .sort_by(|a, b| a.number().partial_cmp(&b.number()).unwrap());
+            .sort_by(|a, b| a.as_f64().partial_cmp(&b.as_f64()).unwrap());
 
         assert!(json
-            .object()
+            .as_object()
             .unwrap()
             .get("my_array")
             .unwrap()
-            .array()
+            .as_array()
             .unwrap()
             .iter()
-            .map(JsonObject::number)
+            .map(JsonObject::as_f64)
             .map(Option::unwrap)
-            .eq(&[42., 73., 727.]));
+            .eq([42., 73., 727.]));
+        Ok(())
+    }
+
+    #[test]
+    fn static_values() -> Result<(), JsonError> {
+        static EMPTY: JsonObject = JsonObject::empty_object();
+        static DEFAULTS: [JsonObject; 3] = [
+            JsonObject::NULL,
+            JsonObject::empty_array(),
+            JsonObject::Object(Object::new()),
+        ];
+
+        assert_eq!(EMPTY, parse_json_string("{}")?);
+        assert_eq!(DEFAULTS[1], parse_json_string("[]")?);
+        assert_eq!(DEFAULTS[2], EMPTY);
+
+        let json = parse_json_string(r#"{ "a" : null, "b" : 1 }"#)?;
+        let object = json.as_object().unwrap();
+
+        let lookup = |key| object.get(key).unwrap_or(JsonObject::null_ref());
+        assert_eq!(lookup("b"), &JsonObject::Number(1.));
+        assert_eq!(lookup("missing"), &JsonObject::NULL);
+        assert!(std::ptr::eq(lookup("missing"), JsonObject::null_ref()));
+
+        let kinds: Vec<_> = object
+            .values()
+            .map(|value| match value {
+                value if *value == JsonObject::NULL => "null",
+                JsonObject::Number(_) => "number",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, ["null", "number"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_and_remove() -> Result<(), JsonError> {
+        let mut object = Object::new();
+        assert!(object.is_empty());
+
+        assert_eq!(object.insert("a", JsonObject::Number(1.)), None);
+        assert_eq!(object.insert("b".to_owned(), JsonObject::Null), None);
+        assert_eq!(object.insert("c", JsonObject::Boolean(true)), None);
+        //replacing keeps the position
+        assert_eq!(
+            object.insert("a", JsonObject::Number(2.)),
+            Some(JsonObject::Number(1.))
+        );
+        assert_eq!(object.len(), 3);
+        assert_eq!(
+            object,
+            parse_json_string(r#"{ "a" : 2, "b" : null, "c" : true }"#)?
+                .into_object()
+                .unwrap()
+        );
+
+        assert!(object.contains_key("b"));
+        assert_eq!(object.remove("b"), Some(JsonObject::Null));
+        assert!(!object.contains_key("b"));
+        assert_eq!(object.remove("b"), None);
+        assert_eq!(object.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn shift_and_swap_remove() -> Result<(), JsonError> {
+        let source = r#"{ "a" : 1, "b" : 2, "c" : 3, "d" : 4, "b" : 5 }"#;
+        let keys = |object: &Object| {
+            object
+                .entries()
+                .iter()
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut object = parse_json_string(source)?.into_object().unwrap();
+        assert_eq!(object.shift_remove("b"), Some(JsonObject::Number(2.)));
+        assert_eq!(keys(&object), ["a", "c", "d", "b"]);
+        assert_eq!(object.remove("a"), Some(JsonObject::Number(1.)));
+        assert_eq!(keys(&object), ["c", "d", "b"]);
+        assert_eq!(object.remove("missing"), None);
+
+        let mut object = parse_json_string(source)?.into_object().unwrap();
+        //the last entry fills the hole
+        assert_eq!(object.swap_remove("b"), Some(JsonObject::Number(2.)));
+        assert_eq!(keys(&object), ["a", "b", "c", "d"]);
+        assert_eq!(object.get("b"), Some(&JsonObject::Number(5.)));
+        assert_eq!(object.swap_remove("d"), Some(JsonObject::Number(4.)));
+        assert_eq!(keys(&object), ["a", "b", "c"]);
+        assert_eq!(object.swap_remove("missing"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys() -> Result<(), Box<dyn std::error::Error>> {
+        let mut object = parse_json_string(r#"{ "a" : 1, "b" : 2, "a" : 3, "a" : 4 }"#)?
+            .into_object()
+            .unwrap();
+
+        assert_eq!(object.key_count("missing"), 0);
+        assert_eq!(object.get_all("missing").count(), 0);
+        assert_eq!(object.key_count("b"), 1);
+        assert_eq!(
+            object.get_all("b").collect::<Vec<_>>(),
+            [&JsonObject::Number(2.)]
+        );
+        assert_eq!(object.key_count("a"), 3);
+        assert_eq!(
+            object.get_all("a").collect::<Vec<_>>(),
+            [
+                &JsonObject::Number(1.),
+                &JsonObject::Number(3.),
+                &JsonObject::Number(4.)
+            ]
+        );
+
+        for value in object.get_all_mut("a") {
+            if let JsonObject::Number(n) = value {
+                *n *= 10.;
+            }
+        }
+
+        //`get` still sees the first one
+        assert_eq!(object.get("a"), Some(&JsonObject::Number(10.)));
+        assert_eq!(
+            object,
+            parse_json_string(r#"{ "a" : 10, "b" : 2, "a" : 30, "a" : 40 }"#)?
+                .into_object()
+                .unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn object_first_and_last() -> Result<(), Box<dyn std::error::Error>> {
+        let mut empty = parse_json_string("{}")?.into_object().unwrap();
+
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+        assert_eq!(empty.first_mut(), None);
+        assert_eq!(empty.pop(), None);
+
+        //tagged union read pattern
+        let mut tagged = parse_json_string(r#"{ "Ok" : { "value" : 1 } }"#)?
+            .into_object()
+            .unwrap();
+
+        let (tag, _) = tagged.first().unwrap();
+        assert_eq!(tag, "Ok");
+        assert_eq!(tagged.first(), tagged.last());
+
+        if let Some((_, value)) = tagged.last_mut() {
+            *value = JsonObject::Null;
+        }
+        assert_eq!(tagged.first(), Some(("Ok", &JsonObject::Null)));
+
+        let mut object = parse_json_string(r#"{ "a" : 1, "b" : 2, "c" : 3 }"#)?
+            .into_object()
+            .unwrap();
+
+        let (key, value) = object.pop().unwrap();
+        assert_eq!(key, "c");
+        assert_eq!(object.last(), Some(("b", &JsonObject::Number(2.))));
+
+        object.entries_mut().push((key, value));
+        assert_eq!(
+            object,
+            parse_json_string(r#"{ "a" : 1, "b" : 2, "c" : 3 }"#)?
+                .into_object()
+                .unwrap()
+        );
+
         Ok(())
     }
+
+    #[test]
+    fn lossy_numeric_getters() {
+        let number = |n: f64| JsonObject::Number(n);
+
+        assert_eq!(number(-2.9).as_i64_lossy(), Some(-2));
+        assert_eq!(number(2.9).as_i64_lossy(), Some(2));
+        assert_eq!(number(i64::MAX as f64).as_i64_lossy(), Some(i64::MAX));
+        assert_eq!(number(1e19).as_i64_lossy(), Some(i64::MAX));
+        assert_eq!(number(i64::MIN as f64).as_i64_lossy(), Some(i64::MIN));
+        assert_eq!(number(-1e19).as_i64_lossy(), Some(i64::MIN));
+        assert_eq!(number(f64::INFINITY).as_i64_lossy(), Some(i64::MAX));
+        assert_eq!(number(f64::NAN).as_i64_lossy(), None);
+        assert_eq!(JsonObject::Null.as_i64_lossy(), None);
+
+        assert_eq!(number(1.5).as_f64_finite(), Some(1.5));
+        assert_eq!(number(f64::NAN).as_f64_finite(), None);
+        assert_eq!(number(f64::INFINITY).as_f64_finite(), None);
+        assert_eq!(number(f64::NEG_INFINITY).as_f64_finite(), None);
+        assert_eq!(JsonObject::Boolean(true).as_f64_finite(), None);
+    }
+
+    #[test]
+    fn exact_numeric_getters() -> Result<(), JsonError> {
+        let json = parse_json_string("[ 42, -42, 2.0, 1e3, 2.5, 9007199254740991, -0.0, \"1\" ]")?;
+        let numbers: Vec<_> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| (n.as_i64(), n.as_u64()))
+            .collect();
+
+        assert_eq!(
+            numbers,
+            [
+                (Some(42), Some(42)),
+                (Some(-42), None),
+                (Some(2), Some(2)),
+                (Some(1000), Some(1000)),
+                (None, None),
+                (Some(9007199254740991), Some(9007199254740991)),
+                (Some(0), Some(0)),
+                (None, None),
+            ]
+        );
+
+        //i64::MAX and u64::MAX round up to the next power of two as an f64, out of range
+        assert_eq!(JsonObject::from(i64::MAX).as_i64(), None);
+        assert_eq!(JsonObject::from(i64::MAX).as_u64(), Some(1 << 63));
+        assert_eq!(JsonObject::from(i64::MIN).as_i64(), Some(i64::MIN));
+        assert_eq!(JsonObject::from(u64::MAX).as_u64(), None);
+        assert_eq!(
+            JsonObject::from(u64::MAX - 2047).as_u64(),
+            Some(u64::MAX - 2047)
+        );
+
+        //only representable as an f64
+        for n in [1e300, -1e19, f64::INFINITY, f64::NAN, 0.1] {
+            let json = JsonObject::from(n);
+            assert_eq!((json.as_i64(), json.as_u64()), (None, None), "{}", n);
+        }
+        assert_eq!(JsonObject::from(1e300).as_f64(), Some(1e300));
+        assert_eq!(JsonObject::from("1").as_f64(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn raw_numeric_getters() -> Result<(), JsonError> {
+        let literals =
+            "[ 5, -42, 2.0, 1e3, 2.5, -2.9, 0.5e1, 120e-1, -0.0, 1e400, 9007199254740991 ]";
+        let raw_options = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..ParseOptions::default()
+        };
+        let raw = parse_json_string_with_options(literals, &raw_options)?;
+        let parsed = parse_json_string(literals)?;
+        let getters = |n: &JsonObject| {
+            (
+                n.as_i64(),
+                n.as_u64(),
+                n.as_i64_lossy(),
+                n.as_f64(),
+                n.as_f64_finite(),
+                n.as_number_clamped(0., 3.),
+            )
+        };
+
+        //the same meaning as the number parsed from the same literal
+        for (raw, parsed) in raw
+            .as_array()
+            .unwrap()
+            .iter()
+            .zip(parsed.as_array().unwrap())
+        {
+            assert!(matches!(raw, JsonObject::RawNumber(_)));
+            assert_eq!(getters(raw), getters(parsed), "{:?}", raw);
+        }
+
+        let raw = |literal: &str| JsonObject::RawNumber(literal.to_owned());
+        assert_eq!(raw("5").as_i64(), Some(5));
+        assert_eq!(raw("5").as_number_clamped(0., 3.), Ok(Some(3.)));
+
+        //integers are read from the literal, past the precision of an f64
+        assert_eq!(raw("9223372036854775807").as_i64(), Some(i64::MAX));
+        assert_eq!(raw("-9223372036854775808").as_i64(), Some(i64::MIN));
+        assert_eq!(raw("9223372036854775808").as_i64(), None);
+        assert_eq!(raw("18446744073709551615").as_u64(), Some(u64::MAX));
+        assert_eq!(raw("18446744073709551616").as_u64(), None);
+        assert_eq!(raw("1844674407370955161.5e1").as_u64(), Some(u64::MAX));
+        assert_eq!(raw("9007199254740993").as_i64(), Some(9007199254740993));
+        assert_eq!(raw("9007199254740993.5").as_i64(), None);
+        assert_eq!(raw("1e-400").as_i64(), None);
+        assert_eq!(
+            raw("9007199254740993.5").as_i64_lossy(),
+            Some(9007199254740993)
+        );
+        assert_eq!(raw("-1e30").as_i64_lossy(), Some(i64::MIN));
+        assert_eq!(raw("1e99999999999").as_i64_lossy(), Some(i64::MAX));
+        assert_eq!(raw("0e99999999999").as_u64(), Some(0));
+
+        //the non-finite literals of `allow_non_finite_numbers`
+        assert_eq!(raw("NaN").as_i64_lossy(), None);
+        assert_eq!(raw("-Infinity").as_i64_lossy(), Some(i64::MIN));
+        assert_eq!(raw("Infinity").as_i64(), None);
+        assert_eq!(raw("Infinity").as_f64_finite(), None);
+        assert_eq!(raw("-Infinity").as_number_clamped(0., 3.), Ok(Some(0.)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn clamped_numeric_getter() {
+        let number = |n: f64| JsonObject::Number(n);
+
+        assert_eq!(number(5.).as_number_clamped(1., 10.), Ok(Some(5.)));
+        assert_eq!(number(-5.).as_number_clamped(1., 10.), Ok(Some(1.)));
+        assert_eq!(
+            number(f64::INFINITY).as_number_clamped(1., 10.),
+            Ok(Some(10.))
+        );
+        assert_eq!(number(3.).as_number_clamped(3., 3.), Ok(Some(3.)));
+        assert_eq!(number(f64::NAN).as_number_clamped(1., 10.), Ok(None));
+        assert_eq!(JsonObject::Null.as_number_clamped(1., 10.), Ok(None));
+        assert_eq!(
+            number(5.).as_number_clamped(10., 1.),
+            Err(InvalidBounds { min: 10., max: 1. })
+        );
+        assert_eq!(
+            InvalidBounds { min: 10., max: 1. }.to_string(),
+            "invalid bounds 10 to 1, the minimum cannot be above the maximum or NaN"
+        );
+        assert!(number(5.).as_number_clamped(f64::NAN, 1.).is_err());
+    }
 }
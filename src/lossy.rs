@@ -0,0 +1,162 @@
+use std::str::{Chars, Utf8Chunks};
+
+use crate::{parse_json_from_iter, JsonError, JsonObject};
+
+/// An invalid UTF-8 sequence inside a string, replaced by U+FFFD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utf8Warning {
+    /// Byte offset of the sequence in the input.
+    pub offset: usize,
+    /// Its length in bytes.
+    pub len: usize,
+}
+
+//decodes while the parser reads, replacing each invalid sequence with U+FFFD. Outside strings the
+//replacement is rejected by the parser like any other stray char, so only the ones inside strings
+//are recorded. String boundaries are tracked from the decoded chars, escapes included.
+struct LossyChars<'a> {
+    chunks: Utf8Chunks<'a>,
+    valid: Chars<'a>,
+    //offset and length of the invalid bytes ending the current chunk
+    invalid: Option<(usize, usize)>,
+    offset: usize,
+    in_string: bool,
+    escaped: bool,
+    warnings: Vec<Utf8Warning>,
+}
+
+impl<'a> LossyChars<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        LossyChars {
+            chunks: input.utf8_chunks(),
+            valid: "".chars(),
+            invalid: None,
+            offset: 0,
+            in_string: false,
+            escaped: false,
+            warnings: vec![],
+        }
+    }
+
+    fn track(&mut self, ch: char) {
+        if !self.in_string {
+            self.in_string = ch == '"';
+        } else if self.escaped {
+            self.escaped = false;
+        } else if ch == '\\' {
+            self.escaped = true;
+        } else if ch == '"' {
+            self.in_string = false;
+        }
+    }
+}
+
+impl Iterator for LossyChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(ch) = self.valid.next() {
+                self.track(ch);
+                return Some(ch);
+            }
+
+            if let Some((offset, len)) = self.invalid.take() {
+                if self.in_string {
+                    self.warnings.push(Utf8Warning { offset, len });
+                }
+                self.track(char::REPLACEMENT_CHARACTER);
+                return Some(char::REPLACEMENT_CHARACTER);
+            }
+
+            let chunk = self.chunks.next()?;
+            let (valid, invalid) = (chunk.valid(), chunk.invalid());
+
+            self.valid = valid.chars();
+            if !invalid.is_empty() {
+                self.invalid = Some((self.offset + valid.len(), invalid.len()));
+            }
+            self.offset += valid.len() + invalid.len();
+        }
+    }
+}
+
+/// Parses almost UTF-8 input, replacing invalid sequences inside strings with U+FFFD and
+/// reporting each of them. Invalid bytes anywhere else are still an error.
+pub fn parse_json_bytes_lossy(input: &[u8]) -> Result<(JsonObject, Vec<Utf8Warning>), JsonError> {
+    let mut chars = LossyChars::new(input);
+    let value = parse_json_from_iter(&mut chars)?;
+
+    Ok((value, chars.warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_json_string, Expected, JsonErrorKind};
+
+    #[test]
+    fn replaced_inside_strings() -> Result<(), JsonError> {
+        //a lone continuation byte, a truncated sequence and an escaped quote before more garbage
+        let input = b"{ \"k\xff\" : [ \"a\xe2\x82b\", \"\\\"\xc0\" ] }";
+
+        let (value, warnings) = parse_json_bytes_lossy(input)?;
+        assert_eq!(
+            value,
+            parse_json_string("{ \"k\u{fffd}\" : [ \"a\u{fffd}b\", \"\\\"\u{fffd}\" ] }")?
+        );
+        assert_eq!(
+            warnings,
+            [
+                Utf8Warning { offset: 4, len: 1 },
+                Utf8Warning { offset: 13, len: 2 },
+                Utf8Warning { offset: 22, len: 1 },
+            ]
+        );
+        assert_eq!(input[4], 0xff);
+        assert_eq!(input[13], 0xe2);
+        assert_eq!(input[22], 0xc0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_between_tokens() {
+        assert_eq!(
+            parse_json_bytes_lossy(b"[ 1, \xff 2 ]").map_err(|e| e.kind),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: char::REPLACEMENT_CHARACTER,
+                expected: Expected::Value
+            })
+        );
+        assert_eq!(
+            parse_json_bytes_lossy(b"{ \x80\"a\": 1 }").map_err(|e| e.kind),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: char::REPLACEMENT_CHARACTER,
+                expected: Expected::ObjectKeyOrEnd
+            })
+        );
+        assert_eq!(
+            parse_json_bytes_lossy(b"[]\xfe").map_err(|e| e.kind),
+            Err(JsonErrorKind::ExtraChars {
+                preview: char::REPLACEMENT_CHARACTER.to_string(),
+                count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn clean_input() -> Result<(), JsonError> {
+        let source = "{ \"é\" : [ \"😐\", true, null ] }";
+
+        let (value, warnings) = parse_json_bytes_lossy(source.as_bytes())?;
+        assert_eq!(value, parse_json_string(source)?);
+        assert!(warnings.is_empty());
+
+        //a real U+FFFD is not a warning
+        let (_, warnings) = parse_json_bytes_lossy("[ \"\u{fffd}\" ]".as_bytes())?;
+        assert!(warnings.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+/// Builds a [`JsonObject`](crate::JsonObject) from JSON-like syntax.
+///
+/// Values can be any expression with an `Into<JsonObject>` conversion, keys any expression a
+/// `String` can be made from. Trailing commas are accepted and duplicate keys are kept, like the
+/// parser does.
+///
+/// ```
+/// use json_parser::{json, parse_json_string};
+///
+/// let score = 4.5;
+/// let json = json!({
+///     "name": "bob",
+///     "tags": ["a", "b"],
+///     "score": score,
+///     "active": true,
+///     "meta": null,
+/// });
+///
+/// assert_eq!(
+///     json,
+///     parse_json_string(r#"{ "name": "bob", "tags": ["a", "b"], "score": 4.5, "active": true, "meta": null }"#)?
+/// );
+/// # Ok::<(), json_parser::JsonError>(())
+/// ```
+#[macro_export]
+macro_rules! json {
+    //arrays, elements are collected into the brackets followed by the tokens left to munch
+    (@array [$($elems:expr,)*]) => {
+        vec![$($elems,)*]
+    };
+    (@array [$($elems:expr),*]) => {
+        vec![$($elems),*]
+    };
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!({$($object)*})] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json!(@array [$($elems,)* $crate::json!($last)])
+    };
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)*] $($rest)*)
+    };
+
+    //objects, the key is munched one token at a time up to the colon
+    (@object $object:ident ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        $object
+            .entries_mut()
+            .push(($crate::Key::from(::std::string::String::from($($key)+)), $value));
+        $crate::json!(@object $object () $($rest)*);
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        $object
+            .entries_mut()
+            .push(($crate::Key::from(::std::string::String::from($($key)+)), $value));
+    };
+    (@object $object:ident ($($key:tt)+) : null $($rest:tt)*) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) : [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) : {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!({$($map)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) : $value:expr , $($rest:tt)*) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) : $value:expr) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!($value)));
+    };
+    (@object $object:ident ($($key:tt)*) $tt:tt $($rest:tt)*) => {
+        $crate::json!(@object $object ($($key)* $tt) $($rest)*);
+    };
+
+    (null) => {
+        $crate::JsonObject::Null
+    };
+    ([]) => {
+        $crate::JsonObject::Array(vec![])
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::JsonObject::Array($crate::json!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::JsonObject::Object($crate::Object::new())
+    };
+    ({ $($tt:tt)+ }) => {{
+        let mut object = $crate::Object::new();
+        $crate::json!(@object object () $($tt)+);
+        $crate::JsonObject::Object(object)
+    }};
+    ($other:expr) => {
+        $crate::JsonObject::from($other)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn matches_the_parser() -> Result<(), JsonError> {
+        assert_eq!(json!(null), parse_json_string("null")?);
+        assert_eq!(json!([]), parse_json_string("[]")?);
+        assert_eq!(json!({}), parse_json_string("{}")?);
+        assert_eq!(json!("s"), parse_json_string(r#""s""#)?);
+
+        assert_eq!(
+            json!({
+                "a": { "b": [ 1, -2.5, [], {}, null ], "c": { "d": null } },
+                "e": [ [ null, [ true ] ], { "f": false } ],
+                "g": "h"
+            }),
+            parse_json_string(
+                r#"{
+                    "a" : { "b" : [ 1, -2.5, [], {}, null ], "c" : { "d" : null } },
+                    "e" : [ [ null, [ true ] ], { "f" : false } ],
+                    "g" : "h"
+                }"#
+            )?
+        );
+
+        //duplicate keys are kept in order
+        assert_eq!(
+            json!({ "k": 1, "k": 2 }),
+            parse_json_string(r#"{ "k" : 1, "k" : 2 }"#)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn expressions_and_trailing_commas() -> Result<(), JsonError> {
+        let name = String::from("bob");
+        let key = "dynamic";
+        let tags = [JsonObject::from("x")];
+
+        let json = json!({
+            "name": name.clone(),
+            key: 1 + 2,
+            format!("k{}", 2): [ tags.len(), tags[0].clone(), ],
+            "nested": json!([ true, ]),
+        });
+
+        assert_eq!(
+            json,
+            parse_json_string(
+                r#"{ "name" : "bob", "dynamic" : 3, "k2" : [ 1, "x" ], "nested" : [ true ] }"#
+            )?
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use crate::pointer::parse_pointer;
+use crate::JsonObject;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayStrategy {
+    /// The overriding array replaces the base one.
+    #[default]
+    Replace,
+    /// The overriding elements are appended to the base ones.
+    Concat,
+    /// Overriding elements are appended unless an element
+    /// [`canonical_eq`](JsonObject::canonical_eq) to them is already present.
+    Union,
+    /// Elements at the same index are merged recursively, extra overriding elements appended.
+    MergeByIndex,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeOptions {
+    pub array_strategy: ArrayStrategy,
+    /// Strategies for arrays at or below a JSON Pointer prefix, the longest matching prefix
+    /// takes precedence over `array_strategy`.
+    pub overrides: Vec<(String, ArrayStrategy)>,
+    /// A null member in the overriding object removes the key from the base instead of being
+    /// merged as a value, like a JSON Merge Patch.
+    pub null_removes_key: bool,
+}
+
+impl MergeOptions {
+    fn strategy_at(&self, path: &[String]) -> ArrayStrategy {
+        self.overrides
+            .iter()
+            .filter_map(|(prefix, strategy)| {
+                let prefix = parse_pointer(prefix).ok()?;
+                if path.starts_with(&prefix) {
+                    Some((prefix.len(), *strategy))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|(len, _)| *len)
+            .map_or(self.array_strategy, |(_, strategy)| strategy)
+    }
+}
+
+impl JsonObject {
+    /// Deep merges `other` into `self`: objects are merged key by key with new keys appended in
+    /// order, arrays according to the options, and in every other case `other` wins.
+    pub fn merge(&mut self, other: JsonObject, options: &MergeOptions) {
+        merge_impl(self, other, options, &mut vec![]);
+    }
+}
+
+/// Deep merges `other` into `base` with the default options, see [`JsonObject::merge`].
+pub fn merge(mut base: JsonObject, other: JsonObject) -> JsonObject {
+    base.merge(other, &MergeOptions::default());
+    base
+}
+
+fn merge_impl(
+    base: &mut JsonObject,
+    other: JsonObject,
+    options: &MergeOptions,
+    path: &mut Vec<String>,
+) {
+    match (base, other) {
+        (JsonObject::Object(base), JsonObject::Object(other)) => {
+            for (key, value) in other.entries {
+                if options.null_removes_key && value == JsonObject::Null {
+                    base.shift_remove(&key);
+                    continue;
+                }
+
+                match base.get_mut(&key) {
+                    Some(existing) => {
+                        path.push(key.into_string());
+                        merge_impl(existing, value, options, path);
+                        path.pop();
+                    }
+                    None => base.entries_mut().push((key, value)),
+                }
+            }
+        }
+        (JsonObject::Array(base), JsonObject::Array(other)) => match options.strategy_at(path) {
+            ArrayStrategy::Replace => *base = other,
+            ArrayStrategy::Concat => base.extend(other),
+            ArrayStrategy::Union => {
+                for value in other {
+                    if !base.iter().any(|existing| existing.canonical_eq(&value)) {
+                        base.push(value);
+                    }
+                }
+            }
+            ArrayStrategy::MergeByIndex => {
+                for (i, value) in other.into_iter().enumerate() {
+                    match base.get_mut(i) {
+                        Some(existing) => {
+                            path.push(i.to_string());
+                            merge_impl(existing, value, options, path);
+                            path.pop();
+                        }
+                        None => base.push(value),
+                    }
+                }
+            }
+        },
+        (base, other) => *base = other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const BASE: &str = r#"{
+        "name" : "base",
+        "server" : { "ports" : [80, 443], "tls" : { "ciphers" : ["a", "b"] } },
+        "users" : [ { "name" : "ann", "admin" : false }, { "name" : "bob" } ]
+    }"#;
+
+    const OVERRIDE: &str = r#"{
+        "server" : { "ports" : [443, 8080], "tls" : { "ciphers" : ["c"] }, "host" : "::" },
+        "users" : [ { "admin" : true } ],
+        "debug" : true
+    }"#;
+
+    fn merged(options: &MergeOptions) -> Result<JsonObject, JsonError> {
+        let mut json = parse_json_string(BASE)?;
+        json.merge(parse_json_string(OVERRIDE)?, options);
+        Ok(json)
+    }
+
+    fn with_strategy(array_strategy: ArrayStrategy) -> MergeOptions {
+        MergeOptions {
+            array_strategy,
+            ..MergeOptions::default()
+        }
+    }
+
+    #[test]
+    fn replace() -> Result<(), JsonError> {
+        assert_eq!(
+            merged(&with_strategy(ArrayStrategy::Replace))?,
+            parse_json_string(
+                r#"{
+                    "name" : "base",
+                    "server" : { "ports" : [443, 8080], "tls" : { "ciphers" : ["c"] }, "host" : "::" },
+                    "users" : [ { "admin" : true } ],
+                    "debug" : true
+                }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn concat() -> Result<(), JsonError> {
+        assert_eq!(
+            merged(&with_strategy(ArrayStrategy::Concat))?,
+            parse_json_string(
+                r#"{
+                    "name" : "base",
+                    "server" : { "ports" : [80, 443, 443, 8080], "tls" : { "ciphers" : ["a", "b", "c"] }, "host" : "::" },
+                    "users" : [ { "name" : "ann", "admin" : false }, { "name" : "bob" }, { "admin" : true } ],
+                    "debug" : true
+                }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn union() -> Result<(), JsonError> {
+        assert_eq!(
+            merged(&with_strategy(ArrayStrategy::Union))?,
+            parse_json_string(
+                r#"{
+                    "name" : "base",
+                    "server" : { "ports" : [80, 443, 8080], "tls" : { "ciphers" : ["a", "b", "c"] }, "host" : "::" },
+                    "users" : [ { "name" : "ann", "admin" : false }, { "name" : "bob" }, { "admin" : true } ],
+                    "debug" : true
+                }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn union_compares_canonically() -> Result<(), JsonError> {
+        let raw = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..ParseOptions::default()
+        };
+        let parse = |input| parse_json_string_with_options(input, &raw);
+
+        let mut json = parse(r#"[ { "a" : 1, "b" : [2] }, 1, 9007199254740993 ]"#)?;
+        json.merge(
+            parse(r#"[ { "b" : [2.0], "a" : 1 }, 1.0, 10e-1, 9007199254740992, 2 ]"#)?,
+            &with_strategy(ArrayStrategy::Union),
+        );
+
+        assert_eq!(
+            json,
+            parse(r#"[ { "a" : 1, "b" : [2] }, 1, 9007199254740993, 9007199254740992, 2 ]"#)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_by_index() -> Result<(), JsonError> {
+        assert_eq!(
+            merged(&with_strategy(ArrayStrategy::MergeByIndex))?,
+            parse_json_string(
+                r#"{
+                    "name" : "base",
+                    "server" : { "ports" : [443, 8080], "tls" : { "ciphers" : ["c", "b"] }, "host" : "::" },
+                    "users" : [ { "name" : "ann", "admin" : true }, { "name" : "bob" } ],
+                    "debug" : true
+                }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn per_path_overrides() -> Result<(), JsonError> {
+        let options = MergeOptions {
+            array_strategy: ArrayStrategy::Replace,
+            overrides: vec![
+                ("/server".to_owned(), ArrayStrategy::Union),
+                ("/server/tls".to_owned(), ArrayStrategy::Concat),
+                ("/users".to_owned(), ArrayStrategy::MergeByIndex),
+            ],
+            ..MergeOptions::default()
+        };
+
+        assert_eq!(
+            merged(&options)?,
+            parse_json_string(
+                r#"{
+                    "name" : "base",
+                    "server" : { "ports" : [80, 443, 8080], "tls" : { "ciphers" : ["a", "b", "c"] }, "host" : "::" },
+                    "users" : [ { "name" : "ann", "admin" : true }, { "name" : "bob" } ],
+                    "debug" : true
+                }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested_objects() -> Result<(), JsonError> {
+        let base =
+            parse_json_string(r#"{ "a" : { "b" : { "c" : 1, "d" : [ 1 ] }, "e" : 2 }, "f" : 3 }"#)?;
+        let other = parse_json_string(
+            r#"{ "a" : { "b" : { "d" : [ 2 ], "g" : { "h" : 4 } }, "e" : "x" }, "i" : 5 }"#,
+        )?;
+
+        assert_eq!(
+            merge(base, other),
+            parse_json_string(
+                r#"{ "a" : { "b" : { "c" : 1, "d" : [ 2 ], "g" : { "h" : 4 } }, "e" : "x" }, "f" : 3, "i" : 5 }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn null_members() -> Result<(), JsonError> {
+        let base = r#"{ "a" : 1, "b" : { "c" : 2, "d" : 3 }, "e" : [ null ] }"#;
+        let other = parse_json_string(
+            r#"{ "a" : null, "b" : { "c" : null }, "e" : [ 1, null ], "missing" : null }"#,
+        )?;
+
+        //kept as values by default
+        assert_eq!(
+            merge(parse_json_string(base)?, other.clone()),
+            parse_json_string(
+                r#"{ "a" : null, "b" : { "c" : null, "d" : 3 }, "e" : [ 1, null ], "missing" : null }"#
+            )?
+        );
+
+        let options = MergeOptions {
+            null_removes_key: true,
+            ..MergeOptions::default()
+        };
+        let mut json = parse_json_string(base)?;
+        json.merge(other, &options);
+        //nulls inside arrays are values either way
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "b" : { "d" : 3 }, "e" : [ 1, null ] }"#)?
+        );
+
+        Ok(())
+    }
+}
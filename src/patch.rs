@@ -0,0 +1,540 @@
+use std::fmt;
+
+use crate::pointer::{parse_array_index, parse_pointer};
+use crate::{JsonObject, Object, PointerError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchErrorKind {
+    /// The patch is not an array of operations, or an operation misses a member or has one of
+    /// the wrong type. Describes what is wrong.
+    Malformed(&'static str),
+    UnknownOperation(String),
+    /// `path` or `from` does not resolve, or for `add` its parent does not.
+    Pointer(PointerError),
+    /// A `test` found a different value.
+    TestFailed,
+    /// A `move` to a location inside the moved value.
+    MoveIntoChild,
+}
+
+impl fmt::Display for PatchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchErrorKind::Malformed(problem) => f.write_str(problem),
+            PatchErrorKind::UnknownOperation(op) => write!(f, "unknown operation {:?}", op),
+            PatchErrorKind::Pointer(err) => err.fmt(f),
+            PatchErrorKind::TestFailed => f.write_str("test failed"),
+            PatchErrorKind::MoveIntoChild => f.write_str("cannot move a value into itself"),
+        }
+    }
+}
+
+/// An operation of a JSON Patch that could not be applied, `index` being its position in the
+/// patch. A patch that is not an array fails at index 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchError {
+    pub index: usize,
+    pub kind: PatchErrorKind,
+}
+
+//`operation 2: test failed`
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operation {}: {}", self.index, self.kind)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl From<PointerError> for PatchErrorKind {
+    #[inline]
+    fn from(err: PointerError) -> Self {
+        PatchErrorKind::Pointer(err)
+    }
+}
+
+/// Applies an RFC 6902 JSON Patch: an array of `add`, `remove`, `replace`, `move`, `copy` and
+/// `test` operations, in order. Either every operation applies or `target` is left untouched,
+/// which costs a clone of it. Members of operations other than the ones they use are ignored,
+/// and `test` compares with [`JsonObject::canonical_eq`].
+///
+/// ```
+/// use json_parser::{apply_patch, json};
+///
+/// let mut document = json!({ "tags": ["a"], "draft": true });
+/// let patch = json!([
+///     { "op": "add", "path": "/tags/-", "value": "b" },
+///     { "op": "remove", "path": "/draft" }
+/// ]);
+///
+/// apply_patch(&mut document, &patch).unwrap();
+/// assert_eq!(document, json!({ "tags": ["a", "b"] }));
+/// ```
+pub fn apply_patch(target: &mut JsonObject, patch: &JsonObject) -> Result<(), PatchError> {
+    let operations = patch.as_array().ok_or(PatchError {
+        index: 0,
+        kind: PatchErrorKind::Malformed("the patch is not an array"),
+    })?;
+
+    let mut patched = target.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        apply_operation(&mut patched, operation).map_err(|kind| PatchError { index, kind })?;
+    }
+
+    *target = patched;
+    Ok(())
+}
+
+fn apply_operation(target: &mut JsonObject, operation: &JsonObject) -> Result<(), PatchErrorKind> {
+    let operation = operation
+        .as_object()
+        .ok_or(PatchErrorKind::Malformed("an operation is not an object"))?;
+
+    let op = string_member(operation, "op", "missing \"op\"")?;
+    let path = parse_pointer(string_member(operation, "path", "missing \"path\"")?)?;
+    let value = || {
+        operation
+            .get("value")
+            .ok_or(PatchErrorKind::Malformed("missing \"value\""))
+    };
+    let from = || -> Result<Vec<String>, PatchErrorKind> {
+        Ok(parse_pointer(string_member(
+            operation,
+            "from",
+            "missing \"from\"",
+        )?)?)
+    };
+
+    match op {
+        "add" => add(target, &path, value()?.clone()),
+        "remove" => remove(target, &path).map(drop),
+        "replace" => {
+            *resolve_mut(target, &path)? = value()?.clone();
+            Ok(())
+        }
+        "move" => {
+            let from = from()?;
+            if from.len() < path.len() && path.starts_with(&from) {
+                return Err(PatchErrorKind::MoveIntoChild);
+            }
+
+            let moved = remove(target, &from)?;
+            add(target, &path, moved)
+        }
+        "copy" => {
+            let copied = resolve_mut(target, &from()?)?.clone();
+            add(target, &path, copied)
+        }
+        "test" => {
+            if resolve_mut(target, &path)?.canonical_eq(value()?) {
+                Ok(())
+            } else {
+                Err(PatchErrorKind::TestFailed)
+            }
+        }
+        op => Err(PatchErrorKind::UnknownOperation(op.to_owned())),
+    }
+}
+
+fn string_member<'a>(
+    operation: &'a Object,
+    key: &str,
+    missing: &'static str,
+) -> Result<&'a str, PatchErrorKind> {
+    operation
+        .get(key)
+        .ok_or(PatchErrorKind::Malformed(missing))?
+        .as_str()
+        .ok_or(PatchErrorKind::Malformed("a member is not a string"))
+}
+
+fn resolve_mut<'a>(
+    value: &'a mut JsonObject,
+    tokens: &[String],
+) -> Result<&'a mut JsonObject, PointerError> {
+    tokens.iter().try_fold(value, |value, token| match value {
+        JsonObject::Object(object) => object.get_mut(token).ok_or(PointerError::NotFound),
+        JsonObject::Array(array) => {
+            let len = array.len();
+            array
+                .get_mut(parse_array_index(token)?)
+                .ok_or(PointerError::IndexOutOfRange(len))
+        }
+        _ => Err(PointerError::NotAContainer),
+    })
+}
+
+//members are replaced when they exist, array elements inserted before the index
+fn add(target: &mut JsonObject, path: &[String], value: JsonObject) -> Result<(), PatchErrorKind> {
+    let (last, parent) = match path.split_last() {
+        Some(split) => split,
+        None => {
+            *target = value;
+            return Ok(());
+        }
+    };
+
+    match resolve_mut(target, parent)? {
+        JsonObject::Object(object) => {
+            object.insert(last.as_str(), value);
+        }
+        JsonObject::Array(array) => {
+            let index = match parse_array_index(last) {
+                Err(PointerError::AppendToken) => array.len(),
+                index => index?,
+            };
+            if index > array.len() {
+                return Err(PointerError::IndexOutOfRange(array.len()).into());
+            }
+            array.insert(index, value);
+        }
+        _ => return Err(PointerError::NotAContainer.into()),
+    }
+
+    Ok(())
+}
+
+fn remove(target: &mut JsonObject, path: &[String]) -> Result<JsonObject, PatchErrorKind> {
+    let (last, parent) = match path.split_last() {
+        Some(split) => split,
+        None => return Ok(std::mem::replace(target, JsonObject::Null)),
+    };
+
+    let removed = match resolve_mut(target, parent)? {
+        JsonObject::Object(object) => object.shift_remove(last).ok_or(PointerError::NotFound)?,
+        JsonObject::Array(array) => {
+            let index = parse_array_index(last)?;
+            if index >= array.len() {
+                return Err(PointerError::IndexOutOfRange(array.len()).into());
+            }
+            array.remove(index)
+        }
+        _ => return Err(PointerError::NotAContainer.into()),
+    };
+
+    Ok(removed)
+}
+
+/// Applies an RFC 7386 JSON Merge Patch: members of a patch object are merged into the target
+/// object recursively, a null member removes the key, and any other patch replaces the target.
+/// Unlike [`JsonObject::merge`], arrays are always replaced and a patch object turns a target
+/// that is not an object into one.
+pub fn merge_patch(target: &mut JsonObject, patch: &JsonObject) {
+    let patch = match patch {
+        JsonObject::Object(patch) => patch,
+        patch => {
+            *target = patch.clone();
+            return;
+        }
+    };
+
+    if !target.is_object() {
+        *target = JsonObject::Object(Object::new());
+    }
+    let object = target.as_object_mut().unwrap();
+
+    for (key, value) in patch {
+        if value.is_null() {
+            object.shift_remove(key);
+            continue;
+        }
+
+        match object.get_mut(key) {
+            Some(existing) => merge_patch(existing, value),
+            None => {
+                let mut added = JsonObject::Null;
+                merge_patch(&mut added, value);
+                object.insert(key, added);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn patched(document: &str, patch: &str) -> Result<JsonObject, PatchError> {
+        let mut document = parse_json_string(document).unwrap();
+        apply_patch(&mut document, &parse_json_string(patch).unwrap())?;
+        Ok(document)
+    }
+
+    fn assert_patched(document: &str, patch: &str, expected: &str) {
+        let result = patched(document, patch).unwrap();
+        let expected = parse_json_string(expected).unwrap();
+        assert!(result.canonical_eq(&expected), "{} != {}", result, expected);
+    }
+
+    fn error(index: usize, kind: PatchErrorKind) -> Result<JsonObject, PatchError> {
+        Err(PatchError { index, kind })
+    }
+
+    //the examples of RFC 6902, appendix A
+    #[test]
+    fn json_patch_examples() {
+        assert_patched(
+            r#"{ "foo": "bar" }"#,
+            r#"[ { "op": "add", "path": "/baz", "value": "qux" } ]"#,
+            r#"{ "baz": "qux", "foo": "bar" }"#,
+        );
+        assert_patched(
+            r#"{ "foo": [ "bar", "baz" ] }"#,
+            r#"[ { "op": "add", "path": "/foo/1", "value": "qux" } ]"#,
+            r#"{ "foo": [ "bar", "qux", "baz" ] }"#,
+        );
+        assert_patched(
+            r#"{ "baz": "qux", "foo": "bar" }"#,
+            r#"[ { "op": "remove", "path": "/baz" } ]"#,
+            r#"{ "foo": "bar" }"#,
+        );
+        assert_patched(
+            r#"{ "foo": [ "bar", "qux", "baz" ] }"#,
+            r#"[ { "op": "remove", "path": "/foo/1" } ]"#,
+            r#"{ "foo": [ "bar", "baz" ] }"#,
+        );
+        assert_patched(
+            r#"{ "baz": "qux", "foo": "bar" }"#,
+            r#"[ { "op": "replace", "path": "/baz", "value": "boo" } ]"#,
+            r#"{ "baz": "boo", "foo": "bar" }"#,
+        );
+        assert_patched(
+            r#"{ "foo": { "bar": "baz", "waldo": "fred" }, "qux": { "corge": "grault" } }"#,
+            r#"[ { "op": "move", "from": "/foo/waldo", "path": "/qux/thud" } ]"#,
+            r#"{ "foo": { "bar": "baz" }, "qux": { "corge": "grault", "thud": "fred" } }"#,
+        );
+        assert_patched(
+            r#"{ "foo": [ "all", "grass", "cows", "eat" ] }"#,
+            r#"[ { "op": "move", "from": "/foo/1", "path": "/foo/3" } ]"#,
+            r#"{ "foo": [ "all", "cows", "eat", "grass" ] }"#,
+        );
+        assert_patched(
+            r#"{ "baz": "qux", "foo": [ "a", 2, "c" ] }"#,
+            r#"[ { "op": "test", "path": "/baz", "value": "qux" },
+                 { "op": "test", "path": "/foo/1", "value": 2 } ]"#,
+            r#"{ "baz": "qux", "foo": [ "a", 2, "c" ] }"#,
+        );
+        assert_eq!(
+            patched(
+                r#"{ "baz": "qux" }"#,
+                r#"[ { "op": "test", "path": "/baz", "value": "bar" } ]"#
+            ),
+            error(0, PatchErrorKind::TestFailed)
+        );
+        assert_patched(
+            r#"{ "foo": "bar" }"#,
+            r#"[ { "op": "add", "path": "/child", "value": { "grandchild": { } } } ]"#,
+            r#"{ "foo": "bar", "child": { "grandchild": { } } }"#,
+        );
+        assert_patched(
+            r#"{ "foo": "bar" }"#,
+            r#"[ { "op": "add", "path": "/baz", "value": "qux", "xyz": 123 } ]"#,
+            r#"{ "foo": "bar", "baz": "qux" }"#,
+        );
+        assert_eq!(
+            patched(
+                r#"{ "foo": "bar" }"#,
+                r#"[ { "op": "add", "path": "/baz/bat", "value": "qux" } ]"#
+            ),
+            error(0, PatchErrorKind::Pointer(PointerError::NotFound))
+        );
+        assert_patched(
+            r#"{ "/": 9, "~1": 10 }"#,
+            r#"[ { "op": "test", "path": "/~01", "value": 10 } ]"#,
+            r#"{ "/": 9, "~1": 10 }"#,
+        );
+        assert_eq!(
+            patched(
+                r#"{ "/": 9, "~1": 10 }"#,
+                r#"[ { "op": "test", "path": "/~01", "value": "10" } ]"#
+            ),
+            error(0, PatchErrorKind::TestFailed)
+        );
+        assert_patched(
+            r#"{ "foo": [ "bar" ] }"#,
+            r#"[ { "op": "add", "path": "/foo/-", "value": [ "abc", "def" ] } ]"#,
+            r#"{ "foo": [ "bar", [ "abc", "def" ] ] }"#,
+        );
+    }
+
+    #[test]
+    fn operations_on_arrays_and_the_root() {
+        assert_patched(
+            r#"{ "a": [ 1, 2 ], "b": { "c": null } }"#,
+            r#"[ { "op": "copy", "from": "/b", "path": "/a/0" },
+                 { "op": "add", "path": "/a/3", "value": 3 },
+                 { "op": "replace", "path": "/b/c", "value": [] },
+                 { "op": "move", "from": "/a", "path": "/b/c/-" },
+                 { "op": "move", "from": "/b", "path": "/b" } ]"#,
+            r#"{ "b": { "c": [ [ { "c": null }, 1, 2, 3 ] ] } }"#,
+        );
+        assert_patched(
+            r#"{ "a": 1 }"#,
+            r#"[ { "op": "replace", "path": "", "value": [ 1 ] },
+                 { "op": "add", "path": "/0", "value": 0 } ]"#,
+            "[ 0, 1 ]",
+        );
+
+        for (patch, kind) in [
+            (
+                r#"{ "op": "add", "path": "/a/3", "value": 0 }"#,
+                PatchErrorKind::Pointer(PointerError::IndexOutOfRange(2)),
+            ),
+            (
+                r#"{ "op": "remove", "path": "/a/-" }"#,
+                PatchErrorKind::Pointer(PointerError::AppendToken),
+            ),
+            (
+                r#"{ "op": "replace", "path": "/a/01", "value": 0 }"#,
+                PatchErrorKind::Pointer(PointerError::InvalidIndex("01".to_owned())),
+            ),
+            (
+                r#"{ "op": "remove", "path": "/b" }"#,
+                PatchErrorKind::Pointer(PointerError::NotFound),
+            ),
+            (
+                r#"{ "op": "copy", "from": "/a/0/x", "path": "/b" }"#,
+                PatchErrorKind::Pointer(PointerError::NotAContainer),
+            ),
+            (
+                r#"{ "op": "move", "from": "/a", "path": "/a/0" }"#,
+                PatchErrorKind::MoveIntoChild,
+            ),
+            (
+                r#"{ "op": "add", "path": "a", "value": 0 }"#,
+                PatchErrorKind::Pointer(PointerError::Syntax),
+            ),
+            (
+                r#"{ "op": "add", "path": "/b" }"#,
+                PatchErrorKind::Malformed("missing \"value\""),
+            ),
+            (
+                r#"{ "op": "copy", "path": "/b" }"#,
+                PatchErrorKind::Malformed("missing \"from\""),
+            ),
+            (
+                r#"{ "path": "/b" }"#,
+                PatchErrorKind::Malformed("missing \"op\""),
+            ),
+            (
+                r#"{ "op": "add", "path": 1, "value": 0 }"#,
+                PatchErrorKind::Malformed("a member is not a string"),
+            ),
+            (
+                r#"{ "op": "append", "path": "/b", "value": 0 }"#,
+                PatchErrorKind::UnknownOperation("append".to_owned()),
+            ),
+            (
+                "[]",
+                PatchErrorKind::Malformed("an operation is not an object"),
+            ),
+        ] {
+            assert_eq!(
+                patched(r#"{ "a": [ 1, 2 ] }"#, &format!("[ {} ]", patch)),
+                error(0, kind),
+                "{}",
+                patch
+            );
+        }
+
+        assert_eq!(
+            patched("{}", "{}"),
+            error(0, PatchErrorKind::Malformed("the patch is not an array"))
+        );
+    }
+
+    #[test]
+    fn failed_patches_change_nothing() {
+        let mut document = json!({ "name": "a", "tags": ["x"], "count": 1 });
+        let original = document.clone();
+
+        let patch = json!([
+            { "op": "replace", "path": "/name", "value": "b" },
+            { "op": "add", "path": "/tags/-", "value": "y" },
+            { "op": "remove", "path": "/count" },
+            { "op": "test", "path": "/tags/0", "value": "z" },
+            { "op": "add", "path": "/never", "value": true }
+        ]);
+        let err = apply_patch(&mut document, &patch).unwrap_err();
+
+        assert_eq!(
+            err,
+            PatchError {
+                index: 3,
+                kind: PatchErrorKind::TestFailed
+            }
+        );
+        assert_eq!(err.to_string(), "operation 3: test failed");
+        assert_eq!(document, original);
+    }
+
+    fn merge_patched(target: &str, patch: &str) -> JsonObject {
+        let mut target = parse_json_string(target).unwrap();
+        merge_patch(&mut target, &parse_json_string(patch).unwrap());
+        target
+    }
+
+    //the examples of RFC 7386, section 3 and appendix A
+    #[test]
+    fn merge_patch_examples() {
+        let document = r#"{
+            "title": "Goodbye!",
+            "author" : { "givenName" : "John", "familyName" : "Doe" },
+            "tags":[ "example", "sample" ],
+            "content": "This will be unchanged"
+        }"#;
+        let patch = r#"{
+            "title": "Hello!",
+            "phoneNumber": "+01-123-456-7890",
+            "author": { "familyName": null },
+            "tags": [ "example" ]
+        }"#;
+        assert_eq!(
+            merge_patched(document, patch),
+            parse_json_string(
+                r#"{
+                    "title": "Hello!",
+                    "author" : { "givenName" : "John" },
+                    "tags": [ "example" ],
+                    "content": "This will be unchanged",
+                    "phoneNumber": "+01-123-456-7890"
+                }"#
+            )
+            .unwrap()
+        );
+
+        for (target, patch, expected) in [
+            (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+            (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+            (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+            (
+                r#"{"a":{"b":"c"}}"#,
+                r#"{"a":{"b":"d","c":null}}"#,
+                r#"{"a":{"b":"d"}}"#,
+            ),
+            (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+            (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+            (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+            (r#"{"a":"foo"}"#, "null", "null"),
+            (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+            (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+            (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+            (
+                r#"{}"#,
+                r#"{"a":{"bb":{"ccc":null}}}"#,
+                r#"{"a":{"bb":{}}}"#,
+            ),
+        ] {
+            assert_eq!(
+                merge_patched(target, patch),
+                parse_json_string(expected).unwrap(),
+                "{} with {}",
+                target,
+                patch
+            );
+        }
+    }
+}
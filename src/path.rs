@@ -0,0 +1,544 @@
+use crate::{JsonObject, Object};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+    /// An empty segment, an unterminated quote or text right after a closing quote.
+    Syntax,
+    /// Traversal reached a value that is neither an object nor an array.
+    NotAContainer,
+    /// Array segments must be unquoted integers.
+    InvalidIndex(String),
+    /// An index past either end of an array of this length.
+    IndexOutOfRange(usize),
+    /// A missing object member, when it is not created.
+    NotFound,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetPathOptions {
+    /// Create missing object members along the path as empty objects, on by default. Otherwise
+    /// they are a [`PathError::NotFound`].
+    pub create_missing: bool,
+}
+
+impl Default for SetPathOptions {
+    fn default() -> Self {
+        SetPathOptions {
+            create_missing: true,
+        }
+    }
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::Syntax => f.write_str("invalid path syntax"),
+            PathError::NotAContainer => f.write_str("the path goes through a scalar value"),
+            PathError::InvalidIndex(segment) => write!(f, "{:?} is not an array index", segment),
+            PathError::IndexOutOfRange(len) => {
+                write!(f, "index out of range for an array of length {}", len)
+            }
+            PathError::NotFound => f.write_str("no value at the path"),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Segment {
+    pub(crate) name: String,
+    //quoted segments are always object keys, even when they look like an index
+    pub(crate) quoted: bool,
+}
+
+impl Segment {
+    //negative indices count from the end, `-1` being the last element
+    pub(crate) fn index(&self, len: usize) -> Option<usize> {
+        if self.quoted {
+            return None;
+        }
+
+        match self.name.strip_prefix('-') {
+            Some(from_end) => {
+                let from_end: usize = parse_index(from_end)?;
+                len.checked_sub(from_end).filter(|_| from_end != 0)
+            }
+            None => parse_index(&self.name).filter(|&index| index < len),
+        }
+    }
+
+    //`index` with the reason it failed, for set_path
+    fn array_index(&self, len: usize) -> Result<usize, PathError> {
+        let is_integer = !self.quoted
+            && parse_index(self.name.strip_prefix('-').unwrap_or(&self.name)).is_some();
+
+        match self.index(len) {
+            Some(index) => Ok(index),
+            None if is_integer => Err(PathError::IndexOutOfRange(len)),
+            None => Err(PathError::InvalidIndex(self.name.clone())),
+        }
+    }
+
+    //`array_index`, but the length or `-` name the element to append
+    fn insert_index(&self, len: usize) -> Result<usize, PathError> {
+        let appends = !self.quoted && (self.name == "-" || parse_index(&self.name) == Some(len));
+
+        if appends {
+            Ok(len)
+        } else {
+            self.array_index(len)
+        }
+    }
+}
+
+fn parse_index(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        None
+    } else {
+        s.parse().ok()
+    }
+}
+
+//`a.b.0` or `a."b.c".-1`, quoted segments accept `\"` and `\\` escapes
+pub(crate) fn parse_path(path: &str) -> Option<Vec<Segment>> {
+    let mut segments = vec![];
+
+    if path.is_empty() {
+        return Some(segments);
+    }
+
+    let mut chars = path.chars().peekable();
+
+    loop {
+        let mut name = String::new();
+        let quoted = chars.peek() == Some(&'"');
+
+        if quoted {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => name.push(chars.next()?),
+                    ch => name.push(ch),
+                }
+            }
+        } else {
+            while let Some(&ch) = chars.peek() {
+                if ch == '.' || ch == '"' {
+                    break;
+                }
+                name.push(ch);
+                chars.next();
+            }
+
+            if name.is_empty() {
+                return None;
+            }
+        }
+
+        segments.push(Segment { name, quoted });
+
+        match chars.next() {
+            None => return Some(segments),
+            Some('.') => continue,
+            Some(_) => return None,
+        }
+    }
+}
+
+impl JsonObject {
+    /// Looks up a value by dotted path such as `servers.0.name`. Numeric segments index arrays,
+    /// negative ones from the end (`-1` is the last element), and segments can be quoted to hold
+    /// dots: `labels."app.kubernetes.io/name"`. An empty path is the value itself. Returns `None`
+    /// for malformed paths as well as missing values.
+    pub fn get_path(&self, path: &str) -> Option<&JsonObject> {
+        parse_path(path)?
+            .iter()
+            .try_fold(self, |value, segment| match value {
+                JsonObject::Object(object) => object.get(&segment.name),
+                JsonObject::Array(array) => array.get(segment.index(array.len())?),
+                _ => None,
+            })
+    }
+
+    /// [`JsonObject::get_path`] for changing the value in place.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut JsonObject> {
+        parse_path(path)?
+            .iter()
+            .try_fold(self, |value, segment| match value {
+                JsonObject::Object(object) => object.get_mut(&segment.name),
+                JsonObject::Array(array) => {
+                    let index = segment.index(array.len())?;
+                    array.get_mut(index)
+                }
+                _ => None,
+            })
+    }
+
+    /// Sets the value at a dotted path, returning the value it replaced. Missing object members
+    /// along the way are created as empty objects like [`JsonObject::set_pointer`] does. In arrays
+    /// the last segment can be the length or `-` to append, other segments must name an existing
+    /// element.
+    #[inline]
+    pub fn set_path(
+        &mut self,
+        path: &str,
+        value: JsonObject,
+    ) -> Result<Option<JsonObject>, PathError> {
+        self.set_path_with_options(path, value, &SetPathOptions::default())
+    }
+
+    /// [`JsonObject::set_path`], optionally without creating missing members.
+    pub fn set_path_with_options(
+        &mut self,
+        path: &str,
+        value: JsonObject,
+        options: &SetPathOptions,
+    ) -> Result<Option<JsonObject>, PathError> {
+        let mut segments = parse_path(path).ok_or(PathError::Syntax)?;
+
+        let last = match segments.pop() {
+            Some(last) => last,
+            None => return Ok(Some(std::mem::replace(self, value))),
+        };
+
+        let mut current = self;
+
+        for segment in segments {
+            current = match current {
+                JsonObject::Object(object) => {
+                    if object.get(&segment.name).is_none() {
+                        if !options.create_missing {
+                            return Err(PathError::NotFound);
+                        }
+                        object.entries_mut().push((
+                            segment.name.as_str().into(),
+                            JsonObject::Object(Object::new()),
+                        ));
+                    }
+                    object.get_mut(&segment.name).unwrap()
+                }
+                JsonObject::Array(array) => {
+                    let index = segment.array_index(array.len())?;
+                    &mut array[index]
+                }
+                _ => return Err(PathError::NotAContainer),
+            };
+        }
+
+        match current {
+            JsonObject::Object(object) => match object.get_mut(&last.name) {
+                Some(old) => Ok(Some(std::mem::replace(old, value))),
+                None => {
+                    object.entries_mut().push((last.name.into(), value));
+                    Ok(None)
+                }
+            },
+            JsonObject::Array(array) => {
+                let index = last.insert_index(array.len())?;
+
+                if index == array.len() {
+                    array.push(value);
+                    Ok(None)
+                } else {
+                    Ok(Some(std::mem::replace(&mut array[index], value)))
+                }
+            }
+            _ => Err(PathError::NotAContainer),
+        }
+    }
+
+    /// Removes and returns the value at a dotted path, shifting later array elements down. An
+    /// empty path takes the whole value and leaves null behind.
+    pub fn remove_path(&mut self, path: &str) -> Result<JsonObject, PathError> {
+        let mut segments = parse_path(path).ok_or(PathError::Syntax)?;
+
+        let last = match segments.pop() {
+            Some(last) => last,
+            None => return Ok(std::mem::replace(self, JsonObject::Null)),
+        };
+
+        let mut current = self;
+
+        for segment in segments {
+            current = match current {
+                JsonObject::Object(object) => {
+                    object.get_mut(&segment.name).ok_or(PathError::NotFound)?
+                }
+                JsonObject::Array(array) => {
+                    let index = segment.array_index(array.len())?;
+                    &mut array[index]
+                }
+                _ => return Err(PathError::NotAContainer),
+            };
+        }
+
+        match current {
+            JsonObject::Object(object) => {
+                object.shift_remove(&last.name).ok_or(PathError::NotFound)
+            }
+            JsonObject::Array(array) => {
+                let index = last.array_index(array.len())?;
+                Ok(array.remove(index))
+            }
+            _ => Err(PathError::NotAContainer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn dotted_paths() -> Result<(), JsonError> {
+        let json =
+            parse_json_string(r#"{ "a" : { "b.c" : [ 10, { "0" : "zero" } ], "1" : true } }"#)?;
+
+        assert_eq!(json.get_path(""), Some(&json));
+        assert_eq!(json.get_path("a.\"b.c\".0"), Some(&JsonObject::Number(10.)));
+        assert_eq!(
+            json.get_path("a.\"b.c\".1.0"),
+            Some(&JsonObject::String("zero".to_owned()))
+        );
+        assert_eq!(json.get_path("a.1"), Some(&JsonObject::Boolean(true)));
+        assert_eq!(json.get_path("a.\"b.c\".2"), None);
+        assert_eq!(json.get_path("a.b"), None);
+        assert_eq!(json.get_path("a..b"), None);
+        assert_eq!(json.get_path("a.\"b.c"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn negative_indices() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{ "events" : [ { "timestamp" : 1 }, { "timestamp" : 2 } ], "one" : [ 7 ], "none" : [] }"#,
+        )?;
+
+        assert_eq!(
+            json.get_path("events.-1.timestamp"),
+            Some(&JsonObject::Number(2.))
+        );
+        assert_eq!(
+            json.get_path("events.-2.timestamp"),
+            Some(&JsonObject::Number(1.))
+        );
+        assert_eq!(json.get_path("events.-3.timestamp"), None);
+        assert_eq!(json.get_path("one.-1"), Some(&JsonObject::Number(7.)));
+        assert_eq!(json.get_path("one.-2"), None);
+        assert_eq!(json.get_path("none.-1"), None);
+        assert_eq!(json.get_path("one.-0"), None);
+        //quoted segments are keys, never indices
+        assert_eq!(json.get_path("one.\"-1\""), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mutation_through_arrays() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json =
+            parse_json_string(r#"{ "servers" : [ { "port" : 80 }, { "port" : 81 } ] }"#)?;
+
+        *json.get_path_mut("servers.-1.port").unwrap() = JsonObject::Number(8081.);
+        assert_eq!(
+            json.set_path("servers.0.port", JsonObject::Number(8080.))?,
+            Some(JsonObject::Number(80.))
+        );
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "servers" : [ { "port" : 8080 }, { "port" : 8081 } ] }"#)?
+        );
+
+        assert_eq!(json.get_path_mut("servers.2"), None);
+        assert_eq!(
+            json.set_path("servers.2.port", JsonObject::Null),
+            Err(PathError::IndexOutOfRange(2))
+        );
+        assert_eq!(
+            json.set_path("servers.\"0\".port", JsonObject::Null),
+            Err(PathError::InvalidIndex("0".to_owned()))
+        );
+        assert_eq!(
+            PathError::IndexOutOfRange(2).to_string(),
+            "index out of range for an array of length 2"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn creates_intermediate_objects() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = parse_json_string("{}")?;
+
+        assert_eq!(
+            json.set_path("server.listen.port", JsonObject::Number(9090.))?,
+            None
+        );
+        assert_eq!(
+            json.set_path(
+                "labels.\"app.kubernetes.io/name\"",
+                JsonObject::String("web".to_owned())
+            )?,
+            None
+        );
+        assert_eq!(
+            json,
+            parse_json_string(
+                r#"{ "server" : { "listen" : { "port" : 9090 } }, "labels" : { "app.kubernetes.io/name" : "web" } }"#
+            )?
+        );
+        assert_eq!(
+            json.get_path("labels.\"app.kubernetes.io/name\""),
+            Some(&JsonObject::String("web".to_owned()))
+        );
+
+        assert!(json.set_path("", JsonObject::Null)?.is_some());
+        assert_eq!(json, JsonObject::Null);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_path_errors() -> Result<(), JsonError> {
+        let source = r#"{ "a" : { "scalar" : 1 } }"#;
+        let mut json = parse_json_string(source)?;
+
+        assert_eq!(
+            json.set_path("a.scalar.b", JsonObject::Null),
+            Err(PathError::NotAContainer)
+        );
+        assert_eq!(
+            json.set_path("a.scalar.b.c", JsonObject::Null),
+            Err(PathError::NotAContainer)
+        );
+        assert_eq!(
+            json.set_path("a..b", JsonObject::Null),
+            Err(PathError::Syntax)
+        );
+        assert_eq!(json.get_path_mut("a.scalar.b"), None);
+        assert_eq!(json, parse_json_string(source)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn built_from_scratch() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = JsonObject::Object(Object::new());
+
+        json.set_path("server.host", json!("localhost"))?;
+        json.set_path("server.ports", json!([]))?;
+        json.set_path("server.ports.0", json!(8080))?;
+        json.set_path("server.ports.-", json!(8081))?;
+        json.set_path("server.ports.2", json!(8082))?;
+        json.set_path("server.tls.enabled", json!(false))?;
+        json.set_path("users", json!([]))?;
+        json.set_path("users.-", json!({}))?;
+        json.set_path("users.0.name", json!("ada"))?;
+        json.set_path("users.-1.roles", json!(["admin"]))?;
+        json.set_path("users.0.roles.-", json!("dev"))?;
+
+        assert_eq!(
+            json,
+            json!({
+                "server": {
+                    "host": "localhost",
+                    "ports": [8080, 8081, 8082],
+                    "tls": { "enabled": false }
+                },
+                "users": [{ "name": "ada", "roles": ["admin", "dev"] }]
+            })
+        );
+
+        //replacing returns the old value, appending does not
+        assert_eq!(
+            json.set_path("server.ports.1", json!(9000))?,
+            Some(json!(8081))
+        );
+        assert_eq!(json.set_path("server.ports.3", json!(9001))?, None);
+        assert_eq!(
+            json.get_path("server.ports"),
+            Some(&json!([8080, 9000, 8082, 9001]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn removal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = json!({
+            "server": { "tls": { "cert": "a.pem" }, "ports": [80, 81, 82] },
+            "name": "web"
+        });
+
+        assert_eq!(json.remove_path("server.tls")?, json!({ "cert": "a.pem" }));
+        assert_eq!(json.remove_path("server.ports.0")?, json!(80));
+        assert_eq!(json.remove_path("server.ports.-1")?, json!(82));
+        assert_eq!(json, json!({ "server": { "ports": [81] }, "name": "web" }));
+
+        assert_eq!(json.remove_path("server.tls"), Err(PathError::NotFound));
+        assert_eq!(
+            json.remove_path("server.tls.cert"),
+            Err(PathError::NotFound)
+        );
+        assert_eq!(
+            json.remove_path("server.ports.1"),
+            Err(PathError::IndexOutOfRange(1))
+        );
+        //there is nothing past the end to remove
+        assert_eq!(
+            json.remove_path("server.ports.-"),
+            Err(PathError::InvalidIndex("-".to_owned()))
+        );
+        assert_eq!(
+            json.remove_path("name.first"),
+            Err(PathError::NotAContainer)
+        );
+        assert_eq!(json.remove_path("server..ports"), Err(PathError::Syntax));
+
+        assert_eq!(
+            json.remove_path("")?,
+            json!({ "server": { "ports": [81] }, "name": "web" })
+        );
+        assert_eq!(json, JsonObject::Null);
+
+        Ok(())
+    }
+
+    #[test]
+    fn without_creating_members() -> Result<(), Box<dyn std::error::Error>> {
+        let options = SetPathOptions {
+            create_missing: false,
+        };
+        let mut json = json!({ "a": { "b": [1] } });
+
+        assert_eq!(
+            json.set_path_with_options("a.c.d", json!(1), &options),
+            Err(PathError::NotFound)
+        );
+        //the last member is still added, only the ones leading to it have to exist
+        assert_eq!(json.set_path_with_options("a.c", json!(2), &options)?, None);
+        assert_eq!(
+            json.set_path_with_options("a.b.-", json!(3), &options)?,
+            None
+        );
+        assert_eq!(json, json!({ "a": { "b": [1, 3], "c": 2 } }));
+
+        //appending only works at the end of the path
+        assert_eq!(
+            json.set_path("a.b.2.x", json!(4)),
+            Err(PathError::IndexOutOfRange(2))
+        );
+        assert_eq!(
+            json.set_path("a.b.-.x", json!(4)),
+            Err(PathError::InvalidIndex("-".to_owned()))
+        );
+        assert_eq!(
+            json.set_path("a.b.3", json!(4)),
+            Err(PathError::IndexOutOfRange(2))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{JsonObject, Object};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerError {
+    /// Not empty and not starting with `/`, or a `~` not followed by `0` or `1`.
+    Syntax,
+    /// Traversal reached a value that is neither an object nor an array.
+    NotAContainer,
+    /// Array tokens must be decimal without leading zeros.
+    InvalidIndex(String),
+    /// An index past the end of an array of this length.
+    IndexOutOfRange(usize),
+    NotFound,
+    /// `-` names the element past the end of an array, which only an append can address.
+    AppendToken,
+}
+
+impl std::fmt::Display for PointerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointerError::Syntax => f.write_str("invalid JSON pointer syntax"),
+            PointerError::NotAContainer => f.write_str("the pointer goes through a scalar value"),
+            PointerError::InvalidIndex(token) => write!(f, "{:?} is not an array index", token),
+            PointerError::IndexOutOfRange(len) => {
+                write!(f, "index out of range for an array of length {}", len)
+            }
+            PointerError::NotFound => f.write_str("no value at the pointer"),
+            PointerError::AppendToken => f.write_str("'-' only names a place to append to"),
+        }
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+//splits `/a~1b/0` into `a/b` and `0`, the empty pointer has no tokens
+pub(crate) fn parse_pointer(pointer: &str) -> Result<Vec<String>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let rest = pointer.strip_prefix('/').ok_or(PointerError::Syntax)?;
+
+    rest.split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '~' => match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => return Err(PointerError::Syntax),
+                    },
+                    ch => unescaped.push(ch),
+                }
+            }
+
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+//appends `/` and the token with `~` and `/` escaped, the inverse of parse_pointer
+pub(crate) fn push_token(pointer: &mut String, token: &str) {
+    pointer.push('/');
+    for ch in token.chars() {
+        match ch {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            ch => pointer.push(ch),
+        }
+    }
+}
+
+pub(crate) fn parse_array_index(token: &str) -> Result<usize, PointerError> {
+    let is_decimal = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+
+    if token == "-" {
+        Err(PointerError::AppendToken)
+    } else if is_decimal {
+        token
+            .parse()
+            .map_err(|_| PointerError::InvalidIndex(token.to_owned()))
+    } else {
+        Err(PointerError::InvalidIndex(token.to_owned()))
+    }
+}
+
+//the value reached by already split tokens
+pub(crate) fn lookup<'a>(value: &'a JsonObject, tokens: &[String]) -> Option<&'a JsonObject> {
+    tokens.iter().try_fold(value, |value, token| match value {
+        JsonObject::Object(object) => object.get(token),
+        JsonObject::Array(array) => array.get(parse_array_index(token).ok()?),
+        _ => None,
+    })
+}
+
+impl JsonObject {
+    /// Looks up a value by JSON Pointer, `""` being the whole document and `/a~1b/0` the first
+    /// element of the member `a/b`. Malformed pointers, invalid array indices and `-` give `None`
+    /// like missing values do.
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonObject> {
+        lookup(self, &parse_pointer(pointer).ok()?)
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonObject> {
+        parse_pointer(pointer)
+            .ok()?
+            .iter()
+            .try_fold(self, |value, token| match value {
+                JsonObject::Object(object) => object.get_mut(token),
+                JsonObject::Array(array) => array.get_mut(parse_array_index(token).ok()?),
+                _ => None,
+            })
+    }
+
+    /// Sets the value at a JSON Pointer, returning the value it replaced.
+    ///
+    /// Missing object members along the way are created as empty objects. In arrays an index
+    /// replaces an existing element, while the length or the `-` token appends; `-` anywhere but
+    /// the last token is an error. Following RFC 6901, `-` is an ordinary member name in objects.
+    pub fn set_pointer(
+        &mut self,
+        pointer: &str,
+        value: JsonObject,
+    ) -> Result<Option<JsonObject>, PointerError> {
+        let mut tokens = parse_pointer(pointer)?;
+
+        let last = match tokens.pop() {
+            Some(last) => last,
+            None => return Ok(Some(std::mem::replace(self, value))),
+        };
+
+        let mut current = self;
+
+        for token in tokens {
+            current = match current {
+                JsonObject::Object(object) => {
+                    if object.get(&token).is_none() {
+                        object.entries_mut().push((
+                            token.as_str().into(),
+                            JsonObject::Object(Object::from_impl(vec![])),
+                        ));
+                    }
+                    object.get_mut(&token).unwrap()
+                }
+                JsonObject::Array(array) => {
+                    let index = parse_array_index(&token)?;
+                    let len = array.len();
+                    array
+                        .get_mut(index)
+                        .ok_or(PointerError::IndexOutOfRange(len))?
+                }
+                _ => return Err(PointerError::NotAContainer),
+            };
+        }
+
+        match current {
+            JsonObject::Object(object) => match object.get_mut(&last) {
+                Some(old) => Ok(Some(std::mem::replace(old, value))),
+                None => {
+                    object.entries_mut().push((last.into(), value));
+                    Ok(None)
+                }
+            },
+            JsonObject::Array(array) => {
+                let index = match parse_array_index(&last) {
+                    Err(PointerError::AppendToken) => array.len(),
+                    index => index?,
+                };
+
+                if index == array.len() {
+                    array.push(value);
+                    Ok(None)
+                } else {
+                    let len = array.len();
+                    let old = array
+                        .get_mut(index)
+                        .ok_or(PointerError::IndexOutOfRange(len))?;
+                    Ok(Some(std::mem::replace(old, value)))
+                }
+            }
+            _ => Err(PointerError::NotAContainer),
+        }
+    }
+
+    /// Removes and returns the value at a JSON Pointer, shifting later array elements down.
+    /// There is nothing to remove past the end of an array so `-` is an error here, as in
+    /// RFC 6902.
+    pub fn remove_pointer(&mut self, pointer: &str) -> Result<JsonObject, PointerError> {
+        let mut tokens = parse_pointer(pointer)?;
+
+        let last = match tokens.pop() {
+            Some(last) => last,
+            None => return Ok(std::mem::replace(self, JsonObject::Null)),
+        };
+
+        let mut current = self;
+
+        for token in tokens {
+            current = match current {
+                JsonObject::Object(object) => {
+                    object.get_mut(&token).ok_or(PointerError::NotFound)?
+                }
+                JsonObject::Array(array) => {
+                    let index = parse_array_index(&token)?;
+                    let len = array.len();
+                    array
+                        .get_mut(index)
+                        .ok_or(PointerError::IndexOutOfRange(len))?
+                }
+                _ => return Err(PointerError::NotAContainer),
+            };
+        }
+
+        match current {
+            JsonObject::Object(object) => {
+                let entries = object.entries_mut();
+                let position = entries
+                    .iter()
+                    .position(|(key, _)| *key == last)
+                    .ok_or(PointerError::NotFound)?;
+                Ok(entries.remove(position).1)
+            }
+            JsonObject::Array(array) => {
+                let index = parse_array_index(&last)?;
+                if index < array.len() {
+                    Ok(array.remove(index))
+                } else {
+                    Err(PointerError::IndexOutOfRange(array.len()))
+                }
+            }
+            _ => Err(PointerError::NotAContainer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn lookups() -> Result<(), JsonError> {
+        let mut json = parse_json_string(
+            r#"{ "foo" : [ { "bar" : 1 }, 2 ], "a/b" : { "m~n" : true }, "0" : "zero", "" : { "" : null } }"#,
+        )?;
+
+        assert_eq!(json.pointer(""), Some(&json));
+        assert_eq!(json.pointer("/foo/0/bar"), Some(&JsonObject::Number(1.)));
+        assert_eq!(json.pointer("/foo/1"), Some(&JsonObject::Number(2.)));
+        assert_eq!(json.pointer("/a~1b/m~0n"), Some(&JsonObject::Boolean(true)));
+        //numeric-looking keys are plain member names in objects
+        assert_eq!(json.pointer("/0"), Some(&JsonObject::from("zero")));
+        assert_eq!(json.pointer("//"), Some(&JsonObject::Null));
+
+        for missing in &[
+            "/foo/2",
+            "/foo/-",
+            "/foo/01",
+            "/foo/+1",
+            "/foo/0/baz",
+            "/foo/1/x",
+            "/a/b",
+            "foo",
+            "/a~2b",
+        ] {
+            assert_eq!(json.pointer(missing), None, "{}", missing);
+        }
+
+        *json.pointer_mut("/foo/0/bar").unwrap() = JsonObject::from("one");
+        assert_eq!(json.pointer("/foo/0/bar"), Some(&JsonObject::from("one")));
+        assert_eq!(json.pointer_mut("/foo/-"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn append_token() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = parse_json_string(r#"{ "items" : [] }"#)?;
+
+        for n in 0..3 {
+            assert_eq!(
+                json.set_pointer("/items/-", JsonObject::Number(n as f64))?,
+                None
+            );
+        }
+
+        assert_eq!(json, parse_json_string(r#"{ "items" : [0, 1, 2] }"#)?);
+
+        //the length appends too, lower indices replace
+        assert_eq!(json.set_pointer("/items/3", JsonObject::Null)?, None);
+        assert_eq!(
+            json.set_pointer("/items/0", JsonObject::Boolean(true))?,
+            Some(JsonObject::Number(0.))
+        );
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "items" : [true, 1, 2, null] }"#)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn append_token_errors() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = parse_json_string(r#"{ "items" : [ { "a" : 1 } ], "scalar" : 1 }"#)?;
+        let before = parse_json_string(r#"{ "items" : [ { "a" : 1 } ], "scalar" : 1 }"#)?;
+
+        assert_eq!(
+            json.set_pointer("/items/-/a", JsonObject::Null),
+            Err(PointerError::AppendToken)
+        );
+        assert_eq!(
+            json.set_pointer("/scalar/-", JsonObject::Null),
+            Err(PointerError::NotAContainer)
+        );
+        assert_eq!(
+            json.remove_pointer("/items/-"),
+            Err(PointerError::AppendToken)
+        );
+        assert_eq!(
+            json.set_pointer("/items/5", JsonObject::Null),
+            Err(PointerError::IndexOutOfRange(1))
+        );
+        assert_eq!(
+            json.set_pointer("/items/01", JsonObject::Null),
+            Err(PointerError::InvalidIndex("01".to_owned()))
+        );
+        assert_eq!(
+            json.set_pointer("items", JsonObject::Null),
+            Err(PointerError::Syntax)
+        );
+        assert_eq!(json, before);
+
+        assert_eq!(
+            PointerError::IndexOutOfRange(1).to_string(),
+            "index out of range for an array of length 1"
+        );
+        assert_eq!(
+            PointerError::InvalidIndex("01".to_owned()).to_string(),
+            r#""01" is not an array index"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_and_remove() -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = JsonObject::Null;
+
+        json.set_pointer("", parse_json_string("{}")?)?;
+        json.set_pointer("/server/ports", parse_json_string("[]")?)?;
+        json.set_pointer("/server/ports/-", JsonObject::Number(80.))?;
+        json.set_pointer("/server/a~1b~0c", JsonObject::Boolean(true))?;
+        //`-` is a plain member name in objects
+        json.set_pointer("/server/-", JsonObject::Null)?;
+
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "server" : { "ports" : [80], "a/b~c" : true, "-" : null } }"#)?
+        );
+
+        assert_eq!(
+            json.remove_pointer("/server/ports/0")?,
+            JsonObject::Number(80.)
+        );
+        assert_eq!(
+            json.remove_pointer("/server/a~1b~0c")?,
+            JsonObject::Boolean(true)
+        );
+        assert_eq!(
+            json.remove_pointer("/server/missing"),
+            Err(PointerError::NotFound)
+        );
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "server" : { "ports" : [], "-" : null } }"#)?
+        );
+
+        Ok(())
+    }
+}
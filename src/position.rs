@@ -0,0 +1,167 @@
+use std::fmt;
+
+use crate::{JsonError, JsonErrorKind};
+
+/// Where an error was noticed, which is the last char the parser read. Both are 1-based and
+/// columns are counted in chars, a `\n` ends its line. Nothing read yet is column 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A parse error laid out for a terminal, see [`JsonError::diagnostic`].
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a, N> {
+    error: &'a JsonError,
+    name: N,
+    text: &'a str,
+}
+
+//`name:line:column: error` followed by the line and a caret under the column
+impl<N: fmt::Display> fmt::Display for Diagnostic<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Position { line, column } = self.error.position;
+        let source_line = source_line(self.text, line);
+
+        write_diagnostic(f, &self.name, line, column, &self.error.kind, source_line)
+    }
+}
+
+impl JsonError {
+    /// The error as `name:line:column: error`, then the line of `text` it is on and a caret
+    /// under its column. `text` is the input that failed to parse and `name` usually its path.
+    ///
+    /// ```
+    /// use json_parser::parse_json_string;
+    ///
+    /// let text = "{\n  \"port\": 80,\n}";
+    /// let error = parse_json_string(text).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     error.diagnostic("config.json", text).to_string(),
+    ///     "config.json:3:1: expected a key but found '}'\n}\n^"
+    /// );
+    /// ```
+    pub fn diagnostic<'a, N: fmt::Display>(&'a self, name: N, text: &'a str) -> Diagnostic<'a, N> {
+        Diagnostic {
+            error: self,
+            name,
+            text,
+        }
+    }
+}
+
+//the 1-based `line` of `text` without its line ending, empty past the end
+pub(crate) fn source_line(text: &str, line: usize) -> &str {
+    text.split('\n')
+        .nth(line.saturating_sub(1))
+        .unwrap_or_default()
+        .trim_end_matches('\r')
+}
+
+pub(crate) fn write_diagnostic(
+    f: &mut fmt::Formatter<'_>,
+    name: &dyn fmt::Display,
+    line: usize,
+    column: usize,
+    kind: &JsonErrorKind,
+    source_line: &str,
+) -> fmt::Result {
+    write!(
+        f,
+        "{}:{}:{}: {}\n{}\n{:>width$}",
+        name,
+        line,
+        column,
+        kind,
+        source_line,
+        "^",
+        width = column
+    )
+}
+
+//the line and column of the last char taken from `iter`, and whether that char was a newline so
+//the next one starts a line. The cursor reads from it, so a char it has peeked at is counted
+pub(crate) struct Located<I> {
+    iter: I,
+    position: Position,
+    after_newline: bool,
+}
+
+impl<I> Located<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        Located {
+            iter,
+            position: Position { line: 1, column: 0 },
+            after_newline: false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn current_position(&self) -> Position {
+        self.position
+    }
+
+    #[inline]
+    pub(crate) fn error(&self, kind: crate::JsonErrorKind) -> crate::JsonError {
+        crate::JsonError {
+            kind,
+            position: self.position,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut I {
+        &mut self.iter
+    }
+
+    //counts a char read some other way, for input that is pushed rather than pulled
+    #[inline]
+    pub(crate) fn advance(&mut self, ch: char) {
+        if self.after_newline {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        self.after_newline = ch == '\n';
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for Located<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.iter.next()?;
+        self.advance(ch);
+        Some(ch)
+    }
+}
+
+//a run read at once has no newline in it
+impl<S: crate::cursor::Source> crate::cursor::Source for Located<S> {
+    #[inline]
+    fn read_unescaped(&mut self, out: &mut String, max_len: usize) {
+        let start = out.len();
+        self.iter.read_unescaped(out, max_len);
+
+        let count = out[start..].chars().count();
+        if count != 0 {
+            if self.after_newline {
+                self.position.line += 1;
+                self.position.column = count;
+            } else {
+                self.position.column += count;
+            }
+            self.after_newline = false;
+        }
+    }
+}
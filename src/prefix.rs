@@ -0,0 +1,96 @@
+use crate::cursor::{Cursor, Plain};
+use crate::position::Located;
+use crate::{parse_json_impl, JsonError, JsonObject, ParseOptions};
+
+/// Parses the value at the start of `input`, which may go on with anything, e.g. the rest of a
+/// log line. Returns the value and how many bytes it took, whitespace before the value included
+/// and after it excluded, so `&input[consumed..]` starts right after the value.
+pub fn parse_json_prefix(input: &str) -> Result<(JsonObject, usize), JsonError> {
+    let mut read = 0;
+    let (value, lookahead) =
+        parse_json_prefix_from_iter(input.chars().inspect(|ch| read += ch.len_utf8()))?;
+
+    Ok((value, read - lookahead.map_or(0, char::len_utf8)))
+}
+
+/// Parses the value at the start of `json_iter` and stops reading right after it. A number only
+/// ends with the char after it, which is returned along with the value since it was taken from
+/// the iterator, so that with a `&mut` to the iterator the caller can resume after the value.
+pub fn parse_json_prefix_from_iter<I: Iterator<Item = char>>(
+    json_iter: I,
+) -> Result<(JsonObject, Option<char>), JsonError> {
+    let mut located = Located::new(Plain(json_iter));
+
+    let parsed = {
+        let mut cursor = Cursor::new(&mut located);
+        parse_json_impl(&mut cursor, &ParseOptions::default(), 0)
+            .map(|value| (value, cursor.into_peeked()))
+    };
+
+    parsed.map_err(|kind| located.error(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, JsonErrorKind, Position};
+
+    #[test]
+    fn values_in_a_line() -> Result<(), JsonError> {
+        let line = r#"INFO payload={"a":1} ids=[1, 2] elapsed=12ms"#;
+
+        let start = line.find("payload=").unwrap() + "payload=".len();
+        let (payload, consumed) = parse_json_prefix(&line[start..])?;
+        assert_eq!(payload, json!({ "a": 1 }));
+        let rest = &line[start + consumed..];
+        assert_eq!(rest, " ids=[1, 2] elapsed=12ms");
+
+        let (ids, consumed) = parse_json_prefix(&rest[" ids=".len()..])?;
+        assert_eq!(ids, json!([1, 2]));
+        assert_eq!(consumed, "[1, 2]".len());
+
+        //back to back, each one starting with the whitespace before it
+        let (first, consumed) = parse_json_prefix(r#"  "é" "ü""#)?;
+        assert_eq!((first, consumed), (json!("é"), "  \"é\"".len()));
+        assert_eq!(
+            parse_json_prefix(&r#"  "é" "ü""#[consumed..])?.0,
+            json!("ü")
+        );
+
+        //a number ends before the char that ended it
+        assert_eq!(parse_json_prefix("12ms")?, (json!(12), 2));
+        assert_eq!(parse_json_prefix("-0.5")?, (json!(-0.5), 4));
+        assert_eq!(parse_json_prefix("true|false")?, (json!(true), 4));
+
+        assert_eq!(
+            parse_json_prefix("[1, 2"),
+            Err(JsonError {
+                kind: JsonErrorKind::UnclosedArray { open: 1 },
+                position: Position { line: 1, column: 5 }
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn resuming_an_iterator() -> Result<(), JsonError> {
+        let mut chars = "1,[2],3".chars();
+
+        let mut values = vec![];
+        loop {
+            let (value, lookahead) = parse_json_prefix_from_iter(&mut chars)?;
+            values.push(value);
+
+            match lookahead.or_else(|| chars.next()) {
+                Some(',') => {}
+                None => break,
+                Some(ch) => panic!("unexpected {:?}", ch),
+            }
+        }
+
+        assert_eq!(values, [json!(1), json!([2]), json!(3)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,591 @@
+use std::iter::once;
+
+use crate::cursor::{Cursor, Source};
+use crate::pointer::{lookup, parse_array_index, parse_pointer};
+use crate::position::Located;
+use crate::{
+    check_depth, entry_value, extra_chars, parse_escape_character_impl, parse_false_impl,
+    parse_json_impl, parse_null_impl, parse_number_impl, parse_string_impl, parse_true_impl,
+    skip_string, unclosed, unexpected, Container, Expected, JsonError, JsonErrorKind, JsonObject,
+    ParseOptions,
+};
+
+/// Parses only the value a JSON Pointer addresses. Everything before it is checked for
+/// structure and skipped without building values, and the input after it is not read at all.
+/// `None` when the pointer does not resolve, including malformed pointers.
+pub fn parse_pointer_only(input: &str, pointer: &str) -> Result<Option<JsonObject>, JsonError> {
+    let tokens = match parse_pointer(pointer) {
+        Ok(tokens) => tokens,
+        Err(_) => return Ok(None),
+    };
+
+    let iter = &mut Located::new(input.chars());
+
+    next_non_whitespace(None, iter)
+        .and_then(|first| find(iter, first, &tokens, 0))
+        .map_err(|kind| iter.error(kind))
+}
+
+/// Parses the values of several JSON Pointers in a single pass over the input, skipping
+/// everything outside of them without building values. Results are in the order of `pointers`,
+/// `None` for the ones that do not resolve. When a pointer addresses a value inside another
+/// one both are returned, the outer value containing the inner one. Unlike
+/// [`parse_pointer_only`] the whole input is validated, trailing characters included.
+pub fn extract_pointers(
+    input: &str,
+    pointers: &[&str],
+) -> Result<Vec<Option<JsonObject>>, JsonError> {
+    //malformed pointers never resolve
+    let targets: Vec<_> = pointers
+        .iter()
+        .map(|pointer| parse_pointer(pointer).ok())
+        .collect();
+    let active: Vec<_> = (0..targets.len())
+        .filter(|&i| targets[i].is_some())
+        .collect();
+
+    let mut extraction = Extraction {
+        targets,
+        results: vec![None; pointers.len()],
+    };
+
+    let iter = &mut Located::new(input.chars());
+    let excess = next_non_whitespace(None, iter)
+        .and_then(|first| extraction.walk(iter, first, 0, active))
+        .map_err(|kind| iter.error(kind))?;
+
+    match next_non_whitespace(excess, iter) {
+        Ok(ch) => {
+            let position = iter.current_position();
+            Err(JsonError {
+                kind: extra_chars(once(ch).chain(iter)),
+                position,
+            })
+        }
+        Err(_) => Ok(extraction.results),
+    }
+}
+
+struct Extraction {
+    targets: Vec<Option<Vec<String>>>,
+    results: Vec<Option<JsonObject>>,
+}
+
+impl Extraction {
+    fn tokens(&self, target: usize) -> &[String] {
+        self.targets[target].as_deref().unwrap_or_default()
+    }
+
+    //`active` holds the targets whose first `depth` tokens lead to the value starting with `first`
+    fn walk(
+        &mut self,
+        iter: &mut dyn Source,
+        first: char,
+        depth: usize,
+        active: Vec<usize>,
+    ) -> Result<Option<char>, JsonErrorKind> {
+        self.walk_impl(iter, first, depth, active)
+            .map_err(|kind| unclosed_at(kind, first, depth))
+    }
+
+    fn walk_impl(
+        &mut self,
+        iter: &mut dyn Source,
+        first: char,
+        depth: usize,
+        active: Vec<usize>,
+    ) -> Result<Option<char>, JsonErrorKind> {
+        if active.is_empty() {
+            return skip_value(iter, first, depth);
+        }
+
+        if active
+            .iter()
+            .any(|&target| self.tokens(target).len() == depth)
+        {
+            let mut cursor = Cursor::resume(iter, Some(first));
+            let value = parse_json_impl(&mut cursor, &ParseOptions::default(), depth)?;
+            let excess = cursor.into_peeked();
+
+            for target in active {
+                let found = lookup(&value, &self.tokens(target)[depth..]).cloned();
+                self.results[target] = found;
+            }
+
+            return Ok(excess);
+        }
+
+        if let '{' | '[' = first {
+            check_depth(depth, &ParseOptions::default())?;
+        }
+
+        match first {
+            '{' => {
+                let mut ch = next_non_whitespace(None, iter)?;
+                match ch {
+                    '}' => return Ok(None),
+                    '"' => {}
+                    ch => return Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+                }
+
+                loop {
+                    if ch != '\"' {
+                        return Err(unexpected(ch, Expected::ObjectKey));
+                    }
+
+                    let key = parse_string_impl(iter)?;
+
+                    match next_non_whitespace(None, iter)? {
+                        ':' => {}
+                        _ => return Err(JsonErrorKind::MissingColon),
+                    }
+
+                    //with duplicate keys the first member wins, as with `Object::get`
+                    let matching = active
+                        .iter()
+                        .copied()
+                        .filter(|&target| {
+                            self.results[target].is_none() && self.tokens(target)[depth] == key
+                        })
+                        .collect();
+
+                    let value = entry_value(next_non_whitespace(None, iter)?)?;
+                    let excess = self.walk(iter, value, depth + 1, matching)?;
+
+                    match next_non_whitespace(excess, iter)? {
+                        ',' => ch = next_non_whitespace(None, iter)?,
+                        '}' => return Ok(None),
+                        ch => return Err(unexpected(ch, Expected::CommaOrObjectEnd)),
+                    }
+                }
+            }
+            '[' => {
+                let mut ch = next_non_whitespace(None, iter)?;
+                if ch == ']' {
+                    return Ok(None);
+                }
+
+                for index in 0.. {
+                    let matching = active
+                        .iter()
+                        .copied()
+                        .filter(|&target| {
+                            parse_array_index(&self.tokens(target)[depth]) == Ok(index)
+                        })
+                        .collect();
+
+                    let excess = self.walk(iter, ch, depth + 1, matching)?;
+
+                    match next_non_whitespace(excess, iter)? {
+                        ',' => ch = next_non_whitespace(None, iter)?,
+                        ']' => return Ok(None),
+                        ch => return Err(unexpected(ch, Expected::CommaOrArrayEnd)),
+                    }
+                }
+
+                unreachable!();
+            }
+            _ => skip_value(iter, first, depth),
+        }
+    }
+}
+
+fn next_non_whitespace(excess: Option<char>, iter: &mut dyn Source) -> Result<char, JsonErrorKind> {
+    excess
+        .into_iter()
+        .chain(iter)
+        .find(|ch| !ch.is_whitespace())
+        .ok_or(JsonErrorKind::EarlyEndOfStream)
+}
+
+//the input ending inside the container starting with `first` leaves it open
+fn unclosed_at(kind: JsonErrorKind, first: char, depth: usize) -> JsonErrorKind {
+    match first {
+        '[' => unclosed(kind, Container::Array, depth),
+        '{' => unclosed(kind, Container::Object, depth),
+        _ => kind,
+    }
+}
+
+//descends into the value starting with `first`, the result is final as soon as it is known
+fn find(
+    iter: &mut dyn Source,
+    first: char,
+    tokens: &[String],
+    depth: usize,
+) -> Result<Option<JsonObject>, JsonErrorKind> {
+    find_impl(iter, first, tokens, depth).map_err(|kind| unclosed_at(kind, first, depth))
+}
+
+fn find_impl(
+    iter: &mut dyn Source,
+    first: char,
+    tokens: &[String],
+    depth: usize,
+) -> Result<Option<JsonObject>, JsonErrorKind> {
+    let (token, rest) = match tokens.split_first() {
+        Some(split) => split,
+        None => {
+            let mut cursor = Cursor::resume(iter, Some(first));
+            let value = parse_json_impl(&mut cursor, &ParseOptions::default(), depth)?;
+            return Ok(Some(value));
+        }
+    };
+
+    if let '{' | '[' = first {
+        check_depth(depth, &ParseOptions::default())?;
+    }
+
+    match first {
+        '{' => {
+            let mut ch = next_non_whitespace(None, iter)?;
+            match ch {
+                '}' => return Ok(None),
+                '"' => {}
+                ch => return Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+            }
+
+            loop {
+                if ch != '\"' {
+                    return Err(unexpected(ch, Expected::ObjectKey));
+                }
+
+                let matched = key_matches(iter, token)?;
+
+                match next_non_whitespace(None, iter)? {
+                    ':' => {}
+                    _ => return Err(JsonErrorKind::MissingColon),
+                }
+
+                let value = entry_value(next_non_whitespace(None, iter)?)?;
+
+                if matched {
+                    return find(iter, value, rest, depth + 1);
+                }
+
+                let excess = skip_value(iter, value, depth + 1)?;
+
+                match next_non_whitespace(excess, iter)? {
+                    ',' => ch = next_non_whitespace(None, iter)?,
+                    '}' => return Ok(None),
+                    ch => return Err(unexpected(ch, Expected::CommaOrObjectEnd)),
+                }
+            }
+        }
+        '[' => {
+            let index = match parse_array_index(token) {
+                Ok(index) => index,
+                Err(_) => return Ok(None),
+            };
+
+            let mut ch = next_non_whitespace(None, iter)?;
+            if ch == ']' {
+                return Ok(None);
+            }
+
+            for _ in 0..index {
+                let excess = skip_value(iter, ch, depth + 1)?;
+
+                match next_non_whitespace(excess, iter)? {
+                    ',' => ch = next_non_whitespace(None, iter)?,
+                    ']' => return Ok(None),
+                    ch => return Err(unexpected(ch, Expected::CommaOrArrayEnd)),
+                }
+            }
+
+            find(iter, ch, rest, depth + 1)
+        }
+        //a scalar has nothing to descend into, but it still has to be valid
+        _ => skip_value(iter, first, depth).map(|_| None),
+    }
+}
+
+//compares an object key with `token` while consuming it, expects '"' to already be eaten
+fn key_matches(iter: &mut dyn Source, token: &str) -> Result<bool, JsonErrorKind> {
+    let mut expected = token.chars();
+    let mut matches = true;
+
+    loop {
+        let ch = match iter.next().ok_or(JsonErrorKind::UnterminatedString)? {
+            '"' => return Ok(matches && expected.next().is_none()),
+            '\\' => parse_escape_character_impl(iter)?,
+            ch => ch,
+        };
+
+        if matches && expected.next() != Some(ch) {
+            matches = false;
+        }
+    }
+}
+
+//validates the value starting with `first` without allocating, returning the excess char
+fn skip_value(
+    iter: &mut dyn Source,
+    first: char,
+    depth: usize,
+) -> Result<Option<char>, JsonErrorKind> {
+    skip_value_impl(iter, first, depth).map_err(|kind| unclosed_at(kind, first, depth))
+}
+
+fn skip_value_impl(
+    iter: &mut dyn Source,
+    first: char,
+    depth: usize,
+) -> Result<Option<char>, JsonErrorKind> {
+    if let '{' | '[' = first {
+        check_depth(depth, &ParseOptions::default())?;
+    }
+
+    match first {
+        '"' => skip_string(iter)?,
+        'n' => drop(parse_null_impl(iter)?),
+        't' => drop(parse_true_impl(iter)?),
+        'f' => drop(parse_false_impl(iter)?),
+        '[' => {
+            let mut ch = next_non_whitespace(None, iter)?;
+            if ch == ']' {
+                return Ok(None);
+            }
+
+            loop {
+                let excess = skip_value(iter, ch, depth + 1)?;
+
+                match next_non_whitespace(excess, iter)? {
+                    ',' => ch = next_non_whitespace(None, iter)?,
+                    ']' => return Ok(None),
+                    ch => return Err(unexpected(ch, Expected::CommaOrArrayEnd)),
+                }
+            }
+        }
+        '{' => {
+            let mut ch = next_non_whitespace(None, iter)?;
+            match ch {
+                '}' => return Ok(None),
+                '"' => {}
+                ch => return Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+            }
+
+            loop {
+                if ch != '\"' {
+                    return Err(unexpected(ch, Expected::ObjectKey));
+                }
+
+                skip_string(iter)?;
+
+                match next_non_whitespace(None, iter)? {
+                    ':' => {}
+                    _ => return Err(JsonErrorKind::MissingColon),
+                }
+
+                let value = entry_value(next_non_whitespace(None, iter)?)?;
+                let excess = skip_value(iter, value, depth + 1)?;
+
+                match next_non_whitespace(excess, iter)? {
+                    ',' => ch = next_non_whitespace(None, iter)?,
+                    '}' => return Ok(None),
+                    ch => return Err(unexpected(ch, Expected::CommaOrObjectEnd)),
+                }
+            }
+        }
+        ch => {
+            let mut cursor = Cursor::new(iter);
+            parse_number_impl(&mut cursor, ch)?;
+            return Ok(cursor.into_peeked());
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const DOCUMENT: &str = r#"{
+        "meta" : { "count" : 2, "next" : null },
+        "results" : {
+            "skipped" : [ "a\"]}", { "x" : [ [], {} ] }, -1.5e3, true, false ],
+            "items" : [ { "id" : 1 }, { "id" : 2, "tags" : [ "x", "y" ] } ],
+            "a\/b" : "escaped key"
+        }
+    }"#;
+
+    //the same value taken out of a fully parsed tree
+    fn full(pointer: &str) -> Option<JsonObject> {
+        parse_json_string(DOCUMENT)
+            .unwrap()
+            .remove_pointer(pointer)
+            .ok()
+    }
+
+    #[test]
+    fn targets() -> Result<(), JsonError> {
+        for pointer in &[
+            "",
+            "/meta",
+            "/meta/count",
+            "/results/items",
+            "/results/items/1/tags/1",
+            "/results/skipped/0",
+            "/results/skipped/4",
+            "/results/a~1b",
+        ] {
+            let projected = parse_pointer_only(DOCUMENT, pointer)?;
+            assert!(projected.is_some(), "{}", pointer);
+            assert_eq!(projected, full(pointer), "{}", pointer);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn absent_targets() -> Result<(), JsonError> {
+        for pointer in &[
+            "/missing",
+            "/meta/count/deeper",
+            "/meta/next/x",
+            "/results/items/2",
+            "/results/items/-",
+            "/results/items/01",
+            "/results/skipped/1/x/0/0",
+            "/results/skipped/1/x/1/a",
+            "no leading slash",
+        ] {
+            assert_eq!(parse_pointer_only(DOCUMENT, pointer)?, None, "{}", pointer);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn several_targets() -> Result<(), JsonError> {
+        let pointers = [
+            "/results/items/1/id",
+            "/meta/count",
+            "/results/skipped/2",
+            "/results/items/0",
+            "/missing",
+            "/results/items/7",
+            "/meta/count/x",
+            "no leading slash",
+        ];
+
+        let extracted = extract_pointers(DOCUMENT, &pointers)?;
+        let expected: Vec<_> = pointers.iter().map(|pointer| full(pointer)).collect();
+
+        assert_eq!(extracted, expected);
+        assert_eq!(extracted.iter().filter(|value| value.is_some()).count(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_targets() -> Result<(), JsonError> {
+        let pointers = [
+            "/results/items/1/tags/0",
+            "/results/items",
+            "/results/items/1",
+            "/results/items/1/tags/5",
+            "",
+        ];
+
+        let extracted = extract_pointers(DOCUMENT, &pointers)?;
+        let expected: Vec<_> = pointers.iter().map(|pointer| full(pointer)).collect();
+
+        assert_eq!(extracted, expected);
+        assert_eq!(extracted[0], Some(JsonObject::String("x".to_owned())));
+        assert_eq!(extracted[3], None);
+
+        assert_eq!(extract_pointers(DOCUMENT, &[])?, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn extraction_validates_everything() {
+        assert_eq!(
+            extract_pointers(r#"{ "b" : 1, "c" : [ } "#, &["/b"]).map_err(|e| e.kind),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: '}',
+                expected: Expected::Value
+            })
+        );
+        assert_eq!(
+            extract_pointers(r#"{ "b" : 1 } x"#, &["/b"]).map_err(|e| e.kind),
+            Err(JsonErrorKind::ExtraChars {
+                preview: "x".to_owned(),
+                count: 1
+            })
+        );
+
+        //positions are the same as the full parser's
+        for input in &["{ \"b\" : 1,\n  \"c\" : [ } ", "{ \"b\" : 1 }\n x"] {
+            assert_eq!(
+                extract_pointers(input, &["/b"]),
+                Err(parse_json_string(input).unwrap_err())
+            );
+        }
+    }
+
+    #[test]
+    fn skipped_content_is_validated() {
+        for (input, error) in &[
+            (
+                r#"{ "a" : [ 1, 2 }, "b" : 1 }"#,
+                JsonErrorKind::UnexpectedChar {
+                    found: '}',
+                    expected: Expected::CommaOrArrayEnd,
+                },
+            ),
+            (
+                r#"{ "a" : [ 1, ], "b" : 1 }"#,
+                JsonErrorKind::UnexpectedChar {
+                    found: ']',
+                    expected: Expected::Value,
+                },
+            ),
+            (
+                r#"{ "a" : nul, "b" : 1 }"#,
+                JsonErrorKind::UnexpectedKeyword,
+            ),
+            (
+                r#"{ "a" : "\q", "b" : 1 }"#,
+                JsonErrorKind::UnknownEscapeCharacter('q'),
+            ),
+            (
+                r#"{ "a" : { "c" 1 }, "b" : 1 }"#,
+                JsonErrorKind::MissingColon,
+            ),
+            (
+                "{ \"a\" : \"\t\", \"b\" : 1 }",
+                JsonErrorKind::UnescapedControlCharacter('\t'),
+            ),
+            (r#"{ "a" : [ "#, JsonErrorKind::UnclosedArray { open: 2 }),
+            (r#"{ "a" : [ "x"#, JsonErrorKind::UnterminatedString),
+        ] {
+            assert_eq!(
+                parse_pointer_only(input, "/b").map_err(|e| e.kind).as_ref(),
+                Err(error),
+                "{}",
+                input
+            );
+        }
+
+        //skipped containers count toward the depth limit too
+        let deep = format!(r#"{{ "a" : {}, "b" : 1 }}"#, "[".repeat(100_000));
+        assert_eq!(
+            parse_pointer_only(&deep, "/b").map_err(|e| e.kind),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+        assert_eq!(
+            extract_pointers(&deep, &["/b"]).map_err(|e| e.kind),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+
+        //the rest is never read once the target is parsed
+        assert_eq!(
+            parse_pointer_only(r#"{ "b" : 1, ] garbage"#, "/b"),
+            Ok(Some(JsonObject::Number(1.)))
+        );
+    }
+}
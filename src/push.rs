@@ -0,0 +1,612 @@
+use std::iter::once;
+use std::str::Chars;
+
+use crate::cursor::{Cursor, Plain};
+use crate::position::Located;
+use crate::{
+    check_depth, parse_number_impl, parse_string_impl, unexpected, Expected, JsonError,
+    JsonErrorKind, JsonObject, Object, ObjectImpl, ParseOptions,
+};
+
+/// What [`StreamingParser::feed`] made of a chunk.
+#[derive(Debug, PartialEq)]
+pub enum Progress {
+    /// The whole chunk was consumed and the document is not complete yet.
+    NeedMore,
+    /// The document ended `consumed` bytes into the chunk, so `&chunk[consumed..]` was not
+    /// read. A number at the top level only ends with the char after it, which is left unread.
+    Complete { value: JsonObject, consumed: usize },
+}
+
+enum Container {
+    Array(Vec<JsonObject>),
+    //the entries so far and the key of the value being parsed
+    Object(ObjectImpl, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum NumberPart {
+    Minus,
+    Zero,
+    Integer,
+    Dot,
+    Fraction,
+    Exponent,
+    ExponentSign,
+    ExponentDigits,
+}
+
+impl NumberPart {
+    //where `ch` takes the number, `None` if it is not part of it
+    pub(crate) fn next(self, ch: char) -> Option<NumberPart> {
+        use NumberPart::*;
+
+        match (self, ch) {
+            (Minus, '0') => Some(Zero),
+            (Minus | Integer, '0'..='9') => Some(Integer),
+            (Zero | Integer, '.') => Some(Dot),
+            (Dot | Fraction, '0'..='9') => Some(Fraction),
+            (Zero | Integer | Fraction, 'e' | 'E') => Some(Exponent),
+            (Exponent, '+' | '-') => Some(ExponentSign),
+            (Exponent | ExponentSign | ExponentDigits, '0'..='9') => Some(ExponentDigits),
+            _ => None,
+        }
+    }
+
+    //the number can end here
+    pub(crate) fn is_complete(self) -> bool {
+        use NumberPart::*;
+
+        matches!(self, Zero | Integer | Fraction | ExponentDigits)
+    }
+}
+
+enum State {
+    //`could_close` right after a '[', where the array can be empty
+    Value {
+        could_close: bool,
+    },
+    //`could_close` right after a '{'
+    Key {
+        could_close: bool,
+    },
+    Colon,
+    AfterValue,
+    //the rest of `null`, `true` or `false`
+    Keyword {
+        rest: Chars<'static>,
+        value: JsonObject,
+    },
+    //the raw text of a string, decoded once the closing '"' is found
+    String {
+        raw: String,
+        escaped: bool,
+        key: bool,
+    },
+    Number {
+        text: String,
+        part: NumberPart,
+    },
+}
+
+/// Parses a document pushed in chunks of any size, for input that arrives bit by bit and should
+/// not block a char iterator. Every partial token, container and string is kept in the parser
+/// between calls, so chunks can split the input anywhere.
+///
+/// The grammar and the resulting values are those of [`parse_json_string`] with the default
+/// [`ParseOptions`].
+///
+/// [`parse_json_string`]: crate::parse_json_string
+pub struct StreamingParser {
+    stack: Vec<Container>,
+    state: State,
+    located: Located<()>,
+    //an error ends parsing, later calls return it again
+    error: Option<JsonError>,
+}
+
+impl Default for StreamingParser {
+    fn default() -> Self {
+        StreamingParser {
+            stack: Vec::new(),
+            state: State::Value { could_close: false },
+            located: Located::new(()),
+            error: None,
+        }
+    }
+}
+
+impl StreamingParser {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the chunk up to the end of the document. After [`Progress::Complete`] the parser
+    /// starts over, so the rest of the chunk can be fed for a following document. Error
+    /// positions count the chars of every chunk fed so far.
+    pub fn feed(&mut self, chunk: &str) -> Result<Progress, JsonError> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+
+        for (offset, ch) in chunk.char_indices() {
+            self.located.advance(ch);
+
+            match self.push(ch) {
+                Ok(None) => {}
+                Ok(Some((value, consumed))) => {
+                    let consumed = offset + if consumed { ch.len_utf8() } else { 0 };
+                    self.state = State::Value { could_close: false };
+                    return Ok(Progress::Complete { value, consumed });
+                }
+                Err(kind) => return Err(self.fail(kind)),
+            }
+        }
+
+        Ok(Progress::NeedMore)
+    }
+
+    /// Ends the input, which completes a number at the top level. Anything else still open is
+    /// an error, as is a document that never started.
+    pub fn finish(mut self) -> Result<JsonObject, JsonError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let kind = match std::mem::replace(&mut self.state, State::AfterValue) {
+            State::Number { text, part } if part.is_complete() && self.stack.is_empty() => {
+                return Ok(JsonObject::Number(number_value(&text)));
+            }
+            State::Keyword { .. } => JsonErrorKind::UnexpectedKeyword,
+            State::String { .. } => JsonErrorKind::UnterminatedString,
+            _ => match self.stack.last() {
+                Some(Container::Array(_)) => JsonErrorKind::UnclosedArray {
+                    open: self.stack.len(),
+                },
+                Some(Container::Object(..)) => JsonErrorKind::UnclosedObject {
+                    open: self.stack.len(),
+                },
+                None => JsonErrorKind::EarlyEndOfStream,
+            },
+        };
+
+        Err(self.located.error(kind))
+    }
+
+    fn fail(&mut self, kind: JsonErrorKind) -> JsonError {
+        let error = self.located.error(kind);
+        self.error = Some(error.clone());
+        error
+    }
+
+    //`Some` with the document and whether `ch` is part of it once it is complete
+    fn push(&mut self, ch: char) -> Result<Option<(JsonObject, bool)>, JsonErrorKind> {
+        let value = match &mut self.state {
+            State::Number { text, part } => match part.next(ch) {
+                Some(next) => {
+                    text.push(ch);
+                    *part = next;
+                    return Ok(None);
+                }
+                None if *part == NumberPart::Zero && ch.is_ascii_digit() => {
+                    return Err(JsonErrorKind::LeadingZero)
+                }
+                None if part.is_complete() => {
+                    //`ch` comes after the number, and is read again once it is in place
+                    let value = JsonObject::Number(number_value(text));
+                    return match self.complete(value) {
+                        Some(document) => Ok(Some((document, false))),
+                        None => self.push(ch),
+                    };
+                }
+                None => return Err(unexpected(ch, Expected::Digit)),
+            },
+            State::Keyword { rest, value } => match rest.next() {
+                Some(expected) if expected == ch => {
+                    if !rest.as_str().is_empty() {
+                        return Ok(None);
+                    }
+                    std::mem::replace(value, JsonObject::Null)
+                }
+                _ => return Err(JsonErrorKind::UnexpectedKeyword),
+            },
+            State::String { raw, escaped, key } => {
+                let closing = match ch {
+                    _ if *escaped => {
+                        *escaped = false;
+                        false
+                    }
+                    '\\' => {
+                        *escaped = true;
+                        false
+                    }
+                    '"' => true,
+                    ch if ch < '\u{20}' => {
+                        return Err(JsonErrorKind::UnescapedControlCharacter(ch))
+                    }
+                    _ => false,
+                };
+
+                if !closing {
+                    raw.push(ch);
+                    return Ok(None);
+                }
+
+                let string = parse_string_impl(&mut Plain(raw.chars().chain(once('"'))))?;
+
+                if !*key {
+                    JsonObject::String(string)
+                } else {
+                    if let Some(Container::Object(_, key)) = self.stack.last_mut() {
+                        *key = string;
+                    }
+                    self.state = State::Colon;
+                    return Ok(None);
+                }
+            }
+            _ if ch.is_whitespace() => return Ok(None),
+            State::Value { could_close } => match ch {
+                ']' if *could_close => self.close(),
+                ',' | '}' if matches!(self.stack.last(), Some(Container::Object(..))) => {
+                    return Err(JsonErrorKind::MissingValue)
+                }
+                '[' | '{' => {
+                    check_depth(self.stack.len(), &ParseOptions::default())?;
+
+                    if ch == '[' {
+                        self.stack.push(Container::Array(vec![]));
+                        self.state = State::Value { could_close: true };
+                    } else {
+                        self.stack.push(Container::Object(vec![], String::new()));
+                        self.state = State::Key { could_close: true };
+                    }
+                    return Ok(None);
+                }
+                _ => {
+                    self.state = match ch {
+                        '"' => State::String {
+                            raw: String::new(),
+                            escaped: false,
+                            key: false,
+                        },
+                        'n' => keyword("ull", JsonObject::Null),
+                        't' => keyword("rue", JsonObject::Boolean(true)),
+                        'f' => keyword("alse", JsonObject::Boolean(false)),
+                        '-' => number(ch, NumberPart::Minus),
+                        '0' => number(ch, NumberPart::Zero),
+                        '1'..='9' => number(ch, NumberPart::Integer),
+                        ch => return Err(unexpected(ch, Expected::Value)),
+                    };
+                    return Ok(None);
+                }
+            },
+            State::Key { could_close } => match ch {
+                '}' if *could_close => self.close(),
+                '"' => {
+                    self.state = State::String {
+                        raw: String::new(),
+                        escaped: false,
+                        key: true,
+                    };
+                    return Ok(None);
+                }
+                ch if *could_close => return Err(unexpected(ch, Expected::ObjectKeyOrEnd)),
+                ch => return Err(unexpected(ch, Expected::ObjectKey)),
+            },
+            State::Colon => match ch {
+                ':' => {
+                    self.state = State::Value { could_close: false };
+                    return Ok(None);
+                }
+                _ => return Err(JsonErrorKind::MissingColon),
+            },
+            State::AfterValue => match (self.stack.last(), ch) {
+                (Some(Container::Array(_)), ',') => {
+                    self.state = State::Value { could_close: false };
+                    return Ok(None);
+                }
+                (Some(Container::Object(..)), ',') => {
+                    self.state = State::Key { could_close: false };
+                    return Ok(None);
+                }
+                (Some(Container::Array(_)), ']') | (Some(Container::Object(..)), '}') => {
+                    self.close()
+                }
+                (Some(Container::Array(_)), ch) => {
+                    return Err(unexpected(ch, Expected::CommaOrArrayEnd))
+                }
+                (_, ch) => return Err(unexpected(ch, Expected::CommaOrObjectEnd)),
+            },
+        };
+
+        Ok(self.complete(value).map(|document| (document, true)))
+    }
+
+    //the innermost container, which has just been closed
+    fn close(&mut self) -> JsonObject {
+        match self.stack.pop() {
+            Some(Container::Array(array)) => JsonObject::Array(array),
+            Some(Container::Object(entries, _)) => JsonObject::Object(Object::from_impl(entries)),
+            None => unreachable!(),
+        }
+    }
+
+    //hands a finished value to its container, `Some` if it is the whole document
+    fn complete(&mut self, value: JsonObject) -> Option<JsonObject> {
+        self.state = State::AfterValue;
+
+        match self.stack.last_mut() {
+            Some(Container::Array(array)) => array.push(value),
+            Some(Container::Object(entries, key)) => {
+                entries.push((std::mem::take(key).into(), value))
+            }
+            None => return Some(value),
+        }
+
+        None
+    }
+}
+
+fn keyword(rest: &'static str, value: JsonObject) -> State {
+    State::Keyword {
+        rest: rest.chars(),
+        value,
+    }
+}
+
+fn number(first: char, part: NumberPart) -> State {
+    State::Number {
+        text: first.to_string(),
+        part,
+    }
+}
+
+//`text` is a complete number, computed the same way as by the other parsers
+pub(crate) fn number_value(text: &str) -> f64 {
+    let mut chars = text.chars();
+    let first = chars.next().unwrap();
+
+    parse_number_impl(&mut Cursor::new(&mut chars), first).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extra_chars, json, parse_json_string};
+    use proptest::prelude::*;
+
+    const DOCUMENTS: &[&str] = &[
+        r#"{ "a" : [ 1, -2.5e-3, 0, 0.125, 1E+2 ], "b" : { "c" : null, "d" : [ true, false ] } }"#,
+        r#"[ "esc\"aped\\", "\u00e9\ud83d\ude00", "\n\t\/", "", "naïve 😀" ]"#,
+        "[[[[]]],{},[{}],{\"\":{}}]",
+        " \n\t 12.5e1 \r\n",
+        "0",
+        "-0.0",
+        "\"top\"",
+        "true",
+        "null ",
+        "{\"k\" : 1, \"k\" : 2}",
+    ];
+
+    const INVALID: &[&str] = &[
+        "",
+        " ",
+        "[",
+        "[1,]",
+        "[1 2]",
+        "{\"a\" 1}",
+        "{,}",
+        "{\"a\":1,}",
+        "tru",
+        "trve",
+        "nul",
+        "\"abc",
+        "\"a\\x\"",
+        "\"\\u12g4\"",
+        "\"a\u{1}\"",
+        "-",
+        "-x",
+        "1.",
+        "1.e3",
+        "1e",
+        "1e+",
+        "[01]",
+        "01",
+        "-00.5",
+        "{\"a\":09}",
+        "[1] 2",
+        "{} }",
+        "[\"a\" \"b\"]",
+    ];
+
+    //between 1 and 7 chars, taken in turn
+    fn chunk_sizes() -> impl Strategy<Value = Vec<usize>> {
+        prop::collection::vec(1..8usize, 1..16)
+    }
+
+    fn cycle(sizes: Vec<usize>) -> impl FnMut() -> usize {
+        let mut sizes = sizes.into_iter().cycle();
+        move || sizes.next().unwrap()
+    }
+
+    fn large_document() -> String {
+        format!(
+            "[{}]",
+            (0..500)
+                .map(|i| format!(
+                    r#"{{ "id" : {}, "name" : "n\u00e9{}", "ok" : {} }}"#,
+                    i,
+                    i,
+                    i % 2 == 0
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    //feeds `input` split after every `size()` chars, anything but whitespace after the document
+    //is an error like for `parse_json_string`
+    fn feed_in_chunks(
+        input: &str,
+        mut size: impl FnMut() -> usize,
+    ) -> Result<JsonObject, JsonErrorKind> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut parser = StreamingParser::new();
+        let mut start = 0;
+
+        while start < chars.len() {
+            let end = (start + size()).min(chars.len());
+            let chunk: String = chars[start..end].iter().collect();
+
+            if let Progress::Complete { value, consumed } =
+                parser.feed(&chunk).map_err(|e| e.kind)?
+            {
+                let rest: String = chunk[consumed..]
+                    .chars()
+                    .chain(chars[end..].iter().copied())
+                    .collect();
+                return match rest.trim_start() {
+                    "" => Ok(value),
+                    extra => Err(extra_chars(extra.chars())),
+                };
+            }
+
+            start = end;
+        }
+
+        parser.finish().map_err(|e| e.kind)
+    }
+
+    #[test]
+    fn same_values_in_any_chunks() -> Result<(), JsonError> {
+        let large = large_document();
+
+        for input in DOCUMENTS.iter().copied().chain([large.as_str()]) {
+            let expected = parse_json_string(input)?;
+
+            assert_eq!(
+                feed_in_chunks(input, || 1).as_ref(),
+                Ok(&expected),
+                "{}",
+                input
+            );
+            assert_eq!(feed_in_chunks(input, || usize::MAX).as_ref(), Ok(&expected));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_errors_in_any_chunks() {
+        for input in INVALID {
+            let expected = parse_json_string(input).map_err(|e| e.kind);
+            assert!(expected.is_err(), "{}", input);
+
+            assert_eq!(feed_in_chunks(input, || 1), expected, "{}", input);
+        }
+
+        let deep = "[".repeat(ParseOptions::DEFAULT_MAX_DEPTH + 1);
+        assert_eq!(
+            feed_in_chunks(&deep, || 10),
+            Err(JsonErrorKind::RecursionLimitExceeded)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn same_values_in_random_chunks(sizes in chunk_sizes()) {
+            let large = large_document();
+
+            for input in DOCUMENTS.iter().copied().chain([large.as_str()]) {
+                let expected = parse_json_string(input).unwrap();
+                prop_assert_eq!(feed_in_chunks(input, cycle(sizes.clone())), Ok(expected));
+            }
+        }
+
+        #[test]
+        fn same_errors_in_random_chunks(sizes in chunk_sizes()) {
+            for input in INVALID {
+                let expected = parse_json_string(input).map_err(|e| e.kind);
+                prop_assert_eq!(feed_in_chunks(input, cycle(sizes.clone())), expected, "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn split_tokens() -> Result<(), JsonError> {
+        let mut parser = StreamingParser::new();
+        assert_eq!(parser.feed("[ tr")?, Progress::NeedMore);
+        assert_eq!(parser.feed("u")?, Progress::NeedMore);
+        assert_eq!(parser.feed("e, \"caf\\u00")?, Progress::NeedMore);
+        assert_eq!(parser.feed("e9 \\")?, Progress::NeedMore);
+        assert_eq!(parser.feed("\"\\ud83d\\u")?, Progress::NeedMore);
+        assert_eq!(parser.feed("de00\", 1")?, Progress::NeedMore);
+        assert_eq!(parser.feed("2")?, Progress::NeedMore);
+        assert_eq!(
+            parser.feed("]\n")?,
+            Progress::Complete {
+                value: json!([true, "caf\u{e9} \"\u{1f600}", 12]),
+                consumed: 1
+            }
+        );
+
+        //a number is only known to be complete when something else follows, or nothing does
+        let mut parser = StreamingParser::new();
+        assert_eq!(parser.feed("-12")?, Progress::NeedMore);
+        assert_eq!(parser.feed(".5e")?, Progress::NeedMore);
+        assert_eq!(parser.feed("1")?, Progress::NeedMore);
+        assert_eq!(parser.finish()?, json!(-125));
+
+        let mut parser = StreamingParser::new();
+        assert_eq!(parser.feed("42")?, Progress::NeedMore);
+        assert_eq!(
+            parser.feed(" [")?,
+            Progress::Complete {
+                value: json!(42),
+                consumed: 0
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn several_documents() -> Result<(), JsonError> {
+        let mut parser = StreamingParser::new();
+        let mut chunk = "{ \"é\" : 1 }[2]  \"three\"\n4 ";
+        let mut values = vec![];
+
+        while let Progress::Complete { value, consumed } = parser.feed(chunk)? {
+            values.push(value);
+            chunk = &chunk[consumed..];
+        }
+
+        assert_eq!(
+            values,
+            [json!({ "é": 1 }), json!([2]), json!("three"), json!(4)]
+        );
+        assert_eq!(chunk, " ");
+
+        //positions carry on across documents and chunks
+        let mut parser = StreamingParser::new();
+        let chunk = "[1]\n[2,";
+        assert_eq!(
+            parser.feed(chunk)?,
+            Progress::Complete {
+                value: json!([1]),
+                consumed: 3
+            }
+        );
+        assert_eq!(parser.feed(&chunk[3..])?, Progress::NeedMore);
+        let error = parser.feed("\n  x]").unwrap_err();
+        assert_eq!(error.kind, unexpected('x', Expected::Value));
+        assert_eq!(error.position, crate::Position { line: 3, column: 3 });
+
+        //and errors stick
+        assert_eq!(parser.feed("1]"), Err(error.clone()));
+        assert_eq!(parser.finish(), Err(error));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use std::io::{self, Read};
+
+use crate::stream::NdjsonStream;
+use crate::{parse_json_from_iter, parse_ndjson_from_iter, JsonError, JsonObject};
+
+/// Errors of [`parse_json_from_reader`], kept apart from [`JsonError`] which stays comparable.
+#[derive(Debug)]
+pub enum ReaderError {
+    Json(JsonError),
+    /// Malformed or truncated UTF-8 starting at this byte offset of the input.
+    InvalidUtf8 {
+        offset: usize,
+    },
+    Io(io::Error),
+}
+
+impl std::fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReaderError::Json(err) => err.fmt(f),
+            ReaderError::InvalidUtf8 { offset } => write!(f, "invalid UTF-8 at byte {}", offset),
+            ReaderError::Io(err) => write!(f, "read error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReaderError::Json(err) => Some(err),
+            ReaderError::InvalidUtf8 { .. } => None,
+            ReaderError::Io(err) => Some(err),
+        }
+    }
+}
+
+/// Parses from a reader with buffered UTF-8 decoding, so the input never has to be held as a
+/// whole `String`. A read error or invalid UTF-8 takes precedence over the parse error it caused.
+pub fn parse_json_from_reader<R: Read>(reader: R) -> Result<JsonObject, ReaderError> {
+    let mut chars = ReadChars::new(reader);
+    let result = parse_json_from_iter(&mut chars);
+
+    match chars.take_error() {
+        Some(error) => Err(error.into()),
+        None => result.map_err(ReaderError::Json),
+    }
+}
+
+impl JsonObject {
+    /// See [`parse_json_from_reader`].
+    #[inline]
+    pub fn from_reader<R: Read>(reader: R) -> Result<JsonObject, ReaderError> {
+        parse_json_from_reader(reader)
+    }
+}
+
+/// Iterator over newline-delimited JSON read from a reader, see [`parse_ndjson_from_reader`].
+pub struct NdjsonReader<R> {
+    stream: NdjsonStream<ReadChars<R>>,
+    failed: bool,
+}
+
+/// [`parse_ndjson`](crate::parse_ndjson) with buffered UTF-8 decoding from a reader. A read
+/// error or invalid UTF-8 is yielded in place of the line it interrupted and ends the iteration.
+pub fn parse_ndjson_from_reader<R: Read>(reader: R) -> NdjsonReader<R> {
+    NdjsonReader {
+        stream: parse_ndjson_from_iter(ReadChars::new(reader)),
+        failed: false,
+    }
+}
+
+impl<R: Read> Iterator for NdjsonReader<R> {
+    type Item = Result<JsonObject, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let result = self.stream.next();
+
+        match self.stream.get_mut().take_error() {
+            Some(error) => {
+                self.failed = true;
+                Some(Err(error.into()))
+            }
+            None => result.map(|result| result.map_err(ReaderError::Json)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ReadError {
+    Io(io::Error),
+    InvalidUtf8 { offset: usize },
+}
+
+impl From<ReadError> for ReaderError {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Io(err) => ReaderError::Io(err),
+            ReadError::InvalidUtf8 { offset } => ReaderError::InvalidUtf8 { offset },
+        }
+    }
+}
+
+//decodes utf-8 from a reader one char at a time, stopping at the first error which is kept
+//aside so that the parser sees a plain end of stream and the caller can report the real cause
+pub(crate) struct ReadChars<R> {
+    reader: R,
+    buffer: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    //bytes taken from the reader so far
+    offset: usize,
+    error: Option<ReadError>,
+}
+
+impl<R: Read> ReadChars<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self::with_capacity(reader, 8 * 1024)
+    }
+
+    pub(crate) fn with_capacity(reader: R, capacity: usize) -> Self {
+        ReadChars {
+            reader,
+            buffer: vec![0; capacity.max(1)].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            offset: 0,
+            error: None,
+        }
+    }
+
+    pub(crate) fn take_error(&mut self) -> Option<ReadError> {
+        self.error.take()
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        if self.pos == self.len {
+            loop {
+                match self.reader.read(&mut self.buffer) {
+                    Ok(0) => return None,
+                    Ok(len) => {
+                        self.pos = 0;
+                        self.len = len;
+                        break;
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => {
+                        self.error = Some(ReadError::Io(err));
+                        return None;
+                    }
+                }
+            }
+        }
+
+        let byte = self.buffer[self.pos];
+        self.pos += 1;
+        self.offset += 1;
+        Some(byte)
+    }
+}
+
+impl<R: Read> Iterator for ReadChars<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        let offset = self.offset;
+        let first = self.next_byte()?;
+
+        let width = match first {
+            0x00..=0x7F => return Some(first as char),
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => {
+                self.error = Some(ReadError::InvalidUtf8 { offset });
+                return None;
+            }
+        };
+
+        let mut bytes = [first, 0, 0, 0];
+
+        for byte in bytes.iter_mut().take(width).skip(1) {
+            match self.next_byte() {
+                Some(next) => *byte = next,
+                None => {
+                    //truncated sequence, unless reading itself failed
+                    self.error.get_or_insert(ReadError::InvalidUtf8 { offset });
+                    return None;
+                }
+            }
+        }
+
+        match std::str::from_utf8(&bytes[..width]) {
+            Ok(s) => s.chars().next(),
+            Err(_) => {
+                self.error = Some(ReadError::InvalidUtf8 { offset });
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Expected, JsonErrorKind};
+
+    #[test]
+    fn multi_byte_chars_across_buffer_boundaries() {
+        let text = "a€😐é b";
+
+        for capacity in 1..8 {
+            let chars = ReadChars::with_capacity(text.as_bytes(), capacity);
+
+            assert_eq!(chars.collect::<String>(), text);
+        }
+    }
+
+    //hands out a single byte per read
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((&byte, rest)), Some(slot)) => {
+                    *slot = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_from_reader() -> Result<(), Box<dyn std::error::Error>> {
+        let text = r#"{ "a€" : [ "😐é", 1.5, null ] }"#;
+        let expected = crate::parse_json_string(text)?;
+
+        assert_eq!(
+            parse_json_from_reader(io::Cursor::new(text.as_bytes()))?,
+            expected
+        );
+        assert_eq!(parse_json_from_reader(Trickle(text.as_bytes()))?, expected);
+        assert_eq!(JsonObject::from_reader(text.as_bytes())?, expected);
+
+        assert!(matches!(
+            parse_json_from_reader(&b"[ 1, ]"[..]),
+            Err(ReaderError::Json(JsonError {
+                kind: JsonErrorKind::UnexpectedChar {
+                    found: ']',
+                    expected: Expected::Value
+                },
+                ..
+            }))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reader_errors() {
+        //the parser only sees the input end early, the cause is reported instead
+        assert!(matches!(
+            parse_json_from_reader(&b"[ \"a\xC3( \" ]"[..]),
+            Err(ReaderError::InvalidUtf8 { offset: 4 })
+        ));
+        //after a complete value too
+        assert!(matches!(
+            parse_json_from_reader(&b"[] \xFF"[..]),
+            Err(ReaderError::InvalidUtf8 { offset: 3 })
+        ));
+        assert_eq!(
+            parse_json_from_reader(&b"[] \xFF"[..])
+                .unwrap_err()
+                .to_string(),
+            "invalid UTF-8 at byte 3"
+        );
+
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk on fire"))
+            }
+        }
+
+        match parse_json_from_reader(io::Read::chain(&b"[ 1"[..], Failing)) {
+            Err(ReaderError::Io(err)) => assert_eq!(err.to_string(), "disk on fire"),
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(
+            parse_json_from_reader(Failing).unwrap_err().to_string(),
+            "read error: disk on fire"
+        );
+    }
+
+    #[test]
+    fn ndjson_from_reader() -> Result<(), Box<dyn std::error::Error>> {
+        let text = "{ \"a€\" : 1 }\n\n[ \"😐\" ]\n";
+        let values =
+            parse_ndjson_from_reader(Trickle(text.as_bytes())).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(values, [crate::json!({ "a€": 1 }), crate::json!(["😐"])]);
+
+        //parse errors are per line, decoding errors end the iteration
+        let mut lines = parse_ndjson_from_reader(&b"[ 1, ]\n[ 2 ]\n[ \"\xFF\" ]\n[ 3 ]"[..]);
+        assert!(matches!(lines.next(), Some(Err(ReaderError::Json(_)))));
+        assert!(matches!(lines.next(), Some(Ok(_))));
+        assert!(matches!(
+            lines.next(),
+            Some(Err(ReaderError::InvalidUtf8 { offset: 16 }))
+        ));
+        assert!(lines.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_utf8_is_kept_aside() {
+        let mut chars = ReadChars::new(&b"ab\xC3("[..]);
+
+        assert_eq!(chars.by_ref().collect::<String>(), "ab");
+        assert!(matches!(
+            chars.take_error(),
+            Some(ReadError::InvalidUtf8 { offset: 2 })
+        ));
+
+        let mut chars = ReadChars::new(&b"ab\xF0\x9F"[..]);
+
+        assert_eq!(chars.by_ref().collect::<String>(), "ab");
+        assert!(matches!(
+            chars.take_error(),
+            Some(ReadError::InvalidUtf8 { offset: 2 })
+        ));
+    }
+}
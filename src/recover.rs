@@ -0,0 +1,579 @@
+use crate::position::Located;
+use crate::push::{number_value, NumberPart};
+use crate::{
+    check_depth, entry_value, extra_chars, parse_escape_character_impl, unclosed, unexpected,
+    Container, Expected, JsonError, JsonErrorKind, JsonObject, Key, Object, ObjectImpl,
+    ParseOptions,
+};
+
+/// Every error in `input` rather than only the first, empty for a valid document. See
+/// [`parse_json_recovering`] for how parsing goes on after an error.
+#[inline]
+pub fn validate_json(input: &str) -> Vec<JsonError> {
+    parse_json_recovering(input).1
+}
+
+/// Parses `input` with the default [`ParseOptions`] without stopping at errors, returning what
+/// could be made of the document along with every error found.
+///
+/// After an error the parser skips to the next `,`, `]` or `}` of the container it is in and
+/// carries on from there, leaving the broken element or entry out of the value. A value where a
+/// comma belongs counts as a missing comma, a line break inside a string as its missing closing
+/// quote, and containers left open at the end of the input as a single error. The first error
+/// is always the one [`parse_json_string`](crate::parse_json_string) fails with, later ones can
+/// be consequences of how an earlier one was recovered from. The value is `None` when there is
+/// none at the top level at all.
+pub fn parse_json_recovering(input: &str) -> (Option<JsonObject>, Vec<JsonError>) {
+    let mut recovery = Recovery {
+        chars: input.chars().collect(),
+        i: 0,
+        open: Vec::new(),
+        errors: Vec::new(),
+        stopped: false,
+    };
+
+    let value = recovery.value(0);
+
+    if recovery.peek_non_whitespace().is_some() {
+        let extra = extra_chars(recovery.chars[recovery.i..].iter().copied());
+        recovery.error_at(recovery.i, extra);
+    }
+
+    (value, recovery.into_errors())
+}
+
+struct Recovery {
+    chars: Vec<char>,
+    //the next char to read
+    i: usize,
+    //the containers being read, innermost last
+    open: Vec<Container>,
+    //with the index of the char they were noticed at, turned into positions at the end
+    errors: Vec<(usize, JsonErrorKind)>,
+    //nothing more is reported once the input cannot be followed anymore
+    stopped: bool,
+}
+
+impl Recovery {
+    fn value(&mut self, depth: usize) -> Option<JsonObject> {
+        let ch = match self.peek_non_whitespace() {
+            Some(ch) => ch,
+            None => {
+                self.end_of_input();
+                return None;
+            }
+        };
+
+        match ch {
+            '[' => {
+                self.i += 1;
+                self.open.push(Container::Array);
+                let array = self.array(depth);
+                self.open.pop();
+                array
+            }
+            '{' => {
+                self.i += 1;
+                self.open.push(Container::Object);
+                let object = self.object(depth);
+                self.open.pop();
+                object
+            }
+            '"' => {
+                self.i += 1;
+                Some(JsonObject::String(self.string()))
+            }
+            '-' if self.chars.get(self.i + 1) == Some(&'I') => {
+                self.i += 1;
+                self.non_finite("Infinity")
+            }
+            '-' | '0'..='9' => self.number(),
+            'N' => self.non_finite("NaN"),
+            'I' => self.non_finite("Infinity"),
+            'n' => self.keyword("null", JsonObject::Null),
+            't' => self.keyword("true", JsonObject::Boolean(true)),
+            'f' => self.keyword("false", JsonObject::Boolean(false)),
+            //left for the container, which resumes there
+            ',' | ']' | '}' => {
+                self.error_at(self.i, unexpected(ch, Expected::Value));
+                None
+            }
+            //`undefined` or any other bare word is skipped as a whole
+            _ => {
+                self.error_at(self.i, unexpected(ch, Expected::Value));
+                self.skip_to_sync();
+                None
+            }
+        }
+    }
+
+    //after the '['
+    fn array(&mut self, depth: usize) -> Option<JsonObject> {
+        if !self.check_depth(depth) {
+            return None;
+        }
+
+        let mut array = Vec::new();
+
+        if self.peek_non_whitespace() == Some(']') {
+            self.i += 1;
+            return Some(JsonObject::Array(array));
+        }
+
+        loop {
+            array.extend(self.value(depth + 1));
+
+            //what follows the element, possibly after skipping what cannot be read
+            loop {
+                match self.peek_non_whitespace() {
+                    Some(',') => {
+                        self.i += 1;
+                        if self.peek_non_whitespace() == Some(']') {
+                            self.error_at(self.i, unexpected(']', Expected::Value));
+                            self.i += 1;
+                            return Some(JsonObject::Array(array));
+                        }
+                        break;
+                    }
+                    Some(']') => {
+                        self.i += 1;
+                        return Some(JsonObject::Array(array));
+                    }
+                    None => {
+                        self.end_of_input();
+                        return Some(JsonObject::Array(array));
+                    }
+                    //most likely closing an object around the array, whose ']' is missing
+                    Some(ch @ '}') => {
+                        self.error_at(self.i, unexpected(ch, Expected::CommaOrArrayEnd));
+                        return Some(JsonObject::Array(array));
+                    }
+                    //a missing comma, the next element starts here
+                    Some(ch) if starts_value(ch) => {
+                        self.error_at(self.i, unexpected(ch, Expected::CommaOrArrayEnd));
+                        break;
+                    }
+                    Some(ch) => {
+                        self.error_at(self.i, unexpected(ch, Expected::CommaOrArrayEnd));
+                        self.skip_to_sync();
+                    }
+                }
+            }
+        }
+    }
+
+    //after the '{'
+    fn object(&mut self, depth: usize) -> Option<JsonObject> {
+        if !self.check_depth(depth) {
+            return None;
+        }
+
+        let mut entries = ObjectImpl::new();
+        let mut expected = Expected::ObjectKeyOrEnd;
+
+        if self.peek_non_whitespace() == Some('}') {
+            self.i += 1;
+            return Some(JsonObject::Object(Object::from_impl(entries)));
+        }
+
+        loop {
+            if let Some(entry) = self.entry(depth, expected) {
+                entries.push(entry);
+            }
+            expected = Expected::ObjectKey;
+
+            loop {
+                match self.peek_non_whitespace() {
+                    Some(',') => {
+                        self.i += 1;
+                        break;
+                    }
+                    Some('}') => {
+                        self.i += 1;
+                        return Some(JsonObject::Object(Object::from_impl(entries)));
+                    }
+                    None => {
+                        self.end_of_input();
+                        return Some(JsonObject::Object(Object::from_impl(entries)));
+                    }
+                    //most likely closing an array around the object, whose '}' is missing
+                    Some(ch @ ']') => {
+                        self.error_at(self.i, unexpected(ch, Expected::CommaOrObjectEnd));
+                        return Some(JsonObject::Object(Object::from_impl(entries)));
+                    }
+                    Some(ch @ '"') => {
+                        self.error_at(self.i, unexpected(ch, Expected::CommaOrObjectEnd));
+                        break;
+                    }
+                    Some(ch) => {
+                        self.error_at(self.i, unexpected(ch, Expected::CommaOrObjectEnd));
+                        self.skip_to_sync();
+                    }
+                }
+            }
+        }
+    }
+
+    //`None` when the entry is broken, the input is then at what follows it
+    fn entry(&mut self, depth: usize, expected: Expected) -> Option<(Key, JsonObject)> {
+        let key = match self.peek_non_whitespace() {
+            Some('"') => {
+                self.i += 1;
+                self.string()
+            }
+            Some(ch) => {
+                self.error_at(self.i, unexpected(ch, expected));
+                self.skip_to_sync();
+                return None;
+            }
+            None => return None,
+        };
+
+        match self.peek_non_whitespace() {
+            Some(':') => {
+                self.i += 1;
+                //left for the object, which resumes there
+                if let Some(Err(kind)) = self.peek_non_whitespace().map(entry_value) {
+                    self.error_at(self.i, kind);
+                    return None;
+                }
+            }
+            //a missing colon, the value starts here
+            Some(ch) if starts_value(ch) => self.error_at(self.i, JsonErrorKind::MissingColon),
+            Some(_) => {
+                self.error_at(self.i, JsonErrorKind::MissingColon);
+                self.skip_to_sync();
+                return None;
+            }
+            None => return None,
+        }
+
+        Some((key.into(), self.value(depth + 1)?))
+    }
+
+    //after the opening '"', what could be read of the string even when it is broken
+    fn string(&mut self) -> String {
+        let mut string = String::new();
+
+        loop {
+            let ch = match self.chars.get(self.i) {
+                Some(&ch) => ch,
+                None => {
+                    self.error_at_end(JsonErrorKind::UnterminatedString);
+                    return string;
+                }
+            };
+            self.i += 1;
+
+            match ch {
+                '"' => return string,
+                '\\' => {
+                    let mut rest = self.chars[self.i..].iter().copied();
+                    let result = parse_escape_character_impl(&mut rest);
+                    self.i = self.chars.len() - rest.len();
+
+                    match result {
+                        Ok(ch) => string.push(ch),
+                        Err(kind @ JsonErrorKind::UnterminatedString) => {
+                            self.error_at_end(kind);
+                            return string;
+                        }
+                        Err(kind) => self.error_at(self.i - 1, kind),
+                    }
+                }
+                //rather than running to the next quote, a string is assumed to end with its line
+                '\n' => {
+                    self.error_at(self.i - 1, JsonErrorKind::UnescapedControlCharacter(ch));
+                    return string;
+                }
+                ch if ch < '\u{20}' => {
+                    self.error_at(self.i - 1, JsonErrorKind::UnescapedControlCharacter(ch))
+                }
+                ch => string.push(ch),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<JsonObject> {
+        let start = self.i;
+        let mut part = match self.chars[start] {
+            '-' => NumberPart::Minus,
+            '0' => NumberPart::Zero,
+            _ => NumberPart::Integer,
+        };
+        self.i += 1;
+
+        while let Some(next) = self.chars.get(self.i).and_then(|&ch| part.next(ch)) {
+            part = next;
+            self.i += 1;
+        }
+
+        let leading_zero =
+            part == NumberPart::Zero && self.chars.get(self.i).is_some_and(char::is_ascii_digit);
+        if part.is_complete() && !leading_zero {
+            let text: String = self.chars[start..self.i].iter().collect();
+            return Some(JsonObject::Number(number_value(&text)));
+        }
+
+        match self.chars.get(self.i) {
+            Some(_) if leading_zero => self.error_at(self.i, JsonErrorKind::LeadingZero),
+            Some(&ch) => self.error_at(self.i, unexpected(ch, Expected::Digit)),
+            None => self.end_of_input(),
+        }
+        self.skip_to_sync();
+
+        None
+    }
+
+    //reports the first char that differs from `word`, like the other parsers
+    fn keyword(&mut self, word: &str, value: JsonObject) -> Option<JsonObject> {
+        let start = self.i;
+
+        for (offset, expected) in word.chars().enumerate() {
+            match self.chars.get(start + offset) {
+                Some(&ch) if ch == expected => {}
+                Some(_) => {
+                    self.error_at(start + offset, JsonErrorKind::UnexpectedKeyword);
+                    self.skip_to_sync();
+                    return None;
+                }
+                None => {
+                    self.error_at(self.chars.len() - 1, JsonErrorKind::UnexpectedKeyword);
+                    self.i = self.chars.len();
+                    return None;
+                }
+            }
+        }
+
+        self.i += word.len();
+        Some(value)
+    }
+
+    //read whole and dropped, misspellings are reported like other keywords
+    fn non_finite(&mut self, word: &str) -> Option<JsonObject> {
+        self.keyword(word, JsonObject::Null)?;
+        self.error_at(self.i - 1, JsonErrorKind::NonFiniteNumber);
+        None
+    }
+
+    //containers in a document nested too deeply are not followed any further
+    fn check_depth(&mut self, depth: usize) -> bool {
+        match check_depth(depth, &ParseOptions::default()) {
+            Ok(()) => true,
+            Err(kind) => {
+                self.error_at(self.i - 1, kind);
+                self.stopped = true;
+                self.i = self.chars.len();
+                false
+            }
+        }
+    }
+
+    fn peek_non_whitespace(&mut self) -> Option<char> {
+        while let Some(&ch) = self.chars.get(self.i) {
+            if !ch.is_whitespace() {
+                return Some(ch);
+            }
+            self.i += 1;
+        }
+
+        None
+    }
+
+    //to the next ',', ']' or '}' outside of the strings and containers skipped on the way
+    fn skip_to_sync(&mut self) {
+        let mut depth = 0_usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        while let Some(&ch) = self.chars.get(self.i) {
+            if in_string {
+                match ch {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' | '\n' => in_string = false,
+                    _ => {}
+                }
+            } else {
+                match ch {
+                    ',' | ']' | '}' if depth == 0 => return,
+                    '[' | '{' => depth += 1,
+                    ']' | '}' => depth -= 1,
+                    '"' => in_string = true,
+                    _ => {}
+                }
+            }
+            self.i += 1;
+        }
+    }
+
+    //every open container runs into the end, it is reported once for the innermost one
+    fn end_of_input(&mut self) {
+        let kind = match self.open.last() {
+            Some(&container) => unclosed(
+                JsonErrorKind::EarlyEndOfStream,
+                container,
+                self.open.len() - 1,
+            ),
+            None => JsonErrorKind::EarlyEndOfStream,
+        };
+        self.error_at_end(kind);
+    }
+
+    fn error_at_end(&mut self, kind: JsonErrorKind) {
+        self.error_at(self.chars.len().saturating_sub(1), kind);
+    }
+
+    //one error per char, so the consequences of an error noticed twice are not reported again
+    fn error_at(&mut self, index: usize, kind: JsonErrorKind) {
+        if self.stopped || self.errors.last().is_some_and(|(last, _)| *last == index) {
+            return;
+        }
+
+        self.errors.push((index, kind));
+    }
+
+    fn into_errors(self) -> Vec<JsonError> {
+        //the input is only gone through once, errors are recorded in order
+        let mut located = Located::new(self.chars.iter().copied());
+        let mut read = 0;
+
+        self.errors
+            .into_iter()
+            .map(|(index, kind)| {
+                //the positions of the empty input and of the char ending it are the same
+                while read <= index && located.next().is_some() {
+                    read += 1;
+                }
+                located.error(kind)
+            })
+            .collect()
+    }
+}
+
+fn starts_value(ch: char) -> bool {
+    matches!(ch, '[' | '{' | '"' | '-' | '0'..='9' | 'n' | 't' | 'f')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, parse_json_string, Position};
+
+    fn errors(input: &str) -> Vec<(usize, usize, String)> {
+        validate_json(input)
+            .into_iter()
+            .map(|error| {
+                (
+                    error.position.line,
+                    error.position.column,
+                    error.kind.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn several_errors() {
+        let input = "{\n  \"a\" : [ 1 2 ],\n  \"b\" : NaN,\n  \"c\" : { \"d\" : true\n";
+
+        assert_eq!(
+            errors(input),
+            [
+                (2, 13, "expected ',' or ']' but found '2'".to_owned()),
+                (3, 11, "NaN and infinities are not valid JSON".to_owned()),
+                (
+                    4,
+                    21,
+                    "the input ends inside an object, its closing '}' is missing (2 containers are left open)"
+                        .to_owned()
+                ),
+            ]
+        );
+
+        let (value, _) = parse_json_recovering(input);
+        assert_eq!(value, Some(json!({ "a": [1, 2], "c": { "d": true } })));
+    }
+
+    #[test]
+    fn valid_documents() {
+        for input in [
+            r#"{ "a" : [ 1, -2.5e-3, 0 ], "b" : { "c" : null, "d" : [ true, false ] } }"#,
+            r#"[ "esc\"aped\\", "é😀", "" ]"#,
+            "[[[[]]],{},[{}],{\"\":{}}]",
+            " 12.5e1 ",
+            "0",
+        ] {
+            assert_eq!(validate_json(input), [], "{}", input);
+            assert_eq!(
+                parse_json_recovering(input).0.as_ref(),
+                parse_json_string(input).as_ref().ok()
+            );
+        }
+    }
+
+    #[test]
+    fn first_error_is_the_parsers() {
+        for input in [
+            "",
+            "   ",
+            "[",
+            "[1,]",
+            "[1 2]",
+            "[,]",
+            "{\"a\" 1}",
+            "{\"a\":1,}",
+            "{a:1}",
+            "{\"a\":1 \"b\":2}",
+            "[1}",
+            "{\"a\":[1}",
+            "[tru]",
+            "[nul",
+            "[truex]",
+            "[-]",
+            "[1.]",
+            "[1e+]",
+            "[01]",
+            "[-00.5, 1]",
+            "\"abc",
+            "\"a\\qb\"",
+            "\"a\\u12\"",
+            "\"a\nb\"",
+            "[1] 2",
+            "[Infinity, -Infinity]",
+            "{\"a\" : [ 1, 2 }, \"b\" : ]",
+        ] {
+            let error = parse_json_string(input).unwrap_err();
+            assert_eq!(validate_json(input).first(), Some(&error), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn recovered_values() {
+        let recovered = |input| parse_json_recovering(input).0;
+
+        assert_eq!(recovered("[1, 2, 3"), Some(json!([1, 2, 3])));
+        assert_eq!(
+            recovered(r#"[1, {"a": [true, x, false}, "s", ]"#),
+            Some(json!([1, { "a": [true, false] }, "s"]))
+        );
+        assert_eq!(
+            recovered("{\"a\": \"unterminated\n, \"b\": {junk: [1, 2], \"c\": 3}}"),
+            Some(json!({ "a": "unterminated", "b": { "c": 3 } }))
+        );
+        assert_eq!(recovered("NaN"), None);
+
+        //nested too deeply, which is not gone into
+        let deep = "[".repeat(200) + &"]".repeat(200);
+        let errors = validate_json(&deep);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, JsonErrorKind::RecursionLimitExceeded);
+        assert_eq!(
+            errors[0].position,
+            Position {
+                line: 1,
+                column: 129
+            }
+        );
+    }
+}
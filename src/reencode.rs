@@ -0,0 +1,423 @@
+use std::io::{self, Read, Write};
+
+use crate::read::{ReadChars, ReadError};
+use crate::serialize::{write_number, write_string};
+use crate::{
+    JsonError, JsonEvent, JsonTokenizer, NonFinitePolicy, SerializeError, SerializeOptions,
+};
+
+#[derive(Debug)]
+pub enum ReencodeError {
+    Json(JsonError),
+    /// Malformed or truncated UTF-8 starting at this byte offset of the input.
+    InvalidUtf8 {
+        offset: usize,
+    },
+    Read(io::Error),
+    Write(io::Error),
+    Options(SerializeError),
+    /// The option needs the whole container in memory before writing it.
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for ReencodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReencodeError::Json(err) => err.fmt(f),
+            ReencodeError::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 at byte {}", offset)
+            }
+            ReencodeError::Read(err) => write!(f, "read error: {}", err),
+            ReencodeError::Write(err) => write!(f, "write error: {}", err),
+            ReencodeError::Options(err) => err.fmt(f),
+            ReencodeError::Unsupported(option) => {
+                write!(f, "{} is not supported when reencoding", option)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReencodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReencodeError::Json(err) => Some(err),
+            ReencodeError::Read(err) | ReencodeError::Write(err) => Some(err),
+            ReencodeError::Options(err) => Some(err),
+            ReencodeError::InvalidUtf8 { .. } | ReencodeError::Unsupported(_) => None,
+        }
+    }
+}
+
+/// Minifies or pretty prints from `input` to `output` without building the document, memory use
+/// is bounded by the nesting depth and the longest string. The output is buffered here and
+/// written in large chunks, so `output` should not be a `BufWriter` or otherwise buffered. On an
+/// error, what was produced before it is still written.
+///
+/// `sort_keys`, `canonical`, `inline_threshold` and `max_width` need whole containers and are
+/// rejected with [`ReencodeError::Unsupported`].
+pub fn reencode<R: Read, W: Write>(
+    input: R,
+    mut output: W,
+    options: &SerializeOptions,
+) -> Result<(), ReencodeError> {
+    options.validate().map_err(ReencodeError::Options)?;
+
+    if options.sort_keys {
+        return Err(ReencodeError::Unsupported("sort_keys"));
+    } else if options.inline_threshold > 0 {
+        return Err(ReencodeError::Unsupported("inline_threshold"));
+    } else if options.max_width.is_some() {
+        return Err(ReencodeError::Unsupported("max_width"));
+    }
+
+    let mut events =
+        JsonTokenizer::new(ReadChars::new(input)).keep_number_text(options.preserve_number_text);
+
+    let mut writer = EventWriter {
+        options,
+        scratch: String::new(),
+        stack: Vec::new(),
+        after_key: false,
+    };
+
+    let mut failure = None;
+    while let Some(event) = events.next() {
+        //a failed read looks like the end of the input to the tokenizer
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                failure = Some(read_error(events.get_mut()).unwrap_or(ReencodeError::Json(err)));
+                break;
+            }
+        };
+
+        let number_text = events.take_number_text();
+        //only found once the output before it is produced, unlike with trees
+        if let (JsonEvent::Number(n), None, NonFinitePolicy::Error) =
+            (&event, &number_text, options.non_finite)
+        {
+            if !n.is_finite() {
+                failure = Some(ReencodeError::Options(SerializeError::NonFiniteNumber));
+                break;
+            }
+        }
+        writer.write_event(event, number_text);
+
+        if writer.scratch.len() >= WRITE_SIZE {
+            output
+                .write_all(writer.scratch.as_bytes())
+                .map_err(ReencodeError::Write)?;
+            writer.scratch.clear();
+        }
+    }
+
+    let failure = failure.or_else(|| read_error(events.get_mut()));
+    if failure.is_none() && options.trailing_newline {
+        writer.scratch.push_str(options.newline.as_str());
+    }
+
+    let written = output
+        .write_all(writer.scratch.as_bytes())
+        .and_then(|()| output.flush());
+    match failure {
+        Some(err) => Err(err),
+        None => written.map_err(ReencodeError::Write),
+    }
+}
+
+//the output is written once this much of it is pending
+const WRITE_SIZE: usize = 8 * 1024;
+
+fn read_error<R: Read>(chars: &mut ReadChars<R>) -> Option<ReencodeError> {
+    match chars.take_error()? {
+        ReadError::Io(err) => Some(ReencodeError::Read(err)),
+        ReadError::InvalidUtf8 { offset } => Some(ReencodeError::InvalidUtf8 { offset }),
+    }
+}
+
+//mirrors the layout of the tree serializer one event at a time
+struct EventWriter<'a> {
+    options: &'a SerializeOptions,
+    scratch: String,
+    //whether each open container already has members
+    stack: Vec<bool>,
+    after_key: bool,
+}
+
+impl EventWriter<'_> {
+    //`number_text` is the source text of a number event, when it is kept
+    fn write_event(&mut self, event: JsonEvent, number_text: Option<String>) {
+        match event {
+            JsonEvent::EndArray => self.close(']'),
+            JsonEvent::EndObject => self.close('}'),
+            JsonEvent::Key(key) => {
+                self.begin_member();
+                write_string(&mut self.scratch, &key, self.options).unwrap();
+                self.scratch.push(':');
+                if self.options.indent.is_some() {
+                    self.scratch.push(' ');
+                }
+                self.after_key = true;
+            }
+            event => {
+                if !self.after_key {
+                    self.begin_member();
+                }
+                self.after_key = false;
+
+                match event {
+                    JsonEvent::StartArray => self.open('['),
+                    JsonEvent::StartObject => self.open('{'),
+                    JsonEvent::String(s) => {
+                        write_string(&mut self.scratch, &s, self.options).unwrap()
+                    }
+                    JsonEvent::Number(n) => match number_text {
+                        Some(text) => self.scratch.push_str(&text),
+                        None => {
+                            write_number(&mut self.scratch, n, self.options.non_finite).unwrap()
+                        }
+                    },
+                    JsonEvent::Boolean(true) => self.scratch.push_str("true"),
+                    JsonEvent::Boolean(false) => self.scratch.push_str("false"),
+                    _ => self.scratch.push_str("null"),
+                }
+            }
+        }
+    }
+
+    fn begin_member(&mut self) {
+        if let Some(has_members) = self.stack.last_mut() {
+            if *has_members {
+                self.scratch.push(',');
+            }
+            *has_members = true;
+
+            let depth = self.stack.len();
+            self.line_break(depth);
+        }
+    }
+
+    fn open(&mut self, ch: char) {
+        self.scratch.push(ch);
+        self.stack.push(false);
+    }
+
+    fn close(&mut self, ch: char) {
+        if self.stack.pop() == Some(true) {
+            let depth = self.stack.len();
+            self.line_break(depth);
+        }
+        self.scratch.push(ch);
+    }
+
+    fn line_break(&mut self, depth: usize) {
+        if let Some(indent) = self.options.indent {
+            self.scratch.push_str(self.options.newline.as_str());
+            for _ in 0..indent * depth {
+                self.scratch.push(self.options.indent_style.as_char());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_json_string, JsonErrorKind};
+
+    fn generated_document(records: usize) -> String {
+        let mut json = String::from("[");
+        for i in 0..records {
+            if i != 0 {
+                json.push_str(",\n");
+            }
+            json.push_str(&format!(
+                r#"{{ "id": {}, "name": "record \"{}\"", "ratio": {}.25e-1, "tags": ["a", []], "meta": {{}}, "ok": {} }}"#,
+                i,
+                i,
+                i,
+                i % 2 == 0
+            ));
+        }
+        json.push(']');
+        json
+    }
+
+    fn reencode_str(json: &str, options: &SerializeOptions) -> Result<String, ReencodeError> {
+        let mut out = vec![];
+        reencode(json.as_bytes(), &mut out, options)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn matches_parse_and_serialize() -> Result<(), Box<dyn std::error::Error>> {
+        let json = generated_document(500);
+        let tree = parse_json_string(&json)?;
+
+        for options in [
+            SerializeOptions::compact(),
+            SerializeOptions::pretty(),
+            SerializeOptions::html_safe(),
+            SerializeOptions {
+                indent: Some(1),
+                newline: crate::NewlineStyle::CrLf,
+                trailing_newline: true,
+                ..SerializeOptions::default()
+            },
+            SerializeOptions {
+                indent: Some(1),
+                indent_style: crate::IndentStyle::Tabs,
+                ..SerializeOptions::default()
+            },
+        ]
+        .iter()
+        {
+            assert_eq!(reencode_str(&json, options)?, tree.serialize(options)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn preserves_number_text() -> Result<(), Box<dyn std::error::Error>> {
+        let options = SerializeOptions {
+            preserve_number_text: true,
+            ..SerializeOptions::default()
+        };
+
+        assert_eq!(
+            reencode_str("[ 1.50 , -0.0e+10, 12345678901234567890123 ]", &options)?,
+            "[1.50,-0.0e+10,12345678901234567890123]"
+        );
+
+        Ok(())
+    }
+
+    //produces `[0,0,0,...,0]` lazily
+    struct Zeros {
+        remaining: usize,
+        started: bool,
+    }
+
+    impl Read for Zeros {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let mut written = 0;
+            for byte in buf.iter_mut() {
+                *byte = if !self.started {
+                    self.started = true;
+                    b'['
+                } else if self.remaining == 0 {
+                    return Ok(written);
+                } else {
+                    self.remaining -= 1;
+                    match self.remaining {
+                        0 => b']',
+                        n if n % 2 == 0 => b',',
+                        _ => b'0',
+                    }
+                };
+                written += 1;
+            }
+            Ok(written)
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingSink {
+        bytes: usize,
+        writes: usize,
+    }
+
+    impl Write for CountingSink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.bytes += buf.len();
+            self.writes += 1;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bounded_memory_smoke_test() -> Result<(), ReencodeError> {
+        //never materialized, two million elements of input go straight through
+        let input = Zeros {
+            remaining: 4_000_000,
+            started: false,
+        };
+        let mut sink = CountingSink::default();
+
+        reencode(input, &mut sink, &SerializeOptions::compact())?;
+
+        assert_eq!(sink.bytes, 4_000_001);
+        //in large chunks rather than one write per token
+        assert!(
+            sink.writes <= sink.bytes / WRITE_SIZE + 1,
+            "{}",
+            sink.writes
+        );
+
+        Ok(())
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("read failed"))
+        }
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("write failed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn io_errors_propagate() {
+        let result = reencode(FailingReader, vec![], &SerializeOptions::compact());
+        assert!(matches!(result, Err(ReencodeError::Read(_))));
+
+        let result = reencode(&b"[1, 2]"[..], FailingWriter, &SerializeOptions::compact());
+        assert!(matches!(result, Err(ReencodeError::Write(_))));
+
+        let result = reencode(&b"[\"\xFF\"]"[..], vec![], &SerializeOptions::compact());
+        assert!(matches!(
+            result,
+            Err(ReencodeError::InvalidUtf8 { offset: 2 })
+        ));
+
+        let mut out = vec![];
+        let result = reencode(&b"[1, 2] x"[..], &mut out, &SerializeOptions::compact());
+        assert!(matches!(
+            result,
+            Err(ReencodeError::Json(JsonError {
+                kind: JsonErrorKind::ExtraChars { .. },
+                ..
+            }))
+        ));
+        //what came before the error is still written
+        assert_eq!(out, b"[1,2]");
+
+        let result = reencode(&b"1 x"[..], vec![], &SerializeOptions::compact());
+        assert!(matches!(
+            result,
+            Err(ReencodeError::Json(JsonError {
+                kind: JsonErrorKind::ExtraChars { .. },
+                ..
+            }))
+        ));
+
+        let result = reencode(&b"[1, 2]"[..], vec![], &SerializeOptions::canonical());
+        assert!(matches!(result, Err(ReencodeError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,294 @@
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::JsonObject;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectorError {
+    /// Selectors start with `$`, the document itself.
+    MissingRoot,
+    /// A char that cannot start or continue a step, at its byte offset.
+    UnexpectedChar {
+        offset: usize,
+        found: char,
+    },
+    UnexpectedEnd,
+    /// A bracketed index that does not fit a `usize`.
+    InvalidIndex(String),
+}
+
+impl std::fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectorError::MissingRoot => f.write_str("selectors start with '$'"),
+            SelectorError::UnexpectedChar { offset, found } => {
+                write!(f, "unexpected character {:?} at byte {}", found, offset)
+            }
+            SelectorError::UnexpectedEnd => {
+                f.write_str("the selector ends in the middle of a step")
+            }
+            SelectorError::InvalidIndex(index) => write!(f, "{:?} is not an array index", index),
+        }
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    //every value of an object or element of an array
+    Wildcard,
+}
+
+/// A parsed JSONPath-style selector, reusable across documents. Supports the root `$`, member
+/// access as `.key` or `['key']`/`["key"]` for keys holding dots or brackets, array indices as
+/// `[0]` and the wildcards `.*` and `[*]`, which both select every value of an object or array:
+///
+/// ```
+/// use json_parser::{json, Selector};
+///
+/// let selector = Selector::parse("$.store.book[*].author").unwrap();
+/// let json = json!({ "store": { "book": [{ "author": "A" }, {}, { "author": "B" }] } });
+///
+/// assert_eq!(selector.select(&json), [&json!("A"), &json!("B")]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    steps: Vec<Step>,
+}
+
+impl Selector {
+    pub fn parse(selector: &str) -> Result<Selector, SelectorError> {
+        let mut chars = selector.char_indices().peekable();
+
+        match chars.next() {
+            Some((_, '$')) => {}
+            _ => return Err(SelectorError::MissingRoot),
+        }
+
+        let mut steps = vec![];
+        while let Some((offset, ch)) = chars.next() {
+            let step = match ch {
+                '.' => parse_dotted(&mut chars)?,
+                '[' => parse_bracketed(&mut chars)?,
+                found => return Err(SelectorError::UnexpectedChar { offset, found }),
+            };
+            steps.push(step);
+        }
+
+        Ok(Selector { steps })
+    }
+
+    /// The matching values in document order. Steps that do not apply, such as a key on an array
+    /// or an index past the end, select nothing rather than failing.
+    pub fn select<'a>(&self, json: &'a JsonObject) -> Vec<&'a JsonObject> {
+        let mut selected = vec![json];
+
+        for step in &self.steps {
+            selected = selected
+                .into_iter()
+                .flat_map(|value| select_step(step, value))
+                .collect();
+        }
+
+        selected
+    }
+}
+
+fn select_step<'a>(step: &Step, value: &'a JsonObject) -> Vec<&'a JsonObject> {
+    match (step, value) {
+        (Step::Key(key), JsonObject::Object(object)) => object.get(key).into_iter().collect(),
+        (Step::Index(i), JsonObject::Array(array)) => array.get(*i).into_iter().collect(),
+        (Step::Wildcard, JsonObject::Object(object)) => object.values().collect(),
+        (Step::Wildcard, JsonObject::Array(array)) => array.iter().collect(),
+        _ => vec![],
+    }
+}
+
+//after `.`: `*` or a name running up to the next `.` or `[`
+fn parse_dotted(chars: &mut Peekable<CharIndices>) -> Result<Step, SelectorError> {
+    if let Some((_, '*')) = chars.peek() {
+        chars.next();
+        return Ok(Step::Wildcard);
+    }
+
+    let mut name = String::new();
+    while let Some(&(offset, ch)) = chars.peek() {
+        match ch {
+            '.' | '[' if name.is_empty() => {
+                return Err(SelectorError::UnexpectedChar { offset, found: ch })
+            }
+            '.' | '[' => break,
+            ']' => return Err(SelectorError::UnexpectedChar { offset, found: ch }),
+            ch => name.push(ch),
+        }
+        chars.next();
+    }
+
+    if name.is_empty() {
+        Err(SelectorError::UnexpectedEnd)
+    } else {
+        Ok(Step::Key(name))
+    }
+}
+
+//after `[`: `*]`, digits and `]`, or a quoted key accepting `\` escapes and `]`
+fn parse_bracketed(chars: &mut Peekable<CharIndices>) -> Result<Step, SelectorError> {
+    let step = match chars.next().ok_or(SelectorError::UnexpectedEnd)? {
+        (_, '*') => Step::Wildcard,
+        (_, quote @ ('\'' | '"')) => {
+            let mut key = String::new();
+            loop {
+                match chars.next().ok_or(SelectorError::UnexpectedEnd)?.1 {
+                    ch if ch == quote => break,
+                    '\\' => key.push(chars.next().ok_or(SelectorError::UnexpectedEnd)?.1),
+                    ch => key.push(ch),
+                }
+            }
+            Step::Key(key)
+        }
+        (_, first @ '0'..='9') => {
+            let mut digits = first.to_string();
+            while let Some(&(_, ch @ '0'..='9')) = chars.peek() {
+                digits.push(ch);
+                chars.next();
+            }
+            Step::Index(
+                digits
+                    .parse()
+                    .map_err(|_| SelectorError::InvalidIndex(digits))?,
+            )
+        }
+        (offset, found) => return Err(SelectorError::UnexpectedChar { offset, found }),
+    };
+
+    match chars.next().ok_or(SelectorError::UnexpectedEnd)? {
+        (_, ']') => Ok(step),
+        (offset, found) => Err(SelectorError::UnexpectedChar { offset, found }),
+    }
+}
+
+impl JsonObject {
+    /// Parses the selector and applies it once, see [`Selector`] to reuse it.
+    #[inline]
+    pub fn select(&self, selector: &str) -> Result<Vec<&JsonObject>, SelectorError> {
+        Ok(Selector::parse(selector)?.select(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn store() -> Result<JsonObject, JsonError> {
+        parse_json_string(
+            r#"{
+                "store" : {
+                    "book" : [
+                        { "author" : "Rees", "title" : "Sayings", "tags" : [ "a", "b" ] },
+                        { "title" : "Anonymous" },
+                        { "author" : "Waugh", "title" : "Sword", "tags" : [ "c" ] }
+                    ],
+                    "bicycle" : { "color" : "red", "price" : 19.95 },
+                    "a.b" : { "c[0]" : true }
+                }
+            }"#,
+        )
+    }
+
+    #[test]
+    fn wildcards() -> Result<(), Box<dyn std::error::Error>> {
+        let json = store()?;
+
+        let authors = Selector::parse("$.store.book[*].author")?;
+        assert_eq!(authors.select(&json), [&json!("Rees"), &json!("Waugh")]);
+        //reused on another document
+        assert_eq!(
+            authors.select(&json!({ "store": { "book": [{ "author": "X" }] } })),
+            [&json!("X")]
+        );
+
+        assert_eq!(
+            json.select("$.store.book[*].tags[*]")?,
+            [&json!("a"), &json!("b"), &json!("c")]
+        );
+        assert_eq!(
+            json.select("$.store.bicycle.*")?,
+            [&json!("red"), &json!(19.95)]
+        );
+        assert_eq!(json.select("$.store.*.color")?, [&json!("red")]);
+        assert_eq!(json.select("$.store.book[2]['title']")?, [&json!("Sword")]);
+        assert_eq!(json.select("$")?, [&json]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bracketed_keys() -> Result<(), Box<dyn std::error::Error>> {
+        let json = store()?;
+
+        assert_eq!(json.select(r#"$.store["a.b"]['c[0]']"#)?, [&json!(true)]);
+        assert_eq!(json.select(r"$.store['a.b']['c\[0\]']")?, [&json!(true)]);
+        //a dot still separates unbracketed keys
+        assert!(json.select("$.store.a.b")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_paths_select_nothing() -> Result<(), Box<dyn std::error::Error>> {
+        let json = store()?;
+
+        for selector in [
+            "$.nothing",
+            "$.store.book[3]",
+            "$.store.book.author",
+            "$.store.bicycle[0]",
+            "$.store.bicycle.color.*",
+            "$.store.book[*].isbn",
+        ] {
+            assert!(json.select(selector)?.is_empty(), "{}", selector);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let json = JsonObject::Null;
+
+        assert_eq!(json.select("store"), Err(SelectorError::MissingRoot));
+        assert_eq!(json.select("$."), Err(SelectorError::UnexpectedEnd));
+        assert_eq!(json.select("$['a'"), Err(SelectorError::UnexpectedEnd));
+        assert_eq!(
+            json.select("$..a"),
+            Err(SelectorError::UnexpectedChar {
+                offset: 2,
+                found: '.'
+            })
+        );
+        assert_eq!(
+            json.select("$[-1]"),
+            Err(SelectorError::UnexpectedChar {
+                offset: 2,
+                found: '-'
+            })
+        );
+        assert_eq!(
+            json.select("$a"),
+            Err(SelectorError::UnexpectedChar {
+                offset: 1,
+                found: 'a'
+            })
+        );
+        assert_eq!(
+            json.select("$[99999999999999999999999]"),
+            Err(SelectorError::InvalidIndex(
+                "99999999999999999999999".to_owned()
+            ))
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Error, Serialize, SerializeMap, Serializer};
+
+use crate::{JsonObject, Object, ObjectImpl};
+
+//integral numbers below this are written without a fraction, as `to_json_string` does
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.;
+
+/// Entries of an object are serialized as a map in their order, duplicates included. Integral
+/// numbers are serialized as integers so formats with both write `1` rather than `1.0`, and a
+/// [`JsonObject::RawNumber`] as the integer or `f64` it holds, or an error when it holds no number.
+impl Serialize for JsonObject {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsonObject::Null => serializer.serialize_unit(),
+            JsonObject::Boolean(b) => serializer.serialize_bool(*b),
+            JsonObject::Number(n) if n.fract() == 0. && n.abs() < MAX_EXACT_INTEGER => {
+                serializer.serialize_i64(*n as i64)
+            }
+            JsonObject::Number(n) => serializer.serialize_f64(*n),
+            JsonObject::RawNumber(literal) => {
+                if let Ok(n) = literal.parse::<i64>() {
+                    serializer.serialize_i64(n)
+                } else if let Ok(n) = literal.parse::<u64>() {
+                    serializer.serialize_u64(n)
+                } else {
+                    let n = literal.parse().map_err(|_| {
+                        S::Error::custom(format_args!("invalid number {:?}", literal))
+                    })?;
+                    serializer.serialize_f64(n)
+                }
+            }
+            JsonObject::String(s) => serializer.serialize_str(s),
+            JsonObject::Array(array) => serializer.collect_seq(array),
+            JsonObject::Object(object) => {
+                let mut map = serializer.serialize_map(Some(object.entries.len()))?;
+                for (key, value) in &object.entries {
+                    map.serialize_entry(key.as_str(), value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Maps keep the order and duplicates the format hands them over with. Every number becomes a
+/// [`JsonObject::Number`], rounding integers beyond 2^53.
+impl<'de> Deserialize<'de> for JsonObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonObjectVisitor)
+    }
+}
+
+struct JsonObjectVisitor;
+
+impl<'de> Visitor<'de> for JsonObjectVisitor {
+    type Value = JsonObject;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonObject, E> {
+        Ok(JsonObject::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<JsonObject, E> {
+        Ok(JsonObject::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonObject, D::Error> {
+        JsonObject::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<JsonObject, E> {
+        Ok(JsonObject::Boolean(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<JsonObject, E> {
+        Ok(JsonObject::Number(n as f64))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<JsonObject, E> {
+        Ok(JsonObject::Number(n as f64))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<JsonObject, E> {
+        Ok(JsonObject::Number(n))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<JsonObject, E> {
+        Ok(JsonObject::String(s.to_owned()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<JsonObject, E> {
+        Ok(JsonObject::String(s))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonObject, A::Error> {
+        let mut array = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            array.push(value);
+        }
+
+        Ok(JsonObject::Array(array))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonObject, A::Error> {
+        let mut entries = ObjectImpl::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry::<String, JsonObject>()? {
+            entries.push((key.into(), value));
+        }
+
+        Ok(JsonObject::Object(Object::from_impl(entries)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const DOCUMENT: &str = r#"{"name":"crate","version":[0,1,0],"z":null,"a":{"nested":[true,false,{"deep":-2.5e-3}],"empty":{},"list":[]},"text":"esc\"aped\n😀","big":1.5e10,"small":0.375,"neg":-42}"#;
+
+    #[test]
+    fn round_trip_through_serde_json() -> Result<(), Box<dyn std::error::Error>> {
+        let parsed = parse_json_string(DOCUMENT)?;
+
+        let written = serde_json::to_string(&parsed)?;
+        assert_eq!(parse_json_string(&written)?, parsed);
+        //keys are written in their order, and integers without a fraction
+        assert!(written.starts_with(r#"{"name":"crate","version":[0,1,0],"z":null,"a":"#));
+
+        let read: JsonObject = serde_json::from_str(DOCUMENT)?;
+        assert_eq!(read, parsed);
+
+        let read: JsonObject = serde_json::from_str(&written)?;
+        assert_eq!(read, parsed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicates_and_raw_numbers() -> Result<(), Box<dyn std::error::Error>> {
+        let read: JsonObject = serde_json::from_str(r#"{ "b" : 1, "a" : 2, "b" : 3 }"#)?;
+        assert_eq!(read.to_json_string(), r#"{"b":1,"a":2,"b":3}"#);
+        assert_eq!(serde_json::to_string(&read)?, r#"{"b":1,"a":2,"b":3}"#);
+
+        let options = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..ParseOptions::default()
+        };
+        let raw = parse_json_string_with_options(
+            "[18446744073709551615, -9007199254740993, 0.5]",
+            &options,
+        )?;
+        assert_eq!(
+            serde_json::to_string(&raw)?,
+            "[18446744073709551615,-9007199254740993,0.5]"
+        );
+
+        //a raw number can be built holding anything
+        let invalid = JsonObject::RawNumber("1.2.3".to_owned());
+        assert_eq!(
+            serde_json::to_string(&invalid).unwrap_err().to_string(),
+            r#"invalid number "1.2.3""#
+        );
+
+        Ok(())
+    }
+}
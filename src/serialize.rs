@@ -0,0 +1,1531 @@
+use std::fmt::{self, Write};
+use std::io;
+
+use crate::{parse_json_string, JsonError, JsonObject, Key, Object};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewlineStyle {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl NewlineStyle {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NewlineStyle::Lf => "\n",
+            NewlineStyle::CrLf => "\r\n",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndentStyle {
+    #[default]
+    Spaces,
+    Tabs,
+}
+
+impl IndentStyle {
+    #[inline]
+    pub fn as_char(self) -> char {
+        match self {
+            IndentStyle::Spaces => ' ',
+            IndentStyle::Tabs => '\t',
+        }
+    }
+}
+
+/// How numbers JSON cannot represent, NaN and the infinities, are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    #[default]
+    Null,
+    /// Fail with [`SerializeError::NonFiniteNumber`] before writing anything.
+    Error,
+    /// `NaN`, `Infinity` and `-Infinity` as Python and javascript write them, which is not
+    /// valid JSON, see [`crate::ParseOptions::allow_non_finite_numbers`] to read them back.
+    Literal,
+}
+
+/// Every output setting in one place. Build it from one of the presets or through
+/// [`SerializeOptions::builder`], fields can then be adjusted individually.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SerializeOptions {
+    /// Escape U+2028 and U+2029 as `\u2028`/`\u2029`.
+    ///
+    /// Both are valid raw inside JSON strings but are line terminators in pre-ES2019
+    /// javascript, which breaks documents embedded in inline `<script>` blocks.
+    pub js_safe: bool,
+    /// Escape `<`, `>` and `&` as `\u003c`, `\u003e` and `\u0026` so the output can be
+    /// interpolated into HTML, including inside `<script>`. Implies `js_safe`.
+    pub html_safe: bool,
+    /// Escape every char above U+007F as `\uXXXX`, astral ones as a surrogate pair, so the
+    /// output is plain ASCII for transports that mangle other bytes.
+    pub ascii_only: bool,
+    /// Escape `/` as `\/`, another way to keep `</script>` out of embedded output.
+    pub escape_solidus: bool,
+    /// Indentation units per nesting level, `None` produces compact output.
+    pub indent: Option<usize>,
+    /// Whether those units are spaces or tabs. Widths for `max_width` and
+    /// `inline_threshold` count a tab as one character.
+    pub indent_style: IndentStyle,
+    /// Line break used between lines of pretty output and for the trailing newline.
+    pub newline: NewlineStyle,
+    /// End the output with a newline, compact output included.
+    pub trailing_newline: bool,
+    /// Pretty output only: arrays and objects holding no non-empty containers are kept on a
+    /// single line when their inline rendering, escapes included, is at most this many
+    /// characters wide. `0` always breaks. Ignored when `max_width` is set.
+    pub inline_threshold: usize,
+    /// Pretty output only: any container that fits on the rest of its line, trailing comma
+    /// included, is kept on that line and the others are broken up. Each container is measured
+    /// by rendering it inline first, so deep documents pay for one measurement per level. Lines
+    /// made of a single scalar or key can still exceed the width.
+    pub max_width: Option<usize>,
+    /// Emit object entries sorted by key instead of in insertion order.
+    pub sort_keys: bool,
+    /// Canonical output: compact with sorted keys. Rejected together with `indent` or
+    /// without `sort_keys`.
+    pub canonical: bool,
+    /// Write numbers exactly as they appeared in the input. Only [`crate::reencode`] has the
+    /// source text, trees always render their `f64`.
+    pub preserve_number_text: bool,
+    pub non_finite: NonFinitePolicy,
+}
+
+impl SerializeOptions {
+    #[inline]
+    pub fn compact() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn pretty() -> Self {
+        SerializeOptions {
+            indent: Some(2),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    pub fn canonical() -> Self {
+        SerializeOptions {
+            canonical: true,
+            sort_keys: true,
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    pub fn html_safe() -> Self {
+        SerializeOptions {
+            js_safe: true,
+            html_safe: true,
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    pub fn builder() -> SerializeOptionsBuilder {
+        SerializeOptionsBuilder::from(Self::default())
+    }
+
+    #[inline]
+    pub fn into_builder(self) -> SerializeOptionsBuilder {
+        SerializeOptionsBuilder::from(self)
+    }
+
+    pub fn validate(&self) -> Result<(), SerializeError> {
+        if self.canonical && !self.sort_keys {
+            Err(SerializeError::ConflictingOptions(
+                "canonical output requires sorted keys",
+            ))
+        } else if self.canonical && self.indent.is_some() {
+            Err(SerializeError::ConflictingOptions(
+                "canonical output cannot be indented",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+macro_rules! builder_setter {
+    ($name:ident, $ty:ty) => {
+        #[inline]
+        pub fn $name(mut self, $name: $ty) -> Self {
+            self.options.$name = $name;
+            self
+        }
+    };
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SerializeOptionsBuilder {
+    options: SerializeOptions,
+}
+
+impl SerializeOptionsBuilder {
+    builder_setter!(js_safe, bool);
+    builder_setter!(html_safe, bool);
+    builder_setter!(ascii_only, bool);
+    builder_setter!(escape_solidus, bool);
+    builder_setter!(indent, Option<usize>);
+    builder_setter!(indent_style, IndentStyle);
+    builder_setter!(newline, NewlineStyle);
+    builder_setter!(trailing_newline, bool);
+    builder_setter!(inline_threshold, usize);
+    builder_setter!(max_width, Option<usize>);
+    builder_setter!(sort_keys, bool);
+    builder_setter!(canonical, bool);
+    builder_setter!(preserve_number_text, bool);
+    builder_setter!(non_finite, NonFinitePolicy);
+
+    pub fn build(self) -> Result<SerializeOptions, SerializeError> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
+
+impl From<SerializeOptions> for SerializeOptionsBuilder {
+    fn from(options: SerializeOptions) -> Self {
+        SerializeOptionsBuilder { options }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SerializeError {
+    ConflictingOptions(&'static str),
+    /// A NaN or infinite number, with [`NonFinitePolicy::Error`].
+    NonFiniteNumber,
+}
+
+impl std::fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SerializeError::ConflictingOptions(problem) => f.write_str(problem),
+            SerializeError::NonFiniteNumber => f.write_str("NaN and infinities are not valid JSON"),
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+impl JsonObject {
+    pub fn serialize(&self, options: &SerializeOptions) -> Result<String, SerializeError> {
+        options.validate()?;
+        check_non_finite(self, options)?;
+
+        let mut out = String::new();
+        write_document(&mut out, self, options).unwrap();
+        Ok(out)
+    }
+
+    #[inline]
+    pub fn to_json_string(&self) -> String {
+        self.to_json_string_with_options(&SerializeOptions::compact())
+    }
+
+    #[inline]
+    pub fn to_json_string_pretty(&self, indent: usize) -> String {
+        self.to_json_string_with_options(&SerializeOptions {
+            indent: Some(indent),
+            ..SerializeOptions::default()
+        })
+    }
+
+    /// # Panics
+    ///
+    /// When the options conflict, see [`SerializeOptions::validate`], or when a number is not
+    /// finite with [`NonFinitePolicy::Error`].
+    pub fn to_json_string_with_options(&self, options: &SerializeOptions) -> String {
+        match self.serialize(options) {
+            Ok(out) => out,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Writes the compact serialization to `writer` as it is produced, the output is the same as
+    /// [`JsonObject::to_json_string`]. Small writes are gathered in a buffer of a few kilobytes,
+    /// which is emptied before returning, but `writer` itself is not flushed.
+    #[inline]
+    pub fn write_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_to_with_options(writer, &SerializeOptions::compact())
+    }
+
+    #[inline]
+    pub fn write_pretty_to<W: io::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        indent: usize,
+    ) -> io::Result<()> {
+        self.write_to_with_options(
+            writer,
+            &SerializeOptions {
+                indent: Some(indent),
+                ..SerializeOptions::default()
+            },
+        )
+    }
+
+    /// Conflicting options and numbers rejected by [`NonFinitePolicy::Error`] are reported as an
+    /// [`io::ErrorKind::InvalidInput`] error before anything is written.
+    pub fn write_to_with_options<W: io::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        options: &SerializeOptions,
+    ) -> io::Result<()> {
+        options
+            .validate()
+            .and_then(|_| check_non_finite(self, options))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        let mut out = IoWriter {
+            writer,
+            buffer: Vec::with_capacity(IO_BUFFER_SIZE),
+            error: None,
+        };
+
+        match write_document(&mut out, self, options).and_then(|_| out.flush_buffer()) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(out
+                .error
+                .take()
+                .unwrap_or_else(|| io::Error::other("formatter error"))),
+        }
+    }
+
+    /// The compact serialization written to a [`fmt::Write`] such as a `String` or a
+    /// `Formatter`, which is how [`Display`](fmt::Display) is implemented.
+    #[inline]
+    pub fn write_to_fmt<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        write_document(writer, self, &SerializeOptions::compact())
+    }
+
+    /// Plain text rendering of a scalar for templating: strings unquoted and unescaped, numbers
+    /// as the serializer writes them, `true`/`false`, and an empty string for `Null` so that
+    /// missing values disappear from the output. Containers have no such rendering.
+    ///
+    /// ```
+    /// use json_parser::parse_json_string;
+    ///
+    /// let json = parse_json_string(r#"{ "name": "Ada \"Countess\"", "year": 1843, "title": null }"#)?;
+    /// let object = json.as_object().unwrap();
+    ///
+    /// let text = format!(
+    ///     "{} wrote the notes in {}{}",
+    ///     object.get("name").and_then(|v| v.to_display_string()).unwrap(),
+    ///     object.get("year").and_then(|v| v.to_display_string()).unwrap(),
+    ///     object.get("title").and_then(|v| v.to_display_string()).unwrap(),
+    /// );
+    ///
+    /// assert_eq!(text, r#"Ada "Countess" wrote the notes in 1843"#);
+    /// # Ok::<(), json_parser::JsonError>(())
+    /// ```
+    pub fn to_display_string(&self) -> Option<String> {
+        match self {
+            JsonObject::String(s) => Some(s.clone()),
+            JsonObject::Number(n) => {
+                let mut out = String::new();
+                write_number(&mut out, *n, NonFinitePolicy::Null).unwrap();
+                Some(out)
+            }
+            JsonObject::RawNumber(literal) => Some(literal.clone()),
+            JsonObject::Boolean(b) => Some(b.to_string()),
+            JsonObject::Null => Some(String::new()),
+            JsonObject::Array(_) | JsonObject::Object(_) => None,
+        }
+    }
+}
+
+/// Compact JSON, or pretty JSON with two space indentation for `{:#}`.
+impl fmt::Display for JsonObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write_document(f, self, &SerializeOptions::pretty())
+        } else {
+            self.write_to_fmt(f)
+        }
+    }
+}
+
+/// Errors of [`format_json`].
+#[derive(Debug, PartialEq)]
+pub enum FormatError {
+    Json(JsonError),
+    Serialize(SerializeError),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Json(err) => err.fmt(f),
+            FormatError::Serialize(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FormatError::Json(err) => Some(err),
+            FormatError::Serialize(err) => Some(err),
+        }
+    }
+}
+
+impl From<JsonError> for FormatError {
+    fn from(err: JsonError) -> Self {
+        FormatError::Json(err)
+    }
+}
+
+impl From<SerializeError> for FormatError {
+    fn from(err: SerializeError) -> Self {
+        FormatError::Serialize(err)
+    }
+}
+
+//parses then re-serializes, so the output of a formatter can always be fed back into it
+pub fn format_json(input: &str, options: &SerializeOptions) -> Result<String, FormatError> {
+    Ok(parse_json_string(input)?.serialize(options)?)
+}
+
+//what every entry point writes, the options are already validated
+fn write_document<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    value: &JsonObject,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    let mut out = Output {
+        out,
+        column: 0,
+        track_column: options.indent.is_some() && options.max_width.is_some(),
+    };
+
+    write_value(&mut out, value, options, 0, 0)?;
+    if options.trailing_newline {
+        out.write_str(options.newline.as_str())?;
+    }
+    Ok(())
+}
+
+//the output along with the column it has reached, which only `max_width` needs
+struct Output<'a, W: ?Sized> {
+    out: &'a mut W,
+    column: usize,
+    track_column: bool,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Output<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.track_column {
+            match s.rfind('\n') {
+                Some(i) => self.column = s[i + 1..].chars().count(),
+                None => self.column += s.chars().count(),
+            }
+        }
+        self.out.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        if self.track_column {
+            self.column = if ch == '\n' { 0 } else { self.column + 1 };
+        }
+        self.out.write_char(ch)
+    }
+}
+
+const IO_BUFFER_SIZE: usize = 8 * 1024;
+
+//gathers the small writes of the serializer and keeps the io error, which `fmt::Error` cannot
+//carry
+struct IoWriter<'a, W: ?Sized> {
+    writer: &'a mut W,
+    buffer: Vec<u8>,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write + ?Sized> IoWriter<'_, W> {
+    fn flush_buffer(&mut self) -> fmt::Result {
+        let result = self.writer.write_all(&self.buffer);
+        self.buffer.clear();
+        result.map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+impl<W: io::Write + ?Sized> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.buffer.len() + s.len() > IO_BUFFER_SIZE {
+            self.flush_buffer()?;
+        }
+
+        if s.len() > IO_BUFFER_SIZE {
+            self.writer.write_all(s.as_bytes()).map_err(|err| {
+                self.error = Some(err);
+                fmt::Error
+            })
+        } else {
+            self.buffer.extend_from_slice(s.as_bytes());
+            Ok(())
+        }
+    }
+}
+
+//`trailer` is the width of what follows the value on its line, the comma for all but the last
+fn write_value<W: fmt::Write + ?Sized>(
+    out: &mut Output<'_, W>,
+    value: &JsonObject,
+    options: &SerializeOptions,
+    depth: usize,
+    trailer: usize,
+) -> fmt::Result {
+    if options.indent.is_some() && fits_inline(out.column, value, options, trailer) {
+        return write_inline(out, value, options);
+    }
+
+    match value {
+        JsonObject::Array(array) => {
+            out.write_char('[')?;
+            for (i, value) in array.iter().enumerate() {
+                if i != 0 {
+                    out.write_char(',')?;
+                }
+                write_line_break(out, options, depth + 1)?;
+                write_value(
+                    out,
+                    value,
+                    options,
+                    depth + 1,
+                    trailer_width(i, array.len()),
+                )?;
+            }
+            if !array.is_empty() {
+                write_line_break(out, options, depth)?;
+            }
+            out.write_char(']')
+        }
+        JsonObject::Object(object) => write_object(out, object, options, depth),
+        scalar => write_scalar(out, scalar, options),
+    }
+}
+
+fn write_scalar<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    value: &JsonObject,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    match value {
+        JsonObject::Null => out.write_str("null"),
+        JsonObject::Boolean(true) => out.write_str("true"),
+        JsonObject::Boolean(false) => out.write_str("false"),
+        //canonical output has a single zero
+        JsonObject::Number(n) if options.canonical && *n == 0. => out.write_char('0'),
+        JsonObject::Number(n) => write_number(out, *n, options.non_finite),
+        JsonObject::RawNumber(literal) => out.write_str(literal),
+        JsonObject::String(s) => write_string(out, s, options),
+        JsonObject::Array(_) | JsonObject::Object(_) => unreachable!("not a scalar"),
+    }
+}
+
+fn sorted_entries<'a>(
+    object: &'a Object,
+    options: &SerializeOptions,
+) -> Vec<&'a (Key, JsonObject)> {
+    let mut entries: Vec<_> = object.entries().iter().collect();
+    if options.sort_keys {
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+    entries
+}
+
+fn write_object<W: fmt::Write + ?Sized>(
+    out: &mut Output<'_, W>,
+    object: &Object,
+    options: &SerializeOptions,
+    depth: usize,
+) -> fmt::Result {
+    out.write_char('{')?;
+    for (i, (key, value)) in sorted_entries(object, options).into_iter().enumerate() {
+        if i != 0 {
+            out.write_char(',')?;
+        }
+        write_line_break(out, options, depth + 1)?;
+        write_string(out, key, options)?;
+        out.write_char(':')?;
+        if options.indent.is_some() {
+            out.write_char(' ')?;
+        }
+        write_value(
+            out,
+            value,
+            options,
+            depth + 1,
+            trailer_width(i, object.entries().len()),
+        )?;
+    }
+    if !object.entries().is_empty() {
+        write_line_break(out, options, depth)?;
+    }
+    out.write_char('}')
+}
+
+fn trailer_width(index: usize, len: usize) -> usize {
+    if index + 1 == len {
+        0
+    } else {
+        1
+    }
+}
+
+fn fits_inline(
+    column: usize,
+    value: &JsonObject,
+    options: &SerializeOptions,
+    trailer: usize,
+) -> bool {
+    let is_container = match value {
+        JsonObject::Array(array) => !array.is_empty(),
+        JsonObject::Object(object) => !object.entries().is_empty(),
+        _ => false,
+    };
+
+    let limit = match options.max_width {
+        Some(max_width) if is_container => match max_width.checked_sub(column + trailer) {
+            Some(limit) => limit,
+            None => return false,
+        },
+        None if options.inline_threshold > 0 && is_leaf_container(value) => {
+            options.inline_threshold
+        }
+        _ => return false,
+    };
+
+    let mut inline = String::new();
+    write_inline(&mut inline, value, options).unwrap();
+
+    inline.chars().count() <= limit
+}
+
+//a non-empty container whose members all render without line breaks
+fn is_leaf_container(value: &JsonObject) -> bool {
+    let is_flat = |value: &JsonObject| match value {
+        JsonObject::Array(array) => array.is_empty(),
+        JsonObject::Object(object) => object.entries().is_empty(),
+        _ => true,
+    };
+
+    match value {
+        JsonObject::Array(array) => !array.is_empty() && array.iter().all(is_flat),
+        JsonObject::Object(object) => !object.entries().is_empty() && object.values().all(is_flat),
+        _ => false,
+    }
+}
+
+//single line rendering used by the pretty printer, `[1, 2]` and `{"a": 1}`
+fn write_inline<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    value: &JsonObject,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    match value {
+        JsonObject::Array(array) => {
+            out.write_char('[')?;
+            for (i, value) in array.iter().enumerate() {
+                if i != 0 {
+                    out.write_str(", ")?;
+                }
+                write_inline(out, value, options)?;
+            }
+            out.write_char(']')
+        }
+        JsonObject::Object(object) => {
+            out.write_char('{')?;
+            for (i, (key, value)) in sorted_entries(object, options).into_iter().enumerate() {
+                if i != 0 {
+                    out.write_str(", ")?;
+                }
+                write_string(out, key, options)?;
+                out.write_str(": ")?;
+                write_inline(out, value, options)?;
+            }
+            out.write_char('}')
+        }
+        scalar => write_scalar(out, scalar, options),
+    }
+}
+
+//no-op in compact mode
+fn write_line_break<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    options: &SerializeOptions,
+    depth: usize,
+) -> fmt::Result {
+    if let Some(indent) = options.indent {
+        out.write_str(options.newline.as_str())?;
+        for _ in 0..indent * depth {
+            out.write_char(options.indent_style.as_char())?;
+        }
+    }
+    Ok(())
+}
+
+//`NonFinitePolicy::Error` is checked before writing, see `check_non_finite`. The shortest digits
+//that read back as `n`, with an exponent outside of [1e-5, 1e16) like serde_json writes them
+pub(crate) fn write_number<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    n: f64,
+    non_finite: NonFinitePolicy,
+) -> fmt::Result {
+    match non_finite {
+        _ if n == 0. || (1e-5..1e16).contains(&n.abs()) => write!(out, "{}", n),
+        _ if n.is_finite() => write!(out, "{:e}", n),
+        NonFinitePolicy::Null => out.write_str("null"),
+        NonFinitePolicy::Literal => out.write_str(non_finite_literal(n)),
+        NonFinitePolicy::Error => Err(fmt::Error),
+    }
+}
+
+pub(crate) fn non_finite_literal(n: f64) -> &'static str {
+    if n.is_nan() {
+        "NaN"
+    } else if n > 0. {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+//a walk of the whole document, only with `NonFinitePolicy::Error`, raw numbers are written as
+//their literal so they are never a problem
+fn check_non_finite(value: &JsonObject, options: &SerializeOptions) -> Result<(), SerializeError> {
+    let non_finite = |value: &JsonObject| matches!(value, JsonObject::Number(n) if !n.is_finite());
+
+    if options.non_finite == NonFinitePolicy::Error && value.descendants().any(non_finite) {
+        Err(SerializeError::NonFiniteNumber)
+    } else {
+        Ok(())
+    }
+}
+
+//shared by every writer path so that all of them escape identically
+pub(crate) fn write_string<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    s: &str,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    out.write_char('"')?;
+
+    for ch in s.chars() {
+        match ch {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{0C}' => out.write_str("\\f")?,
+            '\u{08}' => out.write_str("\\b")?,
+            '\u{2028}' | '\u{2029}' if options.js_safe || options.html_safe => {
+                write!(out, "\\u{:04x}", ch as u32)?;
+            }
+            '<' | '>' | '&' if options.html_safe => {
+                write!(out, "\\u{:04x}", ch as u32)?;
+            }
+            '/' if options.escape_solidus => out.write_str("\\/")?,
+            ch if ch < '\u{20}' => write!(out, "\\u{:04x}", ch as u32)?,
+            ch if !ch.is_ascii() && options.ascii_only => {
+                for unit in ch.encode_utf16(&mut [0; 2]) {
+                    write!(out, "\\u{:04x}", unit)?;
+                }
+            }
+            ch => out.write_char(ch)?,
+        }
+    }
+
+    out.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn number_forms() {
+        let text = |n: f64| JsonObject::Number(n).to_json_string();
+
+        assert_eq!(text(f64::MAX), "1.7976931348623157e308");
+        assert_eq!(text(f64::MIN), "-1.7976931348623157e308");
+        assert_eq!(text(f64::MIN_POSITIVE), "2.2250738585072014e-308");
+        assert_eq!(text(f64::from_bits(1)), "5e-324");
+        assert_eq!(text(1e16), "1e16");
+        assert_eq!(text(123456789012345680000.), "1.2345678901234568e20");
+        assert_eq!(text(-1.5e-7), "-1.5e-7");
+
+        //plain between 1e-5 and 1e16
+        assert_eq!(text(9999999999999998.), "9999999999999998");
+        assert_eq!(text(1e15), "1000000000000000");
+        assert_eq!(text(0.00001), "0.00001");
+        assert_eq!(text(0.1), "0.1");
+        assert_eq!(text(-0.), "-0");
+        assert_eq!(text(42.), "42");
+    }
+
+    #[test]
+    fn compact_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(
+            r#"{ "a" : [1, 2.5, -3e2, true, false, null], "b\"\\" : { "c" : "\t\u0001€😐" } }"#,
+        )?;
+
+        let serialized = json.to_json_string();
+
+        assert_eq!(
+            serialized,
+            r#"{"a":[1,2.5,-300,true,false,null],"b\"\\":{"c":"\t\u0001€😐"}}"#
+        );
+        assert_eq!(parse_json_string(&serialized)?, json);
+
+        Ok(())
+    }
+
+    #[test]
+    fn js_safe_line_terminators() -> Result<(), Box<dyn std::error::Error>> {
+        let json = JsonObject::String("a\u{2028}b\u{2029}c é".to_owned());
+
+        let options = SerializeOptions {
+            js_safe: true,
+            ..SerializeOptions::default()
+        };
+        let safe = json.to_json_string_with_options(&options);
+
+        assert_eq!(safe, r#""a\u2028b\u2029c é""#);
+        assert_eq!(parse_json_string(&safe)?, json);
+
+        //default mode leaves them raw
+        let raw = json.to_json_string();
+
+        assert_eq!(raw, "\"a\u{2028}b\u{2029}c é\"");
+        assert_eq!(parse_json_string(&raw)?, json);
+
+        Ok(())
+    }
+
+    #[test]
+    fn html_safe_profile() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(r#"{ "html" : "</script><b>a & b</b>\u2028" }"#)?;
+
+        let options = SerializeOptions {
+            html_safe: true,
+            ..SerializeOptions::default()
+        };
+        let safe = json.to_json_string_with_options(&options);
+
+        assert!(!safe.contains('<'));
+        assert!(!safe.contains('>'));
+        assert!(!safe.contains('&'));
+        assert!(!safe.contains('\u{2028}'));
+        assert_eq!(
+            safe,
+            r#"{"html":"\u003c/script\u003e\u003cb\u003ea \u0026 b\u003c/b\u003e\u2028"}"#
+        );
+        assert_eq!(parse_json_string(&safe)?, json);
+
+        //composes with js_safe rather than replacing it
+        let both = SerializeOptions {
+            js_safe: true,
+            html_safe: true,
+            ..SerializeOptions::default()
+        };
+
+        assert_eq!(json.to_json_string_with_options(&both), safe);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ascii_only_and_solidus() -> Result<(), Box<dyn std::error::Error>> {
+        let json = JsonObject::String("é😀\t</script>".to_owned());
+
+        let expected = [
+            (false, false, "\"é😀\\t</script>\""),
+            (false, true, "\"é😀\\t<\\/script>\""),
+            (true, false, r#""\u00e9\ud83d\ude00\t</script>""#),
+            (true, true, r#""\u00e9\ud83d\ude00\t<\/script>""#),
+        ];
+        for (ascii_only, escape_solidus, expected) in expected {
+            let options = SerializeOptions::builder()
+                .ascii_only(ascii_only)
+                .escape_solidus(escape_solidus)
+                .build()?;
+            let serialized = json.to_json_string_with_options(&options);
+
+            assert_eq!(serialized, expected);
+            assert_eq!(serialized.is_ascii(), ascii_only);
+            assert_eq!(parse_json_string(&serialized)?, json);
+        }
+
+        //keys are escaped the same way
+        let object = json!({ "clé/": "\u{1}\u{10FFFF}" });
+        let options = SerializeOptions::builder()
+            .ascii_only(true)
+            .escape_solidus(true)
+            .build()?;
+        let serialized = object.to_json_string_with_options(&options);
+        assert_eq!(serialized, r#"{"cl\u00e9\/":"\u0001\udbff\udfff"}"#);
+        assert_eq!(parse_json_string(&serialized)?, object);
+
+        Ok(())
+    }
+
+    const NESTED: &str = r#"{"a":[1,{"b":null}],"c":{},"d":[]}"#;
+
+    #[test]
+    fn display() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(NESTED)?;
+
+        assert_eq!(json.to_string(), json.to_json_string());
+        assert_eq!(format!("{:#}", json), json.to_json_string_pretty(2));
+        assert_eq!(parse_json_string(&json.to_string())?, json);
+
+        let scalars = parse_json_string(r#"[ "q\"\\\n\u0007😐", 10, 0.5, null ]"#)?;
+        assert_eq!(scalars.to_string(), r#"["q\"\\\n\u0007😐",10,0.5,null]"#);
+        assert_eq!(JsonObject::Boolean(true).to_string(), "true");
+
+        Ok(())
+    }
+
+    #[test]
+    fn pretty_output() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(NESTED)?;
+
+        let pretty = json.to_json_string_pretty(2);
+
+        assert_eq!(
+            pretty,
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": {},\n  \"d\": []\n}"
+        );
+        assert_eq!(parse_json_string(&pretty)?, json);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tab_indentation() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(NESTED)?;
+
+        let options = SerializeOptions::builder()
+            .indent(Some(1))
+            .indent_style(IndentStyle::Tabs)
+            .build()?;
+        let pretty = json.serialize(&options)?;
+
+        assert_eq!(
+            pretty,
+            "{\n\t\"a\": [\n\t\t1,\n\t\t{\n\t\t\t\"b\": null\n\t\t}\n\t],\n\t\"c\": {},\n\t\"d\": []\n}"
+        );
+        assert_eq!(parse_json_string(&pretty)?, json);
+
+        //the style alone does not turn indentation on
+        let compact = SerializeOptions {
+            indent_style: IndentStyle::Tabs,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(json.serialize(&compact)?, json.to_json_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn newline_options() -> Result<(), Box<dyn std::error::Error>> {
+        let golden = [
+            (None, NewlineStyle::Lf, false, r#"{"a":[1]}"#),
+            (None, NewlineStyle::Lf, true, "{\"a\":[1]}\n"),
+            (None, NewlineStyle::CrLf, false, r#"{"a":[1]}"#),
+            (None, NewlineStyle::CrLf, true, "{\"a\":[1]}\r\n"),
+            (Some(1), NewlineStyle::Lf, false, "{\n \"a\": [\n  1\n ]\n}"),
+            (
+                Some(1),
+                NewlineStyle::Lf,
+                true,
+                "{\n \"a\": [\n  1\n ]\n}\n",
+            ),
+            (
+                Some(1),
+                NewlineStyle::CrLf,
+                false,
+                "{\r\n \"a\": [\r\n  1\r\n ]\r\n}",
+            ),
+            (
+                Some(1),
+                NewlineStyle::CrLf,
+                true,
+                "{\r\n \"a\": [\r\n  1\r\n ]\r\n}\r\n",
+            ),
+        ];
+
+        for (indent, newline, trailing_newline, expected) in golden.iter() {
+            let options = SerializeOptions {
+                indent: *indent,
+                newline: *newline,
+                trailing_newline: *trailing_newline,
+                ..SerializeOptions::default()
+            };
+
+            assert_eq!(format_json(r#"{ "a" : [ 1 ] }"#, &options)?, *expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_never_emits_bare_lf() -> Result<(), Box<dyn std::error::Error>> {
+        let options = SerializeOptions {
+            indent: Some(4),
+            newline: NewlineStyle::CrLf,
+            trailing_newline: true,
+            ..SerializeOptions::default()
+        };
+
+        //the string value contains an escaped newline, which must stay escaped
+        let formatted = format_json(r#"{"a":[1,{"b":"x\ny"}],"c":{}}"#, &options)?;
+
+        for (i, _) in formatted.match_indices('\n') {
+            assert_eq!(&formatted[i - 1..i], "\r");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn format_json_is_idempotent() -> Result<(), Box<dyn std::error::Error>> {
+        for indent in [None, Some(0), Some(2)].iter() {
+            for newline in [NewlineStyle::Lf, NewlineStyle::CrLf].iter() {
+                for trailing_newline in [false, true].iter() {
+                    let options = SerializeOptions {
+                        indent: *indent,
+                        newline: *newline,
+                        trailing_newline: *trailing_newline,
+                        ..SerializeOptions::default()
+                    };
+
+                    let once = format_json(NESTED, &options)?;
+
+                    assert_eq!(format_json(&once, &options)?, once);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    const FEATURES: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": [[102.0, 0.5], [103.0, 1.5]] },
+                "properties": { "name": "a\"b" }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn inline_short_containers() -> Result<(), Box<dyn std::error::Error>> {
+        let options = SerializeOptions {
+            indent: Some(2),
+            inline_threshold: 20,
+            ..SerializeOptions::default()
+        };
+
+        let expected = r#"{
+  "type": "FeatureCollection",
+  "features": [
+    {
+      "type": "Feature",
+      "geometry": {
+        "type": "LineString",
+        "coordinates": [
+          [102, 0.5],
+          [103, 1.5]
+        ]
+      },
+      "properties": {"name": "a\"b"}
+    }
+  ]
+}"#;
+
+        let formatted = format_json(FEATURES, &options)?;
+
+        assert_eq!(formatted, expected);
+        assert_eq!(parse_json_string(&formatted)?, parse_json_string(FEATURES)?);
+
+        //the escaped quote makes `{"name": "a\"b"}` 16 characters wide rather than 15
+        let options = SerializeOptions {
+            inline_threshold: 15,
+            ..options
+        };
+
+        assert!(format_json(FEATURES, &options)?.contains("\"properties\": {\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn zero_inline_threshold_always_breaks() -> Result<(), Box<dyn std::error::Error>> {
+        let options = SerializeOptions {
+            indent: Some(2),
+            inline_threshold: 0,
+            ..SerializeOptions::default()
+        };
+
+        let json = parse_json_string(FEATURES)?;
+
+        assert_eq!(
+            json.to_json_string_with_options(&options),
+            json.to_json_string_pretty(2)
+        );
+
+        Ok(())
+    }
+
+    const ROUTE: &str = r#"{"type":"FeatureCollection","features":[{"type":"Feature","geometry":{"type":"LineString","coordinates":[[102.0,0.5],[103.0,1.5],[104.0,0.25]]},"properties":{"name":"route","tags":["a","b"]}}]}"#;
+
+    fn with_width(max_width: usize) -> SerializeOptions {
+        SerializeOptions {
+            indent: Some(2),
+            max_width: Some(max_width),
+            ..SerializeOptions::default()
+        }
+    }
+
+    #[test]
+    fn max_width_golden() -> Result<(), Box<dyn std::error::Error>> {
+        let narrow = r#"{
+  "type": "FeatureCollection",
+  "features": [
+    {
+      "type": "Feature",
+      "geometry": {
+        "type": "LineString",
+        "coordinates": [
+          [102, 0.5],
+          [103, 1.5],
+          [104, 0.25]
+        ]
+      },
+      "properties": {
+        "name": "route",
+        "tags": ["a", "b"]
+      }
+    }
+  ]
+}"#;
+
+        let medium = r#"{
+  "type": "FeatureCollection",
+  "features": [
+    {
+      "type": "Feature",
+      "geometry": {
+        "type": "LineString",
+        "coordinates": [[102, 0.5], [103, 1.5], [104, 0.25]]
+      },
+      "properties": {"name": "route", "tags": ["a", "b"]}
+    }
+  ]
+}"#;
+
+        let wide = r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[102, 0.5], [103, 1.5], [104, 0.25]]}, "properties": {"name": "route", "tags": ["a", "b"]}}]}"#;
+
+        assert_eq!(format_json(ROUTE, &with_width(40))?, narrow);
+        assert_eq!(format_json(ROUTE, &with_width(80))?, medium);
+        assert_eq!(format_json(ROUTE, &with_width(usize::MAX))?, wide);
+
+        for line in narrow.lines() {
+            assert!(line.chars().count() <= 40);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_width_is_idempotent() -> Result<(), Box<dyn std::error::Error>> {
+        for width in [0, 20, 40, 80, usize::MAX].iter() {
+            let once = format_json(ROUTE, &with_width(*width))?;
+
+            assert_eq!(format_json(&once, &with_width(*width))?, once);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_width_takes_precedence_over_inline_threshold() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let options = SerializeOptions {
+            inline_threshold: usize::MAX,
+            ..with_width(40)
+        };
+
+        assert_eq!(
+            format_json(ROUTE, &options)?,
+            format_json(ROUTE, &with_width(40))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn format_json_errors() {
+        let error = SerializeOptions {
+            non_finite: NonFinitePolicy::Error,
+            ..SerializeOptions::compact()
+        };
+        assert_eq!(
+            format_json("[1e400]", &error),
+            Err(FormatError::Serialize(SerializeError::NonFiniteNumber))
+        );
+
+        let conflicting = SerializeOptions {
+            sort_keys: false,
+            ..SerializeOptions::canonical()
+        };
+        assert!(matches!(
+            format_json("[]", &conflicting),
+            Err(FormatError::Serialize(SerializeError::ConflictingOptions(
+                _
+            )))
+        ));
+
+        assert!(matches!(
+            format_json("[1,", &SerializeOptions::compact()),
+            Err(FormatError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn presets() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(r#"{"b":"<\u2028>","a":[1,2]}"#)?;
+
+        assert_eq!(
+            json.serialize(&SerializeOptions::compact())?,
+            "{\"b\":\"<\u{2028}>\",\"a\":[1,2]}"
+        );
+        assert_eq!(
+            json.serialize(&SerializeOptions::pretty())?,
+            "{\n  \"b\": \"<\u{2028}>\",\n  \"a\": [\n    1,\n    2\n  ]\n}"
+        );
+        assert_eq!(
+            json.serialize(&SerializeOptions::canonical())?,
+            "{\"a\":[1,2],\"b\":\"<\u{2028}>\"}"
+        );
+        assert_eq!(
+            json.serialize(&SerializeOptions::html_safe())?,
+            r#"{"b":"\u003c\u2028\u003e","a":[1,2]}"#
+        );
+
+        //convenience methods are wrappers around the presets
+        assert_eq!(
+            json.to_json_string(),
+            json.serialize(&SerializeOptions::compact())?
+        );
+        assert_eq!(
+            json.to_json_string_pretty(2),
+            json.serialize(&SerializeOptions::pretty())?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn builder() -> Result<(), Box<dyn std::error::Error>> {
+        let options = SerializeOptions::builder()
+            .indent(Some(4))
+            .sort_keys(true)
+            .trailing_newline(true)
+            .build()?;
+
+        assert_eq!(
+            parse_json_string(r#"{"b":1,"a":2}"#)?.serialize(&options)?,
+            "{\n    \"a\": 2,\n    \"b\": 1\n}\n"
+        );
+
+        let options = SerializeOptions::pretty()
+            .into_builder()
+            .inline_threshold(80)
+            .build()?;
+
+        assert_eq!(
+            parse_json_string("[[1, 2]]")?.serialize(&options)?,
+            "[\n  [1, 2]\n]"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_options_are_rejected() {
+        let conflicting = SerializeOptions::canonical()
+            .into_builder()
+            .sort_keys(false);
+
+        assert!(matches!(
+            conflicting.clone().build(),
+            Err(SerializeError::ConflictingOptions(_))
+        ));
+
+        let options = SerializeOptions {
+            sort_keys: false,
+            ..SerializeOptions::canonical()
+        };
+
+        assert_eq!(
+            JsonObject::Null.serialize(&options),
+            Err(SerializeError::ConflictingOptions(
+                "canonical output requires sorted keys"
+            ))
+        );
+
+        let indented = SerializeOptions::canonical()
+            .into_builder()
+            .indent(Some(2))
+            .build();
+
+        assert!(matches!(
+            indented,
+            Err(SerializeError::ConflictingOptions(_))
+        ));
+    }
+
+    #[test]
+    fn display_string() -> Result<(), Box<dyn std::error::Error>> {
+        let json = parse_json_string(
+            r#"["a \"quoted\" \u00e9", 0.1, 100, 1e21, -2.5e-7, true, false, null, [], {}]"#,
+        )?;
+
+        let rendered: Vec<_> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(JsonObject::to_display_string)
+            .collect();
+
+        assert_eq!(
+            rendered,
+            vec![
+                Some("a \"quoted\" é".to_owned()),
+                Some("0.1".to_owned()),
+                Some("100".to_owned()),
+                Some("1e21".to_owned()),
+                Some("-2.5e-7".to_owned()),
+                Some("true".to_owned()),
+                Some("false".to_owned()),
+                Some("".to_owned()),
+                None,
+                None,
+            ]
+        );
+
+        //0.1 + 0.2 has no shorter exact representation, only the shortest round trip is shown
+        assert_eq!(
+            JsonObject::Number(0.1 + 0.2).to_display_string().unwrap(),
+            "0.30000000000000004"
+        );
+        assert_eq!(
+            JsonObject::Number(1.0 / 3.0 * 3.0)
+                .to_display_string()
+                .unwrap(),
+            "1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn streaming_writes() -> Result<(), Box<dyn std::error::Error>> {
+        let long = "\"é\n".repeat(5000);
+        let json = json!({
+            "name": "stream",
+            "nested": [1, 2.5, { "deep": [null, true, "\u{1F600}"] }, []],
+            "long": long,
+            "empty": {}
+        });
+
+        let mut sink = Vec::new();
+        json.write_to(&mut sink)?;
+        assert_eq!(String::from_utf8(sink)?, json.to_json_string());
+
+        let mut sink = Vec::new();
+        json.write_pretty_to(&mut sink, 4)?;
+        assert_eq!(String::from_utf8(sink)?, json.to_json_string_pretty(4));
+
+        let options = SerializeOptions::builder()
+            .indent(Some(2))
+            .max_width(Some(30))
+            .ascii_only(true)
+            .trailing_newline(true)
+            .build()?;
+        let mut sink = Vec::new();
+        json.write_to_with_options(&mut sink, &options)?;
+        assert_eq!(
+            String::from_utf8(sink)?,
+            json.to_json_string_with_options(&options)
+        );
+
+        let mut out = String::from("json: ");
+        json.write_to_fmt(&mut out)?;
+        assert_eq!(out, format!("json: {}", json));
+        assert_eq!(format!("{:#}", json), json.to_json_string_pretty(2));
+
+        //rejected before anything is written
+        let conflicting = SerializeOptions {
+            indent: Some(2),
+            ..SerializeOptions::canonical()
+        };
+        let mut sink = Vec::new();
+        let err = json
+            .write_to_with_options(&mut sink, &conflicting)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(sink.is_empty());
+
+        Ok(())
+    }
+
+    //accepts `capacity` bytes then fails every write
+    struct FailingWriter {
+        written: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.capacity - self.written.len());
+            if n == 0 {
+                return Err(std::io::Error::other("disk full"));
+            }
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_errors_propagate() {
+        let json = JsonObject::Array(vec![json!("0123456789"); 10_000]);
+
+        for capacity in [0, 10, 100_000] {
+            let mut writer = FailingWriter {
+                written: vec![],
+                capacity,
+            };
+
+            let err = json.write_to(&mut writer).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::Other);
+            assert_eq!(err.to_string(), "disk full");
+            assert_eq!(writer.written.len(), capacity);
+            assert!(json
+                .to_json_string()
+                .as_bytes()
+                .starts_with(&writer.written));
+        }
+    }
+
+    #[test]
+    fn non_finite_policies() -> Result<(), Box<dyn std::error::Error>> {
+        let json = json!({ "a": [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1.5] });
+        let with = |non_finite| SerializeOptions {
+            non_finite,
+            ..SerializeOptions::default()
+        };
+
+        let nulls = json.serialize(&with(NonFinitePolicy::Null))?;
+        assert_eq!(nulls, r#"{"a":[null,null,null,1.5]}"#);
+        assert_eq!(nulls, json.to_json_string());
+        assert_eq!(
+            parse_json_string(&nulls)?,
+            json!({ "a": [null, null, null, 1.5] })
+        );
+
+        let literals = json.serialize(&with(NonFinitePolicy::Literal))?;
+        assert_eq!(literals, r#"{"a":[NaN,Infinity,-Infinity,1.5]}"#);
+        let lenient = ParseOptions {
+            allow_non_finite_numbers: true,
+            ..ParseOptions::default()
+        };
+        let read_back = parse_json_string_with_options(&literals, &lenient)?;
+        assert!(read_back["a"][0].as_f64().is_some_and(f64::is_nan));
+        assert_eq!(read_back["a"][1], json!(f64::INFINITY));
+        assert_eq!(read_back["a"][2], json!(f64::NEG_INFINITY));
+        assert_eq!(
+            read_back.serialize(&with(NonFinitePolicy::Literal))?,
+            literals
+        );
+
+        let error = with(NonFinitePolicy::Error);
+        assert_eq!(json.serialize(&error), Err(SerializeError::NonFiniteNumber));
+        assert_eq!(json!([1, 2.5]).serialize(&error)?, "[1,2.5]");
+        //raw numbers are written as their literal, even one too big for an f64
+        let raw = JsonObject::Array(vec![JsonObject::RawNumber("1e400".to_owned())]);
+        assert_eq!(raw.serialize(&error)?, "[1e400]");
+        let mut written = vec![];
+        let err = json
+            .write_to_with_options(&mut written, &error)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(written.is_empty());
+
+        //streamed, numbers overflowing to infinity are the only ones strict input can hold
+        let reencoded = |non_finite| {
+            let mut out = vec![];
+            reencode(&b"[ 1, 1e400 ]"[..], &mut out, &with(non_finite)).map(|_| out)
+        };
+        assert_eq!(reencoded(NonFinitePolicy::Null)?, b"[1,null]");
+        assert_eq!(reencoded(NonFinitePolicy::Literal)?, b"[1,Infinity]");
+        assert!(matches!(
+            reencoded(NonFinitePolicy::Error),
+            Err(ReencodeError::Options(SerializeError::NonFiniteNumber))
+        ));
+
+        Ok(())
+    }
+
+    proptest! {
+        //every parser reads back exactly the number that was written
+        #[test]
+        fn numbers_round_trip(n in prop::num::f64::NORMAL
+            | prop::num::f64::SUBNORMAL
+            | prop::num::f64::ZERO
+            | prop::num::f64::NEGATIVE
+            | prop::num::f64::POSITIVE) {
+            let text = JsonObject::Number(n).to_json_string();
+            let bits = |parsed: Option<f64>| parsed.map(f64::to_bits);
+
+            prop_assert_eq!(
+                bits(parse_json_string(&text).unwrap().as_f64()),
+                Some(n.to_bits()),
+                "{}",
+                text
+            );
+            prop_assert_eq!(
+                bits(parse_json_str_borrowed(&text).unwrap().to_owned().as_f64()),
+                Some(n.to_bits())
+            );
+            prop_assert_eq!(
+                bits(parse_json_bytes(text.as_bytes()).unwrap().as_f64()),
+                Some(n.to_bits())
+            );
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt;
+
+use crate::walk::{JsonPath, PathSegment};
+use crate::{JsonObject, JsonType};
+
+/// The expected shape of a document, checked by [`JsonObject::validate`]. A light subset of
+/// JSON Schema: types, object members and array elements.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// An object with these members. Duplicated keys have each of their values checked.
+    Object(Vec<(String, Shape, Required)>),
+    /// An array whose every element has the shape.
+    Array(Box<Shape>),
+    String,
+    /// A [`JsonObject::Number`] or [`JsonObject::RawNumber`].
+    Number,
+    Boolean,
+    Null,
+    Any,
+    /// Any of the shapes, e.g. `OneOf(vec![Shape::String, Shape::Null])` for a nullable string.
+    OneOf(Vec<Shape>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Required {
+    Yes,
+    No,
+}
+
+/// What to do with object members the shape does not list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownKeys {
+    #[default]
+    Allow,
+    /// Report each of them as a [`ShapeErrorKind::UnknownKey`].
+    Reject,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidateOptions {
+    pub unknown_keys: UnknownKeys,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeErrorKind {
+    /// The value is not of a type the shape allows, `expected` describes the shape.
+    Mismatch {
+        expected: String,
+        found: JsonType,
+    },
+    /// A required member is absent, the path is where it would be.
+    MissingKey,
+    UnknownKey,
+}
+
+impl fmt::Display for ShapeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeErrorKind::Mismatch { expected, found } => {
+                write!(f, "expected {} but found {}", expected, found)
+            }
+            ShapeErrorKind::MissingKey => f.write_str("missing required key"),
+            ShapeErrorKind::UnknownKey => f.write_str("unknown key"),
+        }
+    }
+}
+
+/// A value that does not have its expected shape and where it is in the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeError {
+    pub kind: ShapeErrorKind,
+    pub path: JsonPath,
+}
+
+//`expected number but found string at "/servers/0/port"`
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {:?}", self.kind, self.path.to_pointer())
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+impl Shape {
+    #[inline]
+    pub fn array_of(elements: Shape) -> Shape {
+        Shape::Array(Box::new(elements))
+    }
+
+    //whether the value has the type of the shape, regardless of what is inside it
+    fn admits(&self, value: &JsonObject) -> bool {
+        match self {
+            Shape::Object(_) => value.is_object(),
+            Shape::Array(_) => value.is_array(),
+            Shape::String => value.is_string(),
+            Shape::Number => value.is_number(),
+            Shape::Boolean => value.is_boolean(),
+            Shape::Null => value.is_null(),
+            Shape::Any => true,
+            Shape::OneOf(shapes) => shapes.iter().any(|shape| shape.admits(value)),
+        }
+    }
+
+    //`object`, `string or null`...
+    fn describe(&self) -> String {
+        match self {
+            Shape::Object(_) => JsonType::Object.to_string(),
+            Shape::Array(_) => JsonType::Array.to_string(),
+            Shape::String => JsonType::String.to_string(),
+            Shape::Number => JsonType::Number.to_string(),
+            Shape::Boolean => JsonType::Boolean.to_string(),
+            Shape::Null => JsonType::Null.to_string(),
+            Shape::Any => "anything".to_owned(),
+            Shape::OneOf(shapes) => describe_one_of(shapes),
+        }
+    }
+}
+
+fn describe_one_of(shapes: &[Shape]) -> String {
+    if shapes.is_empty() {
+        return "nothing".to_owned();
+    }
+
+    shapes
+        .iter()
+        .map(Shape::describe)
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+impl JsonObject {
+    /// Checks the value against the shape, allowing members the shape does not list. Every
+    /// error is reported, not only the first one.
+    #[inline]
+    pub fn validate(&self, shape: &Shape) -> Result<(), Vec<ShapeError>> {
+        self.validate_with_options(shape, &ValidateOptions::default())
+    }
+
+    pub fn validate_with_options(
+        &self,
+        shape: &Shape,
+        options: &ValidateOptions,
+    ) -> Result<(), Vec<ShapeError>> {
+        let mut validation = Validation {
+            options,
+            path: vec![],
+            errors: vec![],
+        };
+        validation.value(self, shape);
+
+        if validation.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(validation.errors)
+        }
+    }
+}
+
+struct Validation<'a> {
+    options: &'a ValidateOptions,
+    path: Vec<PathSegment>,
+    errors: Vec<ShapeError>,
+}
+
+impl Validation<'_> {
+    fn value(&mut self, value: &JsonObject, shape: &Shape) {
+        match (shape, value) {
+            (Shape::Object(members), JsonObject::Object(object)) => {
+                for (key, value) in object {
+                    self.path.push(PathSegment::Key(key.to_owned()));
+                    match members.iter().find(|(name, _, _)| name == key) {
+                        Some((_, shape, _)) => self.value(value, shape),
+                        None if self.options.unknown_keys == UnknownKeys::Reject => {
+                            self.error(ShapeErrorKind::UnknownKey)
+                        }
+                        None => {}
+                    }
+                    self.path.pop();
+                }
+
+                for (name, _, required) in members {
+                    if *required == Required::Yes && !object.contains_key(name) {
+                        self.path.push(PathSegment::Key(name.clone()));
+                        self.error(ShapeErrorKind::MissingKey);
+                        self.path.pop();
+                    }
+                }
+            }
+            (Shape::Array(elements), JsonObject::Array(array)) => {
+                for (i, value) in array.iter().enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    self.value(value, elements);
+                    self.path.pop();
+                }
+            }
+            (Shape::OneOf(shapes), value) => self.one_of(value, shapes),
+            (shape, value) if shape.admits(value) => {}
+            (shape, value) => self.error(ShapeErrorKind::Mismatch {
+                expected: shape.describe(),
+                found: value.json_type(),
+            }),
+        }
+    }
+
+    //the first alternative of the right type without errors, or else the errors of the first one
+    //of the right type, so that a wrong member of a nullable object is reported as such
+    fn one_of(&mut self, value: &JsonObject, shapes: &[Shape]) {
+        let mut candidates = shapes.iter().filter(|shape| shape.admits(value));
+
+        let first = match candidates.next() {
+            Some(first) => first,
+            None => {
+                return self.error(ShapeErrorKind::Mismatch {
+                    expected: describe_one_of(shapes),
+                    found: value.json_type(),
+                })
+            }
+        };
+
+        let reported = self.errors.len();
+        self.value(value, first);
+        if self.errors.len() == reported {
+            return;
+        }
+
+        for shape in candidates {
+            let mut alternative = Validation {
+                options: self.options,
+                path: self.path.clone(),
+                errors: vec![],
+            };
+            alternative.value(value, shape);
+            if alternative.errors.is_empty() {
+                self.errors.truncate(reported);
+                return;
+            }
+        }
+    }
+
+    fn error(&mut self, kind: ShapeErrorKind) {
+        self.errors.push(ShapeError {
+            kind,
+            path: JsonPath::from_segments(self.path.clone()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn server() -> Shape {
+        Shape::Object(vec![
+            ("host".to_owned(), Shape::String, Required::Yes),
+            ("port".to_owned(), Shape::Number, Required::Yes),
+            (
+                "tls".to_owned(),
+                Shape::OneOf(vec![
+                    Shape::Null,
+                    Shape::Object(vec![
+                        ("cert".to_owned(), Shape::String, Required::Yes),
+                        ("verify".to_owned(), Shape::Boolean, Required::No),
+                    ]),
+                ]),
+                Required::No,
+            ),
+        ])
+    }
+
+    fn config() -> Shape {
+        Shape::Object(vec![
+            ("name".to_owned(), Shape::String, Required::Yes),
+            (
+                "servers".to_owned(),
+                Shape::array_of(server()),
+                Required::Yes,
+            ),
+            (
+                "tags".to_owned(),
+                Shape::array_of(Shape::OneOf(vec![Shape::String, Shape::Number])),
+                Required::No,
+            ),
+            ("extra".to_owned(), Shape::Any, Required::No),
+        ])
+    }
+
+    fn mismatch(pointer: &str, expected: &str, found: JsonType) -> (String, ShapeErrorKind) {
+        (
+            pointer.to_owned(),
+            ShapeErrorKind::Mismatch {
+                expected: expected.to_owned(),
+                found,
+            },
+        )
+    }
+
+    fn errors(result: Result<(), Vec<ShapeError>>) -> Vec<(String, ShapeErrorKind)> {
+        result
+            .unwrap_err()
+            .into_iter()
+            .map(|error| (error.path.to_pointer(), error.kind))
+            .collect()
+    }
+
+    #[test]
+    fn valid_config() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{
+                "name" : "edge",
+                "servers" : [
+                    { "host" : "a.example", "port" : 443, "tls" : { "cert" : "a.pem" } },
+                    { "host" : "b.example", "port" : 8080, "tls" : null, "weight" : 2 }
+                ],
+                "tags" : [ "prod", 1 ],
+                "extra" : { "anything" : [ true ] }
+            }"#,
+        )?;
+
+        assert_eq!(json.validate(&config()), Ok(()));
+
+        let strict = ValidateOptions {
+            unknown_keys: UnknownKeys::Reject,
+        };
+        assert_eq!(
+            errors(json.validate_with_options(&config(), &strict)),
+            [("/servers/1/weight".to_owned(), ShapeErrorKind::UnknownKey)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn every_error_at_every_depth() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{
+                "servers" : [
+                    { "host" : "a.example", "port" : "443" },
+                    { "port" : 80, "tls" : { "cert" : 1, "verify" : "yes" } },
+                    { "host" : "c.example", "port" : 1, "tls" : true },
+                    "d.example"
+                ],
+                "tags" : [ "prod", null ]
+            }"#,
+        )?;
+
+        assert_eq!(
+            errors(json.validate(&config())),
+            [
+                mismatch("/servers/0/port", "number", JsonType::String),
+                mismatch("/servers/1/tls/cert", "string", JsonType::Number),
+                mismatch("/servers/1/tls/verify", "boolean", JsonType::String),
+                ("/servers/1/host".to_owned(), ShapeErrorKind::MissingKey),
+                mismatch("/servers/2/tls", "null or object", JsonType::Boolean),
+                mismatch("/servers/3", "object", JsonType::String),
+                mismatch("/tags/1", "string or number", JsonType::Null),
+                ("/name".to_owned(), ShapeErrorKind::MissingKey),
+            ]
+        );
+
+        let error = &json.validate(&config()).unwrap_err()[0];
+        assert_eq!(
+            error.to_string(),
+            r#"expected number but found string at "/servers/0/port""#
+        );
+
+        //the document itself
+        assert_eq!(
+            errors(json!([1]).validate(&config())),
+            [mismatch("", "object", JsonType::Array)]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{JsonObject, Key, Object, ObjectImpl};
+
+/// Object entries kept sorted by key so lookups are a binary search, built from an [`Object`]
+/// at the cost of its insertion order. Duplicate keys keep their relative order and, as with
+/// [`Object::get`], lookups find the first of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SortedObject {
+    entries: ObjectImpl,
+}
+
+impl SortedObject {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //index of the first entry whose key is not less than `key`
+    fn lower_bound(&self, key: &str) -> usize {
+        self.entries
+            .partition_point(|(existing, _)| existing.as_str() < key)
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        let index = self.lower_bound(key);
+        match self.entries.get(index) {
+            Some((existing, _)) if existing == key => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonObject> {
+        Some(&self.entries[self.position(key)?].1)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonObject> {
+        let index = self.position(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Replaces the value of the first entry with this key and returns the old one, or adds an
+    /// entry at its sorted position.
+    pub fn insert(&mut self, key: impl Into<Key>, value: JsonObject) -> Option<JsonObject> {
+        let key = key.into();
+        let index = self.lower_bound(&key);
+
+        match self.entries.get_mut(index) {
+            Some((existing, old)) if *existing == key => Some(std::mem::replace(old, value)),
+            _ => {
+                self.entries.insert(index, (key, value));
+                None
+            }
+        }
+    }
+
+    //removes the first entry with this key
+    pub fn remove(&mut self, key: &str) -> Option<JsonObject> {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    //no mutable access to the entries, it could break the ordering
+    #[inline]
+    pub fn entries(&self) -> &ObjectImpl {
+        &self.entries
+    }
+}
+
+impl From<Object> for SortedObject {
+    fn from(object: Object) -> Self {
+        let mut entries = object.entries;
+        //stable, duplicates stay in insertion order
+        entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+        SortedObject { entries }
+    }
+}
+
+impl From<SortedObject> for Object {
+    #[inline]
+    fn from(sorted: SortedObject) -> Self {
+        Object::from_impl(sorted.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use proptest::prelude::*;
+
+    //out of few possible ones, so that there are duplicates
+    fn keys(count: std::ops::Range<usize>) -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec((0..100u32).prop_map(|n| format!("key{}", n)), count)
+    }
+
+    proptest! {
+        #[test]
+        fn matches_linear_lookup(keys in keys(0..300)) {
+            let mut object = Object::new();
+            for (i, key) in keys.into_iter().enumerate() {
+                object
+                    .entries_mut()
+                    .push((key.into(), JsonObject::Number(i as f64)));
+            }
+            let sorted = SortedObject::from(object.clone());
+
+            prop_assert_eq!(sorted.len(), object.entries().len());
+            prop_assert!(sorted
+                .entries()
+                .windows(2)
+                .all(|pair| pair[0].0 <= pair[1].0));
+
+            for key in (0..120).map(|n| format!("key{}", n)) {
+                prop_assert_eq!(sorted.get(&key), object.get(&key), "{}", key);
+            }
+            prop_assert_eq!(sorted.get(""), None);
+            prop_assert_eq!(sorted.get("key"), None);
+        }
+
+        #[test]
+        fn insert_then_get(keys in keys(1..200)) {
+            let mut sorted = SortedObject::new();
+            prop_assert!(sorted.is_empty());
+
+            for key in keys {
+                let value = JsonObject::String(key.clone());
+                let replaced = sorted.get(&key).is_some();
+                prop_assert_eq!(sorted.insert(key.clone(), value).is_some(), replaced);
+                prop_assert_eq!(sorted.get(&key), Some(&JsonObject::String(key)));
+            }
+
+            prop_assert!(sorted
+                .entries()
+                .windows(2)
+                .all(|pair| pair[0].0 < pair[1].0));
+
+            let first = sorted.entries()[0].0.clone();
+            prop_assert_eq!(
+                sorted.remove(&first),
+                Some(JsonObject::String(first.to_string()))
+            );
+            prop_assert_eq!(sorted.get(&first), None);
+            prop_assert_eq!(sorted.remove(&first), None);
+
+            //the last key is still there unless it was the only one
+            if let Some((last, _)) = sorted.entries().last() {
+                let last = last.clone();
+                *sorted.get_mut(&last).unwrap() = JsonObject::Null;
+                prop_assert_eq!(sorted.get(&last), Some(&JsonObject::Null));
+            }
+        }
+    }
+
+    #[test]
+    fn conversion_round_trip() -> Result<(), JsonError> {
+        let object = parse_json_string(r#"{ "b" : 1, "a" : 2, "c" : 3, "a" : 4 }"#)?
+            .into_object()
+            .unwrap();
+
+        let sorted = SortedObject::from(object);
+        assert_eq!(sorted.get("a"), Some(&JsonObject::Number(2.)));
+
+        //insertion order is gone, duplicates keep theirs
+        assert_eq!(
+            Object::from(sorted),
+            parse_json_string(r#"{ "a" : 2, "a" : 4, "b" : 1, "c" : 3 }"#)?
+                .into_object()
+                .unwrap()
+        );
+
+        Ok(())
+    }
+}
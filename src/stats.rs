@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+
+use crate::pointer::push_token;
+use crate::{parse_json_string, JsonError, JsonObject, Key};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseStats {
+    /// Every char of the input, trailing whitespace included.
+    pub chars: usize,
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+    /// Most containers open at once, 0 for a lone scalar.
+    pub max_depth: usize,
+    /// UTF-8 length of all keys and string values after unescaping.
+    pub string_bytes: usize,
+}
+
+/// [`parse_json_string`] plus counters describing the document. They are taken from the
+/// finished tree, so a failed parse has none and the plain entry points pay nothing for them.
+pub fn parse_json_string_with_stats(input: &str) -> Result<(JsonObject, ParseStats), JsonError> {
+    let value = parse_json_string(input)?;
+    let stats = value.stats();
+
+    let stats = ParseStats {
+        chars: input.chars().count(),
+        objects: stats.object_count,
+        arrays: stats.array_count,
+        strings: stats.string_count,
+        numbers: stats.number_count,
+        booleans: stats.boolean_count,
+        nulls: stats.null_count,
+        max_depth: stats.max_depth,
+        string_bytes: stats.total_string_len,
+    };
+
+    Ok((value, stats))
+}
+
+/// The size and shape of a document, see [`JsonObject::stats`]. Lengths of strings are UTF-8
+/// bytes, keys included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonStats {
+    /// Every value, containers and the document itself included, keys not.
+    pub total_nodes: usize,
+    /// Most containers open at once, 0 for a lone scalar.
+    pub max_depth: usize,
+    pub object_count: usize,
+    pub array_count: usize,
+    pub string_count: usize,
+    pub number_count: usize,
+    pub boolean_count: usize,
+    pub null_count: usize,
+    pub max_array_len: usize,
+    /// Entries of the largest object, duplicate keys included.
+    pub max_object_len: usize,
+    pub max_string_len: usize,
+    pub total_string_len: usize,
+}
+
+impl JsonStats {
+    fn add_string(&mut self, s: &str) {
+        self.max_string_len = self.max_string_len.max(s.len());
+        self.total_string_len += s.len();
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyStats {
+    /// Occurrences of the path, the sum of the per-type counts below.
+    pub count: usize,
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+}
+
+impl KeyStats {
+    fn add(&mut self, value: &JsonObject) {
+        self.count += 1;
+
+        let counter = match value {
+            JsonObject::Object(_) => &mut self.objects,
+            JsonObject::Array(_) => &mut self.arrays,
+            JsonObject::String(_) => &mut self.strings,
+            JsonObject::Number(_) | JsonObject::RawNumber(_) => &mut self.numbers,
+            JsonObject::Boolean(_) => &mut self.booleans,
+            JsonObject::Null => &mut self.nulls,
+        };
+        *counter += 1;
+    }
+}
+
+/// Counts how often every key path of [`JsonObject::key_paths`] occurs across the documents and
+/// with which types. Paths are listed in the order they were first seen.
+pub fn key_histogram<'a>(
+    docs: impl IntoIterator<Item = &'a JsonObject>,
+) -> Vec<(String, KeyStats)> {
+    let mut histogram: Vec<(String, KeyStats)> = vec![];
+    let mut positions = HashMap::new();
+
+    for doc in docs {
+        for (path, value) in doc.key_paths() {
+            let position = *positions.entry(path).or_insert_with_key(|path| {
+                histogram.push((path.clone(), KeyStats::default()));
+                histogram.len() - 1
+            });
+            histogram[position].1.add(value);
+        }
+    }
+
+    histogram
+}
+
+impl JsonObject {
+    /// Counts and extremes over the whole tree, to check an untrusted document is of a
+    /// reasonable size before going through it. See [`crate::ParseOptions::max_total_nodes`] and
+    /// [`crate::ParseOptions::max_string_len`] to refuse it while parsing instead.
+    pub fn stats(&self) -> JsonStats {
+        let mut stats = JsonStats::default();
+        let mut stack = vec![(self, 0)];
+
+        while let Some((value, depth)) = stack.pop() {
+            stats.total_nodes += 1;
+
+            match value {
+                JsonObject::Object(object) => {
+                    stats.object_count += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stats.max_object_len = stats.max_object_len.max(object.len());
+                    for (key, value) in object {
+                        stats.add_string(key);
+                        stack.push((value, depth + 1));
+                    }
+                }
+                JsonObject::Array(array) => {
+                    stats.array_count += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stats.max_array_len = stats.max_array_len.max(array.len());
+                    stack.extend(array.iter().map(|value| (value, depth + 1)));
+                }
+                JsonObject::String(s) => {
+                    stats.string_count += 1;
+                    stats.add_string(s);
+                }
+                JsonObject::Number(_) | JsonObject::RawNumber(_) => stats.number_count += 1,
+                JsonObject::Boolean(_) => stats.boolean_count += 1,
+                JsonObject::Null => stats.null_count += 1,
+            }
+        }
+
+        stats
+    }
+
+    /// Every object member in document order, paired with its path. Paths are written like JSON
+    /// Pointers except that every array index is replaced by `*`, so `/items/*/id` names the `id`
+    /// of any element of `items`. A member literally named `*` is not told apart from the
+    /// wildcard.
+    pub fn key_paths(&self) -> Vec<(String, &JsonObject)> {
+        let mut paths = vec![];
+        //array elements are walked but only object members are listed
+        let mut stack = vec![(String::new(), self, false)];
+
+        while let Some((path, value, member)) = stack.pop() {
+            match value {
+                JsonObject::Object(object) => {
+                    stack.extend(object.entries().iter().rev().map(|(key, value)| {
+                        let mut child = path.clone();
+                        push_token(&mut child, key);
+                        (child, value, true)
+                    }));
+                }
+                JsonObject::Array(array) => {
+                    let child = format!("{}/*", path);
+                    stack.extend(
+                        array
+                            .iter()
+                            .rev()
+                            .map(|value| (child.clone(), value, false)),
+                    );
+                }
+                _ => {}
+            }
+
+            if member {
+                paths.push((path, value));
+            }
+        }
+
+        paths
+    }
+
+    /// Estimated bytes held by this value: the size of the `JsonObject` itself plus, for every
+    /// node below it, each `String`'s capacity and each `Vec`'s capacity times its element size.
+    /// Allocator overhead and alignment padding of allocations are not included.
+    pub fn deep_memory_usage(&self) -> usize {
+        let mut total = size_of::<JsonObject>();
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                JsonObject::String(s) => total += s.capacity(),
+                JsonObject::Array(array) => {
+                    total += array.capacity() * size_of::<JsonObject>();
+                    stack.extend(array.iter());
+                }
+                JsonObject::Object(object) => {
+                    let entries = object.entries();
+                    total += entries.capacity() * size_of::<(Key, JsonObject)>();
+                    for (key, value) in entries {
+                        total += key.heap_size();
+                        stack.push(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        total
+    }
+
+    //drops spare capacity of every string and container in the tree
+    pub fn shrink_to_fit(&mut self) {
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                JsonObject::String(s) => s.shrink_to_fit(),
+                JsonObject::Array(array) => {
+                    array.shrink_to_fit();
+                    stack.extend(array.iter_mut());
+                }
+                JsonObject::Object(object) => {
+                    let entries = object.entries_mut();
+                    entries.shrink_to_fit();
+                    for (key, value) in entries {
+                        key.shrink_to_fit();
+                        stack.push(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn key_paths_in_document_order() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{ "a" : { "x/y" : 1 }, "items" : [ { "id" : 1 }, [ { "id" : 2 } ] ], "b" : null }"#,
+        )?;
+
+        let paths: Vec<_> = json.key_paths().into_iter().map(|(path, _)| path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/a",
+                "/a/x~1y",
+                "/items",
+                "/items/*/id",
+                "/items/*/*/id",
+                "/b"
+            ]
+        );
+
+        assert!(parse_json_string("[1, 2]")?.key_paths().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn histogram_over_records() -> Result<(), JsonError> {
+        let docs = [
+            r#"{ "id" : 1, "user" : { "name" : "ann" }, "tags" : [ { "k" : "a" } ] }"#,
+            r#"{ "id" : "2", "user" : null }"#,
+            r#"{ "user" : { "name" : "bob", "age" : 40 }, "tags" : [ { "k" : 1 }, { "k" : null } ] }"#,
+        ]
+        .iter()
+        .map(|doc| parse_json_string(doc))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let histogram = key_histogram(&docs);
+
+        let paths: Vec<_> = histogram.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/id",
+                "/user",
+                "/user/name",
+                "/tags",
+                "/tags/*/k",
+                "/user/age"
+            ]
+        );
+
+        let stats = |path| &histogram.iter().find(|(p, _)| p == path).unwrap().1;
+
+        assert_eq!(
+            *stats("/id"),
+            KeyStats {
+                count: 2,
+                numbers: 1,
+                strings: 1,
+                ..KeyStats::default()
+            }
+        );
+        assert_eq!(
+            *stats("/user"),
+            KeyStats {
+                count: 3,
+                objects: 2,
+                nulls: 1,
+                ..KeyStats::default()
+            }
+        );
+        assert_eq!(
+            *stats("/tags/*/k"),
+            KeyStats {
+                count: 3,
+                strings: 1,
+                numbers: 1,
+                nulls: 1,
+                ..KeyStats::default()
+            }
+        );
+        assert_eq!(stats("/user/age").count, 1);
+
+        //same input, same order
+        assert_eq!(key_histogram(&docs), histogram);
+        assert!(key_histogram(&[]).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_stats() -> Result<(), JsonError> {
+        let (value, stats) = parse_json_string_with_stats(
+            "{ \"é\" : [ 1, \"ab\", [ true, null, {} ] ], \"k\" : \"\\u00e9\" }\n",
+        )?;
+
+        assert_eq!(
+            value,
+            parse_json_string(r#"{ "é" : [ 1, "ab", [ true, null, {} ] ], "k" : "é" }"#)?
+        );
+        assert_eq!(
+            stats,
+            ParseStats {
+                chars: 58,
+                objects: 2,
+                arrays: 2,
+                strings: 2,
+                numbers: 1,
+                booleans: 1,
+                nulls: 1,
+                max_depth: 4,
+                //"é" twice, "ab" and "k"
+                string_bytes: 2 + 2 + 2 + 1,
+            }
+        );
+
+        let (_, scalar) = parse_json_string_with_stats(" 12 ")?;
+        assert_eq!(
+            scalar,
+            ParseStats {
+                chars: 4,
+                numbers: 1,
+                ..ParseStats::default()
+            }
+        );
+
+        assert_eq!(
+            parse_json_string_with_stats("[ 1, ]").map_err(|e| e.kind),
+            Err(JsonErrorKind::UnexpectedChar {
+                found: ']',
+                expected: Expected::Value
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn document_stats() {
+        let json = json!({
+            "name": "crate",
+            "versions": [[0, 1, 0], [1, 0, 0, "beta"]],
+            "meta": { "yanked": false, "license": null, "tags": [] },
+            "ratio": 0.5,
+            "empty": {}
+        });
+
+        assert_eq!(
+            json.stats(),
+            JsonStats {
+                total_nodes: 18,
+                max_depth: 3,
+                object_count: 3,
+                array_count: 4,
+                string_count: 2,
+                number_count: 7,
+                boolean_count: 1,
+                null_count: 1,
+                max_array_len: 4,
+                max_object_len: 5,
+                max_string_len: 8,
+                //"name", "crate", "versions", "beta", "meta", "yanked", "license", "tags",
+                //"ratio" and "empty"
+                total_string_len: 4 + 5 + 8 + 4 + 4 + 6 + 7 + 4 + 5 + 5,
+            }
+        );
+
+        assert_eq!(
+            json!("é").stats(),
+            JsonStats {
+                total_nodes: 1,
+                string_count: 1,
+                max_string_len: 2,
+                total_string_len: 2,
+                ..JsonStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn memory_usage_formula() {
+        let mut key = String::with_capacity(4);
+        key.push_str("ab");
+
+        let mut entries = Vec::with_capacity(2);
+        entries.push((key.into(), JsonObject::String(String::with_capacity(8))));
+
+        let mut array = Vec::with_capacity(3);
+        array.push(JsonObject::Object(Object::from_impl(entries)));
+        array.push(JsonObject::Null);
+
+        let json = JsonObject::Array(array);
+
+        assert_eq!(
+            json.deep_memory_usage(),
+            size_of::<JsonObject>()
+                + 3 * size_of::<JsonObject>()
+                + 2 * size_of::<(Key, JsonObject)>()
+                + 4
+                + 8
+        );
+    }
+
+    #[test]
+    fn grows_with_capacity_and_shrinks() -> Result<(), JsonError> {
+        let mut json = parse_json_string(r#"{ "a" : [ "x", { "b" : "y" } ] }"#)?;
+
+        let before = json.deep_memory_usage();
+
+        let first = json
+            .as_object_mut()
+            .and_then(|object| object.get_mut("a"))
+            .and_then(JsonObject::as_array_mut)
+            .and_then(|array| array.get_mut(0))
+            .and_then(JsonObject::as_string_mut)
+            .unwrap();
+        let old_capacity = first.capacity();
+        first.reserve(1000);
+        let added = first.capacity() - old_capacity;
+
+        let grown = json.deep_memory_usage();
+        assert!(added >= 1000 - old_capacity);
+        assert_eq!(grown, before + added);
+
+        json.shrink_to_fit();
+
+        let shrunk = json.deep_memory_usage();
+        assert!(shrunk < grown);
+        assert!(shrunk <= before);
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "a" : [ "x", { "b" : "y" } ] }"#)?
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//proptest strategies for the tests of several modules
+
+use proptest::prelude::*;
+
+use crate::{JsonObject, Object};
+
+//short strings of chars that are escaped or take more than one byte of UTF-8
+pub(crate) fn strings() -> impl Strategy<Value = String> + Clone {
+    const ALPHABET: &[char] = &['a', 'b', '"', '\\', '\n', '\u{1}', 'é', '😀', '/'];
+
+    prop::collection::vec(prop::sample::select(ALPHABET), 0..4)
+        .prop_map(|chars| chars.into_iter().collect())
+}
+
+//quarters and signed zeros, which are exact in every notation
+pub(crate) fn numbers() -> impl Strategy<Value = f64> + Clone {
+    (-1000i32..=1000).prop_map(|n| -f64::from(n) / 4.)
+}
+
+//documents up to `depth` containers deep, objects can hold a key more than once
+pub(crate) fn documents(
+    keys: impl Strategy<Value = String> + Clone + 'static,
+    depth: u32,
+) -> BoxedStrategy<JsonObject> {
+    let scalar = prop_oneof![
+        Just(JsonObject::Null),
+        any::<bool>().prop_map(JsonObject::Boolean),
+        numbers().prop_map(JsonObject::Number),
+        strings().prop_map(JsonObject::String),
+    ];
+
+    scalar
+        .prop_recursive(depth, 64, 4, move |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(JsonObject::Array),
+                prop::collection::vec((keys.clone(), inner), 0..4).prop_map(|entries| {
+                    let mut object = Object::new();
+                    for (key, value) in entries {
+                        object.entries_mut().push((key.into(), value));
+                    }
+                    JsonObject::Object(object)
+                }),
+            ]
+        })
+        .boxed()
+}
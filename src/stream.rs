@@ -0,0 +1,390 @@
+use std::iter::once;
+use std::str::Chars;
+
+use crate::cursor::{Cursor, Plain};
+use crate::position::Located;
+use crate::{
+    extra_chars, parse_json_impl, unclosed, unexpected, Container, Expected, JsonError,
+    JsonErrorKind, JsonObject, ParseOptions,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    //before the opening '['
+    Start,
+    //after '[' or ',', an element has to follow
+    Element,
+    //after an element, expecting ',' or ']'
+    AfterElement,
+    Done,
+}
+
+/// Iterator over the elements of a top-level array, see [`parse_json_array_stream`].
+pub struct ArrayStream<I> {
+    iter: Located<I>,
+    excess: Option<char>,
+    state: State,
+    options: ParseOptions,
+}
+
+/// Parses a document that is a single array one element at a time, so only the element being
+/// parsed is held in memory. Elements are yielded as soon as they are complete and nothing past
+/// them is read. The first error is yielded once and ends the iteration, including extra
+/// characters after the closing `]`.
+pub fn parse_json_array_stream<I: Iterator<Item = char>>(iter: I) -> ArrayStream<I> {
+    ArrayStream {
+        iter: Located::new(iter),
+        excess: None,
+        state: State::Start,
+        options: ParseOptions::default(),
+    }
+}
+
+impl<I: Iterator<Item = char>> ArrayStream<I> {
+    //`None` once the closing ']' has been read
+    fn step(&mut self) -> Result<Option<JsonObject>, JsonErrorKind> {
+        let mut source = Plain(&mut self.iter);
+        let mut cursor = Cursor::resume(&mut source, self.excess.take());
+
+        loop {
+            if self.state == State::Element {
+                //the element is at depth 1, inside the streamed array
+                let value = parse_json_impl(&mut cursor, &self.options, 1)?;
+
+                self.excess = cursor.into_peeked();
+                self.state = State::AfterElement;
+                return Ok(Some(value));
+            }
+
+            match (self.state, cursor.next_non_whitespace(&self.options)?) {
+                (State::Start, '[') => {
+                    self.state = State::Element;
+                    if cursor.peek_non_whitespace(&self.options)? == Some(']') {
+                        return Ok(None);
+                    }
+                }
+                (State::AfterElement, ',') => self.state = State::Element,
+                (State::AfterElement, ']') => return Ok(None),
+                (State::Start, ch) => return Err(unexpected(ch, Expected::ArrayStart)),
+                (_, ch) => return Err(unexpected(ch, Expected::CommaOrArrayEnd)),
+            }
+        }
+    }
+
+    //only whitespace may follow the array
+    fn check_end(&mut self) -> Option<JsonError> {
+        let ch = Cursor::resume(&mut Plain(&mut self.iter), self.excess.take())
+            .peek_non_whitespace(&self.options)
+            .ok()??;
+        let position = self.iter.current_position();
+
+        Some(JsonError {
+            kind: extra_chars(once(ch).chain(&mut self.iter)),
+            position,
+        })
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for ArrayStream<I> {
+    type Item = Result<JsonObject, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None;
+        }
+
+        let error = match self.step() {
+            Ok(Some(value)) => return Some(Ok(value)),
+            Ok(None) => None,
+            Err(kind) if self.state == State::Start => Some(self.iter.error(kind)),
+            //the streamed array is open past its '['
+            Err(kind) => Some(self.iter.error(unclosed(kind, Container::Array, 0))),
+        };
+
+        self.state = State::Done;
+        error.or_else(|| self.check_end()).map(Err)
+    }
+}
+
+/// Iterator over the documents of newline-delimited JSON, see [`parse_ndjson`].
+pub struct NdjsonStream<I> {
+    iter: Located<I>,
+    options: ParseOptions,
+    done: bool,
+}
+
+/// Parses newline-delimited JSON (JSON Lines), one document per line. Blank lines are skipped.
+/// A document cannot span lines, but escaped newlines inside strings are fine. An invalid line
+/// yields its error and parsing resumes on the next line, the error position gives the line
+/// number in the whole input.
+#[inline]
+pub fn parse_ndjson(input: &str) -> NdjsonStream<Chars<'_>> {
+    parse_ndjson_from_iter(input.chars())
+}
+
+/// [`parse_ndjson`] over any source of chars, documents are parsed as they are read.
+pub fn parse_ndjson_from_iter<I: Iterator<Item = char>>(iter: I) -> NdjsonStream<I> {
+    NdjsonStream {
+        iter: Located::new(iter),
+        options: ParseOptions::default(),
+        done: false,
+    }
+}
+
+impl<I> NdjsonStream<I> {
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut I {
+        self.iter.get_mut()
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for NdjsonStream<I> {
+    type Item = Result<JsonObject, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let mut line = Line {
+                iter: &mut self.iter,
+                ended: false,
+            };
+
+            let first = match line.find(|ch| !ch.is_whitespace()) {
+                Some(ch) => ch,
+                None => {
+                    self.done = !line.ended;
+                    continue;
+                }
+            };
+
+            let mut source = Plain(&mut line);
+            let mut cursor = Cursor::resume(&mut source, Some(first));
+            let parsed = parse_json_impl(&mut cursor, &self.options, 0);
+            //only whitespace may follow the value on its line
+            let extra = cursor.into_peeked().filter(|ch| !ch.is_whitespace());
+
+            let result = match parsed {
+                Ok(value) => match extra.or_else(|| line.find(|ch| !ch.is_whitespace())) {
+                    Some(ch) => {
+                        let position = line.iter.current_position();
+                        Err(JsonError {
+                            kind: extra_chars(once(ch).chain(&mut line)),
+                            position,
+                        })
+                    }
+                    None => Ok(value),
+                },
+                Err(kind) => Err(line.iter.error(kind)),
+            };
+
+            //skip what is left of a bad line
+            line.by_ref().for_each(drop);
+            self.done = !line.ended;
+
+            return Some(result);
+        }
+
+        None
+    }
+}
+
+//the chars up to the next '\n', which is consumed but not yielded
+struct Line<'a, I> {
+    iter: &'a mut Located<I>,
+    ended: bool,
+}
+
+impl<I: Iterator<Item = char>> Iterator for Line<'_, I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        if self.ended {
+            return None;
+        }
+
+        match self.iter.next()? {
+            '\n' => {
+                self.ended = true;
+                None
+            }
+            ch => Some(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{json, parse_json_string, Position};
+
+    fn collect(input: &str) -> Vec<Result<JsonObject, JsonErrorKind>> {
+        parse_json_array_stream(input.chars())
+            .map(|result| result.map_err(|e| e.kind))
+            .collect()
+    }
+
+    #[test]
+    fn elements() -> Result<(), JsonError> {
+        let input = r#" [ 1, "two" ,{ "three" : [ 3 ] },
+            [ ], null,-4.5e1 ] "#;
+
+        let elements = parse_json_array_stream(input.chars()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(JsonObject::Array(elements), parse_json_string(input)?);
+
+        assert_eq!(collect("[]"), []);
+        assert_eq!(collect(" \n[ \t]\n"), []);
+        assert_eq!(collect("[1]"), [Ok(json!(1))]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_end_the_stream() {
+        use JsonErrorKind::*;
+
+        assert_eq!(collect(""), [Err(EarlyEndOfStream)]);
+        assert_eq!(collect("{}"), [Err(unexpected('{', Expected::ArrayStart))]);
+        assert_eq!(collect("[,]"), [Err(unexpected(',', Expected::Value))]);
+        assert_eq!(
+            collect("[1,]"),
+            [Ok(json!(1)), Err(unexpected(']', Expected::Value))]
+        );
+        assert_eq!(
+            collect("[true false]"),
+            [
+                Ok(json!(true)),
+                Err(unexpected('f', Expected::CommaOrArrayEnd))
+            ]
+        );
+        assert_eq!(
+            collect(r#"[ 1, [ "a", "#),
+            [Ok(json!(1)), Err(UnclosedArray { open: 2 })]
+        );
+        assert_eq!(
+            collect("[ 1, 2"),
+            [Ok(json!(1)), Ok(json!(2)), Err(UnclosedArray { open: 1 })]
+        );
+        assert_eq!(
+            collect("[ 1 ] 2 3"),
+            [
+                Ok(json!(1)),
+                Err(ExtraChars {
+                    preview: "2 3".to_owned(),
+                    count: 3
+                })
+            ]
+        );
+
+        let mut stream = parse_json_array_stream("[ 1, x, 2 ]".chars());
+        assert_eq!(stream.next().map(|r| r.is_ok()), Some(true));
+        assert_eq!(
+            stream.next().map(|r| r.map_err(|e| e.position)),
+            Some(Err(Position { line: 1, column: 6 }))
+        );
+        assert!(stream.next().is_none());
+
+        let mut stream = parse_json_array_stream("[ 1 ]\n  x".chars());
+        stream.next();
+        assert_eq!(
+            stream.next().map(|r| r.map_err(|e| e.position)),
+            Some(Err(Position { line: 2, column: 3 }))
+        );
+    }
+
+    fn ndjson(input: &str) -> Vec<Result<JsonObject, (JsonErrorKind, usize)>> {
+        parse_ndjson(input)
+            .map(|result| result.map_err(|e| (e.kind, e.position.line)))
+            .collect()
+    }
+
+    #[test]
+    fn ndjson_lines() {
+        use JsonErrorKind::*;
+
+        let input = "{ \"a\" : 1 }\n[ 2, \"x\\ny\" ]\n\"three\"";
+        let expected = [
+            Ok(json!({ "a": 1 })),
+            Ok(json!([2, "x\ny"])),
+            Ok(json!("three")),
+        ];
+        assert_eq!(ndjson(input), expected);
+        assert_eq!(ndjson(&format!("{}\n", input)), expected);
+        assert_eq!(ndjson(&format!("\n  \r\n{}\r\n\n", input)), expected);
+
+        assert_eq!(ndjson(""), []);
+        assert_eq!(ndjson("\n \n"), []);
+
+        //bad lines do not affect the others
+        assert_eq!(
+            ndjson("[ 1 ]\n{ \"a\" : }\ntrue\n"),
+            [Ok(json!([1])), Err((MissingValue, 2)), Ok(json!(true))]
+        );
+        assert_eq!(
+            ndjson("1 2\n\n[ 3 ]"),
+            [
+                Err((
+                    ExtraChars {
+                        preview: "2".to_owned(),
+                        count: 1
+                    },
+                    1
+                )),
+                Ok(json!([3]))
+            ]
+        );
+
+        //a value cannot continue on the next line, nor a string contain a raw newline
+        assert_eq!(
+            ndjson("[ 1,\n 2 ]\nnull"),
+            [
+                Err((UnclosedArray { open: 1 }, 1)),
+                Err((
+                    ExtraChars {
+                        preview: "]".to_owned(),
+                        count: 1
+                    },
+                    2
+                )),
+                Ok(json!(null))
+            ]
+        );
+        assert_eq!(
+            ndjson("\"a\nb\""),
+            [
+                Err((UnterminatedString, 1)),
+                Err((unexpected('b', Expected::Value), 2))
+            ]
+        );
+    }
+
+    #[test]
+    fn lazy() -> Result<(), JsonError> {
+        let input = format!(
+            "[{}]",
+            (0..10_000)
+                .map(|i| format!(r#"{{ "id" : {}, "tags" : [ "t{}" ] }}"#, i, i))
+                .collect::<Vec<_>>()
+                .join(",\n")
+        );
+
+        let read = Cell::new(0);
+        let counting = input.chars().inspect(|_| read.set(read.get() + 1));
+
+        let first: Vec<_> = parse_json_array_stream(counting)
+            .take(3)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(first[2], json!({ "id": 2, "tags": ["t2"] }));
+        assert!(read.get() < 100, "read {} chars", read.get());
+
+        let mut count = 0;
+        for (i, element) in parse_json_array_stream(input.chars()).enumerate() {
+            assert_eq!(element?["id"], i as f64);
+            count += 1;
+        }
+        assert_eq!(count, 10_000);
+
+        Ok(())
+    }
+}
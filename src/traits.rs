@@ -0,0 +1,508 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::BuildHasher;
+
+use crate::walk::{JsonPath, PathSegment};
+use crate::{ConversionError, JsonObject, Object};
+
+/// Conversion of a Rust value into a document, implemented by hand for your own types:
+///
+/// ```
+/// use json_parser::{json, JsonObject, Object, ToJson};
+///
+/// struct User {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// impl ToJson for User {
+///     fn to_json(&self) -> JsonObject {
+///         let mut object = Object::new();
+///         object.insert("name", self.name.to_json());
+///         object.insert("tags", self.tags.to_json());
+///         JsonObject::Object(object)
+///     }
+/// }
+///
+/// let user = User { name: "ann".to_owned(), tags: vec!["admin".to_owned()] };
+/// assert_eq!(user.to_json(), json!({ "name": "ann", "tags": ["admin"] }));
+/// ```
+pub trait ToJson {
+    fn to_json(&self) -> JsonObject;
+}
+
+/// Conversion of a document into a Rust value, the counterpart of [`ToJson`]. Object members are
+/// best read with [`JsonObject::field`], which records where a nested conversion failed:
+///
+/// ```
+/// use json_parser::{json, FromJson, FromJsonError, JsonObject};
+///
+/// #[derive(Debug)]
+/// struct User {
+///     name: String,
+///     age: Option<u32>,
+/// }
+///
+/// impl FromJson for User {
+///     fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+///         Ok(User {
+///             name: value.field("name")?,
+///             age: value.field("age")?,
+///         })
+///     }
+/// }
+///
+/// let users = json!([{ "name": "ann" }, { "name": "bob", "age": "old" }]);
+/// let err = Vec::<User>::from_json(&users).unwrap_err();
+/// assert_eq!(err.to_string(), "/1/age: expected number, found string");
+/// ```
+pub trait FromJson: Sized {
+    fn from_json(value: &JsonObject) -> Result<Self, FromJsonError>;
+
+    /// The value of a missing object member read with [`JsonObject::field`], `None` to fail
+    /// with [`ConversionError::MissingKey`]. `Option` reads it as `None`.
+    #[inline]
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+/// A [`FromJson`] conversion that failed, and the path of the value it failed on.
+#[derive(Debug, PartialEq)]
+pub struct FromJsonError {
+    pub path: JsonPath,
+    pub kind: ConversionError,
+}
+
+impl FromJsonError {
+    /// The error of the member `key` of the value the path is relative to.
+    pub fn in_key(mut self, key: &str) -> Self {
+        self.path.push_front(PathSegment::Key(key.to_owned()));
+        self
+    }
+
+    /// The error of element `index` of the value the path is relative to.
+    pub fn in_index(mut self, index: usize) -> Self {
+        self.path.push_front(PathSegment::Index(index));
+        self
+    }
+}
+
+/// An error about the value itself, prefix the path with [`FromJsonError::in_key`] and
+/// [`FromJsonError::in_index`].
+impl From<ConversionError> for FromJsonError {
+    #[inline]
+    fn from(kind: ConversionError) -> Self {
+        FromJsonError {
+            path: JsonPath::default(),
+            kind,
+        }
+    }
+}
+
+//`/users/3/age: expected number, found string`
+impl fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}: {}", self.path, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for FromJsonError {}
+
+impl JsonObject {
+    /// Converts the member `key` of an object, for implementing [`FromJson`]. Errors inside the
+    /// member have their path prefixed with the key, a missing member is up to
+    /// [`FromJson::from_missing`].
+    pub fn field<T: FromJson>(&self, key: &str) -> Result<T, FromJsonError> {
+        let object = self.as_object().ok_or_else(|| self.mismatch("object"))?;
+
+        match object.get(key) {
+            Some(value) => T::from_json(value).map_err(|err| err.in_key(key)),
+            None => {
+                T::from_missing().ok_or_else(|| ConversionError::MissingKey(key.to_owned()).into())
+            }
+        }
+    }
+}
+
+impl ToJson for JsonObject {
+    #[inline]
+    fn to_json(&self) -> JsonObject {
+        self.clone()
+    }
+}
+
+impl FromJson for JsonObject {
+    #[inline]
+    fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+        Ok(value.clone())
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    #[inline]
+    fn to_json(&self) -> JsonObject {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for str {
+    #[inline]
+    fn to_json(&self) -> JsonObject {
+        JsonObject::from(self)
+    }
+}
+
+macro_rules! to_json_from {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJson for $ty {
+                #[inline]
+                fn to_json(&self) -> JsonObject {
+                    JsonObject::from(self.clone())
+                }
+            }
+        )*
+    };
+}
+
+to_json_from!(f64, f32, i64, i32, u64, u32, usize, bool, String);
+
+//the `TryFrom` conversions, which fail on the value itself
+macro_rules! from_json_try_from {
+    ($($ty:ty),*) => {
+        $(
+            impl FromJson for $ty {
+                #[inline]
+                fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+                    Ok(<$ty>::try_from(value)?)
+                }
+            }
+        )*
+    };
+}
+
+from_json_try_from!(f64, i64, bool, String);
+
+//only integral numbers in range, like `i64`
+macro_rules! from_json_integer {
+    ($($ty:ident: $getter:ident),*) => {
+        $(
+            impl FromJson for $ty {
+                fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+                    let n = f64::try_from(value)?;
+
+                    let invalid = ConversionError::InvalidNumber {
+                        expected: stringify!($ty),
+                        found: n,
+                    };
+                    value
+                        .$getter()
+                        .and_then(|n| $ty::try_from(n).ok())
+                        .ok_or_else(|| invalid.into())
+                }
+            }
+        )*
+    };
+}
+
+from_json_integer!(i32: as_i64, u32: as_i64, u64: as_u64, usize: as_u64);
+
+impl<T: ToJson> ToJson for Option<T> {
+    #[inline]
+    fn to_json(&self) -> JsonObject {
+        match self {
+            Some(value) => value.to_json(),
+            None => JsonObject::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    #[inline]
+    fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+        match value {
+            JsonObject::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+
+    #[inline]
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> JsonObject {
+        JsonObject::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    #[inline]
+    fn to_json(&self) -> JsonObject {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+        value
+            .as_array()
+            .ok_or_else(|| value.mismatch("array"))?
+            .iter()
+            .enumerate()
+            .map(|(i, element)| T::from_json(element).map_err(|err| err.in_index(i)))
+            .collect()
+    }
+}
+
+//entries in the order of the map, which for a `HashMap` is arbitrary
+impl<T: ToJson, S: BuildHasher> ToJson for HashMap<String, T, S> {
+    fn to_json(&self) -> JsonObject {
+        let object: Object = self
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.to_json()))
+            .collect();
+        JsonObject::Object(object)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> JsonObject {
+        let object: Object = self
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.to_json()))
+            .collect();
+        JsonObject::Object(object)
+    }
+}
+
+//later entries replace earlier ones with the same key, like `TryFrom` does
+macro_rules! from_json_map {
+    ($($map:ident),*) => {
+        $(
+            impl<T: FromJson> FromJson for $map<String, T> {
+                fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+                    value
+                        .as_object()
+                        .ok_or_else(|| value.mismatch("object"))?
+                        .iter()
+                        .map(|(key, value)| {
+                            let value = T::from_json(value).map_err(|err| err.in_key(key))?;
+                            Ok((key.to_owned(), value))
+                        })
+                        .collect()
+                }
+            }
+        )*
+    };
+}
+
+from_json_map!(HashMap, BTreeMap);
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct User {
+        name: String,
+        age: u32,
+        email: Option<String>,
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Team {
+        users: Vec<User>,
+        lead: Option<User>,
+        scores: BTreeMap<String, f64>,
+    }
+
+    impl ToJson for User {
+        fn to_json(&self) -> JsonObject {
+            let mut object = Object::new();
+            object.insert("name", self.name.to_json());
+            object.insert("age", self.age.to_json());
+            object.insert("email", self.email.to_json());
+            object.insert("tags", self.tags.to_json());
+            JsonObject::Object(object)
+        }
+    }
+
+    impl FromJson for User {
+        fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+            Ok(User {
+                name: value.field("name")?,
+                age: value.field("age")?,
+                email: value.field("email")?,
+                tags: value.field("tags")?,
+            })
+        }
+    }
+
+    impl ToJson for Team {
+        fn to_json(&self) -> JsonObject {
+            let mut object = Object::new();
+            object.insert("users", self.users.to_json());
+            object.insert("lead", self.lead.to_json());
+            object.insert("scores", self.scores.to_json());
+            JsonObject::Object(object)
+        }
+    }
+
+    impl FromJson for Team {
+        fn from_json(value: &JsonObject) -> Result<Self, FromJsonError> {
+            Ok(Team {
+                users: value.field("users")?,
+                lead: value.field("lead")?,
+                scores: value.field("scores")?,
+            })
+        }
+    }
+
+    fn user(name: &str, age: u32, email: Option<&str>) -> User {
+        User {
+            name: name.to_owned(),
+            age,
+            email: email.map(str::to_owned),
+            tags: vec!["a".to_owned(), name.to_owned()],
+        }
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let team = Team {
+            users: vec![
+                user("ann", 31, Some("ann@example.com")),
+                user("bob", 4, None),
+            ],
+            lead: None,
+            scores: vec![("ann".to_owned(), 1.5), ("bob".to_owned(), -2.)]
+                .into_iter()
+                .collect(),
+        };
+
+        let json = team.to_json();
+        assert_eq!(
+            json,
+            json!({
+                "users": [
+                    { "name": "ann", "age": 31, "email": "ann@example.com", "tags": ["a", "ann"] },
+                    { "name": "bob", "age": 4, "email": null, "tags": ["a", "bob"] }
+                ],
+                "lead": null,
+                "scores": { "ann": 1.5, "bob": -2 }
+            })
+        );
+        assert_eq!(Team::from_json(&json)?, team);
+        assert_eq!(
+            Team::from_json(&parse_json_string(&json.to_json_string())?)?,
+            team
+        );
+
+        //missing options are `None`, like nulls
+        let json = parse_json_string(
+            r#"{ "users" : [], "scores" : {}, "lead" : { "name" : "eve", "age" : 7, "tags" : [] } }"#,
+        )?;
+        let lead = User {
+            name: "eve".to_owned(),
+            age: 7,
+            email: None,
+            tags: vec![],
+        };
+        assert_eq!(Team::from_json(&json)?.lead, Some(lead));
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_have_paths() {
+        let mut json = Team {
+            users: (0..5).map(|i| user("u", i, None)).collect(),
+            lead: None,
+            scores: BTreeMap::new(),
+        }
+        .to_json();
+
+        json["users"][3]["age"] = json!("old");
+        let err = Team::from_json(&json).unwrap_err();
+        assert_eq!(err.path.to_pointer(), "/users/3/age");
+        assert_eq!(
+            err.kind,
+            ConversionError::Mismatch {
+                expected: "number",
+                found: "string"
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "/users/3/age: expected number, found string"
+        );
+
+        json["users"][3]["age"] = json!(-1);
+        assert_eq!(
+            Team::from_json(&json).unwrap_err().to_string(),
+            "/users/3/age: expected u32, found -1"
+        );
+
+        json["users"][3]["age"] = json!(3);
+        json["users"][1]["tags"][1] = json!(null);
+        assert_eq!(
+            Team::from_json(&json).unwrap_err().to_string(),
+            "/users/1/tags/1: expected string, found null"
+        );
+
+        json["users"][1]["tags"] = json!([]);
+        json["scores"]["x y"] = json!(true);
+        assert_eq!(
+            Team::from_json(&json).unwrap_err().path.to_pointer(),
+            "/scores/x y"
+        );
+
+        json["scores"] = json!({});
+        json["lead"] = json!({ "age": 1, "tags": [] });
+        assert_eq!(
+            Team::from_json(&json).unwrap_err().to_string(),
+            r#"/lead: missing key "name""#
+        );
+
+        assert_eq!(
+            User::from_json(&json!([])).unwrap_err().to_string(),
+            "expected object, found array"
+        );
+    }
+
+    #[test]
+    fn basic_types() -> Result<(), FromJsonError> {
+        assert_eq!("a".to_json(), json!("a"));
+        assert_eq!((&&"a".to_owned()).to_json(), json!("a"));
+        assert_eq!([1u32, 2].to_json(), json!([1, 2]));
+        assert_eq!(Some(false).to_json(), json!(false));
+
+        assert_eq!(i64::from_json(&json!(-3))?, -3);
+        assert_eq!(u64::from_json(&json!(1e15))?, 1_000_000_000_000_000);
+        assert!(i32::from_json(&json!(1e10)).is_err());
+        assert!(usize::from_json(&json!(0.5)).is_err());
+        assert_eq!(f64::from_json(&json!(0.5))?, 0.5);
+        assert_eq!(Option::<bool>::from_json(&json!(null))?, None);
+        assert_eq!(JsonObject::from_json(&json!([1]))?, json!([1]));
+
+        let map: HashMap<String, Vec<i64>> = HashMap::from_json(&json!({ "a": [1], "b": [] }))?;
+        assert_eq!(map["a"], [1]);
+        assert_eq!(map.to_json().as_object().map(Object::len), Some(2));
+        //of duplicate keys the last one wins
+        let duplicated = parse_json_string(r#"{ "a" : 1, "a" : 2 }"#).unwrap();
+        assert_eq!(BTreeMap::<String, i64>::from_json(&duplicated)?["a"], 2);
+
+        Ok(())
+    }
+}
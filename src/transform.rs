@@ -0,0 +1,315 @@
+use std::cmp::Ordering;
+
+use crate::{JsonObject, Key};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneOptions {
+    /// Also remove empty elements from arrays, which shifts the indices of later elements.
+    pub prune_arrays: bool,
+    /// Count `""` as empty as well.
+    pub empty_strings: bool,
+}
+
+impl JsonObject {
+    /// Removes object members whose value is an empty object or array, and optionally array
+    /// elements and empty strings. Children are pruned before their parent is inspected, so a
+    /// container that only held empty values goes away too and one pass reaches the fixed point.
+    ///
+    /// The value itself is never removed or replaced, instead `true` is returned when it is
+    /// empty after pruning.
+    pub fn prune_empty(&mut self, options: &PruneOptions) -> bool {
+        match self {
+            JsonObject::Object(object) => {
+                object
+                    .entries_mut()
+                    .retain_mut(|(_, value)| !value.prune_empty(options));
+                object.entries().is_empty()
+            }
+            JsonObject::Array(array) => {
+                if options.prune_arrays {
+                    array.retain_mut(|value| !value.prune_empty(options));
+                } else {
+                    for value in array.iter_mut() {
+                        value.prune_empty(options);
+                    }
+                }
+                array.is_empty()
+            }
+            JsonObject::String(s) => options.empty_strings && s.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// [`crate::Object::sort_by`] applied to every object in the document, including objects
+    /// inside arrays. Array elements keep their order.
+    pub fn sort_objects_by(
+        &mut self,
+        mut cmp: impl FnMut(&(Key, JsonObject), &(Key, JsonObject)) -> Ordering,
+    ) {
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                JsonObject::Object(object) => {
+                    object.sort_by(&mut cmp);
+                    stack.extend(object.values_mut());
+                }
+                JsonObject::Array(array) => stack.extend(array.iter_mut()),
+                _ => {}
+            }
+        }
+    }
+
+    /// [`crate::Object::sort_keys`] applied to every object in the document. Two documents
+    /// differing only in member order are `==` once both are canonicalized, see also
+    /// [`JsonObject::canonical_eq`] which compares without sorting.
+    pub fn canonicalize(&mut self) {
+        self.sort_objects_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    /// Normalizes every key and string value to Unicode NFC, like
+    /// [`crate::ParseOptions::normalize_nfc`] does at parse time.
+    #[cfg(feature = "nfc")]
+    pub fn normalize_nfc(&mut self) {
+        use crate::normalize_nfc;
+
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                JsonObject::String(s) => *s = normalize_nfc(std::mem::take(s)),
+                JsonObject::Array(array) => stack.extend(array.iter_mut()),
+                JsonObject::Object(object) => {
+                    for (key, value) in object.entries_mut() {
+                        *key = normalize_nfc(std::mem::take(key).into_string()).into();
+                        stack.push(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn cascading_emptiness() -> Result<(), JsonError> {
+        let mut json = parse_json_string(
+            r#"{ "a" : { "b" : { "c" : {}, "d" : [] } }, "keep" : 1, "list" : [ {}, { "x" : {} }, 2 ] }"#,
+        )?;
+
+        assert!(!json.prune_empty(&PruneOptions::default()));
+
+        //arrays keep their elements by default, only emptied in place
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "keep" : 1, "list" : [ {}, {}, 2 ] }"#)?
+        );
+
+        let options = PruneOptions {
+            prune_arrays: true,
+            ..PruneOptions::default()
+        };
+
+        assert!(!json.prune_empty(&options));
+        assert_eq!(
+            json,
+            parse_json_string(r#"{ "keep" : 1, "list" : [ 2 ] }"#)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_strings() -> Result<(), JsonError> {
+        let source = r#"{ "a" : "", "b" : { "c" : "" }, "d" : [ "" ] }"#;
+
+        let mut json = parse_json_string(source)?;
+        assert!(!json.prune_empty(&PruneOptions::default()));
+        assert_eq!(json, parse_json_string(source)?);
+
+        let options = PruneOptions {
+            empty_strings: true,
+            prune_arrays: true,
+        };
+
+        assert!(json.prune_empty(&options));
+        assert_eq!(json, parse_json_string("{}")?);
+
+        Ok(())
+    }
+
+    //well-known keys first in a fixed order, then the rest alphabetically
+    fn priority(key: &str) -> (usize, Option<&str>) {
+        const KNOWN: [&str; 3] = ["name", "version", "description"];
+
+        match KNOWN.iter().position(|known| *known == key) {
+            Some(rank) => (rank, None),
+            None => (KNOWN.len(), Some(key)),
+        }
+    }
+
+    fn keys(json: &JsonObject) -> Vec<&str> {
+        json.as_object()
+            .unwrap()
+            .entries()
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn priority_keys_first() -> Result<(), JsonError> {
+        let source = r#"{ "license" : "MIT", "version" : "1", "authors" : [], "name" : "x", "description" : "" }"#;
+
+        let mut json = parse_json_string(source)?;
+        json.as_object_mut()
+            .unwrap()
+            .sort_by(|(a, _), (b, _)| priority(a).cmp(&priority(b)));
+        assert_eq!(
+            keys(&json),
+            ["name", "version", "description", "authors", "license"]
+        );
+
+        let mut cached = parse_json_string(source)?;
+        let mut calls = 0;
+        cached.as_object_mut().unwrap().sort_by_cached_key(|key| {
+            calls += 1;
+            let (rank, rest) = priority(key);
+            (rank, rest.map(str::to_owned))
+        });
+        assert_eq!(cached, json);
+        assert_eq!(calls, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_is_stable() -> Result<(), JsonError> {
+        let mut json =
+            parse_json_string(r#"{ "b" : 1, "name" : 0, "a" : 2, "c" : 3, "b" : 4, "a" : 5 }"#)?;
+
+        //everything but the known keys ranks equal and keeps its order
+        json.as_object_mut()
+            .unwrap()
+            .sort_by_cached_key(|key| priority(key).0);
+        assert_eq!(keys(&json), ["name", "b", "a", "c", "b", "a"]);
+        assert_eq!(
+            json.all_numbers().collect::<Vec<_>>(),
+            [0., 1., 2., 3., 4., 5.]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn recursive_sort() -> Result<(), JsonError> {
+        let mut json = parse_json_string(
+            r#"{ "z" : { "y" : 1, "name" : 2 }, "list" : [ { "b" : 1, "a" : 2 }, [ { "d" : 3, "c" : 4 } ] ], "name" : "top" }"#,
+        )?;
+
+        json.sort_objects_by(|(a, _), (b, _)| priority(a).cmp(&priority(b)));
+        assert_eq!(
+            json,
+            parse_json_string(
+                r#"{ "name" : "top", "list" : [ { "a" : 2, "b" : 1 }, [ { "c" : 4, "d" : 3 } ] ], "z" : { "name" : 2, "y" : 1 } }"#,
+            )?
+        );
+        assert_eq!(keys(&json), ["name", "list", "z"]);
+
+        //arrays are not reordered, scalars are left alone
+        let mut array = parse_json_string("[ 3, 1, 2 ]")?;
+        array.sort_objects_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(array.all_numbers().collect::<Vec<_>>(), [3., 1., 2.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_order() -> Result<(), JsonError> {
+        let mut left = parse_json_string(
+            r#"{ "b" : [ { "y" : 1, "x" : 2.0 } ], "a" : { "d" : null, "c" : { "f" : 1, "e" : 2 } } }"#,
+        )?;
+        let mut right = parse_json_string(
+            r#"{ "a" : { "c" : { "e" : 2, "f" : 1e0 }, "d" : null }, "b" : [ { "x" : 2, "y" : 1 } ] }"#,
+        )?;
+
+        assert_ne!(left, right);
+        assert!(left.canonical_eq(&right));
+
+        left.canonicalize();
+        right.canonicalize();
+        assert_eq!(left, right);
+        assert_eq!(keys(&left), ["a", "b"]);
+        assert_eq!(keys(&left["a"]["c"]), ["e", "f"]);
+
+        //duplicates stay in order, arrays are not sorted
+        let mut object = parse_json_string(r#"{ "k" : 2, "j" : [ "b", "a" ], "k" : 1 }"#)?;
+        object.as_object_mut().unwrap().sort_keys();
+        assert_eq!(
+            object,
+            parse_json_string(r#"{ "j" : [ "b", "a" ], "k" : 2, "k" : 1 }"#)?
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "nfc")]
+    #[test]
+    fn nfc_normalization() -> Result<(), JsonError> {
+        //the same key precomposed and with a combining acute accent
+        let source = "{ \"caf\u{e9}\" : 1, \"cafe\u{301}\" : 2, \"v\" : \"e\\u0301\" }";
+
+        let json = parse_json_string(source)?;
+        let object = json.as_object().unwrap();
+        assert_eq!(object.entries().len(), 3);
+        assert_ne!(object.entries()[0].0, object.entries()[1].0);
+        assert_eq!(object.get("caf\u{e9}"), Some(&JsonObject::Number(1.)));
+        assert_eq!(object.get("cafe\u{301}"), Some(&JsonObject::Number(2.)));
+
+        let options = ParseOptions {
+            normalize_nfc: true,
+            ..ParseOptions::default()
+        };
+        let normalized = parse_json_string_with_options(source, &options)?;
+        let object = normalized.as_object().unwrap();
+        //duplicates are kept, lookups find the first
+        assert_eq!(object.entries()[0].0, object.entries()[1].0);
+        assert_eq!(object.get("caf\u{e9}"), Some(&JsonObject::Number(1.)));
+        assert_eq!(
+            object.get("v"),
+            Some(&JsonObject::String("\u{e9}".to_owned()))
+        );
+
+        let mut json = json;
+        json.normalize_nfc();
+        assert_eq!(json, normalized);
+
+        Ok(())
+    }
+
+    #[test]
+    fn top_level_is_reported_not_replaced() -> Result<(), JsonError> {
+        let mut json = parse_json_string(r#"[ { "a" : [ {} ] } ]"#)?;
+
+        let options = PruneOptions {
+            prune_arrays: true,
+            ..PruneOptions::default()
+        };
+
+        assert!(json.prune_empty(&options));
+        assert_eq!(json, parse_json_string("[]")?);
+
+        //already at the fixed point
+        assert!(json.prune_empty(&options));
+        assert_eq!(json, parse_json_string("[]")?);
+
+        let mut scalar = JsonObject::Null;
+        assert!(!scalar.prune_empty(&options));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,589 @@
+use std::fmt;
+
+use crate::pointer::push_token;
+use crate::JsonObject;
+
+/// A step from a container to one of its values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a value is in a document, see [`JsonObject::walk`]. Empty for the document itself and
+/// displayed as a JSON Pointer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    #[inline]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The key of the member this is, `None` for array elements and the document itself.
+    pub fn key(&self) -> Option<&str> {
+        match self.segments.last()? {
+            PathSegment::Key(key) => Some(key),
+            PathSegment::Index(_) => None,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(key) => push_token(&mut pointer, key),
+                PathSegment::Index(i) => pointer.push_str(&format!("/{}", i)),
+            }
+        }
+        pointer
+    }
+
+    #[inline]
+    pub(crate) fn from_segments(segments: Vec<PathSegment>) -> Self {
+        JsonPath { segments }
+    }
+
+    //errors built from the inside out learn their path one parent at a time
+    pub(crate) fn push_front(&mut self, segment: PathSegment) {
+        self.segments.insert(0, segment);
+    }
+
+    //back to the `depth` segments of the parent, then one more
+    fn enter(&mut self, depth: usize, segment: Option<PathSegment>) {
+        self.segments.truncate(depth);
+        self.segments.extend(segment);
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_pointer())
+    }
+}
+
+//the children of a container with their segments and the length of its path, in reverse so they
+//are popped in order
+fn push_children<T>(
+    stack: &mut Vec<(usize, Option<PathSegment>, T)>,
+    depth: usize,
+    children: impl DoubleEndedIterator<Item = (PathSegment, T)>,
+) {
+    stack.extend(
+        children
+            .rev()
+            .map(|(segment, value)| (depth, Some(segment), value)),
+    );
+}
+
+/// Iterator over the scalars of a document with their JSON Pointers, see [`JsonObject::leaves`].
+pub struct Leaves<'a> {
+    //pending values in reverse document order, with their pointers
+    stack: Vec<(String, &'a JsonObject)>,
+    include_empty_containers: bool,
+}
+
+impl<'a> Leaves<'a> {
+    /// Also yield `{}` and `[]`, so the document can be rebuilt from its leaves.
+    pub fn include_empty_containers(mut self, include: bool) -> Self {
+        self.include_empty_containers = include;
+        self
+    }
+}
+
+impl<'a> Iterator for Leaves<'a> {
+    type Item = (String, &'a JsonObject);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((pointer, value)) = self.stack.pop() {
+            match value {
+                JsonObject::Object(object) if !object.entries().is_empty() => {
+                    for (key, value) in object.entries().iter().rev() {
+                        let mut child = pointer.clone();
+                        push_token(&mut child, key);
+                        self.stack.push((child, value));
+                    }
+                }
+                JsonObject::Array(array) if !array.is_empty() => {
+                    for (i, value) in array.iter().enumerate().rev() {
+                        self.stack.push((format!("{}/{}", pointer, i), value));
+                    }
+                }
+                JsonObject::Object(_) | JsonObject::Array(_) => {
+                    if self.include_empty_containers {
+                        return Some((pointer, value));
+                    }
+                }
+                _ => return Some((pointer, value)),
+            }
+        }
+
+        None
+    }
+}
+
+//every value including containers in document order, each container before its contents
+pub(crate) struct Descendants<'a> {
+    stack: Vec<&'a JsonObject>,
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a JsonObject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.stack.pop()?;
+
+        match value {
+            JsonObject::Object(object) => self.stack.extend(object.values().rev()),
+            JsonObject::Array(array) => self.stack.extend(array.iter().rev()),
+            _ => {}
+        }
+
+        Some(value)
+    }
+}
+
+impl JsonObject {
+    pub(crate) fn descendants(&self) -> Descendants<'_> {
+        Descendants { stack: vec![self] }
+    }
+
+    /// Every number anywhere in the document, in document order. Raw numbers are read as
+    /// [`as_f64`](JsonObject::as_f64) does.
+    pub fn all_numbers(&self) -> impl Iterator<Item = f64> + '_ {
+        self.descendants().filter_map(JsonObject::as_f64)
+    }
+
+    /// Every string value anywhere in the document, in document order. Keys are not included.
+    pub fn all_strings(&self) -> impl Iterator<Item = &str> + '_ {
+        self.descendants().filter_map(|value| match value {
+            JsonObject::String(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn all_booleans(&self) -> impl Iterator<Item = bool> + '_ {
+        self.descendants().filter_map(|value| match value {
+            JsonObject::Boolean(b) => Some(*b),
+            _ => None,
+        })
+    }
+
+    /// Calls `f` with every value in the document and where it is, depth first with each
+    /// container before its contents.
+    pub fn walk(&self, f: &mut impl FnMut(&JsonPath, &JsonObject)) {
+        let mut path = JsonPath::default();
+        let mut stack = vec![(0, None, self)];
+
+        while let Some((depth, segment, value)) = stack.pop() {
+            path.enter(depth, segment);
+            f(&path, value);
+
+            match value {
+                JsonObject::Object(object) => push_children(
+                    &mut stack,
+                    path.len(),
+                    object
+                        .entries()
+                        .iter()
+                        .map(|(key, value)| (PathSegment::Key(key.to_string()), value)),
+                ),
+                JsonObject::Array(array) => push_children(
+                    &mut stack,
+                    path.len(),
+                    array
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| (PathSegment::Index(i), value)),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    /// [`JsonObject::walk`] for changing values in place. Children are visited after `f` has
+    /// seen their container, so they are the ones it left: replacing an object with a string
+    /// skips the old members.
+    pub fn walk_mut(&mut self, f: &mut impl FnMut(&JsonPath, &mut JsonObject)) {
+        let mut path = JsonPath::default();
+        let mut stack = vec![(0, None, self)];
+
+        while let Some((depth, segment, value)) = stack.pop() {
+            path.enter(depth, segment);
+            f(&path, value);
+
+            match value {
+                JsonObject::Object(object) => push_children(
+                    &mut stack,
+                    path.len(),
+                    object
+                        .entries_mut()
+                        .iter_mut()
+                        .map(|(key, value)| (PathSegment::Key(key.to_string()), value)),
+                ),
+                JsonObject::Array(array) => push_children(
+                    &mut stack,
+                    path.len(),
+                    array
+                        .iter_mut()
+                        .enumerate()
+                        .map(|(i, value)| (PathSegment::Index(i), value)),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    /// Replaces every value with what `f` makes of it, in the order of [`JsonObject::walk_mut`].
+    pub fn map_values(mut self, mut f: impl FnMut(&JsonPath, JsonObject) -> JsonObject) -> Self {
+        self.walk_mut(&mut |path, value| *value = f(path, std::mem::take(value)));
+        self
+    }
+
+    /// Every string, number, boolean and null in document order, paired with its JSON Pointer.
+    pub fn leaves(&self) -> Leaves<'_> {
+        Leaves {
+            stack: vec![(String::new(), self)],
+            include_empty_containers: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pointer::parse_pointer;
+    use crate::*;
+
+    fn container_for(token: &str) -> JsonObject {
+        if token.parse::<usize>().is_ok() {
+            JsonObject::Array(vec![])
+        } else {
+            parse_json_string("{}").unwrap()
+        }
+    }
+
+    //gets or inserts the child at `token`, skipped array slots are filled with null
+    fn child<'a>(parent: &'a mut JsonObject, token: &str, value: JsonObject) -> &'a mut JsonObject {
+        match parent {
+            JsonObject::Object(object) => {
+                if object.get(token).is_none() {
+                    object.entries_mut().push((token.into(), value));
+                }
+                object.get_mut(token).unwrap()
+            }
+            JsonObject::Array(array) => {
+                let index = token.parse::<usize>().unwrap();
+                if index >= array.len() {
+                    array.resize_with(index, || JsonObject::Null);
+                    array.push(value);
+                }
+                &mut array[index]
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    //rebuilds a document from its leaves, treating numeric tokens as array indices
+    fn unflatten<'a>(leaves: impl Iterator<Item = (String, &'a JsonObject)>) -> JsonObject {
+        let mut root = JsonObject::Null;
+
+        for (pointer, value) in leaves {
+            let value = parse_json_string(&value.to_json_string()).unwrap();
+            let tokens = parse_pointer(&pointer).unwrap();
+
+            let (last, parents) = match tokens.split_last() {
+                Some(split) => split,
+                None => {
+                    root = value;
+                    continue;
+                }
+            };
+
+            if let JsonObject::Null = root {
+                root = container_for(&tokens[0]);
+            }
+
+            let mut current = &mut root;
+            for (i, token) in parents.iter().enumerate() {
+                current = child(current, token, container_for(&tokens[i + 1]));
+            }
+            child(current, last, value);
+        }
+
+        root
+    }
+
+    const DOCUMENT: &str = r#"{
+        "a/b" : { "c~d" : 1, "e" : [ "x", null, { "f" : true } ] },
+        "empty" : {},
+        "list" : [ [], 2 ],
+        "s" : "t"
+    }"#;
+
+    #[test]
+    fn pointers_in_document_order() -> Result<(), JsonError> {
+        let json = parse_json_string(DOCUMENT)?;
+
+        let pointers: Vec<_> = json.leaves().map(|(pointer, _)| pointer).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/a~1b/c~0d",
+                "/a~1b/e/0",
+                "/a~1b/e/1",
+                "/a~1b/e/2/f",
+                "/list/1",
+                "/s"
+            ]
+        );
+
+        for (pointer, value) in json.leaves() {
+            let tokens = parse_pointer(&pointer).unwrap();
+            let mut current = &json;
+            for token in &tokens {
+                current = match current {
+                    JsonObject::Object(object) => object.get(token).unwrap(),
+                    JsonObject::Array(array) => &array[token.parse::<usize>().unwrap()],
+                    _ => unreachable!(),
+                };
+            }
+            assert!(std::ptr::eq(current, value));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_containers() -> Result<(), JsonError> {
+        let json = parse_json_string(DOCUMENT)?;
+
+        let pointers: Vec<_> = json
+            .leaves()
+            .include_empty_containers(true)
+            .map(|(pointer, _)| pointer)
+            .collect();
+        assert_eq!(
+            pointers,
+            [
+                "/a~1b/c~0d",
+                "/a~1b/e/0",
+                "/a~1b/e/1",
+                "/a~1b/e/2/f",
+                "/empty",
+                "/list/0",
+                "/list/1",
+                "/s"
+            ]
+        );
+
+        let empty = parse_json_string("[]")?;
+        assert_eq!(empty.leaves().count(), 0);
+        assert_eq!(
+            empty
+                .leaves()
+                .include_empty_containers(true)
+                .collect::<Vec<_>>(),
+            [(String::new(), &empty)]
+        );
+
+        let scalar = JsonObject::Boolean(false);
+        assert_eq!(
+            scalar.leaves().collect::<Vec<_>>(),
+            [(String::new(), &scalar)]
+        );
+
+        Ok(())
+    }
+
+    const RECORDS: &str = r#"[
+        { "url" : "https://a", "ts" : 10, "ok" : true, "tags" : [ "x", 3 ] },
+        { "url" : "https://b", "ts" : 30, "ok" : false, "nested" : { "ts" : 20, "url" : null } }
+    ]"#;
+
+    #[test]
+    fn typed_iterators() -> Result<(), JsonError> {
+        let json = parse_json_string(RECORDS)?;
+
+        assert_eq!(json.all_numbers().collect::<Vec<_>>(), [10., 3., 30., 20.]);
+        assert_eq!(json.all_numbers().sum::<f64>(), 63.);
+        assert_eq!(json.all_numbers().fold(f64::MIN, f64::max), 30.);
+        assert_eq!(
+            json.all_strings().collect::<Vec<_>>(),
+            ["https://a", "x", "https://b"]
+        );
+        assert_eq!(json.all_booleans().collect::<Vec<_>>(), [true, false]);
+
+        for empty in &["{}", "[]", "null"] {
+            let json = parse_json_string(empty)?;
+            assert_eq!(json.all_numbers().count(), 0);
+            assert_eq!(json.all_strings().count(), 0);
+            assert_eq!(json.all_booleans().count(), 0);
+        }
+
+        let json = parse_json_string("\"only\"")?;
+        assert_eq!(json.all_strings().collect::<Vec<_>>(), ["only"]);
+
+        let options = ParseOptions {
+            number_policy: NumberPolicy::PreserveString,
+            ..ParseOptions::default()
+        };
+        let json = parse_json_string_with_options("[ 5, 1.5, 12345678901234567890123 ]", &options)?;
+        assert_eq!(
+            json.all_numbers().collect::<Vec<_>>(),
+            [5., 1.5, 12345678901234567890123.]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn descendants_are_lazy() -> Result<(), JsonError> {
+        let source = format!("[ 1, [ {} 0 ] ]", "0, ".repeat(10_000));
+        let json = parse_json_string(&source)?;
+
+        let mut descendants = json.descendants();
+        descendants.next();
+        assert_eq!(descendants.next(), Some(&JsonObject::Number(1.)));
+        //the big array is still a single pending value
+        assert_eq!(descendants.stack.len(), 1);
+
+        assert_eq!(json.all_numbers().take(2).collect::<Vec<_>>(), [1., 0.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), JsonError> {
+        let json = parse_json_string(DOCUMENT)?;
+
+        assert_eq!(
+            unflatten(json.leaves().include_empty_containers(true)),
+            json
+        );
+        //without the flag the husks are lost
+        assert_eq!(
+            unflatten(json.leaves()),
+            parse_json_string(
+                r#"{
+                    "a/b" : { "c~d" : 1, "e" : [ "x", null, { "f" : true } ] },
+                    "list" : [ null, 2 ],
+                    "s" : "t"
+                }"#
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn redacting_at_any_depth() -> Result<(), JsonError> {
+        let mut json = parse_json_string(
+            r#"{ "password" : "a", "users" : [ { "name" : "x", "password" : "b" },
+                { "auth" : { "password" : { "hash" : "c" } } } ], "passwords" : [ "d" ] }"#,
+        )?;
+
+        json.walk_mut(&mut |path, value| {
+            if path.key() == Some("password") {
+                *value = JsonObject::String("***".to_owned());
+            }
+        });
+
+        assert_eq!(
+            json,
+            json!({
+                "password": "***",
+                "users": [
+                    { "name": "x", "password": "***" },
+                    { "auth": { "password": "***" } }
+                ],
+                "passwords": ["d"]
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn paths_of_numbers() -> Result<(), JsonError> {
+        let json = parse_json_string(
+            r#"{ "a/b" : [ 1, "x", { "c" : 2.5 } ], "d" : { "e" : [ [ 3 ] ] }, "f" : null }"#,
+        )?;
+
+        let mut numbers = vec![];
+        json.walk(&mut |path, value| {
+            if let JsonObject::Number(n) = value {
+                numbers.push((path.to_string(), *n));
+            }
+        });
+        assert_eq!(
+            numbers,
+            [
+                ("/a~1b/0".to_owned(), 1.),
+                ("/a~1b/2/c".to_owned(), 2.5),
+                ("/d/e/0/0".to_owned(), 3.)
+            ]
+        );
+
+        //parents come before their children
+        let mut paths = vec![];
+        json.walk(&mut |path, _| paths.push(path.clone()));
+        assert_eq!(paths.len(), 11);
+        assert!(paths[0].is_empty());
+        assert_eq!(paths[1].segments(), [PathSegment::Key("a/b".to_owned())]);
+        assert_eq!(
+            paths[2].segments(),
+            [PathSegment::Key("a/b".to_owned()), PathSegment::Index(0)]
+        );
+        assert_eq!(paths[10].to_pointer(), "/f");
+
+        Ok(())
+    }
+
+    #[test]
+    fn replacing_containers() -> Result<(), JsonError> {
+        let json = parse_json_string(r#"{ "keep" : [ " a ", 1.6 ], "drop" : { "n" : 1.2 } }"#)?;
+
+        let mut visited = vec![];
+        let mapped = json.map_values(|path, value| {
+            visited.push(path.to_pointer());
+            match value {
+                JsonObject::Object(object) if path.key() == Some("drop") => {
+                    JsonObject::String(format!("{} members", object.entries().len()))
+                }
+                JsonObject::String(s) => JsonObject::String(s.trim().to_owned()),
+                JsonObject::Number(n) => JsonObject::Number(n.round()),
+                value => value,
+            }
+        });
+
+        assert_eq!(mapped, json!({ "keep": ["a", 2], "drop": "1 members" }));
+        //the old members of "drop" are never seen
+        assert_eq!(visited, ["", "/keep", "/keep/0", "/keep/1", "/drop"]);
+
+        //and new children are
+        let mut json = json!([1]);
+        json.walk_mut(&mut |_, value| {
+            if let JsonObject::Number(n) = value {
+                if *n < 3. {
+                    *value = json!([*n + 1.]);
+                }
+            }
+        });
+        assert_eq!(json, json!([[[3]]]));
+
+        Ok(())
+    }
+}
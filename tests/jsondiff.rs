@@ -0,0 +1,140 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use json_parser::{apply_patch, parse_json_string, JsonObject};
+
+//`cargo test` builds the examples next to the test binaries, in `target/<profile>/examples`
+fn jsondiff() -> Command {
+    let exe = std::env::current_exe().unwrap();
+    let dir = exe.parent().unwrap().parent().unwrap();
+    let path = dir
+        .join("examples")
+        .join(format!("jsondiff{}", std::env::consts::EXE_SUFFIX));
+
+    assert!(
+        path.exists(),
+        "{} is missing, run the whole test suite so examples are built",
+        path.display()
+    );
+
+    Command::new(path)
+}
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/jsondiff")
+        .join(name)
+}
+
+fn load(name: &str) -> JsonObject {
+    parse_json_string(&std::fs::read_to_string(fixture(name)).unwrap()).unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn same_documents() {
+    for pair in [("base.json", "base.json"), ("base.json", "reordered.json")] {
+        let output = jsondiff()
+            .args([fixture(pair.0), fixture(pair.1)])
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(0), "{:?}", pair);
+        assert!(output.stdout.is_empty());
+        assert!(output.stderr.is_empty());
+    }
+
+    let patch = jsondiff()
+        .arg("--patch")
+        .args([fixture("reordered.json"), fixture("base.json")])
+        .output()
+        .unwrap();
+    assert_eq!(patch.status.code(), Some(0));
+    assert!(patch.stdout.is_empty());
+}
+
+#[test]
+fn report() {
+    let output = jsondiff()
+        .args([fixture("base.json"), fixture("changed.json")])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output).lines().collect::<Vec<_>>(),
+        [
+            "changed /listen/port: 80 -> 8080",
+            r#"changed /upstreams/1: "b.internal" -> "c.internal""#,
+            r#"added /upstreams/2: "d.internal""#,
+            "removed /debug: true",
+            r#"added /tls: {"cert":"edge.pem"}"#,
+        ]
+    );
+}
+
+#[test]
+fn patches() {
+    for (from, to) in [
+        ("base.json", "changed.json"),
+        ("changed.json", "base.json"),
+        ("base.json", "renamed.json"),
+    ] {
+        let output = jsondiff()
+            .arg("--patch")
+            .args([fixture(from), fixture(to)])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+
+        let patch = parse_json_string(&stdout(&output)).unwrap();
+        let mut patched = load(from);
+        apply_patch(&mut patched, &patch).unwrap();
+        assert!(patched.canonical_eq(&load(to)), "{} to {}", from, to);
+    }
+
+    //a renamed member is moved rather than removed and added again
+    let output = jsondiff()
+        .arg("--patch")
+        .args([fixture("base.json"), fixture("renamed.json")])
+        .output()
+        .unwrap();
+    assert_eq!(
+        stdout(&output),
+        "[\n  {\n    \"op\": \"move\",\n    \"from\": \"/limits\",\n    \"path\": \"/quota\"\n  }\n]\n"
+    );
+}
+
+#[test]
+fn unreadable_inputs() {
+    let path = fixture("invalid.json");
+    let output = jsondiff()
+        .args([fixture("base.json"), path.clone()])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "{}:3:27: expected a key but found '}}'\n  \"listen\": {{ \"port\": 80, }},\n                          ^\n",
+            path.display()
+        )
+    );
+
+    let missing = jsondiff()
+        .args([fixture("base.json"), fixture("missing.json")])
+        .output()
+        .unwrap();
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8(missing.stderr)
+        .unwrap()
+        .contains("missing.json: "));
+
+    let usage = jsondiff().arg(fixture("base.json")).output().unwrap();
+    assert_eq!(usage.status.code(), Some(2));
+}
@@ -0,0 +1,156 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+//`cargo test` builds the examples next to the test binaries, in `target/<profile>/examples`
+fn jsonlint() -> Command {
+    let exe = std::env::current_exe().unwrap();
+    let dir = exe.parent().unwrap().parent().unwrap();
+    let path = dir
+        .join("examples")
+        .join(format!("jsonlint{}", std::env::consts::EXE_SUFFIX));
+
+    assert!(
+        path.exists(),
+        "{} is missing, run the whole test suite so examples are built",
+        path.display()
+    );
+
+    Command::new(path)
+}
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/jsonlint")
+        .join(name)
+}
+
+fn fixtures(kind: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = std::fs::read_dir(fixture(kind))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+//diagnostics are the `path:line:column: error` lines, each followed by a snippet and a caret
+fn diagnostics(output: &Output) -> Vec<String> {
+    String::from_utf8(output.stderr.clone())
+        .unwrap()
+        .lines()
+        .step_by(3)
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn good_fixtures_pass() {
+    let output = jsonlint().args(fixtures("good")).output().unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn bad_fixtures_fail_with_one_diagnostic_each() {
+    let bad = fixtures("bad");
+    let output = jsonlint().args(&bad).output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let diagnostics = diagnostics(&output);
+    assert_eq!(diagnostics.len(), bad.len());
+    for (diagnostic, path) in diagnostics.iter().zip(&bad) {
+        assert!(diagnostic.starts_with(&path.display().to_string()));
+    }
+}
+
+#[test]
+fn diagnostic_points_at_the_error() {
+    let path = fixture("bad/mismatched.json");
+    let output = jsonlint().arg(&path).output().unwrap();
+
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "{}:2:16: expected ',' or ']' but found '}}'\n  \"a\" : [ 1, 2 }},\n               ^\n",
+            path.display()
+        )
+    );
+}
+
+#[test]
+fn mixed_inputs_fail() {
+    let mut paths = fixtures("good");
+    paths.push(fixture("bad/trailing_comma.json"));
+    paths.push(fixture("missing.json"));
+
+    let output = jsonlint().args(&paths).output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("trailing_comma.json:2:21: expected a value but found ']'"));
+    assert!(stderr.contains("missing.json: "));
+}
+
+#[test]
+fn reads_stdin() {
+    let lint = |input: &str| {
+        let mut child = jsonlint()
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    assert!(lint("{ \"a\" : [] }\n").status.success());
+
+    let output = lint("[ 1,\n  true, ]\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        diagnostics(&output),
+        ["<stdin>:2:9: expected a value but found ']'"]
+    );
+}
+
+#[test]
+fn all_errors() {
+    let mut child = jsonlint()
+        .arg("--all-errors")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"[ 1 2,\n  NaN,\n  { \"a\" : true ]\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        diagnostics(&output),
+        [
+            "<stdin>:1:5: expected ',' or ']' but found '2'",
+            "<stdin>:2:5: NaN and infinities are not valid JSON",
+            "<stdin>:3:16: expected ',' or '}' but found ']'",
+        ]
+    );
+
+    let good = jsonlint()
+        .arg("--all-errors")
+        .args(fixtures("good"))
+        .output()
+        .unwrap();
+    assert!(good.status.success());
+}